@@ -140,7 +140,7 @@ pub struct UiTokenAccount {
     pub close_authority: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum UiAccountState {
     Uninitialized,
@@ -248,6 +248,17 @@ pub fn get_token_account_mint(data: &[u8]) -> Option<Pubkey> {
     }
 }
 
+pub fn get_mint_decimals(data: &[u8]) -> Option<u8> {
+    Mint::unpack(data).map(|mint| mint.decimals).ok()
+}
+
+/// The state of an initialized or frozen token account; `None` for any other data
+pub fn get_token_account_state(data: &[u8]) -> Option<UiAccountState> {
+    Account::unpack(data)
+        .map(|account| account.state.into())
+        .ok()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -355,6 +366,45 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_get_mint_decimals() {
+        let mut mint_data = vec![0; Mint::get_packed_len()];
+        assert_eq!(get_mint_decimals(&mint_data), None);
+
+        let mint = Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        Mint::pack(mint, &mut mint_data).unwrap();
+        assert_eq!(get_mint_decimals(&mint_data), Some(6));
+        assert_eq!(get_mint_decimals(&[0; 3]), None);
+    }
+
+    #[test]
+    fn test_get_token_account_state() {
+        let mut account_data = vec![0; Account::get_packed_len()];
+        assert_eq!(get_token_account_state(&account_data), None);
+
+        let mut account = Account::unpack_unchecked(&account_data).unwrap();
+        account.state = AccountState::Initialized;
+        Account::pack(account, &mut account_data).unwrap();
+        assert_eq!(
+            get_token_account_state(&account_data),
+            Some(UiAccountState::Initialized)
+        );
+
+        account.state = AccountState::Frozen;
+        Account::pack(account, &mut account_data).unwrap();
+        assert_eq!(
+            get_token_account_state(&account_data),
+            Some(UiAccountState::Frozen)
+        );
+
+        // Mint data is not a token account
+        assert_eq!(get_token_account_state(&[1; 82]), None);
+    }
+
     #[test]
     fn test_ui_token_amount_real_string() {
         assert_eq!(&real_number_string(1, 0), "1");
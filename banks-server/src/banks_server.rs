@@ -137,12 +137,14 @@ fn verify_transaction(
     transaction: &Transaction,
     libsecp256k1_0_5_upgrade_enabled: bool,
     libsecp256k1_fail_on_bad_count: bool,
+    ed25519_program_enabled: bool,
 ) -> transaction::Result<()> {
     if let Err(err) = transaction.verify() {
         Err(err)
     } else if let Err(err) = transaction.verify_precompiles(
         libsecp256k1_0_5_upgrade_enabled,
         libsecp256k1_fail_on_bad_count,
+        ed25519_program_enabled,
     ) {
         Err(err)
     } else {
@@ -240,6 +242,7 @@ impl Banks for BanksServer {
             &transaction,
             self.bank(commitment).libsecp256k1_0_5_upgrade_enabled(),
             self.bank(commitment).libsecp256k1_fail_on_bad_count(),
+            self.bank(commitment).ed25519_program_enabled(),
         ) {
             return Some(Err(err));
         }
@@ -30,6 +30,8 @@ echo "Executing $testName"
 case $testName in
 test-stable)
   _ "$cargo" stable test --jobs "$NPROC" --all --exclude solana-local-cluster ${V:+--verbose} -- --nocapture
+  # LedgerStorage tests against the in-process BigTable emulator
+  _ "$cargo" stable test --jobs "$NPROC" --package solana-storage-bigtable --features emulator --test ledger_storage ${V:+--verbose} -- --nocapture
   ;;
 test-stable-bpf)
   # Clear the C dependency files, if dependency moves these files are not regenerated
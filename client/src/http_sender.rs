@@ -1,33 +1,39 @@
-//! The standard [`RpcSender`] over HTTP.
+//! The standard [`RpcSender`] over HTTP, and [`FailoverHttpSender`], which
+//! spreads requests over several HTTP endpoints.
 
 use {
     crate::{
-        client_error::Result,
+        client_error::{ClientError, ClientErrorKind, Result},
         rpc_custom_error,
         rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
         rpc_response::RpcSimulateTransactionResult,
         rpc_sender::RpcSender,
     },
+    async_trait::async_trait,
+    futures::future::join_all,
     log::*,
     reqwest::{
         self,
         header::{CONTENT_TYPE, RETRY_AFTER},
         StatusCode,
     },
+    serde_json::json,
+    solana_sdk::clock::Slot,
     std::{
         sync::{
             atomic::{AtomicU64, Ordering},
-            Arc,
+            Arc, Mutex,
         },
-        thread::sleep,
-        time::Duration,
+        time::{Duration, Instant},
     },
+    tokio::time::sleep,
 };
 
 pub struct HttpSender {
-    client: Arc<reqwest::blocking::Client>,
+    client: Arc<reqwest::Client>,
     url: String,
     request_id: AtomicU64,
+    too_many_requests_retries: usize,
 }
 
 /// The standard [`RpcSender`] over HTTP.
@@ -44,51 +50,57 @@ impl HttpSender {
     ///
     /// The URL is an HTTP URL, usually for port 8899.
     pub fn new_with_timeout(url: String, timeout: Duration) -> Self {
-        // `reqwest::blocking::Client` panics if run in a tokio async context.  Shuttle the
-        // request to a different tokio thread to avoid this
-        let client = Arc::new(
-            tokio::task::block_in_place(move || {
-                reqwest::blocking::Client::builder()
-                    .timeout(timeout)
-                    .build()
-            })
-            .expect("build rpc client"),
-        );
+        Self::new_with_client(build_client(timeout), url, 5)
+    }
 
+    fn new_with_client(
+        client: Arc<reqwest::Client>,
+        url: String,
+        too_many_requests_retries: usize,
+    ) -> Self {
         Self {
             client,
             url,
             request_id: AtomicU64::new(0),
+            too_many_requests_retries,
         }
     }
 }
 
+fn build_client(timeout: Duration) -> Arc<reqwest::Client> {
+    Arc::new(
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("build rpc client"),
+    )
+}
+
 #[derive(Deserialize, Debug)]
 struct RpcErrorObject {
     code: i64,
     message: String,
 }
 
+#[async_trait]
 impl RpcSender for HttpSender {
-    fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value> {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
         let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
         let request_json = request.build_request_json(request_id, params).to_string();
 
-        let mut too_many_requests_retries = 5;
+        let mut too_many_requests_retries = self.too_many_requests_retries;
         loop {
-            // `reqwest::blocking::Client` panics if run in a tokio async context.  Shuttle the
-            // request to a different tokio thread to avoid this
-            let response = {
-                let client = self.client.clone();
-                let request_json = request_json.clone();
-                tokio::task::block_in_place(move || {
-                    client
-                        .post(&self.url)
-                        .header(CONTENT_TYPE, "application/json")
-                        .body(request_json)
-                        .send()
-                })
-            };
+            let response = self
+                .client
+                .post(&self.url)
+                .header(CONTENT_TYPE, "application/json")
+                .body(request_json.clone())
+                .send()
+                .await;
 
             match response {
                 Ok(response) => {
@@ -113,13 +125,13 @@ impl RpcSender for HttpSender {
                                 response, too_many_requests_retries, duration
                             );
 
-                            sleep(duration);
+                            sleep(duration).await;
                             continue;
                         }
                         return Err(response.error_for_status().unwrap_err().into());
                     }
 
-                    let response_text = tokio::task::block_in_place(move || response.text())?;
+                    let response_text = response.text().await?;
 
                     let json: serde_json::Value = serde_json::from_str(&response_text)?;
                     if json["error"].is_object() {
@@ -172,21 +184,495 @@ impl RpcSender for HttpSender {
     }
 }
 
+/// Configuration of a [`FailoverHttpSender`].
+#[derive(Debug, Clone)]
+pub struct FailoverHttpSenderConfig {
+    /// Timeout of each HTTP request.
+    pub timeout: Duration,
+    /// How long the health of the endpoints is trusted before it is checked
+    /// again.
+    pub health_check_interval: Duration,
+    /// How many slots an endpoint may trail the most advanced endpoint and
+    /// still be considered healthy.
+    pub max_slot_lag: Slot,
+    /// How long an endpoint is passed over after it fails with a connection
+    /// error, or an HTTP 429 or 503 response.
+    pub failure_cooldown: Duration,
+}
+
+impl Default for FailoverHttpSenderConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            health_check_interval: Duration::from_secs(10),
+            max_slot_lag: 150,
+            failure_cooldown: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Statistics of one endpoint of a [`FailoverHttpSender`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EndpointStats {
+    pub url: String,
+    /// Outcome of the latest health check. Endpoints are assumed healthy until
+    /// they are first checked.
+    pub healthy: bool,
+    /// Processed slot reported by the latest health check.
+    pub slot: Option<Slot>,
+    /// Requests sent to the endpoint, not counting health checks.
+    pub requests: u64,
+    /// Requests that returned an error of any kind.
+    pub errors: u64,
+    /// Requests that failed with a connection error, or an HTTP 429 or 503
+    /// response, and were passed on to the next endpoint, if any.
+    pub failovers: u64,
+    pub last_error: Option<String>,
+}
+
+struct EndpointState {
+    stats: EndpointStats,
+    cooldown_until: Option<Instant>,
+}
+
+struct Endpoint {
+    sender: HttpSender,
+    state: Mutex<EndpointState>,
+}
+
+impl Endpoint {
+    fn is_available(&self, now: Instant) -> bool {
+        let state = self.state.lock().unwrap();
+        state.stats.healthy
+            && state
+                .cooldown_until
+                .map(|cooldown_until| now >= cooldown_until)
+                .unwrap_or(true)
+    }
+}
+
+/// An [`RpcSender`] over HTTP that routes requests to the healthiest of
+/// several endpoints.
+///
+/// Each request goes to the first endpoint, in the order given, that passed
+/// the latest health check and has not recently failed. An endpoint is
+/// healthy when `getHealth` succeeds and its processed slot is within
+/// [`max_slot_lag`] of the most advanced endpoint; the check is repeated
+/// every [`health_check_interval`]. [`send`] starts the check in the
+/// background when it is due and routes on the outcome of the previous one,
+/// so a slow endpoint doesn't hold up requests.
+///
+/// A request that fails with a connection error, a timeout, an HTTP 429 or
+/// 503 response, or a node-unhealthy RPC error is retried on the next
+/// endpoint, and the failed endpoint is passed over for
+/// [`failure_cooldown`]. Any other error is returned as is. Unhealthy and
+/// cooling-down endpoints are still tried, last, when no other endpoint
+/// succeeds.
+///
+/// Clones share their endpoints and statistics, so a clone may be kept to
+/// observe [`endpoint_stats`] after the sender has been moved into an
+/// [`RpcClient`].
+///
+/// [`max_slot_lag`]: FailoverHttpSenderConfig::max_slot_lag
+/// [`health_check_interval`]: FailoverHttpSenderConfig::health_check_interval
+/// [`failure_cooldown`]: FailoverHttpSenderConfig::failure_cooldown
+/// [`send`]: RpcSender::send
+/// [`endpoint_stats`]: FailoverHttpSender::endpoint_stats
+/// [`RpcClient`]: crate::rpc_client::RpcClient
+///
+/// # Examples
+///
+/// ```
+/// # use solana_client::{
+/// #     http_sender::FailoverHttpSender,
+/// #     rpc_client::{RpcClient, RpcClientConfig},
+/// # };
+/// let sender = FailoverHttpSender::new(vec![
+///     "http://localhost:8899".to_string(),
+///     "http://localhost:8999".to_string(),
+/// ]);
+/// let stats_sender = sender.clone();
+/// let rpc_client = RpcClient::new_sender(sender, RpcClientConfig::default());
+/// for stats in stats_sender.endpoint_stats() {
+///     println!("{}: {} requests", stats.url, stats.requests);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct FailoverHttpSender {
+    endpoints: Arc<Vec<Endpoint>>,
+    config: FailoverHttpSenderConfig,
+    last_health_check: Arc<tokio::sync::Mutex<Option<Instant>>>,
+}
+
+impl FailoverHttpSender {
+    /// Create a failover HTTP RPC sender with the default configuration.
+    ///
+    /// The URLs are HTTP URLs, usually for port 8899, in order of preference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `urls` is empty.
+    pub fn new(urls: Vec<String>) -> Self {
+        Self::new_with_config(urls, FailoverHttpSenderConfig::default())
+    }
+
+    /// Create a failover HTTP RPC sender.
+    ///
+    /// The URLs are HTTP URLs, usually for port 8899, in order of preference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `urls` is empty.
+    pub fn new_with_config(urls: Vec<String>, config: FailoverHttpSenderConfig) -> Self {
+        assert!(!urls.is_empty(), "no RPC endpoints");
+        let client = build_client(config.timeout);
+        let endpoints = urls
+            .into_iter()
+            .map(|url| Endpoint {
+                // 429s fail over to the next endpoint instead of being retried
+                sender: HttpSender::new_with_client(client.clone(), url.clone(), 0),
+                state: Mutex::new(EndpointState {
+                    stats: EndpointStats {
+                        url,
+                        healthy: true,
+                        ..EndpointStats::default()
+                    },
+                    cooldown_until: None,
+                }),
+            })
+            .collect();
+
+        Self {
+            endpoints: Arc::new(endpoints),
+            config,
+            last_health_check: Arc::new(tokio::sync::Mutex::new(None)),
+        }
+    }
+
+    /// Statistics of each endpoint, in the order the URLs were given.
+    pub fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| endpoint.state.lock().unwrap().stats.clone())
+            .collect()
+    }
+
+    /// Start a health check on the current runtime if one is due.
+    fn spawn_health_check(&self) {
+        // Concurrent requests don't start a check that is already underway
+        let mut last_health_check = match self.last_health_check.clone().try_lock_owned() {
+            Ok(last_health_check) => last_health_check,
+            Err(_) => return,
+        };
+        if let Some(last_health_check) = *last_health_check {
+            if last_health_check.elapsed() < self.config.health_check_interval {
+                return;
+            }
+        }
+
+        let sender = self.clone();
+        tokio::spawn(async move {
+            sender.check_health().await;
+            *last_health_check = Some(Instant::now());
+        });
+    }
+
+    async fn check_health(&self) {
+        let slots = join_all(self.endpoints.iter().map(|endpoint| async move {
+            endpoint
+                .sender
+                .send(RpcRequest::GetHealth, serde_json::Value::Null)
+                .await
+                .ok()?;
+            endpoint
+                .sender
+                .send(RpcRequest::GetSlot, json!([{"commitment": "processed"}]))
+                .await
+                .ok()?
+                .as_u64()
+        }))
+        .await;
+
+        let max_slot = slots.iter().flatten().max().copied();
+        for (endpoint, slot) in self.endpoints.iter().zip(slots) {
+            let mut state = endpoint.state.lock().unwrap();
+            state.stats.healthy = match (slot, max_slot) {
+                (Some(slot), Some(max_slot)) => max_slot - slot <= self.config.max_slot_lag,
+                _ => false,
+            };
+            if !state.stats.healthy {
+                debug!("{} is unhealthy at slot {:?}", state.stats.url, slot);
+            }
+            state.stats.slot = slot;
+        }
+    }
+}
+
+fn is_failover_error(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Reqwest(err) => {
+            err.is_connect()
+                || err.is_timeout()
+                || matches!(
+                    err.status(),
+                    Some(StatusCode::TOO_MANY_REQUESTS) | Some(StatusCode::SERVICE_UNAVAILABLE)
+                )
+        }
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+        }
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl RpcSender for FailoverHttpSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.spawn_health_check();
+
+        let now = Instant::now();
+        let mut endpoints: Vec<&Endpoint> = self.endpoints.iter().collect();
+        // Stable, so available endpoints keep their order of preference
+        endpoints.sort_by_key(|endpoint| !endpoint.is_available(now));
+
+        let mut last_err = None;
+        for endpoint in endpoints {
+            let result = endpoint.sender.send(request, params.clone()).await;
+
+            let mut state = endpoint.state.lock().unwrap();
+            state.stats.requests += 1;
+            match result {
+                Ok(response) => return Ok(response),
+                Err(err) => {
+                    state.stats.errors += 1;
+                    state.stats.last_error = Some(err.to_string());
+                    if !is_failover_error(&err) {
+                        return Err(err);
+                    }
+                    debug!("{} failed, failing over: {}", state.stats.url, err);
+                    state.stats.failovers += 1;
+                    state.cooldown_until = Some(Instant::now() + self.config.failure_cooldown);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.expect("at least one endpoint"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {
+        super::*,
+        jsonrpc_core::{futures::prelude::*, IoHandler, Params, Value},
+        jsonrpc_http_server::ServerBuilder,
+        serde_json::Number,
+        std::{
+            io::{Read, Write},
+            net::TcpListener,
+            sync::mpsc::channel,
+            thread,
+        },
+    };
 
     #[tokio::test(flavor = "multi_thread")]
     async fn http_sender_on_tokio_multi_thread() {
         let http_sender = HttpSender::new("http://localhost:1234".to_string());
-        let _ = http_sender.send(RpcRequest::GetVersion, serde_json::Value::Null);
+        let _ = http_sender
+            .send(RpcRequest::GetVersion, serde_json::Value::Null)
+            .await;
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn http_sender_on_tokio_current_thread() {
+        let http_sender = HttpSender::new("http://localhost:1234".to_string());
+        let _ = http_sender
+            .send(RpcRequest::GetVersion, serde_json::Value::Null)
+            .await;
+    }
+
+    fn start_rpc_server(slot: Slot) -> String {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let mut io = IoHandler::default();
+            io.add_method("getHealth", |_params: Params| {
+                future::ok(Value::String("ok".to_string()))
+            });
+            io.add_method("getSlot", move |_params: Params| {
+                future::ok(Value::Number(Number::from(slot)))
+            });
+            // Answers with the slot, to identify the endpoint that served the request
+            io.add_method("getBalance", move |_params: Params| {
+                future::ok(Value::Number(Number::from(slot)))
+            });
+
+            let server = ServerBuilder::new(io)
+                .threads(1)
+                .start_http(&"127.0.0.1:0".parse().unwrap())
+                .expect("Unable to start RPC server");
+            sender.send(*server.address()).unwrap();
+            server.wait();
+        });
+        format!("http://{}", receiver.recv().unwrap())
+    }
+
+    fn start_unavailable_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                // Read the headers, which are followed by a short body
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = stream.write_all(
+                    b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                );
+            }
+        });
+        url
+    }
+
+    fn start_unresponsive_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            // Accept connections and hold them open without ever answering
+            let _streams: Vec<_> = listener.incoming().flatten().collect();
+        });
+        url
+    }
+
+    fn closed_port_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn failover_http_sender_fails_over() {
+        let urls = vec![
+            closed_port_url(),
+            start_unavailable_server(),
+            start_rpc_server(100),
+        ];
+        let sender = FailoverHttpSender::new(urls.clone());
+        // Leave all endpoints healthy, so that the first two are tried
+        *sender.last_health_check.try_lock().unwrap() = Some(Instant::now());
+
+        let balance = sender
+            .send(RpcRequest::GetBalance, json!([]))
+            .await
+            .unwrap();
+        assert_eq!(balance, json!(100));
+
+        let stats = sender.endpoint_stats();
+        assert_eq!(
+            stats.iter().map(|stats| &stats.url).collect::<Vec<_>>(),
+            urls.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            stats
+                .iter()
+                .map(|stats| (stats.requests, stats.errors, stats.failovers))
+                .collect::<Vec<_>>(),
+            vec![(1, 1, 1), (1, 1, 1), (1, 0, 0)]
+        );
+        assert!(stats[1].last_error.as_ref().unwrap().contains("503"));
+
+        // Failed endpoints are passed over while they cool down
+        let balance = sender
+            .send(RpcRequest::GetBalance, json!([]))
+            .await
+            .unwrap();
+        assert_eq!(balance, json!(100));
+        assert_eq!(
+            sender
+                .endpoint_stats()
+                .iter()
+                .map(|stats| stats.requests)
+                .collect::<Vec<_>>(),
+            vec![1, 1, 2]
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
-    #[should_panic(expected = "can call blocking only when running on the multi-threaded runtime")]
-    async fn http_sender_ontokio_current_thread_should_panic() {
-        // RpcClient::new() will panic in the tokio current-thread runtime due to `tokio::task::block_in_place()` usage, and there
-        // doesn't seem to be a way to detect whether the tokio runtime is multi_thread or current_thread...
-        let _ = HttpSender::new("http://localhost:1234".to_string());
+    async fn failover_http_sender_routes_to_healthy_endpoints() {
+        let sender = FailoverHttpSender::new_with_config(
+            vec![
+                closed_port_url(),
+                start_rpc_server(10),
+                start_rpc_server(1_000),
+            ],
+            FailoverHttpSenderConfig {
+                max_slot_lag: 150,
+                ..FailoverHttpSenderConfig::default()
+            },
+        );
+        sender.check_health().await;
+
+        let balance = sender
+            .send(RpcRequest::GetBalance, json!([]))
+            .await
+            .unwrap();
+        assert_eq!(balance, json!(1_000));
+
+        let stats = sender.endpoint_stats();
+        assert_eq!(
+            stats
+                .iter()
+                .map(|stats| (stats.healthy, stats.slot, stats.requests))
+                .collect::<Vec<_>>(),
+            vec![
+                (false, None, 0),
+                (false, Some(10), 0),
+                (true, Some(1_000), 1)
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn failover_http_sender_does_not_wait_on_health_checks() {
+        let sender =
+            FailoverHttpSender::new(vec![start_rpc_server(100), start_unresponsive_server()]);
+
+        // The health check hangs on the second endpoint until the request timeout
+        let balance = tokio::time::timeout(
+            Duration::from_secs(5),
+            sender.send(RpcRequest::GetBalance, json!([])),
+        )
+        .await
+        .expect("send waited on the health check")
+        .unwrap();
+        assert_eq!(balance, json!(100));
+        assert!(sender.endpoint_stats()[0].slot.is_none());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn failover_http_sender_returns_rpc_errors() {
+        let sender = FailoverHttpSender::new(vec![start_rpc_server(1), start_rpc_server(1)]);
+
+        // The servers don't implement `getEpochInfo`
+        let result = sender.send(RpcRequest::GetEpochInfo, json!([])).await;
+        assert!(matches!(
+            result.unwrap_err().kind(),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { .. })
+        ));
+        assert_eq!(
+            sender
+                .endpoint_stats()
+                .iter()
+                .map(|stats| (stats.requests, stats.errors, stats.failovers))
+                .collect::<Vec<_>>(),
+            vec![(1, 1, 0), (0, 0, 0)]
+        );
     }
 }
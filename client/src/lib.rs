@@ -6,9 +6,11 @@ pub mod blockhash_query;
 pub mod client_error;
 pub mod http_sender;
 pub mod mock_sender;
+pub mod nonblocking;
 pub mod nonce_utils;
 pub mod perf_utils;
 pub mod pubsub_client;
+pub mod replay_sender;
 pub mod rpc_cache;
 pub mod rpc_client;
 pub mod rpc_config;
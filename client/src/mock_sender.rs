@@ -13,6 +13,7 @@ use {
         },
         rpc_sender::RpcSender,
     },
+    async_trait::async_trait,
     serde_json::{json, Number, Value},
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
@@ -82,8 +83,13 @@ impl MockSender {
     }
 }
 
+#[async_trait]
 impl RpcSender for MockSender {
-    fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value> {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
         if let Some(value) = self.mocks.write().unwrap().remove(&request) {
             return Ok(value);
         }
@@ -213,6 +219,7 @@ impl RpcSender for MockSender {
                             pre_token_balances: None,
                             post_token_balances: None,
                             rewards: None,
+                            return_data: None,
                         }),
                 },
                 block_time: Some(1628633791),
@@ -320,6 +327,10 @@ impl RpcSender for MockSender {
                     logs: None,
                     accounts: None,
                     units_consumed: None,
+                    inner_instructions: None,
+                    pre_token_balances: None,
+                    post_token_balances: None,
+                    return_data: None,
                 },
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
@@ -0,0 +1,338 @@
+//! A client for subscribing to messages from the RPC server, asynchronously.
+//!
+//! This is the async counterpart of [`crate::pubsub_client`]. A single
+//! [`PubsubClient`] owns one websocket connection, serviced by a tokio task,
+//! and multiplexes any number of subscriptions over it. Each subscription is
+//! returned as a [`Stream`] of notifications together with a function that
+//! unsubscribes it.
+//!
+//! [`Stream`]: futures::stream::Stream
+
+use {
+    crate::{
+        pubsub_client::PubsubClientError,
+        rpc_config::{
+            RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+        },
+        rpc_response::{
+            Response as RpcResponse, RpcKeyedAccount, RpcLogsResponse, RpcSignatureResult, RpcVote,
+            SlotInfo, SlotUpdate,
+        },
+    },
+    futures::{
+        future::{ready, BoxFuture, FutureExt},
+        sink::SinkExt,
+        stream::{BoxStream, StreamExt},
+    },
+    log::*,
+    serde::de::DeserializeOwned,
+    serde_json::{json, Map, Value},
+    solana_account_decoder::UiAccount,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
+    std::{collections::BTreeMap, time::Duration},
+    tokio::{
+        net::TcpStream,
+        sync::{mpsc, oneshot},
+        task::JoinHandle,
+        time::sleep,
+    },
+    tokio_stream::wrappers::UnboundedReceiverStream,
+    tokio_tungstenite::{
+        connect_async,
+        tungstenite::{
+            protocol::frame::{coding::CloseCode, CloseFrame},
+            Message,
+        },
+        MaybeTlsStream, WebSocketStream,
+    },
+    url::Url,
+};
+
+pub type PubsubClientResult<T = ()> = Result<T, PubsubClientError>;
+
+/// Ends a subscription; the returned future resolves once the server has
+/// acknowledged the unsubscribe request, or the connection has closed.
+pub type UnsubscribeFn = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// The notification stream of a subscription and the function that ends it.
+pub type SubscribeResult<'a, T> = PubsubClientResult<(BoxStream<'a, T>, UnsubscribeFn)>;
+
+type SubscribeResponseMsg =
+    Result<(mpsc::UnboundedReceiver<Value>, UnsubscribeFn), PubsubClientError>;
+type SubscribeRequestMsg = (String, Value, oneshot::Sender<SubscribeResponseMsg>);
+type UnsubscribeRequestMsg = (String, u64, oneshot::Sender<()>);
+
+// Interval of inactivity after which the connection is kept alive with a ping
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize, Debug)]
+struct RpcErrorObject {
+    code: i64,
+    message: String,
+}
+
+/// An async client of the Solana pubsub websocket API.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use futures::StreamExt;
+/// # use solana_client::nonblocking::pubsub_client::PubsubClient;
+/// # futures::executor::block_on(async {
+/// let pubsub_client = PubsubClient::new("ws://localhost:8900").await?;
+/// {
+///     let (mut slots, unsubscribe) = pubsub_client.slot_subscribe().await?;
+///     if let Some(slot_info) = slots.next().await {
+///         println!("{:?}", slot_info);
+///     }
+///     unsubscribe().await;
+/// }
+/// pubsub_client.shutdown().await?;
+/// # Ok::<(), solana_client::pubsub_client::PubsubClientError>(())
+/// # });
+/// ```
+pub struct PubsubClient {
+    subscribe_sender: mpsc::UnboundedSender<SubscribeRequestMsg>,
+    shutdown_sender: oneshot::Sender<()>,
+    ws: JoinHandle<PubsubClientResult>,
+}
+
+impl PubsubClient {
+    /// Connect to the pubsub server at `url`, usually a websocket URL for port
+    /// 8900, as in "ws://localhost:8900".
+    ///
+    /// Must be called from within a tokio runtime, which services the
+    /// connection until [`shutdown`](PubsubClient::shutdown) is called or the
+    /// server closes it.
+    pub async fn new(url: &str) -> PubsubClientResult<Self> {
+        let url = Url::parse(url)?;
+        let (ws, _response) = connect_async(url).await?;
+
+        let (subscribe_sender, subscribe_receiver) = mpsc::unbounded_channel();
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+
+        Ok(Self {
+            subscribe_sender,
+            shutdown_sender,
+            ws: tokio::spawn(Self::run_ws(ws, subscribe_receiver, shutdown_receiver)),
+        })
+    }
+
+    /// Close the connection, ending all of its subscriptions.
+    pub async fn shutdown(self) -> PubsubClientResult {
+        let _ = self.shutdown_sender.send(());
+        self.ws
+            .await
+            .map_err(|err| PubsubClientError::ConnectionClosed(err.to_string()))?
+    }
+
+    async fn subscribe<'a, T>(&self, operation: &str, params: Value) -> SubscribeResult<'a, T>
+    where
+        T: DeserializeOwned + Send + 'a,
+    {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.subscribe_sender
+            .send((operation.to_string(), params, response_sender))
+            .map_err(|err| PubsubClientError::ConnectionClosed(err.to_string()))?;
+
+        let (notifications, unsubscribe) = response_receiver
+            .await
+            .map_err(|err| PubsubClientError::ConnectionClosed(err.to_string()))??;
+        Ok((
+            UnboundedReceiverStream::new(notifications)
+                .filter_map(|value| ready(serde_json::from_value::<T>(value).ok()))
+                .boxed(),
+            unsubscribe,
+        ))
+    }
+
+    pub async fn account_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<UiAccount>> {
+        self.subscribe("account", json!([pubkey.to_string(), config]))
+            .await
+    }
+
+    pub async fn logs_subscribe(
+        &self,
+        filter: RpcTransactionLogsFilter,
+        config: RpcTransactionLogsConfig,
+    ) -> SubscribeResult<'_, RpcResponse<RpcLogsResponse>> {
+        self.subscribe("logs", json!([filter, config])).await
+    }
+
+    pub async fn program_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        config: Option<RpcProgramAccountsConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcKeyedAccount>> {
+        self.subscribe("program", json!([pubkey.to_string(), config]))
+            .await
+    }
+
+    pub async fn vote_subscribe(&self) -> SubscribeResult<'_, RpcVote> {
+        self.subscribe("vote", json!([])).await
+    }
+
+    pub async fn root_subscribe(&self) -> SubscribeResult<'_, Slot> {
+        self.subscribe("root", json!([])).await
+    }
+
+    pub async fn signature_subscribe(
+        &self,
+        signature: &Signature,
+        config: Option<RpcSignatureSubscribeConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcSignatureResult>> {
+        self.subscribe("signature", json!([signature.to_string(), config]))
+            .await
+    }
+
+    pub async fn slot_subscribe(&self) -> SubscribeResult<'_, SlotInfo> {
+        self.subscribe("slot", json!([])).await
+    }
+
+    pub async fn slot_updates_subscribe(&self) -> SubscribeResult<'_, SlotUpdate> {
+        self.subscribe("slotsUpdates", json!([])).await
+    }
+
+    async fn run_ws(
+        mut ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        mut subscribe_receiver: mpsc::UnboundedReceiver<SubscribeRequestMsg>,
+        mut shutdown_receiver: oneshot::Receiver<()>,
+    ) -> PubsubClientResult {
+        let mut request_id: u64 = 0;
+        let mut pending_subscribes = BTreeMap::new();
+        let mut pending_unsubscribes = BTreeMap::<u64, oneshot::Sender<()>>::new();
+        let mut subscriptions = BTreeMap::<u64, mpsc::UnboundedSender<Value>>::new();
+        let (unsubscribe_sender, mut unsubscribe_receiver) =
+            mpsc::unbounded_channel::<UnsubscribeRequestMsg>();
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_receiver => {
+                    let frame = CloseFrame { code: CloseCode::Normal, reason: "".into() };
+                    ws.send(Message::Close(Some(frame))).await?;
+                    ws.flush().await?;
+                    break;
+                },
+                () = sleep(PING_INTERVAL) => {
+                    ws.send(Message::Ping(Vec::new())).await?;
+                },
+                Some((operation, params, response_sender)) = subscribe_receiver.recv() => {
+                    request_id += 1;
+                    let method = format!("{}Subscribe", operation);
+                    let body = json!({
+                        "jsonrpc":"2.0","id":request_id,"method":method,"params":params
+                    });
+                    ws.send(Message::Text(body.to_string())).await?;
+                    pending_subscribes.insert(request_id, (operation, response_sender));
+                },
+                Some((operation, subscription_id, response_sender)) = unsubscribe_receiver.recv() => {
+                    subscriptions.remove(&subscription_id);
+                    request_id += 1;
+                    let method = format!("{}Unsubscribe", operation);
+                    let body = json!({
+                        "jsonrpc":"2.0","id":request_id,"method":method,"params":[subscription_id]
+                    });
+                    ws.send(Message::Text(body.to_string())).await?;
+                    pending_unsubscribes.insert(request_id, response_sender);
+                },
+                message = ws.next() => {
+                    let text = match message {
+                        Some(message) => match message? {
+                            Message::Text(text) => text,
+                            Message::Ping(data) => {
+                                ws.send(Message::Pong(data)).await?;
+                                continue;
+                            }
+                            Message::Binary(_) | Message::Pong(_) => continue,
+                            Message::Close(_) => break,
+                        },
+                        None => break,
+                    };
+                    let mut json: Map<String, Value> = serde_json::from_str(&text)?;
+
+                    // Subscribe or unsubscribe response, e.g.
+                    // `{"jsonrpc":"2.0","result":5308752,"id":1}`
+                    if let Some(id) = json.get("id") {
+                        let id = id.as_u64().ok_or_else(|| PubsubClientError::SubscribeFailed {
+                            reason: "invalid `id` field".to_string(),
+                            message: text.clone(),
+                        })?;
+
+                        if let Some(response_sender) = pending_unsubscribes.remove(&id) {
+                            let _ = response_sender.send(());
+                        } else if let Some((operation, response_sender)) =
+                            pending_subscribes.remove(&id)
+                        {
+                            let response = match (json.get("error"), json.get("result").and_then(Value::as_u64)) {
+                                (Some(error), _) => {
+                                    let reason = match serde_json::from_value::<RpcErrorObject>(error.clone()) {
+                                        Ok(RpcErrorObject { code, message }) => format!("{} ({})", message, code),
+                                        Err(err) => format!("failed to deserialize RPC error response: {} [{}]", error, err),
+                                    };
+                                    Err(PubsubClientError::SubscribeFailed { reason, message: text.clone() })
+                                }
+                                (None, Some(subscription_id)) => {
+                                    let (notification_sender, notification_receiver) = mpsc::unbounded_channel();
+                                    subscriptions.insert(subscription_id, notification_sender);
+
+                                    let unsubscribe_sender = unsubscribe_sender.clone();
+                                    let unsubscribe: UnsubscribeFn = Box::new(move || {
+                                        async move {
+                                            let (response_sender, response_receiver) = oneshot::channel();
+                                            // Nothing to do if the connection is already closed
+                                            if unsubscribe_sender
+                                                .send((operation, subscription_id, response_sender))
+                                                .is_ok()
+                                            {
+                                                let _ = response_receiver.await;
+                                            }
+                                        }
+                                        .boxed()
+                                    });
+                                    Ok((notification_receiver, unsubscribe))
+                                }
+                                (None, None) => Err(PubsubClientError::SubscribeFailed {
+                                    reason: "invalid `result` field".to_string(),
+                                    message: text.clone(),
+                                }),
+                            };
+                            let _ = response_sender.send(response);
+                        } else {
+                            warn!("unknown request id: {}", id);
+                        }
+                        continue;
+                    }
+
+                    // Notification, e.g.
+                    // `{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{...},"subscription":3}}`
+                    if let Some(Value::Object(params)) = json.get_mut("params") {
+                        if let Some(subscription_id) = params.get("subscription").and_then(Value::as_u64) {
+                            let is_delivered = match (subscriptions.get(&subscription_id), params.remove("result")) {
+                                (Some(notification_sender), Some(result)) => notification_sender.send(result).is_ok(),
+                                (Some(_), None) => true,
+                                (None, _) => false,
+                            };
+                            // Unsubscribe on behalf of streams that were dropped without unsubscribing
+                            if !is_delivered {
+                                subscriptions.remove(&subscription_id);
+                                if let Some(Value::String(method)) = json.get("method") {
+                                    if let Some(operation) = method.strip_suffix("Notification") {
+                                        let (response_sender, _response_receiver) = oneshot::channel();
+                                        let _ = unsubscribe_sender.send((operation.to_string(), subscription_id, response_sender));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 use {
     crate::{
         rpc_config::{
-            RpcSignatureSubscribeConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+            RpcAccountInfoConfig, RpcBlockConfig, RpcBlockSubscribeFilter,
+            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcSubscriptionReplayConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter, RpcTransactionSubscribeConfig,
+            RpcTransactionSubscribeFilter,
         },
         rpc_response::{
-            Response as RpcResponse, RpcLogsResponse, RpcSignatureResult, SlotInfo, SlotUpdate,
+            Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     log::*,
@@ -14,13 +18,14 @@ use {
         value::Value::{Number, Object},
         Map, Value,
     },
-    solana_sdk::signature::Signature,
+    solana_account_decoder::UiAccount,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
     std::{
         marker::PhantomData,
         net::TcpStream,
         sync::{
             atomic::{AtomicBool, Ordering},
-            mpsc::{channel, Receiver},
+            mpsc::{channel, Receiver, Sender},
             Arc, RwLock,
         },
         thread::JoinHandle,
@@ -43,6 +48,12 @@ pub enum PubsubClientError {
 
     #[error("unexpected message format: {0}")]
     UnexpectedMessageError(String),
+
+    #[error("subscribe failed: {reason}")]
+    SubscribeFailed { reason: String, message: String },
+
+    #[error("connection closed: {0}")]
+    ConnectionClosed(String),
 }
 
 pub struct PubsubClientSubscription<T>
@@ -162,60 +173,51 @@ pub type SignatureSubscription = (
     PubsubClientSubscription<RpcResponse<RpcSignatureResult>>,
     Receiver<RpcResponse<RpcSignatureResult>>,
 );
+pub type AccountSubscription = (
+    PubsubClientSubscription<RpcResponse<UiAccount>>,
+    Receiver<RpcResponse<UiAccount>>,
+);
+pub type ProgramSubscription = (
+    PubsubClientSubscription<RpcResponse<RpcKeyedAccount>>,
+    Receiver<RpcResponse<RpcKeyedAccount>>,
+);
+pub type BlockSubscription = (
+    PubsubClientSubscription<RpcResponse<RpcBlockUpdate>>,
+    Receiver<RpcResponse<RpcBlockUpdate>>,
+);
+pub type TransactionSubscription = (
+    PubsubClientSubscription<RpcResponse<RpcTransactionUpdate>>,
+    Receiver<RpcResponse<RpcTransactionUpdate>>,
+);
+pub type VoteSubscription = (PubsubClientSubscription<RpcVote>, Receiver<RpcVote>);
+pub type RootSubscription = (PubsubClientSubscription<Slot>, Receiver<Slot>);
 
 pub struct PubsubClient {}
 
 impl PubsubClient {
-    pub fn logs_subscribe(
+    /// Subscribe with `body` and forward every notification to the returned receiver until the
+    /// subscription is shut down or the receiver is dropped
+    fn subscribe<T>(
         url: &str,
-        filter: RpcTransactionLogsFilter,
-        config: RpcTransactionLogsConfig,
-    ) -> Result<LogsSubscription, PubsubClientError> {
+        operation: &'static str,
+        body: String,
+    ) -> Result<(PubsubClientSubscription<T>, Receiver<T>), PubsubClientError>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
         let url = Url::parse(url)?;
         let (socket, _response) = connect(url)?;
         let (sender, receiver) = channel();
 
         let socket = Arc::new(RwLock::new(socket));
-        let socket_clone = socket.clone();
         let exit = Arc::new(AtomicBool::new(false));
-        let exit_clone = exit.clone();
-
-        let subscription_id =
-            PubsubClientSubscription::<RpcResponse<RpcLogsResponse>>::send_subscribe(
-                &socket_clone,
-                json!({
-                    "jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":[filter, config]
-                })
-                .to_string(),
-            )?;
-
-        let t_cleanup = std::thread::spawn(move || {
-            loop {
-                if exit_clone.load(Ordering::Relaxed) {
-                    break;
-                }
+        let subscription_id = PubsubClientSubscription::<T>::send_subscribe(&socket, body)?;
 
-                match PubsubClientSubscription::read_message(&socket_clone) {
-                    Ok(message) => match sender.send(message) {
-                        Ok(_) => (),
-                        Err(err) => {
-                            info!("receive error: {:?}", err);
-                            break;
-                        }
-                    },
-                    Err(err) => {
-                        info!("receive error: {:?}", err);
-                        break;
-                    }
-                }
-            }
-
-            info!("websocket - exited receive loop");
-        });
+        let t_cleanup = Self::cleanup_with_sender(exit.clone(), socket.clone(), sender);
 
         let result = PubsubClientSubscription {
             message_type: PhantomData,
-            operation: "logs",
+            operation,
             socket,
             subscription_id,
             t_cleanup: Some(t_cleanup),
@@ -225,29 +227,21 @@ impl PubsubClient {
         Ok((result, receiver))
     }
 
-    pub fn slot_subscribe(url: &str) -> Result<SlotsSubscription, PubsubClientError> {
-        let url = Url::parse(url)?;
-        let (socket, _response) = connect(url)?;
-        let (sender, receiver) = channel::<SlotInfo>();
-
-        let socket = Arc::new(RwLock::new(socket));
-        let socket_clone = socket.clone();
-        let exit = Arc::new(AtomicBool::new(false));
-        let exit_clone = exit.clone();
-        let subscription_id = PubsubClientSubscription::<SlotInfo>::send_subscribe(
-            &socket_clone,
-            json!({
-                "jsonrpc":"2.0","id":1,"method":"slotSubscribe","params":[]
-            })
-            .to_string(),
-        )?;
-
-        let t_cleanup = std::thread::spawn(move || {
+    fn cleanup_with_sender<T>(
+        exit: Arc<AtomicBool>,
+        socket: Arc<RwLock<WebSocket<MaybeTlsStream<TcpStream>>>>,
+        sender: Sender<T>,
+    ) -> JoinHandle<()>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        std::thread::spawn(move || {
             loop {
-                if exit_clone.load(Ordering::Relaxed) {
+                if exit.load(Ordering::Relaxed) {
                     break;
                 }
-                match PubsubClientSubscription::read_message(&socket_clone) {
+
+                match PubsubClientSubscription::read_message(&socket) {
                     Ok(message) => match sender.send(message) {
                         Ok(_) => (),
                         Err(err) => {
@@ -263,85 +257,186 @@ impl PubsubClient {
             }
 
             info!("websocket - exited receive loop");
-        });
+        })
+    }
 
-        let result = PubsubClientSubscription {
-            message_type: PhantomData,
-            operation: "slot",
-            socket,
-            subscription_id,
-            t_cleanup: Some(t_cleanup),
-            exit,
-        };
+    pub fn account_subscribe(
+        url: &str,
+        pubkey: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> Result<AccountSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "account",
+            json!({
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"accountSubscribe",
+                "params":[
+                    pubkey.to_string(),
+                    config
+                ]
+            })
+            .to_string(),
+        )
+    }
 
-        Ok((result, receiver))
+    pub fn program_subscribe(
+        url: &str,
+        pubkey: &Pubkey,
+        config: Option<RpcProgramAccountsConfig>,
+    ) -> Result<ProgramSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "program",
+            json!({
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"programSubscribe",
+                "params":[
+                    pubkey.to_string(),
+                    config
+                ]
+            })
+            .to_string(),
+        )
     }
 
-    pub fn signature_subscribe(
+    pub fn vote_subscribe(url: &str) -> Result<VoteSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "vote",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"voteSubscribe","params":[]
+            })
+            .to_string(),
+        )
+    }
+
+    pub fn root_subscribe(url: &str) -> Result<RootSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "root",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"rootSubscribe","params":[]
+            })
+            .to_string(),
+        )
+    }
+
+    /// Subscribes to roots, first receiving the buffered notifications of the roots set since
+    /// `from_slot`
+    pub fn root_subscribe_from(
         url: &str,
-        signature: &Signature,
-        config: Option<RpcSignatureSubscribeConfig>,
-    ) -> Result<SignatureSubscription, PubsubClientError> {
-        let url = Url::parse(url)?;
-        let (socket, _response) = connect(url)?;
-        let (sender, receiver) = channel();
+        from_slot: Slot,
+    ) -> Result<RootSubscription, PubsubClientError> {
+        let config = RpcSubscriptionReplayConfig {
+            from_slot: Some(from_slot),
+        };
+        Self::subscribe(
+            url,
+            "root",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"rootSubscribe","params":[config]
+            })
+            .to_string(),
+        )
+    }
 
-        let socket = Arc::new(RwLock::new(socket));
-        let socket_clone = socket.clone();
-        let exit = Arc::new(AtomicBool::new(false));
-        let exit_clone = exit.clone();
-        let body = json!({
-            "jsonrpc":"2.0",
-            "id":1,
-            "method":"signatureSubscribe",
-            "params":[
-                signature.to_string(),
-                config
-            ]
-        })
-        .to_string();
-        let subscription_id =
-            PubsubClientSubscription::<RpcResponse<RpcSignatureResult>>::send_subscribe(
-                &socket_clone,
-                body,
-            )?;
-
-        let t_cleanup = std::thread::spawn(move || {
-            loop {
-                if exit_clone.load(Ordering::Relaxed) {
-                    break;
-                }
+    pub fn logs_subscribe(
+        url: &str,
+        filter: RpcTransactionLogsFilter,
+        config: RpcTransactionLogsConfig,
+    ) -> Result<LogsSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "logs",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":[filter, config]
+            })
+            .to_string(),
+        )
+    }
 
-                let message: Result<RpcResponse<RpcSignatureResult>, PubsubClientError> =
-                    PubsubClientSubscription::read_message(&socket_clone);
+    pub fn block_subscribe(
+        url: &str,
+        filter: RpcBlockSubscribeFilter,
+        config: Option<RpcBlockConfig>,
+    ) -> Result<BlockSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "block",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"blockSubscribe","params":[filter, config]
+            })
+            .to_string(),
+        )
+    }
 
-                if let Ok(msg) = message {
-                    match sender.send(msg.clone()) {
-                        Ok(_) => (),
-                        Err(err) => {
-                            info!("receive error: {:?}", err);
-                            break;
-                        }
-                    }
-                } else {
-                    info!("receive error: {:?}", message);
-                    break;
-                }
-            }
+    pub fn transaction_subscribe(
+        url: &str,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> Result<TransactionSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "transaction",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"transactionSubscribe","params":[filter, config]
+            })
+            .to_string(),
+        )
+    }
 
-            info!("websocket - exited receive loop");
-        });
+    pub fn slot_subscribe(url: &str) -> Result<SlotsSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "slot",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"slotSubscribe","params":[]
+            })
+            .to_string(),
+        )
+    }
 
-        let result = PubsubClientSubscription {
-            message_type: PhantomData,
-            operation: "signature",
-            socket,
-            subscription_id,
-            t_cleanup: Some(t_cleanup),
-            exit,
+    /// Subscribes to slots, first receiving the buffered notifications of the slots processed
+    /// since `from_slot`
+    pub fn slot_subscribe_from(
+        url: &str,
+        from_slot: Slot,
+    ) -> Result<SlotsSubscription, PubsubClientError> {
+        let config = RpcSubscriptionReplayConfig {
+            from_slot: Some(from_slot),
         };
+        Self::subscribe(
+            url,
+            "slot",
+            json!({
+                "jsonrpc":"2.0","id":1,"method":"slotSubscribe","params":[config]
+            })
+            .to_string(),
+        )
+    }
 
-        Ok((result, receiver))
+    pub fn signature_subscribe(
+        url: &str,
+        signature: &Signature,
+        config: Option<RpcSignatureSubscribeConfig>,
+    ) -> Result<SignatureSubscription, PubsubClientError> {
+        Self::subscribe(
+            url,
+            "signature",
+            json!({
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"signatureSubscribe",
+                "params":[
+                    signature.to_string(),
+                    config
+                ]
+            })
+            .to_string(),
+        )
     }
 
     pub fn slot_updates_subscribe(
@@ -0,0 +1,377 @@
+//! [`RpcSender`]s that record a session with a node to a file and replay it.
+//!
+//! [`RecordingSender`] wraps another sender, usually an [`HttpSender`], and
+//! appends every request it sends, together with the node's response, to a
+//! recording. [`ReplaySender`] later serves those responses back without any
+//! network access, so that client code can be tested deterministically
+//! against responses captured from a real cluster.
+//!
+//! A recording is a [JSON Lines] file with one [`RecordedExchange`] per line.
+//!
+//! [JSON Lines]: https://jsonlines.org
+
+use {
+    crate::{
+        client_error::{ClientErrorKind, Result},
+        http_sender::HttpSender,
+        rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
+        rpc_sender::RpcSender,
+    },
+    async_trait::async_trait,
+    serde_json::Value,
+    std::{
+        collections::{HashMap, VecDeque},
+        fs::{File, OpenOptions},
+        io::{self, BufRead, BufReader, Write},
+        path::Path,
+        sync::{
+            mpsc::{channel, Receiver, Sender},
+            Mutex,
+        },
+        thread::{self, JoinHandle},
+    },
+};
+
+/// A request and the node's response to it, as stored in a recording.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedExchange {
+    pub method: String,
+    pub params: Value,
+    pub response: RecordedResponse,
+}
+
+/// The outcome of a recorded request.
+///
+/// Only responses from the node are recorded; transport errors, such as a
+/// failure to connect, are returned to the caller but never written out.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordedResponse {
+    Result(Value),
+    Error {
+        code: i64,
+        message: String,
+        data: RpcResponseErrorData,
+    },
+}
+
+/// An [`RpcSender`] that records the requests sent through another sender,
+/// and the responses to them, for later replay by a [`ReplaySender`].
+///
+/// Exchanges are written out by a separate thread, so that sending a request
+/// never waits on the recording. A failure to record an exchange is logged
+/// and does not fail the request.
+pub struct RecordingSender<T: RpcSender> {
+    sender: T,
+    recording: Option<Mutex<Sender<RecordedExchange>>>,
+    recorder: Option<JoinHandle<()>>,
+}
+
+impl RecordingSender<HttpSender> {
+    /// Create an HTTP RPC sender that records to the file at `path`.
+    ///
+    /// The URL is an HTTP URL, usually for port 8899. Exchanges are appended
+    /// if the file already exists.
+    pub fn new<P: AsRef<Path>>(url: String, path: P) -> io::Result<Self> {
+        Self::new_with_sender(HttpSender::new(url), path)
+    }
+}
+
+impl<T: RpcSender> RecordingSender<T> {
+    /// Record the requests sent through `sender` to the file at `path`.
+    ///
+    /// Exchanges are appended if the file already exists.
+    pub fn new_with_sender<P: AsRef<Path>>(sender: T, path: P) -> io::Result<Self> {
+        let recording = OpenOptions::new().create(true).append(true).open(path)?;
+        let (exchange_sender, exchange_receiver) = channel();
+        let recorder = thread::Builder::new()
+            .name("rpc-recorder".to_string())
+            .spawn(move || Self::run_recorder(recording, exchange_receiver))?;
+        Ok(Self {
+            sender,
+            recording: Some(Mutex::new(exchange_sender)),
+            recorder: Some(recorder),
+        })
+    }
+
+    fn run_recorder(mut recording: File, exchange_receiver: Receiver<RecordedExchange>) {
+        for exchange in exchange_receiver {
+            let result = serde_json::to_string(&exchange)
+                .map_err(io::Error::from)
+                .and_then(|mut line| {
+                    line.push('\n');
+                    recording.write_all(line.as_bytes())?;
+                    recording.flush()
+                });
+            if let Err(err) = result {
+                warn!("Failed to record {} request: {}", exchange.method, err);
+            }
+        }
+    }
+
+    fn record(&self, exchange: RecordedExchange) {
+        if let Some(recording) = &self.recording {
+            // The recorder only stops once the sender is dropped
+            let _ = recording.lock().unwrap().send(exchange);
+        }
+    }
+}
+
+impl<T: RpcSender> Drop for RecordingSender<T> {
+    /// Waits for the pending exchanges to be recorded
+    fn drop(&mut self) {
+        drop(self.recording.take());
+        if let Some(recorder) = self.recorder.take() {
+            if recorder.join().is_err() {
+                warn!("RPC recorder thread panicked");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T: RpcSender + Send + Sync> RpcSender for RecordingSender<T> {
+    async fn send(&self, request: RpcRequest, params: Value) -> Result<Value> {
+        let result = self.sender.send(request, params.clone()).await;
+
+        let response = match &result {
+            Ok(value) => RecordedResponse::Result(value.clone()),
+            Err(err) => match err.kind() {
+                ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code,
+                    message,
+                    data,
+                }) => RecordedResponse::Error {
+                    code: *code,
+                    message: message.clone(),
+                    data: data.clone(),
+                },
+                _ => return result,
+            },
+        };
+        self.record(RecordedExchange {
+            method: request.to_string(),
+            params,
+            response,
+        });
+        result
+    }
+}
+
+/// An [`RpcSender`] that serves the responses of a recording made by a
+/// [`RecordingSender`].
+///
+/// Requests are matched by method and parameters. When the same request was
+/// recorded several times, as when polling for a signature status, the
+/// responses are served in the order they were recorded, and the last of
+/// them is repeated once they run out. A request that is not in the
+/// recording fails with an [`RpcError::RpcRequestError`].
+pub struct ReplaySender {
+    responses: Mutex<HashMap<(String, String), VecDeque<RecordedResponse>>>,
+}
+
+impl ReplaySender {
+    /// Load the recording in the file at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut exchanges = vec![];
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            exchanges.push(serde_json::from_str(&line)?);
+        }
+        Ok(Self::new_with_exchanges(exchanges))
+    }
+
+    pub fn new_with_exchanges(exchanges: Vec<RecordedExchange>) -> Self {
+        let mut responses = HashMap::<_, VecDeque<_>>::new();
+        for RecordedExchange {
+            method,
+            params,
+            response,
+        } in exchanges
+        {
+            responses
+                .entry((method, params.to_string()))
+                .or_default()
+                .push_back(response);
+        }
+        Self {
+            responses: Mutex::new(responses),
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for ReplaySender {
+    async fn send(&self, request: RpcRequest, params: Value) -> Result<Value> {
+        let key = (request.to_string(), params.to_string());
+        let mut responses = self.responses.lock().unwrap();
+        let recorded = responses.get_mut(&key).ok_or_else(|| {
+            RpcError::RpcRequestError(format!(
+                "request not found in recording: {} {}",
+                key.0, key.1
+            ))
+        })?;
+        let response = if recorded.len() > 1 {
+            recorded.pop_front().unwrap()
+        } else {
+            recorded[0].clone()
+        };
+
+        match response {
+            RecordedResponse::Result(value) => Ok(value),
+            RecordedResponse::Error {
+                code,
+                message,
+                data,
+            } => Err(RpcError::RpcResponseError {
+                code,
+                message,
+                data,
+            }
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            mock_sender::MockSender,
+            rpc_client::{RpcClient, RpcClientConfig},
+            rpc_custom_error,
+        },
+        serde_json::json,
+        solana_sdk::{pubkey::Pubkey, signature::Signature},
+    };
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.jsonl");
+        let pubkey = Pubkey::new_unique();
+
+        let rpc_client = RpcClient::new_sender(
+            RecordingSender::new_with_sender(MockSender::new("succeeds".to_string()), &path)
+                .unwrap(),
+            RpcClientConfig::default(),
+        );
+        let balance = rpc_client.get_balance(&pubkey).unwrap();
+        let slot = rpc_client.get_slot().unwrap();
+        drop(rpc_client);
+
+        let rpc_client = RpcClient::new_sender(
+            ReplaySender::new(&path).unwrap(),
+            RpcClientConfig::default(),
+        );
+        assert_eq!(rpc_client.get_slot().unwrap(), slot);
+        assert_eq!(rpc_client.get_balance(&pubkey).unwrap(), balance);
+        // Responses are repeated once a request's recorded responses run out
+        assert_eq!(rpc_client.get_balance(&pubkey).unwrap(), balance);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_recording_failure() {
+        // Every write to /dev/full fails, which must not fail the requests
+        let rpc_client = RpcClient::new_sender(
+            RecordingSender::new_with_sender(MockSender::new("succeeds".to_string()), "/dev/full")
+                .unwrap(),
+            RpcClientConfig::default(),
+        );
+        assert!(rpc_client.get_slot().is_ok());
+        assert!(rpc_client.get_balance(&Pubkey::new_unique()).is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_replay_mismatch() {
+        let pubkey = Pubkey::new_unique();
+        let sender = ReplaySender::new_with_exchanges(vec![RecordedExchange {
+            method: "getBalance".to_string(),
+            params: json!([pubkey.to_string()]),
+            response: RecordedResponse::Result(json!({"context": {"slot": 1}, "value": 42})),
+        }]);
+
+        assert_eq!(
+            sender
+                .send(RpcRequest::GetBalance, json!([pubkey.to_string()]))
+                .await
+                .unwrap(),
+            json!({"context": {"slot": 1}, "value": 42})
+        );
+
+        let params = json!([Pubkey::new_unique().to_string()]);
+        let err = sender
+            .send(RpcRequest::GetBalance, params.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ClientErrorKind::RpcError(RpcError::RpcRequestError(message))
+                if *message == format!("request not found in recording: getBalance {}", params)
+        ));
+        assert!(sender.send(RpcRequest::GetSlot, Value::Null).await.is_err());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_replay_in_order() {
+        let params = json!([[Signature::default().to_string()]]);
+        let statuses = |value: Value| RecordedExchange {
+            method: "getSignatureStatuses".to_string(),
+            params: params.clone(),
+            response: RecordedResponse::Result(value),
+        };
+        let sender = ReplaySender::new_with_exchanges(vec![
+            statuses(json!(1)),
+            statuses(json!(2)),
+            statuses(json!(3)),
+        ]);
+
+        let mut responses = vec![];
+        for _ in 0..4 {
+            responses.push(
+                sender
+                    .send(RpcRequest::GetSignatureStatuses, params.clone())
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert_eq!(responses, vec![json!(1), json!(2), json!(3), json!(3)]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_replay_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.jsonl");
+        let exchange = RecordedExchange {
+            method: "getSnapshotSlot".to_string(),
+            params: Value::Null,
+            response: RecordedResponse::Error {
+                code: rpc_custom_error::JSON_RPC_SERVER_ERROR_NO_SNAPSHOT,
+                message: "No snapshot".to_string(),
+                data: RpcResponseErrorData::Empty,
+            },
+        };
+        std::fs::write(
+            &path,
+            format!("{}\n", serde_json::to_string(&exchange).unwrap()),
+        )
+        .unwrap();
+
+        let sender = ReplaySender::new(&path).unwrap();
+        let err = sender
+            .send(RpcRequest::GetSnapshotSlot, Value::Null)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if *code == rpc_custom_error::JSON_RPC_SERVER_ERROR_NO_SNAPSHOT
+        ));
+    }
+}
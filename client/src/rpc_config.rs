@@ -42,6 +42,10 @@ pub struct RpcSimulateTransactionConfig {
     pub commitment: Option<CommitmentConfig>,
     pub encoding: Option<UiTransactionEncoding>,
     pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
+    #[serde(default)]
+    pub inner_instructions: bool,
+    #[serde(default)]
+    pub token_balances: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -152,6 +156,34 @@ pub struct RpcProgramAccountsConfig {
     pub with_context: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPaginatedConfig {
+    pub filters: Option<Vec<RpcFilterType>>,
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>, // Opaque cursor returned with the previous page
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTokenHoldersSort {
+    Address, // Ascending token account address
+    Balance, // Descending balance, then ascending token account address
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenHoldersConfig {
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub exclude_zero_balance: Option<bool>,
+    pub sort: Option<RpcTokenHoldersSort>, // `Address` if `None`
+    pub limit: Option<usize>,
+    pub cursor: Option<String>, // Opaque cursor returned with the previous page
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcTransactionLogsFilter {
@@ -160,11 +192,41 @@ pub enum RpcTransactionLogsFilter {
     Mentions(Vec<String>), // base58-encoded list of addresses
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcBlockSubscribeFilter {
+    All,
+    MentionsAccountOrProgram(String), // base58-encoded address
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeFilter {
+    /// Include vote transactions, excluded by default
+    pub vote: Option<bool>,
+    /// Only include transactions that mention any of these addresses
+    pub account_include: Option<Vec<String>>,
+    /// Exclude transactions that mention any of these addresses
+    pub account_exclude: Option<Vec<String>>,
+    /// Only include transactions that mention all of these addresses
+    pub account_required: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeConfig {
+    pub encoding: Option<UiTransactionEncoding>,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcTransactionLogsConfig {
     #[serde(flatten)]
     pub commitment: Option<CommitmentConfig>,
+    /// Replay the buffered notifications from this slot before sending new ones
+    pub from_slot: Option<Slot>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -180,6 +242,15 @@ pub struct RpcSignatureSubscribeConfig {
     #[serde(flatten)]
     pub commitment: Option<CommitmentConfig>,
     pub enable_received_notification: Option<bool>,
+    /// Notify immediately if the signature was processed at or after this slot
+    pub from_slot: Option<Slot>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSubscriptionReplayConfig {
+    /// Replay the buffered notifications from this slot before sending new ones
+    pub from_slot: Option<Slot>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
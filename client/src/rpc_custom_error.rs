@@ -19,6 +19,13 @@ pub const JSON_RPC_SERVER_ERROR_KEY_EXCLUDED_FROM_SECONDARY_INDEX: i64 = -32010;
 pub const JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE: i64 = -32011;
 pub const JSON_RPC_SCAN_ERROR: i64 = -32012;
 pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013;
+pub const JSON_RPC_SERVER_ERROR_CURSOR_SLOT_NOT_REACHED: i64 = -32014;
+pub const JSON_RPC_SERVER_ERROR_RATE_LIMITED: i64 = -32015;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE: i64 = -32016;
+pub const JSON_RPC_SERVER_ERROR_REPLAY_SLOT_NOT_AVAILABLE: i64 = -32017;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_PROOFS_NOT_AVAILABLE: i64 = -32018;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNTS_HASH_NOT_PUBLISHED: i64 = -32019;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_WRITTEN_SINCE_ACCOUNTS_HASH: i64 = -32020;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -54,6 +61,31 @@ pub enum RpcCustomError {
     ScanError { message: String },
     #[error("TransactionSignatureLenMismatch")]
     TransactionSignatureLenMismatch,
+    #[error("CursorSlotNotReached")]
+    CursorSlotNotReached {
+        cursor_slot: Slot,
+        context_slot: Slot,
+    },
+    #[error("RateLimited")]
+    RateLimited {
+        method: String,
+        retry_after_ms: Option<u64>,
+    },
+    #[error("AccountHistoryNotAvailable")]
+    AccountHistoryNotAvailable,
+    #[error("AccountHistoryNotRecorded")]
+    AccountHistoryNotRecorded { slot: Slot },
+    #[error("ReplaySlotNotAvailable")]
+    ReplaySlotNotAvailable {
+        slot: Slot,
+        first_available_slot: Option<Slot>,
+    },
+    #[error("AccountProofsNotAvailable")]
+    AccountProofsNotAvailable,
+    #[error("AccountsHashNotPublished")]
+    AccountsHashNotPublished,
+    #[error("AccountWrittenSinceAccountsHash")]
+    AccountWrittenSinceAccountsHash { slot: Slot },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +94,12 @@ pub struct NodeUnhealthyErrorData {
     pub num_slots_behind: Option<Slot>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitedErrorData {
+    pub retry_after_ms: Option<u64>,
+}
+
 impl From<RpcCustomError> for Error {
     fn from(e: RpcCustomError) -> Self {
         match e {
@@ -161,6 +199,80 @@ impl From<RpcCustomError> for Error {
                 message: "Transaction signature length mismatch".to_string(),
                 data: None,
             },
+            RpcCustomError::CursorSlotNotReached {
+                cursor_slot,
+                context_slot,
+            } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_CURSOR_SLOT_NOT_REACHED),
+                message: format!(
+                    "Cursor was issued at slot {}, but this node is at slot {}",
+                    cursor_slot, context_slot
+                ),
+                data: None,
+            },
+            RpcCustomError::RateLimited {
+                method,
+                retry_after_ms,
+            } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_RATE_LIMITED),
+                message: if let Some(retry_after_ms) = retry_after_ms {
+                    format!(
+                        "Too many requests for {}, retry in {}ms",
+                        method, retry_after_ms
+                    )
+                } else {
+                    format!("Too many concurrent requests for {}", method)
+                },
+                data: Some(serde_json::json!(RateLimitedErrorData { retry_after_ms })),
+            },
+            RpcCustomError::AccountHistoryNotAvailable => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE),
+                message: "Account history is not available from this node".to_string(),
+                data: None,
+            },
+            RpcCustomError::AccountHistoryNotRecorded { slot } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE),
+                message: format!(
+                    "Account history is not available at slot {}: this node didn't record the account's state then",
+                    slot
+                ),
+                data: None,
+            },
+            RpcCustomError::ReplaySlotNotAvailable {
+                slot,
+                first_available_slot,
+            } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_REPLAY_SLOT_NOT_AVAILABLE),
+                message: match first_available_slot {
+                    Some(first_available_slot) => format!(
+                        "Notifications from slot {} are no longer available for replay. First available slot: {}",
+                        slot, first_available_slot
+                    ),
+                    None => format!("Notifications from slot {} are not available for replay", slot),
+                },
+                data: None,
+            },
+            RpcCustomError::AccountProofsNotAvailable => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_PROOFS_NOT_AVAILABLE),
+                message: "Account proofs are not available from this node".to_string(),
+                data: None,
+            },
+            RpcCustomError::AccountsHashNotPublished => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNTS_HASH_NOT_PUBLISHED),
+                message: "No accounts hash to prove accounts against has been published to gossip yet"
+                    .to_string(),
+                data: None,
+            },
+            RpcCustomError::AccountWrittenSinceAccountsHash { slot } => Self {
+                code: ErrorCode::ServerError(
+                    JSON_RPC_SERVER_ERROR_ACCOUNT_WRITTEN_SINCE_ACCOUNTS_HASH,
+                ),
+                message: format!(
+                    "Account has been written since the accounts hash of slot {}, retry after the next accounts hash",
+                    slot
+                ),
+                data: None,
+            },
         }
     }
 }
@@ -1,10 +1,21 @@
-use thiserror::Error;
+use {
+    solana_account_decoder::parse_token::{get_token_account_state, UiAccountState},
+    solana_sdk::account::{AccountSharedData, ReadableAccount},
+    thiserror::Error,
+};
+
+const MAX_DATA_SIZE: usize = 128;
+const MAX_DATA_BASE58_SIZE: usize = 128;
+const MAX_DATA_BASE64_SIZE: usize = 172;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcFilterType {
     DataSize(u64),
     Memcmp(Memcmp),
+    Lamports(LamportsRange),
+    ZeroedData,
+    TokenAccountState(UiAccountState),
 }
 
 impl RpcFilterType {
@@ -13,11 +24,10 @@ impl RpcFilterType {
             RpcFilterType::DataSize(_) => Ok(()),
             RpcFilterType::Memcmp(compare) => {
                 let encoding = compare.encoding.as_ref().unwrap_or(&MemcmpEncoding::Binary);
+                let MemcmpEncodedBytes::Binary(bytes) = &compare.bytes;
                 match encoding {
-                    MemcmpEncoding::Binary => {
-                        let MemcmpEncodedBytes::Binary(bytes) = &compare.bytes;
-
-                        if bytes.len() > 128 {
+                    MemcmpEncoding::Binary | MemcmpEncoding::Base58 => {
+                        if bytes.len() > MAX_DATA_BASE58_SIZE {
                             Err(RpcFilterError::Base58DataTooLarge)
                         } else {
                             bs58::decode(&bytes)
@@ -26,7 +36,42 @@ impl RpcFilterType {
                                 .map_err(|e| e.into())
                         }
                     }
+                    MemcmpEncoding::Base64 => {
+                        if bytes.len() > MAX_DATA_BASE64_SIZE {
+                            return Err(RpcFilterError::Base64DataTooLarge);
+                        }
+                        let bytes = base64::decode(bytes)?;
+                        if bytes.len() > MAX_DATA_SIZE {
+                            Err(RpcFilterError::Base64DataTooLarge)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                }
+            }
+            RpcFilterType::Lamports(range) => match (range.min, range.max) {
+                (Some(min), Some(max)) if min > max => Err(RpcFilterError::InvalidLamportsRange),
+                _ => Ok(()),
+            },
+            RpcFilterType::ZeroedData => Ok(()),
+            RpcFilterType::TokenAccountState(state) => match state {
+                UiAccountState::Uninitialized => {
+                    Err(RpcFilterError::UninitializedTokenAccountState)
                 }
+                UiAccountState::Initialized | UiAccountState::Frozen => Ok(()),
+            },
+        }
+    }
+
+    /// Whether `account` meets the filter criteria
+    pub fn allows(&self, account: &AccountSharedData) -> bool {
+        match self {
+            RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
+            RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
+            RpcFilterType::Lamports(range) => range.contains(account.lamports()),
+            RpcFilterType::ZeroedData => account.data().iter().all(|byte| *byte == 0),
+            RpcFilterType::TokenAccountState(state) => {
+                get_token_account_state(account.data()) == Some(*state)
             }
         }
     }
@@ -38,12 +83,23 @@ pub enum RpcFilterError {
     DecodeError(#[from] bs58::decode::Error),
     #[error("encoded binary (base 58) data should be less than 129 bytes")]
     Base58DataTooLarge,
+    #[error("base64 decode error")]
+    Base64DecodeError(#[from] base64::DecodeError),
+    #[error("decoded binary (base 64) data should be less than 129 bytes")]
+    Base64DataTooLarge,
+    #[error("lamports range minimum should not exceed its maximum")]
+    InvalidLamportsRange,
+    #[error("token account state should be initialized or frozen")]
+    UninitializedTokenAccountState,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum MemcmpEncoding {
+    /// Base-58, kept for compatibility with existing clients
     Binary,
+    Base58,
+    Base64,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -63,14 +119,18 @@ pub struct Memcmp {
 }
 
 impl Memcmp {
+    /// The bytes to match, or `None` if they cannot be decoded with the specified encoding
+    pub fn bytes(&self) -> Option<Vec<u8>> {
+        let MemcmpEncodedBytes::Binary(bytes) = &self.bytes;
+        match self.encoding.as_ref().unwrap_or(&MemcmpEncoding::Binary) {
+            MemcmpEncoding::Binary | MemcmpEncoding::Base58 => bs58::decode(bytes).into_vec().ok(),
+            MemcmpEncoding::Base64 => base64::decode(bytes).ok(),
+        }
+    }
+
     pub fn bytes_match(&self, data: &[u8]) -> bool {
-        match &self.bytes {
-            MemcmpEncodedBytes::Binary(bytes) => {
-                let bytes = bs58::decode(bytes).into_vec();
-                if bytes.is_err() {
-                    return false;
-                }
-                let bytes = bytes.unwrap();
+        match self.bytes() {
+            Some(bytes) => {
                 if self.offset > data.len() {
                     return false;
                 }
@@ -79,13 +139,31 @@ impl Memcmp {
                 }
                 data[self.offset..self.offset + bytes.len()] == bytes[..]
             }
+            None => false,
         }
     }
 }
 
+/// Inclusive bounds on an account's lamports; a missing bound is unconstrained
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LamportsRange {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl LamportsRange {
+    pub fn contains(&self, lamports: u64) -> bool {
+        self.min.map(|min| lamports >= min).unwrap_or(true)
+            && self.max.map(|max| lamports <= max).unwrap_or(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{account::WritableAccount, pubkey::Pubkey},
+    };
 
     #[test]
     fn test_bytes_match() {
@@ -146,6 +224,93 @@ mod tests {
             encoding: None,
         }
         .bytes_match(&data));
+
+        // Base-64 match of data succeeds
+        assert!(Memcmp {
+            offset: 1,
+            bytes: MemcmpEncodedBytes::Binary(base64::encode(vec![2, 3, 4])),
+            encoding: Some(MemcmpEncoding::Base64),
+        }
+        .bytes_match(&data));
+
+        // Invalid base-64 fails
+        assert!(!Memcmp {
+            offset: 0,
+            bytes: MemcmpEncodedBytes::Binary("III".to_string()),
+            encoding: Some(MemcmpEncoding::Base64),
+        }
+        .bytes_match(&data));
+    }
+
+    #[test]
+    fn test_allows() {
+        let account = AccountSharedData::new(42, 3, &Pubkey::default());
+        assert!(RpcFilterType::DataSize(3).allows(&account));
+        assert!(!RpcFilterType::DataSize(4).allows(&account));
+
+        assert!(RpcFilterType::Lamports(LamportsRange::default()).allows(&account));
+        assert!(RpcFilterType::Lamports(LamportsRange {
+            min: Some(42),
+            max: Some(42),
+        })
+        .allows(&account));
+        assert!(!RpcFilterType::Lamports(LamportsRange {
+            min: Some(43),
+            max: None,
+        })
+        .allows(&account));
+        assert!(!RpcFilterType::Lamports(LamportsRange {
+            min: None,
+            max: Some(41),
+        })
+        .allows(&account));
+
+        assert!(RpcFilterType::ZeroedData.allows(&account));
+        let mut nonzero_account = account.clone();
+        nonzero_account.data_as_mut_slice()[2] = 1;
+        assert!(!RpcFilterType::ZeroedData.allows(&nonzero_account));
+
+        let initialized = RpcFilterType::TokenAccountState(UiAccountState::Initialized);
+        let frozen = RpcFilterType::TokenAccountState(UiAccountState::Frozen);
+        assert!(!initialized.allows(&account));
+        assert!(!frozen.allows(&account));
+        let mut token_account = AccountSharedData::new(42, 165, &Pubkey::default());
+        // spl_token::state::Account::state
+        token_account.data_as_mut_slice()[108] = 1;
+        assert!(initialized.allows(&token_account));
+        assert!(!frozen.allows(&token_account));
+        token_account.data_as_mut_slice()[108] = 2;
+        assert!(!initialized.allows(&token_account));
+        assert!(frozen.allows(&token_account));
+    }
+
+    #[test]
+    fn test_filter_serde() {
+        let filters: Vec<RpcFilterType> = serde_json::from_str(
+            r#"[
+                {"memcmp": {"offset": 4, "bytes": "AQID", "encoding": "base64"}},
+                {"lamports": {"min": 1}},
+                "zeroedData",
+                {"tokenAccountState": "frozen"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filters,
+            vec![
+                RpcFilterType::Memcmp(Memcmp {
+                    offset: 4,
+                    bytes: MemcmpEncodedBytes::Binary("AQID".to_string()),
+                    encoding: Some(MemcmpEncoding::Base64),
+                }),
+                RpcFilterType::Lamports(LamportsRange {
+                    min: Some(1),
+                    max: None,
+                }),
+                RpcFilterType::ZeroedData,
+                RpcFilterType::TokenAccountState(UiAccountState::Frozen),
+            ]
+        );
     }
 
     #[test]
@@ -178,5 +343,59 @@ mod tests {
             .verify(),
             Err(RpcFilterError::Base58DataTooLarge)
         );
+
+        let base64_bytes = base64::encode(vec![1; MAX_DATA_SIZE]);
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Binary(base64_bytes),
+                encoding: Some(MemcmpEncoding::Base64),
+            })
+            .verify(),
+            Ok(())
+        );
+
+        let base64_bytes = base64::encode(vec![1; MAX_DATA_SIZE + 1]);
+        assert_eq!(
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Binary(base64_bytes),
+                encoding: Some(MemcmpEncoding::Base64),
+            })
+            .verify(),
+            Err(RpcFilterError::Base64DataTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_verify_lamports() {
+        assert_eq!(
+            RpcFilterType::Lamports(LamportsRange {
+                min: Some(2),
+                max: Some(2),
+            })
+            .verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::Lamports(LamportsRange {
+                min: Some(3),
+                max: Some(2),
+            })
+            .verify(),
+            Err(RpcFilterError::InvalidLamportsRange)
+        );
+    }
+
+    #[test]
+    fn test_verify_token_account_state() {
+        assert_eq!(
+            RpcFilterType::TokenAccountState(UiAccountState::Frozen).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::TokenAccountState(UiAccountState::Uninitialized).verify(),
+            Err(RpcFilterError::UninitializedTokenAccountState)
+        );
     }
 }
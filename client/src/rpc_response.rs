@@ -2,16 +2,21 @@ use {
     crate::client_error,
     solana_account_decoder::{parse_token::UiTokenAmount, UiAccount},
     solana_sdk::{
+        account::Account,
+        account_proof::{self, MerkleProof, MerkleProofLevel},
         clock::{Epoch, Slot, UnixTimestamp},
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         inflation::Inflation,
+        pubkey::Pubkey,
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus,
+        ConfirmedTransactionStatusWithSignature, EncodedTransactionWithStatusMeta,
+        TransactionConfirmationStatus, UiConfirmedBlock, UiInnerInstructions,
+        UiTransactionReturnData, UiTransactionTokenBalance,
     },
-    std::{collections::HashMap, fmt, net::SocketAddr},
+    std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
 };
 
 pub type RpcResult<T> = client_error::Result<Response<T>>;
@@ -123,6 +128,14 @@ pub struct RpcKeyedAccount {
     pub account: UiAccount,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPage {
+    pub accounts: Vec<RpcKeyedAccount>,
+    /// Opaque cursor to pass in the next request, `None` once the scan is complete
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub struct SlotInfo {
     pub slot: Slot,
@@ -130,6 +143,14 @@ pub struct SlotInfo {
     pub root: Slot,
 }
 
+/// A more human-friendly version of Vote, with the bank state signature base58 encoded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RpcVote {
+    pub slots: Vec<Slot>,
+    pub hash: String,
+    pub timestamp: Option<UnixTimestamp>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SlotTransactionStats {
@@ -204,6 +225,28 @@ pub struct RpcLogsResponse {
     pub logs: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcBlockUpdateError {
+    BlockStoreError,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockUpdate {
+    pub slot: Slot,
+    pub block: Option<UiConfirmedBlock>,
+    pub err: Option<RpcBlockUpdateError>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionUpdate {
+    pub signature: String, // Signature as base58 string
+    pub transaction: EncodedTransactionWithStatusMeta,
+    pub block_time: Option<UnixTimestamp>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessedSignatureResult {
@@ -336,6 +379,14 @@ pub struct RpcSimulateTransactionResult {
     pub logs: Option<Vec<String>>,
     pub accounts: Option<Vec<Option<UiAccount>>>,
     pub units_consumed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_data: Option<UiTransactionReturnData>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -386,6 +437,89 @@ pub struct RpcTokenAccountBalance {
     pub amount: UiTokenAmount,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenHolder {
+    pub address: String,
+    pub owner: String,
+    #[serde(flatten)]
+    pub amount: UiTokenAmount,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenHoldersPage {
+    pub holders: Vec<RpcTokenHolder>,
+    /// Opaque cursor to pass in the next request, `None` once all holders have been returned
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMerkleProofLevel {
+    pub index: usize,
+    pub siblings: Vec<String>,
+}
+
+impl From<&MerkleProofLevel> for RpcMerkleProofLevel {
+    fn from(level: &MerkleProofLevel) -> Self {
+        Self {
+            index: level.index,
+            siblings: level.siblings.iter().map(|hash| hash.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountProof {
+    /// The slot of the accounts hash the account is proven against, which the node published in
+    /// gossip
+    pub slot: Slot,
+    pub account: UiAccount,
+    /// The slot the account was last written in, which its hash commits to
+    pub last_write_slot: Slot,
+    /// Path from the account hash to the accounts hash, from the leaf up
+    pub proof: Vec<RpcMerkleProofLevel>,
+    pub accounts_hash: String,
+}
+
+impl RpcAccountProof {
+    /// Verify that the account is the state of `pubkey` in the bank whose accounts hash is
+    /// `accounts_hash`. The accounts hash itself must be checked against a trusted source, such
+    /// as the accounts hashes that known validators publish in gossip for `slot`.
+    pub fn verify(&self, pubkey: &Pubkey) -> bool {
+        let parse_hash = |hash: &String| Hash::from_str(hash).ok();
+        let parse_level = |level: &RpcMerkleProofLevel| {
+            Some(MerkleProofLevel {
+                index: level.index,
+                siblings: level
+                    .siblings
+                    .iter()
+                    .map(parse_hash)
+                    .collect::<Option<_>>()?,
+            })
+        };
+        let parse = || {
+            let account = self.account.decode::<Account>()?;
+            let proof = MerkleProof {
+                levels: self.proof.iter().map(parse_level).collect::<Option<_>>()?,
+            };
+            Some((account, proof, parse_hash(&self.accounts_hash)?))
+        };
+        match parse() {
+            Some((account, proof, accounts_hash)) => account_proof::verify_account_proof(
+                pubkey,
+                &account,
+                self.last_write_slot,
+                &proof,
+                &accounts_hash,
+            ),
+            None => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcConfirmedTransactionStatusWithSignature {
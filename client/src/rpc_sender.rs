@@ -1,18 +1,31 @@
 //! A transport for RPC calls.
 
-use crate::{client_error::Result, rpc_request::RpcRequest};
+use {
+    crate::{client_error::Result, rpc_request::RpcRequest},
+    async_trait::async_trait,
+};
 
 /// A transport for RPC calls.
 ///
 /// `RpcSender` implements the underlying transport of requests to, and
 /// responses from, a Solana node, and is used primarily by [`RpcClient`].
 ///
+/// Senders are asynchronous; the blocking [`RpcClient`] drives them on its own
+/// runtime, while the [`nonblocking::rpc_client::RpcClient`] awaits them
+/// directly.
+///
 /// It is typically implemented by [`HttpSender`] in production, and
 /// [`MockSender`] in unit tests.
 ///
 /// [`RpcClient`]: crate::rpc_client::RpcClient
+/// [`nonblocking::rpc_client::RpcClient`]: crate::nonblocking::rpc_client::RpcClient
 /// [`HttpSender`]: crate::http_sender::HttpSender
 /// [`MockSender`]: crate::mock_sender::MockSender
+#[async_trait]
 pub trait RpcSender {
-    fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value>;
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value>;
 }
@@ -817,6 +817,7 @@ impl BankingStage {
             results,
             inner_instructions,
             transaction_logs,
+            transaction_return_data,
             mut retryable_txs,
             tx_count,
             signature_count,
@@ -874,6 +875,7 @@ impl BankingStage {
                     TransactionTokenBalancesSet::new(pre_token_balances, post_token_balances),
                     inner_instructions,
                     transaction_logs,
+                    transaction_return_data,
                     tx_results.rent_debits,
                 );
             }
@@ -1038,7 +1040,7 @@ impl BankingStage {
     }
 
     // This function deserializes packets into transactions, computes the blake3 hash of transaction messages,
-    // and verifies secp256k1 instructions. A list of valid transactions are returned with their message hashes
+    // and verifies secp256k1 and ed25519 instructions. A list of valid transactions are returned with their message hashes
     // and packet indexes.
     // Also returned is packet indexes for transaction should be retried due to cost limits.
     #[allow(clippy::needless_collect)]
@@ -1047,6 +1049,7 @@ impl BankingStage {
         transaction_indexes: &[usize],
         libsecp256k1_0_5_upgrade_enabled: bool,
         libsecp256k1_fail_on_bad_count: bool,
+        ed25519_program_enabled: bool,
         cost_tracker: &Arc<RwLock<CostTracker>>,
         banking_stage_stats: &BankingStageStats,
     ) -> (Vec<SanitizedTransaction>, Vec<usize>, Vec<usize>) {
@@ -1066,6 +1069,7 @@ impl BankingStage {
                 tx.verify_precompiles(
                     libsecp256k1_0_5_upgrade_enabled,
                     libsecp256k1_fail_on_bad_count,
+                    ed25519_program_enabled,
                 )
                 .ok()?;
                 Some((tx, *tx_index))
@@ -1163,6 +1167,7 @@ impl BankingStage {
                 &packet_indexes,
                 bank.libsecp256k1_0_5_upgrade_enabled(),
                 bank.libsecp256k1_fail_on_bad_count(),
+                bank.ed25519_program_enabled(),
                 cost_tracker,
                 banking_stage_stats,
             );
@@ -1266,6 +1271,7 @@ impl BankingStage {
                 transaction_indexes,
                 bank.libsecp256k1_0_5_upgrade_enabled(),
                 bank.libsecp256k1_fail_on_bad_count(),
+                bank.ed25519_program_enabled(),
                 cost_tracker,
                 banking_stage_stats,
             );
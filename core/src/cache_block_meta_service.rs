@@ -1,7 +1,7 @@
 pub use solana_ledger::blockstore_processor::CacheBlockMetaSender;
 use {
     crossbeam_channel::{Receiver, RecvTimeoutError},
-    solana_ledger::blockstore::Blockstore,
+    solana_ledger::{account_history::AccountHistoryRecorder, blockstore::Blockstore},
     solana_measure::measure::Measure,
     solana_runtime::bank::Bank,
     std::{
@@ -27,6 +27,7 @@ impl CacheBlockMetaService {
     pub fn new(
         cache_block_meta_receiver: CacheBlockMetaReceiver,
         blockstore: Arc<Blockstore>,
+        mut account_history_recorder: Option<AccountHistoryRecorder>,
         exit: &Arc<AtomicBool>,
     ) -> Self {
         let exit = exit.clone();
@@ -43,7 +44,16 @@ impl CacheBlockMetaService {
                     }
                     Ok(bank) => {
                         let mut cache_block_meta_timer = Measure::start("cache_block_meta_timer");
-                        Self::cache_block_meta(bank, &blockstore);
+                        Self::cache_block_meta(&bank, &blockstore);
+                        if let Some(recorder) = account_history_recorder.as_mut() {
+                            if let Err(e) = recorder.record_bank(&bank) {
+                                error!(
+                                    "record account history failed: slot {:?} {:?}",
+                                    bank.slot(),
+                                    e
+                                );
+                            }
+                        }
                         cache_block_meta_timer.stop();
                         if cache_block_meta_timer.as_ms() > CACHE_BLOCK_TIME_WARNING_MS {
                             warn!(
@@ -52,14 +62,20 @@ impl CacheBlockMetaService {
                             );
                         }
                     }
-                    _ => {}
+                    Err(RecvTimeoutError::Timeout) => {
+                        if let Some(recorder) = account_history_recorder.as_mut() {
+                            if let Err(e) = recorder.write_rooted() {
+                                error!("write account history failed: {:?}", e);
+                            }
+                        }
+                    }
                 }
             })
             .unwrap();
         Self { thread_hdl }
     }
 
-    fn cache_block_meta(bank: Arc<Bank>, blockstore: &Arc<Blockstore>) {
+    fn cache_block_meta(bank: &Bank, blockstore: &Arc<Blockstore>) {
         if let Err(e) = blockstore.cache_block_time(bank.slot(), bank.clock().unix_timestamp) {
             error!("cache_block_time failed: slot {:?} {:?}", bank.slot(), e);
         }
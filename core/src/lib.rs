@@ -52,6 +52,7 @@ pub mod sigverify;
 pub mod sigverify_shreds;
 pub mod sigverify_stage;
 pub mod snapshot_packager_service;
+pub mod snapshot_uploader;
 pub mod test_validator;
 pub mod tower_storage;
 pub mod tpu;
@@ -1,10 +1,14 @@
+use crate::snapshot_uploader::SnapshotUploader;
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError};
 use solana_gossip::cluster_info::{ClusterInfo, MAX_SNAPSHOT_HASHES};
+use solana_measure::measure::Measure;
 use solana_runtime::{
     snapshot_archive_info::SnapshotArchiveInfoGetter, snapshot_package::PendingSnapshotPackage,
     snapshot_utils,
 };
 use solana_sdk::{clock::Slot, hash::Hash};
 use std::{
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -15,6 +19,7 @@ use std::{
 
 pub struct SnapshotPackagerService {
     t_snapshot_packager: JoinHandle<()>,
+    t_snapshot_uploader: Option<JoinHandle<()>>,
 }
 
 impl SnapshotPackagerService {
@@ -24,7 +29,20 @@ impl SnapshotPackagerService {
         exit: &Arc<AtomicBool>,
         cluster_info: &Arc<ClusterInfo>,
         maximum_snapshots_to_retain: usize,
+        snapshot_uploader: Option<Arc<dyn SnapshotUploader>>,
     ) -> Self {
+        let (snapshot_archive_sender, t_snapshot_uploader) = match snapshot_uploader {
+            Some(snapshot_uploader) => {
+                let (snapshot_archive_sender, snapshot_archive_receiver) = unbounded();
+                let t_snapshot_uploader = Self::start_snapshot_uploader(
+                    snapshot_archive_receiver,
+                    snapshot_uploader,
+                    exit,
+                );
+                (Some(snapshot_archive_sender), Some(t_snapshot_uploader))
+            }
+            None => (None, None),
+        };
         let exit = exit.clone();
         let cluster_info = cluster_info.clone();
 
@@ -57,6 +75,11 @@ impl SnapshotPackagerService {
                     )
                     .expect("failed to archive snapshot package");
 
+                    // Uploading is slow, so it must not hold up archiving the next package
+                    if let Some(snapshot_archive_sender) = &snapshot_archive_sender {
+                        let _ = snapshot_archive_sender.send(snapshot_package.path().clone());
+                    }
+
                     hashes.push((snapshot_package.slot(), *snapshot_package.hash()));
                     while hashes.len() > MAX_SNAPSHOT_HASHES {
                         hashes.remove(0);
@@ -68,17 +91,73 @@ impl SnapshotPackagerService {
 
         Self {
             t_snapshot_packager,
+            t_snapshot_uploader,
         }
     }
 
+    fn start_snapshot_uploader(
+        snapshot_archive_receiver: Receiver<PathBuf>,
+        snapshot_uploader: Arc<dyn SnapshotUploader>,
+        exit: &Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        let exit = exit.clone();
+        Builder::new()
+            .name("snapshot-uploader".to_string())
+            .spawn(move || loop {
+                if exit.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let snapshot_archive_path =
+                    match snapshot_archive_receiver.recv_timeout(Duration::from_millis(100)) {
+                        Ok(snapshot_archive_path) => snapshot_archive_path,
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
+                // The archive was purged while older ones were uploaded, so a newer archive is
+                // waiting to be uploaded
+                if !snapshot_archive_path.is_file() {
+                    continue;
+                }
+
+                let mut timer = Measure::start("snapshot_upload");
+                // Stops on exit, so that an upload doesn't hold up joining the thread
+                let result = snapshot_uploader.upload(&snapshot_archive_path, &exit);
+                timer.stop();
+                match &result {
+                    Ok(()) => info!(
+                        "Uploaded {}, elapsed ms: {}",
+                        snapshot_archive_path.display(),
+                        timer.as_ms()
+                    ),
+                    Err(err) => warn!(
+                        "Failed to upload {}: {}",
+                        snapshot_archive_path.display(),
+                        err
+                    ),
+                }
+                datapoint_info!(
+                    "snapshot-upload",
+                    ("duration_ms", timer.as_ms(), i64),
+                    ("success", result.is_ok(), bool)
+                );
+            })
+            .unwrap()
+    }
+
     pub fn join(self) -> thread::Result<()> {
-        self.t_snapshot_packager.join()
+        self.t_snapshot_packager.join()?;
+        if let Some(t_snapshot_uploader) = self.t_snapshot_uploader {
+            t_snapshot_uploader.join()?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snapshot_uploader;
     use bincode::serialize_into;
     use solana_runtime::{
         accounts_db::AccountStorageEntry,
@@ -92,9 +171,30 @@ mod tests {
         fs::{self, remove_dir_all, OpenOptions},
         io::Write,
         path::{Path, PathBuf},
+        sync::Mutex,
     };
     use tempfile::TempDir;
 
+    /// Records the snapshot archives it is asked to upload
+    #[derive(Default)]
+    struct TestSnapshotUploader {
+        uploaded_snapshot_archives: Mutex<Vec<PathBuf>>,
+    }
+
+    impl SnapshotUploader for TestSnapshotUploader {
+        fn upload(
+            &self,
+            snapshot_archive_path: &Path,
+            _exit: &Arc<AtomicBool>,
+        ) -> snapshot_uploader::Result<()> {
+            self.uploaded_snapshot_archives
+                .lock()
+                .unwrap()
+                .push(snapshot_archive_path.to_path_buf());
+            Ok(())
+        }
+    }
+
     // Create temporary placeholder directory for all test files
     fn make_tmp_dir_path() -> PathBuf {
         let out_dir = std::env::var("FARF_DIR").unwrap_or_else(|_| "farf".to_string());
@@ -214,4 +314,49 @@ mod tests {
             archive_format,
         );
     }
+
+    #[test]
+    fn test_snapshot_uploader() {
+        let snapshot_archives_dir = TempDir::new().unwrap();
+        let snapshot_archive_path = |slot| {
+            snapshot_archives_dir.path().join(format!(
+                "snapshot-{}-{}.tar.zst",
+                slot,
+                Hash::default()
+            ))
+        };
+        fs::write(snapshot_archive_path(100), b"snapshot").unwrap();
+        fs::write(snapshot_archive_path(300), b"snapshot").unwrap();
+
+        let snapshot_uploader = Arc::new(TestSnapshotUploader::default());
+        let exit = Arc::new(AtomicBool::new(false));
+        let (snapshot_archive_sender, snapshot_archive_receiver) = unbounded();
+        let t_snapshot_uploader = SnapshotPackagerService::start_snapshot_uploader(
+            snapshot_archive_receiver,
+            snapshot_uploader.clone(),
+            &exit,
+        );
+        // The archive at slot 200 was purged before it could be uploaded
+        for slot in [100, 200, 300] {
+            snapshot_archive_sender
+                .send(snapshot_archive_path(slot))
+                .unwrap();
+        }
+        drop(snapshot_archive_sender);
+        t_snapshot_uploader.join().unwrap();
+        assert_eq!(
+            *snapshot_uploader.uploaded_snapshot_archives.lock().unwrap(),
+            vec![snapshot_archive_path(100), snapshot_archive_path(300)]
+        );
+
+        // The thread stops on exit, even while the packager can still send archives
+        let (_snapshot_archive_sender, snapshot_archive_receiver) = unbounded();
+        let t_snapshot_uploader = SnapshotPackagerService::start_snapshot_uploader(
+            snapshot_archive_receiver,
+            snapshot_uploader,
+            &exit,
+        );
+        exit.store(true, Ordering::Relaxed);
+        t_snapshot_uploader.join().unwrap();
+    }
 }
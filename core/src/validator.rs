@@ -14,6 +14,7 @@ use {
         serve_repair_service::ServeRepairService,
         sigverify,
         snapshot_packager_service::SnapshotPackagerService,
+        snapshot_uploader::SnapshotUploader,
         tower_storage::TowerStorage,
         tpu::{Tpu, DEFAULT_TPU_COALESCE_MS},
         tvu::{Sockets, Tvu, TvuConfig},
@@ -31,6 +32,7 @@ use {
         gossip_service::GossipService,
     },
     solana_ledger::{
+        account_history::{AccountHistoryConfig, AccountHistoryRecorder},
         bank_forks_utils,
         blockstore::{Blockstore, BlockstoreSignals, CompletedSlotsReceiver, PurgeType},
         blockstore_db::BlockstoreRecoveryMode,
@@ -57,7 +59,7 @@ use {
         rpc_completed_slots_service::RpcCompletedSlotsService,
         rpc_pubsub_service::{PubSubConfig, PubSubService},
         rpc_service::JsonRpcService,
-        rpc_subscriptions::RpcSubscriptions,
+        rpc_subscriptions::{BlockSubscriptionSource, RpcSubscriptions},
         transaction_status_service::TransactionStatusService,
     },
     solana_runtime::{
@@ -116,6 +118,7 @@ pub struct ValidatorConfig {
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>, // (JsonRpc, JsonRpcPubSub)
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: Option<SnapshotConfig>,
+    pub snapshot_uploader: Option<Arc<dyn SnapshotUploader>>,
     pub max_ledger_shreds: Option<u64>,
     pub broadcast_stage_type: BroadcastStageType,
     pub enable_partition: Option<Arc<AtomicBool>>,
@@ -176,6 +179,7 @@ impl Default for ValidatorConfig {
             rpc_addrs: None,
             pubsub_config: PubSubConfig::default(),
             snapshot_config: None,
+            snapshot_uploader: None,
             broadcast_stage_type: BroadcastStageType::Standard,
             enable_partition: None,
             enforce_ulimit_nofile: true,
@@ -469,12 +473,22 @@ impl Validator {
         let optimistically_confirmed_bank =
             OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
 
-        let rpc_subscriptions = Arc::new(RpcSubscriptions::new_with_vote_subscription(
+        let block_subscription_source =
+            config
+                .rpc_config
+                .enable_rpc_transaction_history
+                .then(|| BlockSubscriptionSource {
+                    blockstore: blockstore.clone(),
+                    max_complete_transaction_status_slot: max_complete_transaction_status_slot
+                        .clone(),
+                });
+        let rpc_subscriptions = Arc::new(RpcSubscriptions::new_with_config(
             &exit,
             bank_forks.clone(),
             block_commitment_cache.clone(),
             optimistically_confirmed_bank.clone(),
-            config.pubsub_config.enable_vote_subscription,
+            &config.pubsub_config,
+            block_subscription_source,
         ));
 
         let max_slots = Arc::new(MaxSlots::default());
@@ -543,6 +557,10 @@ impl Validator {
                 ));
             }
 
+            if config.rpc_config.enable_rpc_account_proofs {
+                bank.enable_accounts_hash_trees();
+            }
+
             let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
 
             let accountsdb_repl_service = config.accountsdb_repl_service_config.as_ref().map(|accountsdb_repl_service_config| {
@@ -652,6 +670,7 @@ impl Validator {
                     &exit,
                     &cluster_info,
                     snapshot_config.maximum_snapshots_to_retain,
+                    config.snapshot_uploader.clone(),
                 );
                 (
                     Some(snapshot_packager_service),
@@ -1177,6 +1196,7 @@ fn new_banks_from_ledger(
                 blockstore.clone(),
                 exit,
                 config.rpc_config.enable_cpi_and_log_storage,
+                config.rpc_config.account_history.clone(),
             )
         } else {
             TransactionHistoryServices::default()
@@ -1359,6 +1379,7 @@ fn initialize_rpc_transaction_history_services(
     blockstore: Arc<Blockstore>,
     exit: &Arc<AtomicBool>,
     enable_cpi_and_log_storage: bool,
+    account_history: Option<AccountHistoryConfig>,
 ) -> TransactionHistoryServices {
     let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
     let (transaction_status_sender, transaction_status_receiver) = unbounded();
@@ -1383,9 +1404,12 @@ fn initialize_rpc_transaction_history_services(
 
     let (cache_block_meta_sender, cache_block_meta_receiver) = unbounded();
     let cache_block_meta_sender = Some(cache_block_meta_sender);
+    let account_history_recorder =
+        account_history.map(|config| AccountHistoryRecorder::new(blockstore.clone(), config));
     let cache_block_meta_service = Some(CacheBlockMetaService::new(
         cache_block_meta_receiver,
         blockstore,
+        account_history_recorder,
         exit,
     ));
     TransactionHistoryServices {
@@ -1,4 +1,8 @@
-use solana_client::{pubsub_client::PubsubClient, rpc_client::RpcClient, rpc_response::SlotInfo};
+use futures::StreamExt;
+use solana_client::{
+    nonblocking, pubsub_client::PubsubClient, rpc_client::RpcClient,
+    rpc_config::RpcAccountInfoConfig, rpc_response::SlotInfo,
+};
 use solana_core::test_validator::TestValidator;
 use solana_rpc::{
     optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
@@ -8,12 +12,13 @@ use solana_rpc::{
 use solana_runtime::{
     bank::Bank,
     bank_forks::BankForks,
-    commitment::BlockCommitmentCache,
+    commitment::{BlockCommitmentCache, CommitmentSlots},
     genesis_utils::{create_genesis_config, GenesisConfigInfo},
 };
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     native_token::sol_to_lamports,
+    pubkey::Pubkey,
     rpc_port,
     signature::{Keypair, Signer},
     system_transaction,
@@ -143,3 +148,181 @@ fn test_slot_subscription() {
 
     assert_eq!(errors, [].to_vec());
 }
+
+#[test]
+fn test_account_subscription() {
+    let pubsub_addr = SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+        solana_net_utils::find_available_port_in_range(
+            IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            (10_000, 65_535),
+        )
+        .unwrap(),
+    );
+    let exit = Arc::new(AtomicBool::new(false));
+    let GenesisConfigInfo {
+        genesis_config,
+        mint_keypair,
+        ..
+    } = create_genesis_config(10_000);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let blockhash = bank.last_blockhash();
+    let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+    let bank0 = bank_forks.read().unwrap().get(0).unwrap().clone();
+    let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+    bank_forks.write().unwrap().insert(bank1);
+    let optimistically_confirmed_bank =
+        OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+    let subscriptions = Arc::new(RpcSubscriptions::new(
+        &exit,
+        bank_forks.clone(),
+        Arc::new(RwLock::new(BlockCommitmentCache::default())),
+        optimistically_confirmed_bank,
+    ));
+    let pubsub_service =
+        PubSubService::new(PubSubConfig::default(), &subscriptions, pubsub_addr, &exit);
+    std::thread::sleep(Duration::from_millis(400));
+
+    let bob = Keypair::new();
+    let (mut client, receiver) = PubsubClient::account_subscribe(
+        &format!("ws://0.0.0.0:{}/", pubsub_addr.port()),
+        &bob.pubkey(),
+        Some(RpcAccountInfoConfig {
+            commitment: Some(CommitmentConfig::processed()),
+            ..RpcAccountInfoConfig::default()
+        }),
+    )
+    .unwrap();
+    std::thread::sleep(Duration::from_millis(400));
+
+    let tx = system_transaction::transfer(&mint_keypair, &bob.pubkey(), 100, blockhash);
+    bank_forks
+        .read()
+        .unwrap()
+        .get(1)
+        .unwrap()
+        .process_transaction(&tx)
+        .unwrap();
+    subscriptions.notify_subscribers(CommitmentSlots {
+        slot: 1,
+        ..CommitmentSlots::default()
+    });
+
+    let response = receiver.recv_timeout(Duration::from_millis(400));
+
+    exit.store(true, Ordering::Relaxed);
+    client.shutdown().unwrap();
+    pubsub_service.close().unwrap();
+
+    let response = response.unwrap();
+    assert_eq!(response.context.slot, 1);
+    assert_eq!(response.value.lamports, 100);
+}
+
+#[test]
+fn test_root_subscription() {
+    let pubsub_addr = SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+        solana_net_utils::find_available_port_in_range(
+            IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            (10_000, 65_535),
+        )
+        .unwrap(),
+    );
+    let exit = Arc::new(AtomicBool::new(false));
+    let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+    let optimistically_confirmed_bank =
+        OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+    let subscriptions = Arc::new(RpcSubscriptions::new(
+        &exit,
+        bank_forks,
+        Arc::new(RwLock::new(BlockCommitmentCache::default())),
+        optimistically_confirmed_bank,
+    ));
+    let pubsub_service =
+        PubSubService::new(PubSubConfig::default(), &subscriptions, pubsub_addr, &exit);
+    std::thread::sleep(Duration::from_millis(400));
+
+    let (mut client, receiver) =
+        PubsubClient::root_subscribe(&format!("ws://0.0.0.0:{}/", pubsub_addr.port())).unwrap();
+    std::thread::sleep(Duration::from_millis(400));
+
+    subscriptions.notify_roots(vec![2, 1, 3]);
+
+    let mut roots = Vec::new();
+    while let Ok(root) = receiver.recv_timeout(Duration::from_millis(400)) {
+        roots.push(root);
+        if roots.len() == 3 {
+            break;
+        }
+    }
+
+    exit.store(true, Ordering::Relaxed);
+    client.shutdown().unwrap();
+    pubsub_service.close().unwrap();
+
+    assert_eq!(roots, vec![1, 2, 3]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_slot_subscription_async() {
+    let pubsub_addr = SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+        solana_net_utils::find_available_port_in_range(
+            IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            (10_000, 65_535),
+        )
+        .unwrap(),
+    );
+    let exit = Arc::new(AtomicBool::new(false));
+    let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+    let optimistically_confirmed_bank =
+        OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+    let subscriptions = Arc::new(RpcSubscriptions::new(
+        &exit,
+        bank_forks,
+        Arc::new(RwLock::new(BlockCommitmentCache::default())),
+        optimistically_confirmed_bank,
+    ));
+    let pubsub_service =
+        PubSubService::new(PubSubConfig::default(), &subscriptions, pubsub_addr, &exit);
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    let pubsub_client = nonblocking::pubsub_client::PubsubClient::new(&format!(
+        "ws://0.0.0.0:{}/",
+        pubsub_addr.port()
+    ))
+    .await
+    .unwrap();
+    let (mut slots, unsubscribe) = pubsub_client.slot_subscribe().await.unwrap();
+
+    let mut actual = Vec::new();
+    for i in 0..3 {
+        subscriptions.notify_slot(i + 1, i, i);
+        match tokio::time::timeout(Duration::from_millis(400), slots.next()).await {
+            Ok(Some(slot_info)) => actual.push(slot_info),
+            _ => break,
+        }
+    }
+
+    unsubscribe().await;
+    drop(slots);
+    pubsub_client.shutdown().await.unwrap();
+    exit.store(true, Ordering::Relaxed);
+    pubsub_service.close().unwrap();
+
+    assert_eq!(
+        actual,
+        (0..3)
+            .map(|i| SlotInfo {
+                slot: i + 1,
+                parent: i,
+                root: i,
+            })
+            .collect::<Vec<_>>()
+    );
+}
@@ -502,6 +502,7 @@ mod tests {
             &exit,
             &cluster_info,
             DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            None,
         );
 
         let _package_receiver = std::thread::Builder::new()
@@ -925,6 +926,7 @@ mod tests {
             snapshot_test_config
                 .snapshot_config
                 .maximum_snapshots_to_retain,
+            None,
         );
 
         let accounts_hash_verifier = AccountsHashVerifier::new(
@@ -2,7 +2,13 @@
 use console::Emoji;
 use indicatif::{ProgressBar, ProgressStyle};
 use log::*;
-use solana_runtime::{snapshot_utils, snapshot_utils::ArchiveFormat};
+use solana_runtime::{
+    snapshot_archive_info::{
+        FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+    },
+    snapshot_utils,
+    snapshot_utils::ArchiveFormat,
+};
 use solana_sdk::{clock::Slot, genesis_config::DEFAULT_GENESIS_ARCHIVE, hash::Hash};
 use std::fs::{self, File};
 use std::io;
@@ -294,3 +300,198 @@ pub fn download_snapshot<'a, 'b>(
         desired_snapshot_hash.0, rpc_addr
     ))
 }
+
+/// The URL of the file named `file_name` next to the snapshot archives uploaded to `url`
+fn snapshot_archives_file_url(url: &str, file_name: &str) -> Result<reqwest::Url, String> {
+    let mut file_url =
+        reqwest::Url::parse(url).map_err(|err| format!("Invalid URL {}: {}", url, err))?;
+    file_url
+        .path_segments_mut()
+        .map_err(|_| format!("Invalid URL {}: cannot be a base", url))?
+        .pop_if_empty()
+        .push(file_name);
+    Ok(file_url)
+}
+
+/// Fetch the latest snapshot archives file of the snapshot archives uploaded to `url`, such as
+/// by a validator's snapshot uploader, and return the archives it lists with the paths they have
+/// once downloaded into `snapshot_archives_dir`
+pub fn get_latest_snapshot_archives_from_url(
+    url: &str,
+    snapshot_archives_dir: &Path,
+) -> Result<
+    (
+        FullSnapshotArchiveInfo,
+        Option<IncrementalSnapshotArchiveInfo>,
+    ),
+    String,
+> {
+    let latest_url =
+        snapshot_archives_file_url(url, snapshot_utils::LATEST_SNAPSHOT_ARCHIVES_FILE_NAME)?;
+    let contents = reqwest::blocking::Client::new()
+        .get(latest_url.clone())
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|err| format!("Failed to fetch {}: {}", latest_url, err))?;
+    snapshot_utils::parse_latest_snapshot_archives_file(&contents, snapshot_archives_dir)
+        .map_err(|err| format!("Invalid {}: {}", latest_url, err))
+}
+
+/// Download the snapshot archives returned by `get_latest_snapshot_archives_from_url()` from
+/// `url`, unless they are already in the snapshot archives directory
+pub fn download_snapshot_archives_from_url<'a, 'b>(
+    url: &str,
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+    use_progress_bar: bool,
+    maximum_snapshots_to_retain: usize,
+    progress_notify_callback: &'a mut DownloadProgressCallbackOption<'b>,
+) -> Result<(), String> {
+    let snapshot_archives_dir = full_snapshot_archive_info
+        .path()
+        .parent()
+        .expect("snapshot archives directory");
+    snapshot_utils::purge_old_snapshot_archives(snapshot_archives_dir, maximum_snapshots_to_retain);
+
+    for archive_path in std::iter::once(full_snapshot_archive_info.path())
+        .chain(incremental_snapshot_archive_info.map(|archive_info| archive_info.path()))
+    {
+        if archive_path.is_file() {
+            continue;
+        }
+        let file_name = archive_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .expect("snapshot archive file name");
+        download_file(
+            snapshot_archives_file_url(url, file_name)?.as_str(),
+            archive_path,
+            use_progress_bar,
+            progress_notify_callback,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{
+            collections::HashMap,
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+            sync::{Arc, Mutex},
+            thread,
+        },
+        tempfile::TempDir,
+    };
+
+    /// Serve `files` by path on a local HTTP server, returning its URL and the paths requested
+    fn start_file_server(files: HashMap<String, Vec<u8>>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/snapshots", listener.local_addr().unwrap());
+        let requested_paths = Arc::<Mutex<Vec<String>>>::default();
+        let recorded_paths = requested_paths.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                reader.read_line(&mut request_line).unwrap();
+                // Skip the headers of the GET request, which has no body
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+                let response = match files.get(&path) {
+                    Some(contents) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            contents.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(contents);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                recorded_paths.lock().unwrap().push(path);
+                let _ = (&stream).write_all(&response);
+            }
+        });
+        (url, requested_paths)
+    }
+
+    #[test]
+    fn test_download_snapshot_archives_from_url() {
+        let full_snapshot_archive = format!("snapshot-100-{}.tar.zst", Hash::default());
+        let incremental_snapshot_archive =
+            format!("incremental-snapshot-100-150-{}.tar.zst", Hash::default());
+        let files: HashMap<_, _> = vec![
+            (
+                format!(
+                    "/snapshots/{}",
+                    snapshot_utils::LATEST_SNAPSHOT_ARCHIVES_FILE_NAME
+                ),
+                format!(
+                    "{}\n{}\n",
+                    full_snapshot_archive, incremental_snapshot_archive
+                )
+                .into_bytes(),
+            ),
+            (
+                format!("/snapshots/{}", full_snapshot_archive),
+                b"full".to_vec(),
+            ),
+            (
+                format!("/snapshots/{}", incremental_snapshot_archive),
+                b"incremental".to_vec(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let (url, requested_paths) = start_file_server(files);
+        let snapshot_archives_dir = TempDir::new().unwrap();
+
+        let (full_snapshot_archive_info, incremental_snapshot_archive_info) =
+            get_latest_snapshot_archives_from_url(&url, snapshot_archives_dir.path()).unwrap();
+        assert_eq!(full_snapshot_archive_info.slot(), 100);
+        let incremental_snapshot_archive_info = incremental_snapshot_archive_info.unwrap();
+        assert_eq!(incremental_snapshot_archive_info.slot(), 150);
+        for _ in 0..2 {
+            download_snapshot_archives_from_url(
+                &url,
+                &full_snapshot_archive_info,
+                Some(&incremental_snapshot_archive_info),
+                false,
+                2,
+                &mut None,
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            fs::read(snapshot_archives_dir.path().join(&full_snapshot_archive)).unwrap(),
+            b"full"
+        );
+        assert_eq!(
+            fs::read(
+                snapshot_archives_dir
+                    .path()
+                    .join(&incremental_snapshot_archive)
+            )
+            .unwrap(),
+            b"incremental"
+        );
+        // Archives that were already downloaded are kept
+        assert_eq!(requested_paths.lock().unwrap().len(), 3);
+
+        // Without a latest snapshot archives file there is nothing to download
+        let (url, _requested_paths) = start_file_server(HashMap::new());
+        assert!(get_latest_snapshot_archives_from_url(&url, snapshot_archives_dir.path()).is_err());
+    }
+}
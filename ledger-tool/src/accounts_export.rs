@@ -0,0 +1,441 @@
+//! The `accounts_export` module writes accounts to CSV or Parquet files, one row per account,
+//! so that they can be loaded into analytics databases.
+
+use parquet::{
+    basic::Compression,
+    column::writer::get_typed_column_writer_mut,
+    data_type::{BoolType, ByteArray, ByteArrayType, DataType, Int64Type},
+    errors::ParquetError,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, RowGroupWriter, SerializedFileWriter},
+    },
+    schema::parser::parse_message_type,
+};
+use solana_account_decoder::{
+    parse_account_data::{parse_account_data, AccountAdditionalData},
+    parse_token::{get_mint_decimals, get_token_account_mint, spl_token_id_v2_0},
+};
+use solana_runtime::{bank::Bank, snapshot_utils::SnapshotArchiveReader};
+use solana_sdk::{account::ReadableAccount, clock::Slot, pubkey::Pubkey, sysvar};
+use std::{
+    collections::{HashMap, HashSet},
+    error,
+    fs::File,
+    mem,
+    path::Path,
+    sync::Arc,
+};
+
+// Rows buffered before a Parquet row group is written, unless the buffered account data
+// reaches `PARQUET_ROW_GROUP_MAX_DATA_SIZE` first
+const PARQUET_ROW_GROUP_SIZE: usize = 64 * 1024;
+const PARQUET_ROW_GROUP_MAX_DATA_SIZE: usize = 128 * 1024 * 1024;
+
+// Accounts loaded from a bank at a time
+const BANK_ACCOUNTS_PAGE_SIZE: usize = 10_000;
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountsExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AccountsExportConfig {
+    /// Only export accounts owned by these programs. All accounts are exported if `None`
+    pub owners: Option<HashSet<Pubkey>>,
+    pub include_sysvars: bool,
+    /// Export the raw account data, base64 encoded in CSV files
+    pub include_data: bool,
+    /// Export the `jsonParsed` encoding of the accounts of programs known to the account
+    /// decoder. Token accounts are only parsed if their mint was added with `add_mint()`
+    pub json_parsed: bool,
+}
+
+/// A row of the export
+struct AccountRow<'a> {
+    pubkey: &'a Pubkey,
+    owner: &'a Pubkey,
+    lamports: u64,
+    executable: bool,
+    rent_epoch: u64,
+    slot: Slot,
+    data_len: usize,
+    /// Empty unless the data is exported
+    data: &'a [u8],
+    parsed: Option<String>,
+}
+
+trait AccountsWriter {
+    fn write(&mut self, row: &AccountRow) -> Result<()>;
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+struct CsvAccountsWriter {
+    writer: csv::Writer<File>,
+    include_data: bool,
+    json_parsed: bool,
+}
+
+impl CsvAccountsWriter {
+    fn new(file: File, config: &AccountsExportConfig) -> Result<Self> {
+        let mut writer = csv::Writer::from_writer(file);
+        let mut header = vec![
+            "pubkey",
+            "owner",
+            "lamports",
+            "executable",
+            "rent_epoch",
+            "slot",
+            "data_len",
+        ];
+        if config.include_data {
+            header.push("data");
+        }
+        if config.json_parsed {
+            header.push("parsed");
+        }
+        writer.write_record(&header)?;
+        Ok(Self {
+            writer,
+            include_data: config.include_data,
+            json_parsed: config.json_parsed,
+        })
+    }
+}
+
+impl AccountsWriter for CsvAccountsWriter {
+    fn write(&mut self, row: &AccountRow) -> Result<()> {
+        let mut record = vec![
+            row.pubkey.to_string(),
+            row.owner.to_string(),
+            row.lamports.to_string(),
+            row.executable.to_string(),
+            row.rent_epoch.to_string(),
+            row.slot.to_string(),
+            row.data_len.to_string(),
+        ];
+        if self.include_data {
+            record.push(base64::encode(row.data));
+        }
+        if self.json_parsed {
+            record.push(row.parsed.clone().unwrap_or_default());
+        }
+        self.writer.write_record(&record)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// The columns of the rows buffered for the next row group
+#[derive(Default)]
+struct ParquetColumns {
+    pubkey: Vec<ByteArray>,
+    owner: Vec<ByteArray>,
+    lamports: Vec<i64>,
+    executable: Vec<bool>,
+    rent_epoch: Vec<i64>,
+    slot: Vec<i64>,
+    data_len: Vec<i64>,
+    data: Vec<ByteArray>,
+    data_size: usize,
+    parsed: Vec<ByteArray>,
+    // The `parsed` column is optional: 1 if a row has a value, 0 if not
+    parsed_def_levels: Vec<i16>,
+}
+
+struct ParquetAccountsWriter {
+    writer: SerializedFileWriter<File>,
+    columns: ParquetColumns,
+    include_data: bool,
+    json_parsed: bool,
+}
+
+impl ParquetAccountsWriter {
+    fn new(file: File, config: &AccountsExportConfig) -> Result<Self> {
+        let mut schema = "message account {
+            REQUIRED BYTE_ARRAY pubkey (UTF8);
+            REQUIRED BYTE_ARRAY owner (UTF8);
+            REQUIRED INT64 lamports (INTEGER(64, false));
+            REQUIRED BOOLEAN executable;
+            REQUIRED INT64 rent_epoch (INTEGER(64, false));
+            REQUIRED INT64 slot (INTEGER(64, false));
+            REQUIRED INT64 data_len (INTEGER(64, false));"
+            .to_string();
+        if config.include_data {
+            schema.push_str("REQUIRED BYTE_ARRAY data;");
+        }
+        if config.json_parsed {
+            schema.push_str("OPTIONAL BYTE_ARRAY parsed (JSON);");
+        }
+        schema.push('}');
+        let schema = Arc::new(parse_message_type(&schema)?);
+        let properties = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                // Dictionary pages buy little for mostly-unique pubkeys and data, and the
+                // dictionary hasher in this parquet release performs unaligned reads
+                .set_dictionary_enabled(false)
+                .build(),
+        );
+        Ok(Self {
+            writer: SerializedFileWriter::new(file, schema, properties)?,
+            columns: ParquetColumns::default(),
+            include_data: config.include_data,
+            json_parsed: config.json_parsed,
+        })
+    }
+
+    fn write_column<T: DataType>(
+        row_group: &mut dyn RowGroupWriter,
+        values: &[T::T],
+        def_levels: Option<&[i16]>,
+    ) -> Result<()> {
+        let mut column = row_group
+            .next_column()?
+            .ok_or_else(|| ParquetError::General("too few columns in schema".to_string()))?;
+        get_typed_column_writer_mut::<T>(&mut column).write_batch(values, def_levels, None)?;
+        row_group.close_column(column)?;
+        Ok(())
+    }
+
+    fn write_row_group(&mut self) -> Result<()> {
+        let columns = mem::take(&mut self.columns);
+        if columns.pubkey.is_empty() {
+            return Ok(());
+        }
+        let mut row_group = self.writer.next_row_group()?;
+        Self::write_column::<ByteArrayType>(row_group.as_mut(), &columns.pubkey, None)?;
+        Self::write_column::<ByteArrayType>(row_group.as_mut(), &columns.owner, None)?;
+        Self::write_column::<Int64Type>(row_group.as_mut(), &columns.lamports, None)?;
+        Self::write_column::<BoolType>(row_group.as_mut(), &columns.executable, None)?;
+        Self::write_column::<Int64Type>(row_group.as_mut(), &columns.rent_epoch, None)?;
+        Self::write_column::<Int64Type>(row_group.as_mut(), &columns.slot, None)?;
+        Self::write_column::<Int64Type>(row_group.as_mut(), &columns.data_len, None)?;
+        if self.include_data {
+            Self::write_column::<ByteArrayType>(row_group.as_mut(), &columns.data, None)?;
+        }
+        if self.json_parsed {
+            Self::write_column::<ByteArrayType>(
+                row_group.as_mut(),
+                &columns.parsed,
+                Some(&columns.parsed_def_levels),
+            )?;
+        }
+        self.writer.close_row_group(row_group)?;
+        Ok(())
+    }
+}
+
+impl AccountsWriter for ParquetAccountsWriter {
+    fn write(&mut self, row: &AccountRow) -> Result<()> {
+        let columns = &mut self.columns;
+        columns
+            .pubkey
+            .push(row.pubkey.to_string().into_bytes().into());
+        columns
+            .owner
+            .push(row.owner.to_string().into_bytes().into());
+        // Parquet stores unsigned integers in signed physical types
+        columns.lamports.push(row.lamports as i64);
+        columns.executable.push(row.executable);
+        columns.rent_epoch.push(row.rent_epoch as i64);
+        columns.slot.push(row.slot as i64);
+        columns.data_len.push(row.data_len as i64);
+        if self.include_data {
+            columns.data.push(row.data.to_vec().into());
+            columns.data_size += row.data.len();
+        }
+        if self.json_parsed {
+            match &row.parsed {
+                Some(parsed) => {
+                    columns.parsed.push(parsed.clone().into_bytes().into());
+                    columns.parsed_def_levels.push(1);
+                }
+                None => columns.parsed_def_levels.push(0),
+            }
+        }
+
+        if columns.pubkey.len() >= PARQUET_ROW_GROUP_SIZE
+            || columns.data_size >= PARQUET_ROW_GROUP_MAX_DATA_SIZE
+        {
+            self.write_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.write_row_group()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// Writes the accounts passed to `export()` that match its config to a file
+pub struct AccountsExporter {
+    config: AccountsExportConfig,
+    writer: Box<dyn AccountsWriter>,
+    mint_decimals: HashMap<Pubkey, u8>,
+    num_exported: usize,
+}
+
+impl AccountsExporter {
+    pub fn new(
+        path: &Path,
+        format: AccountsExportFormat,
+        config: AccountsExportConfig,
+    ) -> Result<Self> {
+        let file = File::create(path)?;
+        let writer: Box<dyn AccountsWriter> = match format {
+            AccountsExportFormat::Csv => Box::new(CsvAccountsWriter::new(file, &config)?),
+            AccountsExportFormat::Parquet => Box::new(ParquetAccountsWriter::new(file, &config)?),
+        };
+        Ok(Self {
+            config,
+            writer,
+            mint_decimals: HashMap::new(),
+            num_exported: 0,
+        })
+    }
+
+    /// Whether token mints must be added before exporting, to parse the token accounts
+    pub fn needs_mints(&self) -> bool {
+        self.config.json_parsed
+    }
+
+    /// Record the decimals of `account` if it is a token mint
+    pub fn add_mint<T: ReadableAccount>(&mut self, pubkey: &Pubkey, account: &T) {
+        if account.owner() == &spl_token_id_v2_0() {
+            if let Some(decimals) = get_mint_decimals(account.data()) {
+                self.mint_decimals.insert(*pubkey, decimals);
+            }
+        }
+    }
+
+    fn is_exported<T: ReadableAccount>(&self, pubkey: &Pubkey, account: &T) -> bool {
+        // Accounts with no lamports have been closed
+        if account.lamports() == 0 {
+            return false;
+        }
+        if !self.config.include_sysvars && sysvar::is_sysvar_id(pubkey) {
+            return false;
+        }
+        match &self.config.owners {
+            Some(owners) => owners.contains(account.owner()),
+            None => true,
+        }
+    }
+
+    fn parse<T: ReadableAccount>(&self, pubkey: &Pubkey, account: &T) -> Option<String> {
+        let spl_token_decimals = if account.owner() == &spl_token_id_v2_0() {
+            get_token_account_mint(account.data())
+                .and_then(|mint| self.mint_decimals.get(&mint).copied())
+        } else {
+            None
+        };
+        let parsed_account = parse_account_data(
+            pubkey,
+            account.owner(),
+            account.data(),
+            Some(AccountAdditionalData { spl_token_decimals }),
+        )
+        .ok()?;
+        serde_json::to_string(&parsed_account).ok()
+    }
+
+    /// Write `account` if it matches the config. `slot` is the slot the account was last
+    /// written in
+    pub fn export<T: ReadableAccount>(
+        &mut self,
+        pubkey: &Pubkey,
+        account: &T,
+        slot: Slot,
+    ) -> Result<()> {
+        if !self.is_exported(pubkey, account) {
+            return Ok(());
+        }
+        let parsed = if self.config.json_parsed {
+            self.parse(pubkey, account)
+        } else {
+            None
+        };
+        self.writer.write(&AccountRow {
+            pubkey,
+            owner: account.owner(),
+            lamports: account.lamports(),
+            executable: account.executable(),
+            rent_epoch: account.rent_epoch(),
+            slot,
+            data_len: account.data().len(),
+            data: if self.config.include_data {
+                account.data()
+            } else {
+                &[]
+            },
+            parsed,
+        })?;
+        self.num_exported += 1;
+        Ok(())
+    }
+
+    /// Flush the file, and return the number of accounts exported
+    pub fn finish(self) -> Result<usize> {
+        self.writer.finish()?;
+        Ok(self.num_exported)
+    }
+}
+
+/// Export the latest version of each account stored in a snapshot archive, without unpacking the
+/// archive. See `SnapshotArchiveReader::scan_latest_accounts()` for the temporary disk space this
+/// takes
+pub fn export_snapshot_archive_accounts(
+    reader: &SnapshotArchiveReader,
+    exporter: &mut AccountsExporter,
+) -> Result<()> {
+    if exporter.needs_mints() {
+        // The decimals of a mint are set once it is initialized, so any stored version will do
+        reader.scan_stored_accounts(|_slot, account| {
+            exporter.add_mint(&account.meta.pubkey, account)
+        })?;
+    }
+    let mut result = Ok(());
+    reader.scan_latest_accounts(|slot, account| {
+        if result.is_ok() {
+            result = exporter.export(&account.meta.pubkey, account, slot);
+        }
+    })?;
+    result
+}
+
+/// Export the accounts of `bank`, loading a page of them at a time
+pub fn export_bank_accounts(bank: &Bank, exporter: &mut AccountsExporter) -> Result<()> {
+    if exporter.needs_mints() {
+        let mints = bank.get_filtered_program_accounts(&spl_token_id_v2_0(), |account| {
+            get_mint_decimals(account.data()).is_some()
+        })?;
+        for (pubkey, account) in &mints {
+            exporter.add_mint(pubkey, account);
+        }
+    }
+    let mut start_after = None;
+    loop {
+        let accounts = bank.get_all_accounts_with_modified_slots_paginated(
+            start_after.as_ref(),
+            BANK_ACCOUNTS_PAGE_SIZE,
+        )?;
+        match accounts.last() {
+            Some((pubkey, _account, _slot)) => start_after = Some(*pubkey),
+            None => return Ok(()),
+        }
+        for (pubkey, account, slot) in &accounts {
+            exporter.export(pubkey, account, *slot)?;
+        }
+    }
+}
@@ -31,7 +31,7 @@ async fn upload(
 
     solana_ledger::bigtable_upload::upload_confirmed_blocks(
         Arc::new(blockstore),
-        bigtable,
+        Arc::new(bigtable),
         starting_slot,
         ending_slot,
         allow_missing_metadata,
@@ -26,7 +26,8 @@ use solana_ledger::{
     shred::Shred,
 };
 use solana_runtime::{
-    accounts_index::AccountsIndexConfig,
+    accounts_db::AccountShrinkThreshold,
+    accounts_index::{AccountSecondaryIndexes, AccountsIndexConfig},
     bank::{Bank, RewardCalculationEvent},
     bank_forks::BankForks,
     hardened_unpack::{open_genesis_config, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
@@ -37,7 +38,7 @@ use solana_runtime::{
     },
 };
 use solana_sdk::{
-    account::{AccountSharedData, ReadableAccount, WritableAccount},
+    account::{Account, AccountSharedData, ReadableAccount, WritableAccount},
     clock::{Epoch, Slot},
     genesis_config::{ClusterType, GenesisConfig},
     hash::Hash,
@@ -66,8 +67,12 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+mod accounts_export;
+use accounts_export::*;
 mod bigtable;
 use bigtable::*;
+mod snapshot;
+use snapshot::*;
 
 #[derive(PartialEq)]
 enum LedgerOutputMethod {
@@ -824,6 +829,77 @@ fn open_genesis_config_by(ledger_path: &Path, matches: &ArgMatches<'_>) -> Genes
     open_genesis_config(ledger_path, max_genesis_archive_unpacked_size)
 }
 
+fn output_account_history_entry(slot: Slot, account: &impl ReadableAccount) {
+    println!("{}:", slot);
+    println!("  - balance: {} SOL", lamports_to_sol(account.lamports()));
+    println!("  - owner: '{}'", account.owner());
+    println!("  - executable: {}", account.executable());
+    println!("  - rent_epoch: {}", account.rent_epoch());
+    println!("  - data: '{}'", bs58::encode(account.data()).into_string());
+    println!("  - data_len: {}", account.data().len());
+}
+
+// Reconstruct the history of an account from the state at each full snapshot archive, keyed by
+// the slot in which each state was written
+fn account_history_from_snapshot_archives(
+    snapshot_archives_dir: &Path,
+    genesis_config: &GenesisConfig,
+    pubkey: &Pubkey,
+    max_slot: Option<Slot>,
+) -> Result<BTreeMap<Slot, AccountSharedData>, String> {
+    let mut full_snapshot_archives =
+        snapshot_utils::get_full_snapshot_archives(snapshot_archives_dir);
+    full_snapshot_archives.retain(|archive| {
+        max_slot
+            .map(|max_slot| archive.slot() <= max_slot)
+            .unwrap_or(true)
+    });
+    full_snapshot_archives.sort_unstable();
+
+    let mut history = BTreeMap::new();
+    for full_snapshot_archive_info in full_snapshot_archives {
+        eprintln!(
+            "Loading snapshot archive {}",
+            full_snapshot_archive_info.path().display()
+        );
+        let temp_dir = tempfile::TempDir::new().map_err(|err| err.to_string())?;
+        let account_paths = vec![temp_dir.path().join("accounts")];
+        let bank_snapshots_dir = temp_dir.path().join("snapshot");
+        fs::create_dir_all(&account_paths[0]).map_err(|err| err.to_string())?;
+        fs::create_dir_all(&bank_snapshots_dir).map_err(|err| err.to_string())?;
+
+        let (bank, _timings) = snapshot_utils::bank_from_snapshot_archives(
+            &account_paths,
+            &[],
+            &bank_snapshots_dir,
+            &full_snapshot_archive_info,
+            None,
+            genesis_config,
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            false,
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            true,
+            false,
+            None,
+        )
+        .map_err(|err| {
+            format!(
+                "Failed to load snapshot archive {}: {}",
+                full_snapshot_archive_info.path().display(),
+                err
+            )
+        })?;
+        if let Some((account, slot)) = bank.get_account_modified_slot(pubkey) {
+            history.insert(slot, account);
+        }
+    }
+    Ok(history)
+}
+
 fn assert_capitalization(bank: &Bank) {
     let debug_verify = true;
     assert!(bank.calculate_and_verify_capitalization(debug_verify));
@@ -996,7 +1072,7 @@ fn main() {
                 .takes_value(true)
                 .possible_values(&["json", "json-compact"])
                 .help("Return information in specified output format, \
-                       currently only available for bigtable subcommands"),
+                       currently only available for bigtable and snapshot subcommands"),
         )
         .arg(
             Arg::with_name("verbose")
@@ -1008,6 +1084,7 @@ fn main() {
                 .help("Show additional information where supported"),
         )
         .bigtable_subcommand()
+        .snapshot_subcommand()
         .subcommand(
             SubCommand::with_name("print")
             .about("Print the ledger")
@@ -1356,6 +1433,102 @@ fn main() {
                     .help("Exclude account data (useful for large number of accounts)"),
             )
             .arg(&max_genesis_archive_unpacked_size_arg)
+        ).subcommand(
+            SubCommand::with_name("export-accounts")
+            .about("Export the accounts after processing in the ledger, or those of a snapshot \
+                    archive, to a CSV or Parquet file with a row per account")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&halt_at_slot_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("output_file")
+                    .index(1)
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("File to write the accounts to"),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .value_name("FORMAT")
+                    .takes_value(true)
+                    .possible_values(&["csv", "parquet"])
+                    .default_value("csv")
+                    .help("Format of the file"),
+            )
+            .arg(
+                Arg::with_name("from_snapshot_archive")
+                    .long("from-snapshot-archive")
+                    .value_name("ARCHIVE")
+                    .takes_value(true)
+                    .help("Stream the accounts from this full or incremental snapshot archive \
+                           instead of processing the ledger. An incremental snapshot archive \
+                           only holds the accounts written since its full snapshot. The archive \
+                           is read twice to skip the versions of accounts stored more than once, \
+                           which takes 48 bytes of space in the temporary directory (TMPDIR) \
+                           per stored account, and memory for the skipped versions"),
+            )
+            .arg(
+                Arg::with_name("owner")
+                    .long("owner")
+                    .value_name("PROGRAM_ID")
+                    .takes_value(true)
+                    .multiple(true)
+                    .validator(is_pubkey)
+                    .help("Only export the accounts owned by this program. \
+                           May be specified multiple times"),
+            )
+            .arg(
+                Arg::with_name("include_sysvars")
+                    .long("include-sysvars")
+                    .takes_value(false)
+                    .help("Include sysvars too"),
+            )
+            .arg(
+                Arg::with_name("exclude_account_data")
+                    .long("exclude-account-data")
+                    .takes_value(false)
+                    .help("Exclude account data"),
+            )
+            .arg(
+                Arg::with_name("json_parsed")
+                    .long("json-parsed")
+                    .takes_value(false)
+                    .help("Add a column with the jsonParsed encoding of the accounts of \
+                           programs known to the account decoder"),
+            )
+        ).subcommand(
+            SubCommand::with_name("account-history")
+            .about("Print the recorded states of an account, or its state at a slot")
+            .arg(
+                Arg::with_name("address")
+                    .index(1)
+                    .value_name("ADDRESS")
+                    .takes_value(true)
+                    .validator(is_pubkey)
+                    .required(true)
+                    .help("Account address"),
+            )
+            .arg(
+                Arg::with_name("slot")
+                    .long("slot")
+                    .value_name("SLOT")
+                    .takes_value(true)
+                    .validator(is_slot)
+                    .help("Only print the state of the account at this slot"),
+            )
+            .arg(
+                Arg::with_name("from_snapshot_archives")
+                    .long("from-snapshot-archives")
+                    .takes_value(false)
+                    .help("Reconstruct the history from the state of the account in each \
+                           full snapshot archive, instead of the history recorded by \
+                           a validator running with --enable-rpc-account-history"),
+            )
+            .arg(&max_genesis_archive_unpacked_size_arg)
         ).subcommand(
             SubCommand::with_name("capitalization")
             .about("Print capitalization (aka, total supply) while checksumming it")
@@ -1555,6 +1728,7 @@ fn main() {
 
     match matches.subcommand() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
+        ("snapshot", Some(arg_matches)) => snapshot_process_command(arg_matches),
         ("print", Some(arg_matches)) => {
             let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
             let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
@@ -2317,6 +2491,128 @@ fn main() {
                 }
             }
         }
+        ("export-accounts", Some(arg_matches)) => {
+            let output_file = PathBuf::from(arg_matches.value_of("output_file").unwrap());
+            let format = match arg_matches.value_of("format").unwrap() {
+                "parquet" => AccountsExportFormat::Parquet,
+                _ => AccountsExportFormat::Csv,
+            };
+            let config = AccountsExportConfig {
+                owners: pubkeys_of(arg_matches, "owner").map(|owners| owners.into_iter().collect()),
+                include_sysvars: arg_matches.is_present("include_sysvars"),
+                include_data: !arg_matches.is_present("exclude_account_data"),
+                json_parsed: arg_matches.is_present("json_parsed"),
+            };
+            let mut exporter =
+                AccountsExporter::new(&output_file, format, config).unwrap_or_else(|err| {
+                    eprintln!("Unable to create {}: {}", output_file.display(), err);
+                    exit(1);
+                });
+
+            let result =
+                if let Some(snapshot_archive) = arg_matches.value_of("from_snapshot_archive") {
+                    let snapshot_archive = PathBuf::from(snapshot_archive);
+                    let (archive_format, _base_slot) =
+                        parse_snapshot_archive_path(&snapshot_archive).unwrap_or_else(|err| {
+                            eprintln!("{}", err);
+                            exit(1);
+                        });
+                    snapshot_utils::SnapshotArchiveReader::open(&snapshot_archive, archive_format)
+                        .map_err(|err| err.into())
+                        .and_then(|reader| export_snapshot_archive_accounts(&reader, &mut exporter))
+                } else {
+                    let dev_halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
+                    let process_options = ProcessOptions {
+                        dev_halt_at_slot,
+                        new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                        poh_verify: false,
+                        ..ProcessOptions::default()
+                    };
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = open_blockstore(
+                        &ledger_path,
+                        AccessType::TryPrimaryThenSecondary,
+                        wal_recovery_mode,
+                    );
+                    let (bank_forks, _leader_schedule_cache, _snapshot_hash) = load_bank_forks(
+                        arg_matches,
+                        &genesis_config,
+                        &blockstore,
+                        process_options,
+                        snapshot_archive_path,
+                    )
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to load ledger: {:?}", err);
+                        exit(1);
+                    });
+                    export_bank_accounts(&bank_forks.working_bank(), &mut exporter)
+                };
+
+            match result.and_then(|()| exporter.finish()) {
+                Ok(num_exported) => println!(
+                    "Exported {} accounts to {}",
+                    num_exported,
+                    output_file.display()
+                ),
+                Err(err) => {
+                    eprintln!("Failed to export accounts: {}", err);
+                    exit(1);
+                }
+            }
+        }
+        ("account-history", Some(arg_matches)) => {
+            let pubkey = pubkey_of(arg_matches, "address").unwrap();
+            let slot = value_t!(arg_matches, "slot", Slot).ok();
+
+            let history = if arg_matches.is_present("from_snapshot_archives") {
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let snapshot_archives_dir = snapshot_archive_path
+                    .clone()
+                    .unwrap_or_else(|| ledger_path.clone());
+                account_history_from_snapshot_archives(
+                    &snapshot_archives_dir,
+                    &genesis_config,
+                    &pubkey,
+                    slot,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(1);
+                })
+                .into_iter()
+                .map(|(slot, account)| (slot, Account::from(account)))
+                .collect()
+            } else {
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::TryPrimaryThenSecondary,
+                    wal_recovery_mode,
+                );
+                let history = match slot {
+                    Some(slot) => blockstore
+                        .get_account_at_slot(&pubkey, slot)
+                        .map(|entry| entry.into_iter().collect()),
+                    None => blockstore.get_account_history(&pubkey, 0, usize::MAX),
+                };
+                history.unwrap_or_else(|err| {
+                    eprintln!("Failed to read account history: {:?}", err);
+                    exit(1);
+                })
+            };
+
+            // With a slot, only the latest state at or before it is printed
+            let skip = if slot.is_some() {
+                history.len().saturating_sub(1)
+            } else {
+                0
+            };
+            if history.is_empty() {
+                println!("No history found for {}", pubkey);
+            }
+            for (slot, account) in history.iter().skip(skip) {
+                output_account_history_entry(*slot, account);
+            }
+        }
         ("capitalization", Some(arg_matches)) => {
             let dev_halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
             let process_options = ProcessOptions {
@@ -0,0 +1,493 @@
+/// The `snapshot` subcommand
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use serde::Serialize;
+use solana_cli_output::{OutputFormat, QuietDisplay, VerboseDisplay};
+use solana_runtime::{
+    append_vec::StoredAccountMeta,
+    snapshot_archive_info::{
+        FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+    },
+    snapshot_utils::{get_full_snapshot_archives, ArchiveFormat, SnapshotArchiveReader},
+};
+use solana_sdk::{
+    clock::{Epoch, Slot},
+    hash::{hash, Hash},
+    pubkey::Pubkey,
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// What is compared of each account. The data is compared by its hash, so that an account takes
+/// the same memory whatever the size of its data
+#[derive(Debug, PartialEq)]
+struct AccountSummary {
+    lamports: u64,
+    owner: Pubkey,
+    executable: bool,
+    rent_epoch: Epoch,
+    data_len: usize,
+    data_hash: Hash,
+}
+
+impl From<&StoredAccountMeta<'_>> for AccountSummary {
+    fn from(account: &StoredAccountMeta) -> Self {
+        Self {
+            lamports: account.account_meta.lamports,
+            owner: account.account_meta.owner,
+            executable: account.account_meta.executable,
+            rent_epoch: account.account_meta.rent_epoch,
+            data_len: account.data.len(),
+            data_hash: hash(account.data),
+        }
+    }
+}
+
+struct Snapshot {
+    summary: CliSnapshotSummary,
+    accounts: HashMap<Pubkey, AccountSummary>,
+}
+
+/// The format of a full or incremental snapshot archive, and the slot of the full snapshot an
+/// incremental snapshot is based on, from the archive's file name
+pub fn parse_snapshot_archive_path(
+    snapshot_archive_path: &Path,
+) -> Result<(ArchiveFormat, Option<Slot>), String> {
+    match FullSnapshotArchiveInfo::new_from_path(snapshot_archive_path.to_path_buf()) {
+        Ok(info) => Ok((info.archive_format(), None)),
+        Err(_) => {
+            IncrementalSnapshotArchiveInfo::new_from_path(snapshot_archive_path.to_path_buf())
+                .map(|info| (info.archive_format(), Some(info.base_slot())))
+                .map_err(|err| format!("{}: {}", snapshot_archive_path.display(), err))
+        }
+    }
+}
+
+/// The latest state of each account stored in a snapshot archive, including those with no
+/// lamports
+fn load_snapshot_accounts(
+    reader: &SnapshotArchiveReader,
+    snapshot_archive_path: &Path,
+) -> Result<HashMap<Pubkey, AccountSummary>, String> {
+    Ok(reader
+        .latest_accounts(|account| AccountSummary::from(account))
+        .map_err(|err| format!("{}: {}", snapshot_archive_path.display(), err))?
+        .into_iter()
+        .map(|(pubkey, (_, account))| (pubkey, account))
+        .collect())
+}
+
+/// Loads the accounts of a snapshot archive. An incremental snapshot archive is loaded on top of
+/// the full snapshot archive it is based on, which must be in the same directory.
+///
+/// Every account of the snapshot is held in memory, about 150 bytes each, so this takes tens of
+/// GB for a snapshot of mainnet-beta
+fn load_snapshot(snapshot_archive_path: &Path) -> Result<Snapshot, String> {
+    let (archive_format, base_slot) = parse_snapshot_archive_path(snapshot_archive_path)?;
+    let reader = SnapshotArchiveReader::open(snapshot_archive_path, archive_format)
+        .map_err(|err| format!("{}: {}", snapshot_archive_path.display(), err))?;
+    let mut accounts = match base_slot {
+        Some(base_slot) => {
+            let snapshot_archives_dir = snapshot_archive_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."));
+            let full_snapshot_archive_info = get_full_snapshot_archives(snapshot_archives_dir)
+                .into_iter()
+                .find(|info| info.slot() == base_slot)
+                .ok_or_else(|| {
+                    format!(
+                        "{}: full snapshot archive of slot {} not found in {}",
+                        snapshot_archive_path.display(),
+                        base_slot,
+                        snapshot_archives_dir.display()
+                    )
+                })?;
+            let base_reader = SnapshotArchiveReader::open(
+                full_snapshot_archive_info.path(),
+                full_snapshot_archive_info.archive_format(),
+            )
+            .map_err(|err| format!("{}: {}", full_snapshot_archive_info.path().display(), err))?;
+            load_snapshot_accounts(&base_reader, full_snapshot_archive_info.path())?
+        }
+        None => HashMap::new(),
+    };
+    // The incremental snapshot's accounts were all written after its base, and those with no
+    // lamports have been closed since
+    accounts.extend(load_snapshot_accounts(&reader, snapshot_archive_path)?);
+    accounts.retain(|_, account| account.lamports > 0);
+    Ok(Snapshot {
+        summary: CliSnapshotSummary {
+            path: snapshot_archive_path.display().to_string(),
+            slot: reader.slot(),
+            base_slot,
+            hash: reader.hash().to_string(),
+            capitalization: reader.capitalization(),
+            accounts: accounts.len(),
+        },
+        accounts,
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CliSnapshotSummary {
+    path: String,
+    slot: Slot,
+    /// Slot of the full snapshot an incremental snapshot is based on
+    base_slot: Option<Slot>,
+    hash: String,
+    capitalization: u64,
+    /// Number of accounts with lamports in the snapshot
+    accounts: usize,
+}
+
+impl fmt::Display for CliSnapshotSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "  {}", self.path)?;
+        match self.base_slot {
+            Some(base_slot) => writeln!(
+                f,
+                "    incremental snapshot of slot {} based on slot {}",
+                self.slot, base_slot
+            )?,
+            None => writeln!(f, "    full snapshot of slot {}", self.slot)?,
+        }
+        writeln!(f, "    bank hash: {}", self.hash)?;
+        writeln!(f, "    capitalization: {}", self.capitalization)?;
+        writeln!(f, "    accounts: {}", self.accounts)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CliAccountDiff {
+    pubkey: String,
+    old_lamports: Option<u64>,
+    new_lamports: Option<u64>,
+    /// The fields that differ, for accounts in both snapshots
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changed_fields: Vec<&'static str>,
+}
+
+impl CliAccountDiff {
+    fn lamports_delta(&self) -> i128 {
+        i128::from(self.new_lamports.unwrap_or_default())
+            - i128::from(self.old_lamports.unwrap_or_default())
+    }
+}
+
+impl fmt::Display for CliAccountDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.old_lamports, self.new_lamports) {
+            (None, Some(lamports)) => writeln!(f, "  + {} {}", self.pubkey, lamports),
+            (Some(lamports), None) => writeln!(f, "  - {} {}", self.pubkey, lamports),
+            (old_lamports, new_lamports) => writeln!(
+                f,
+                "  ~ {} {} -> {} ({})",
+                self.pubkey,
+                old_lamports.unwrap_or_default(),
+                new_lamports.unwrap_or_default(),
+                self.changed_fields.join(", "),
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CliSnapshotDiff {
+    old: CliSnapshotSummary,
+    new: CliSnapshotSummary,
+    capitalization_delta: i128,
+    /// Sum of the lamport deltas of the added, removed and changed accounts
+    lamports_delta: i128,
+    added: Vec<CliAccountDiff>,
+    removed: Vec<CliAccountDiff>,
+    changed: Vec<CliAccountDiff>,
+}
+
+impl CliSnapshotDiff {
+    fn new(old: Snapshot, new: Snapshot) -> Self {
+        let pubkeys: BTreeSet<_> = old.accounts.keys().chain(new.accounts.keys()).collect();
+        let mut added = vec![];
+        let mut removed = vec![];
+        let mut changed = vec![];
+        for pubkey in pubkeys {
+            let old_account = old.accounts.get(pubkey);
+            let new_account = new.accounts.get(pubkey);
+            let mut account_diff = CliAccountDiff {
+                pubkey: pubkey.to_string(),
+                old_lamports: old_account.map(|account| account.lamports),
+                new_lamports: new_account.map(|account| account.lamports),
+                changed_fields: vec![],
+            };
+            match (old_account, new_account) {
+                (None, Some(_)) => added.push(account_diff),
+                (Some(_), None) => removed.push(account_diff),
+                (Some(old_account), Some(new_account)) if old_account != new_account => {
+                    let fields = [
+                        ("lamports", old_account.lamports != new_account.lamports),
+                        ("owner", old_account.owner != new_account.owner),
+                        (
+                            "executable",
+                            old_account.executable != new_account.executable,
+                        ),
+                        (
+                            "rent epoch",
+                            old_account.rent_epoch != new_account.rent_epoch,
+                        ),
+                        (
+                            "data",
+                            old_account.data_len != new_account.data_len
+                                || old_account.data_hash != new_account.data_hash,
+                        ),
+                    ];
+                    account_diff.changed_fields = fields
+                        .iter()
+                        .filter(|(_, changed)| *changed)
+                        .map(|(field, _)| *field)
+                        .collect();
+                    changed.push(account_diff);
+                }
+                _ => {}
+            }
+        }
+
+        let lamports_delta = added
+            .iter()
+            .chain(&removed)
+            .chain(&changed)
+            .map(CliAccountDiff::lamports_delta)
+            .sum();
+        Self {
+            capitalization_delta: i128::from(new.summary.capitalization)
+                - i128::from(old.summary.capitalization),
+            lamports_delta,
+            old: old.summary,
+            new: new.summary,
+            added,
+            removed,
+            changed,
+        }
+    }
+}
+
+impl fmt::Display for CliSnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Old snapshot:")?;
+        write!(f, "{}", self.old)?;
+        writeln!(f, "New snapshot:")?;
+        write!(f, "{}", self.new)?;
+        writeln!(f, "Accounts added: {}", self.added.len())?;
+        writeln!(f, "Accounts removed: {}", self.removed.len())?;
+        writeln!(f, "Accounts changed: {}", self.changed.len())?;
+        writeln!(f, "Capitalization delta: {}", self.capitalization_delta)?;
+        writeln!(f, "Lamports delta of the accounts: {}", self.lamports_delta)
+    }
+}
+
+impl QuietDisplay for CliSnapshotDiff {}
+impl VerboseDisplay for CliSnapshotDiff {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "{}", self)?;
+        for account_diff in self.added.iter().chain(&self.removed).chain(&self.changed) {
+            write!(w, "{}", account_diff)?;
+        }
+        Ok(())
+    }
+}
+
+fn diff(
+    old_snapshot_archive_path: &Path,
+    new_snapshot_archive_path: &Path,
+    output_format: OutputFormat,
+) -> Result<(), String> {
+    let old = load_snapshot(old_snapshot_archive_path)?;
+    let new = load_snapshot(new_snapshot_archive_path)?;
+    let snapshot_diff = CliSnapshotDiff::new(old, new);
+    println!("{}", output_format.formatted_string(&snapshot_diff));
+    Ok(())
+}
+
+pub trait SnapshotSubCommand {
+    fn snapshot_subcommand(self) -> Self;
+}
+
+impl SnapshotSubCommand for App<'_, '_> {
+    fn snapshot_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("snapshot")
+                .about("Inspect snapshot archives without loading them into a bank")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("diff")
+                        .about(
+                            "Compare the accounts of two full or incremental snapshot archives. \
+                             Accounts with no lamports are treated as absent, and an \
+                             incremental snapshot archive is loaded on top of the full \
+                             snapshot archive it is based on, which must be in the same \
+                             directory. The accounts of both snapshots are held in memory, \
+                             which takes about 150 bytes per account of each snapshot, tens of \
+                             GB for snapshots of mainnet-beta",
+                        )
+                        .arg(
+                            Arg::with_name("old_snapshot_archive")
+                                .index(1)
+                                .value_name("OLD_SNAPSHOT_ARCHIVE")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Path to the snapshot archive to compare from"),
+                        )
+                        .arg(
+                            Arg::with_name("new_snapshot_archive")
+                                .index(2)
+                                .value_name("NEW_SNAPSHOT_ARCHIVE")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Path to the snapshot archive to compare to"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn snapshot_process_command(matches: &ArgMatches<'_>) {
+    let verbose = matches.is_present("verbose");
+    let output_format = OutputFormat::from_matches(matches, "output_format", verbose);
+
+    let result = match matches.subcommand() {
+        ("diff", Some(arg_matches)) => {
+            let old_snapshot_archive_path =
+                PathBuf::from(arg_matches.value_of("old_snapshot_archive").unwrap());
+            let new_snapshot_archive_path =
+                PathBuf::from(arg_matches.value_of("new_snapshot_archive").unwrap());
+            diff(
+                &old_snapshot_archive_path,
+                &new_snapshot_archive_path,
+                output_format,
+            )
+        }
+        _ => unreachable!(),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_runtime::{
+        bank::Bank,
+        genesis_utils::create_genesis_config,
+        snapshot_utils::{bank_to_full_snapshot_archive, bank_to_incremental_snapshot_archive},
+    };
+    use solana_sdk::signature::{Keypair, Signer};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_snapshot_diff() {
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+        let key3 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        bank0.transfer(1, &mint_keypair, &key1.pubkey()).unwrap();
+        bank0.transfer(2, &mint_keypair, &key2.pubkey()).unwrap();
+        while !bank0.is_complete() {
+            bank0.register_tick(&Hash::new_unique());
+        }
+        let bank1 = Arc::new(Bank::new_from_parent(&bank0, &collector, 1));
+        bank1.transfer(3, &mint_keypair, &key1.pubkey()).unwrap();
+        while !bank1.is_complete() {
+            bank1.register_tick(&Hash::new_unique());
+        }
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            snapshot_archives_dir.path(),
+            ArchiveFormat::TarBzip2,
+            std::usize::MAX,
+        )
+        .unwrap();
+
+        // Closes key1 and creates key3, leaving the other accounts of the full snapshot
+        // out of the incremental snapshot
+        let bank2 = Arc::new(Bank::new_from_parent(&bank1, &collector, 2));
+        bank2.transfer(4, &key1, &key2.pubkey()).unwrap();
+        bank2.transfer(5, &mint_keypair, &key3.pubkey()).unwrap();
+        assert_eq!(bank2.get_balance(&key1.pubkey()), 0);
+        while !bank2.is_complete() {
+            bank2.register_tick(&Hash::new_unique());
+        }
+        let incremental_snapshot_archive_info = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank2,
+            bank1.slot(),
+            None,
+            snapshot_archives_dir.path(),
+            ArchiveFormat::TarBzip2,
+            std::usize::MAX,
+        )
+        .unwrap();
+
+        let full = load_snapshot(full_snapshot_archive_info.path()).unwrap();
+        let incremental = load_snapshot(incremental_snapshot_archive_info.path()).unwrap();
+        assert_eq!(full.summary.base_slot, None);
+        assert_eq!(incremental.summary.base_slot, Some(bank1.slot()));
+        assert_eq!(incremental.summary.accounts, incremental.accounts.len());
+
+        let pubkeys = |account_diffs: &[CliAccountDiff]| -> Vec<String> {
+            account_diffs
+                .iter()
+                .map(|account_diff| account_diff.pubkey.clone())
+                .collect()
+        };
+        let snapshot_diff = CliSnapshotDiff::new(full, incremental);
+        assert_eq!(pubkeys(&snapshot_diff.added), [key3.pubkey().to_string()]);
+        assert_eq!(pubkeys(&snapshot_diff.removed), [key1.pubkey().to_string()]);
+        assert_eq!(snapshot_diff.removed[0].old_lamports, Some(4));
+        assert_eq!(snapshot_diff.removed[0].new_lamports, None);
+        let key2_diff = snapshot_diff
+            .changed
+            .iter()
+            .find(|account_diff| account_diff.pubkey == key2.pubkey().to_string())
+            .unwrap();
+        assert_eq!(key2_diff.old_lamports, Some(2));
+        assert_eq!(key2_diff.new_lamports, Some(6));
+        assert_eq!(key2_diff.changed_fields, ["lamports"]);
+        assert_eq!(
+            snapshot_diff.capitalization_delta,
+            i128::from(bank2.capitalization()) - i128::from(bank1.capitalization())
+        );
+        assert_eq!(
+            snapshot_diff.lamports_delta,
+            snapshot_diff.capitalization_delta
+        );
+
+        // A snapshot doesn't differ from itself
+        let snapshot_diff = CliSnapshotDiff::new(
+            load_snapshot(full_snapshot_archive_info.path()).unwrap(),
+            load_snapshot(full_snapshot_archive_info.path()).unwrap(),
+        );
+        assert!(snapshot_diff.added.is_empty());
+        assert!(snapshot_diff.removed.is_empty());
+        assert!(snapshot_diff.changed.is_empty());
+        assert_eq!(snapshot_diff.lamports_delta, 0);
+
+        // An incremental snapshot can't be loaded without its base
+        std::fs::remove_file(full_snapshot_archive_info.path()).unwrap();
+        assert!(load_snapshot(incremental_snapshot_archive_info.path()).is_err());
+    }
+}
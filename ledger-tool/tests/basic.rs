@@ -1,6 +1,9 @@
 use assert_cmd::prelude::*;
+use parquet::file::reader::{FileReader, SerializedFileReader};
 use solana_ledger::create_new_tmp_ledger;
 use solana_ledger::genesis_utils::create_genesis_config;
+use solana_sdk::{signature::Signer, system_program};
+use std::fs;
 use std::process::Command;
 use std::process::Output;
 
@@ -47,3 +50,81 @@ fn nominal() {
     assert!(output.status.success());
     assert_eq!(count_newlines(&output.stdout), ticks + meta_lines);
 }
+
+#[test]
+fn export_accounts() {
+    let genesis = create_genesis_config(100);
+    let mint = genesis.mint_keypair.pubkey().to_string();
+    let (ledger_path, _blockhash) = create_new_tmp_ledger!(&genesis.genesis_config);
+    let ledger_path = ledger_path.to_str().unwrap();
+
+    let csv_path = format!("{}/accounts.csv", ledger_path);
+    let output = run_ledger_tool(&["-l", ledger_path, "export-accounts", &csv_path]);
+    assert!(output.status.success());
+    let csv = fs::read_to_string(&csv_path).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("pubkey,owner,lamports,executable,rent_epoch,slot,data_len,data")
+    );
+    let num_accounts = lines.clone().count();
+    assert!(lines.any(|line| line.starts_with(&mint)));
+
+    // Only system accounts, without their data
+    let system_program = system_program::id().to_string();
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "export-accounts",
+        &csv_path,
+        "--owner",
+        &system_program,
+        "--exclude-account-data",
+    ]);
+    assert!(output.status.success());
+    let csv = fs::read_to_string(&csv_path).unwrap();
+    let rows: Vec<Vec<_>> = csv
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').collect())
+        .collect();
+    assert!(rows.iter().any(|row| row[0] == mint));
+    assert!(rows
+        .iter()
+        .all(|row| row.len() == 7 && row[1] == system_program));
+
+    let parquet_path = format!("{}/accounts.parquet", ledger_path);
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "export-accounts",
+        &parquet_path,
+        "--format",
+        "parquet",
+        "--json-parsed",
+    ]);
+    assert!(output.status.success());
+    let reader = SerializedFileReader::new(fs::File::open(&parquet_path).unwrap()).unwrap();
+    let metadata = reader.metadata().file_metadata();
+    assert_eq!(metadata.num_rows() as usize, num_accounts);
+    let columns: Vec<_> = metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| column.name())
+        .collect();
+    assert_eq!(
+        columns,
+        [
+            "pubkey",
+            "owner",
+            "lamports",
+            "executable",
+            "rent_epoch",
+            "slot",
+            "data_len",
+            "data",
+            "parsed"
+        ]
+    );
+}
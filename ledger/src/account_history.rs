@@ -0,0 +1,237 @@
+//! The `account_history` module records the accounts written in each rooted slot into the
+//! blockstore, so that the state of an account at a past slot can be looked up.
+
+use {
+    crate::{
+        blockstore::Blockstore, blockstore_db::Result, blockstore_meta::AccountHistorySession,
+    },
+    solana_runtime::bank::Bank,
+    solana_sdk::{account::Account, clock::Slot, pubkey::Pubkey},
+    std::{
+        collections::{BTreeMap, HashSet},
+        sync::Arc,
+    },
+};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccountHistoryConfig {
+    /// Only record accounts owned by these programs, plus closed accounts. All accounts are
+    /// recorded if `None`
+    pub owners: Option<HashSet<Pubkey>>,
+}
+
+/// Collects the accounts written by frozen banks, and writes them to the blockstore once their
+/// slot is rooted. Writes of slots that are not rooted are discarded.
+///
+/// The rooted slots written without gaps form a session, persisted in the blockstore along with
+/// the owners recorded, so that lookups can tell a state that was never recorded from one that
+/// didn't exist.
+pub struct AccountHistoryRecorder {
+    blockstore: Arc<Blockstore>,
+    config: AccountHistoryConfig,
+    pending: BTreeMap<Slot, Vec<(Pubkey, Account)>>,
+    /// The first slot and progress of the current session
+    session: Option<(Slot, AccountHistorySession)>,
+}
+
+impl AccountHistoryRecorder {
+    pub fn new(blockstore: Arc<Blockstore>, config: AccountHistoryConfig) -> Self {
+        Self {
+            blockstore,
+            config,
+            pending: BTreeMap::new(),
+            session: None,
+        }
+    }
+
+    fn is_recorded_owner(&self, owner: &Pubkey) -> bool {
+        match &self.config.owners {
+            Some(owners) => owners.contains(owner),
+            None => true,
+        }
+    }
+
+    /// Collect the accounts written by `bank`, which must be frozen. Accounts reassigned from a
+    /// recorded owner to another are recorded too, so that their recorded state isn't stale
+    pub fn record_bank(&mut self, bank: &Bank) -> Result<()> {
+        let parent = bank.parent();
+        let accounts = bank
+            .get_all_accounts_modified_since_parent()
+            .into_iter()
+            .map(|(pubkey, account)| (pubkey, Account::from(account)))
+            .filter(|(pubkey, account)| {
+                account.lamports == 0
+                    || self.is_recorded_owner(&account.owner)
+                    || parent
+                        .as_ref()
+                        .and_then(|parent| parent.get_account(pubkey))
+                        .map(|parent_account| self.is_recorded_owner(parent_account.owner()))
+                        .unwrap_or(false)
+            })
+            .collect();
+        self.pending.insert(bank.slot(), accounts);
+        self.write_rooted()
+    }
+
+    /// Write the accounts of pending slots that have been rooted, and discard the slots that
+    /// can no longer be rooted
+    pub fn write_rooted(&mut self) -> Result<()> {
+        let max_root = self.blockstore.max_root();
+        let unresolved = self.pending.split_off(&max_root.saturating_add(1));
+        let resolved = std::mem::replace(&mut self.pending, unresolved);
+        for (slot, accounts) in resolved {
+            if self.blockstore.is_root(slot) {
+                if let Err(err) = self.write_slot(slot, &accounts) {
+                    // The session can't be continued past a slot that wasn't recorded
+                    self.session = None;
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_slot(&mut self, slot: Slot, accounts: &[(Pubkey, Account)]) -> Result<()> {
+        // A new session starts after a rooted slot that wasn't recorded
+        if let Some((_, session)) = &self.session {
+            let skipped_root = (session.last_slot.saturating_add(1)..slot)
+                .any(|skipped_slot| self.blockstore.is_root(skipped_slot));
+            if skipped_root {
+                self.session = None;
+            }
+        }
+        self.blockstore.write_account_history(slot, accounts)?;
+
+        let owners = self.config.owners.as_ref().map(|owners| {
+            let mut owners: Vec<_> = owners.iter().cloned().collect();
+            owners.sort();
+            owners
+        });
+        let (first_slot, session) = self.session.get_or_insert_with(|| {
+            (
+                slot,
+                AccountHistorySession {
+                    last_slot: slot,
+                    owners,
+                },
+            )
+        });
+        session.last_slot = slot;
+        self.blockstore
+            .write_account_history_session(*first_slot, session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+            get_tmp_ledger_path_auto_delete,
+        },
+        solana_sdk::{
+            account::AccountSharedData, signature::Signer, system_program, system_transaction,
+        },
+    };
+
+    #[test]
+    fn test_account_history_recorder() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let recipient = Pubkey::new_unique();
+
+        let mut recorder = AccountHistoryRecorder::new(
+            blockstore.clone(),
+            AccountHistoryConfig {
+                owners: Some(vec![system_program::id()].into_iter().collect()),
+            },
+        );
+
+        // Two forks write different balances for the recipient
+        let transfer = |bank: &Bank, lamports| {
+            bank.process_transaction(&system_transaction::transfer(
+                &mint_keypair,
+                &recipient,
+                lamports,
+                bank.last_blockhash(),
+            ))
+            .unwrap();
+            bank.freeze();
+        };
+        let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        transfer(&bank1, 100);
+        let bank2 = Bank::new_from_parent(&bank0, &Pubkey::default(), 2);
+        transfer(&bank2, 200);
+        recorder.record_bank(&bank1).unwrap();
+        recorder.record_bank(&bank2).unwrap();
+        assert_eq!(blockstore.get_account_at_slot(&recipient, 2).unwrap(), None);
+
+        // Only the rooted fork is written
+        blockstore.set_roots([0, 2].iter()).unwrap();
+        recorder.write_rooted().unwrap();
+        assert!(recorder.pending.is_empty());
+        let (slot, account) = blockstore
+            .get_account_at_slot(&recipient, u64::MAX)
+            .unwrap()
+            .unwrap();
+        assert_eq!(slot, 2);
+        assert_eq!(account.lamports, 200);
+        assert_eq!(
+            blockstore
+                .get_account_at_slot(&mint_keypair.pubkey(), 2)
+                .unwrap()
+                .map(|(slot, _)| slot),
+            Some(2)
+        );
+        let session = |last_slot| AccountHistorySession {
+            last_slot,
+            owners: Some(vec![system_program::id()]),
+        };
+        assert_eq!(
+            blockstore.get_account_history_session(2).unwrap(),
+            Some((2, session(2)))
+        );
+
+        // An account reassigned to an owner that isn't recorded is still recorded
+        let bank2 = Arc::new(bank2);
+        let bank3 = Bank::new_from_parent(&bank2, &Pubkey::default(), 3);
+        let other_owner = Pubkey::new_unique();
+        bank3.store_account(&recipient, &AccountSharedData::new(200, 0, &other_owner));
+        bank3.freeze();
+        recorder.record_bank(&bank3).unwrap();
+        blockstore.set_roots([3].iter()).unwrap();
+        recorder.write_rooted().unwrap();
+        assert_eq!(
+            blockstore
+                .get_account_at_slot(&recipient, 3)
+                .unwrap()
+                .map(|(slot, account)| (slot, account.owner)),
+            Some((3, other_owner))
+        );
+        assert_eq!(
+            blockstore.get_account_history_session(3).unwrap(),
+            Some((2, session(3)))
+        );
+
+        // A rooted slot that wasn't recorded ends the session
+        let bank5 = Bank::new_from_parent(&Arc::new(bank3), &Pubkey::default(), 5);
+        bank5.freeze();
+        blockstore.set_roots([4, 5].iter()).unwrap();
+        recorder.record_bank(&bank5).unwrap();
+        assert_eq!(
+            blockstore.get_account_history_session(5).unwrap(),
+            Some((5, session(5)))
+        );
+        assert_eq!(
+            blockstore.get_account_history_session(4).unwrap(),
+            Some((2, session(3)))
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::blockstore::Blockstore;
 use log::*;
 use solana_measure::measure::Measure;
 use solana_sdk::clock::Slot;
+use solana_storage_bigtable::LedgerStorageAdapter;
 use std::{
     collections::HashSet,
     result::Result,
@@ -20,7 +21,7 @@ const BLOCK_READ_AHEAD_DEPTH: usize = NUM_BLOCKS_TO_UPLOAD_IN_PARALLEL * 2;
 
 pub async fn upload_confirmed_blocks(
     blockstore: Arc<Blockstore>,
-    bigtable: solana_storage_bigtable::LedgerStorage,
+    bigtable: Arc<dyn LedgerStorageAdapter>,
     starting_slot: Slot,
     ending_slot: Option<Slot>,
     allow_missing_metadata: bool,
@@ -28,6 +28,7 @@ use {
     solana_rayon_threadlimit::get_thread_count,
     solana_runtime::hardened_unpack::{unpack_genesis_archive, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
     solana_sdk::{
+        account::Account,
         clock::{Slot, UnixTimestamp, DEFAULT_TICKS_PER_SECOND, MS_PER_TICK},
         genesis_config::{GenesisConfig, DEFAULT_GENESIS_ARCHIVE, DEFAULT_GENESIS_FILE},
         hash::Hash,
@@ -147,6 +148,8 @@ pub struct Blockstore {
     perf_samples_cf: LedgerColumn<cf::PerfSamples>,
     block_height_cf: LedgerColumn<cf::BlockHeight>,
     program_costs_cf: LedgerColumn<cf::ProgramCosts>,
+    account_history_cf: LedgerColumn<cf::AccountHistory>,
+    account_history_sessions_cf: LedgerColumn<cf::AccountHistorySessions>,
     bank_hash_cf: LedgerColumn<cf::BankHash>,
     last_root: Arc<RwLock<Slot>>,
     insert_shreds_lock: Arc<Mutex<()>>,
@@ -348,6 +351,8 @@ impl Blockstore {
         let perf_samples_cf = db.column();
         let block_height_cf = db.column();
         let program_costs_cf = db.column();
+        let account_history_cf = db.column();
+        let account_history_sessions_cf = db.column();
         let bank_hash_cf = db.column();
 
         let db = Arc::new(db);
@@ -398,6 +403,8 @@ impl Blockstore {
             perf_samples_cf,
             block_height_cf,
             program_costs_cf,
+            account_history_cf,
+            account_history_sessions_cf,
             bank_hash_cf,
             new_shreds_signals: vec![],
             completed_slots_senders: vec![],
@@ -2707,6 +2714,78 @@ impl Blockstore {
         self.program_costs_cf.delete(*key)
     }
 
+    /// Record the state of accounts written in `slot`
+    pub fn write_account_history(&self, slot: Slot, accounts: &[(Pubkey, Account)]) -> Result<()> {
+        let mut write_batch = self.db.batch()?;
+        for (pubkey, account) in accounts {
+            write_batch.put::<cf::AccountHistory>((*pubkey, slot), account)?;
+            write_batch.put_bytes::<cf::AccountHistorySlots>((slot, *pubkey), &[])?;
+        }
+        self.db.write(write_batch)
+    }
+
+    /// Record that the account history of the session starting at `first_slot` has been
+    /// written up to `session.last_slot`
+    pub fn write_account_history_session(
+        &self,
+        first_slot: Slot,
+        session: &AccountHistorySession,
+    ) -> Result<()> {
+        self.account_history_sessions_cf.put(first_slot, session)
+    }
+
+    /// Returns the account history session that started last at or before `slot`, with its
+    /// first slot. `slot` is only part of the session if it's no later than its last slot
+    pub fn get_account_history_session(
+        &self,
+        slot: Slot,
+    ) -> Result<Option<(Slot, AccountHistorySession)>> {
+        self.account_history_sessions_cf
+            .iter(IteratorMode::From(slot, IteratorDirection::Reverse))?
+            .next()
+            .map(|(first_slot, data)| Ok((first_slot, deserialize(&data)?)))
+            .transpose()
+    }
+
+    /// Returns the latest recorded state of an account at or before `slot`, with the slot in
+    /// which that state was written. Fails if `slot` has been cleaned up
+    pub fn get_account_at_slot(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+    ) -> Result<Option<(Slot, Account)>> {
+        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let mut iterator = self.account_history_cf.iter(IteratorMode::From(
+            (*pubkey, slot),
+            IteratorDirection::Reverse,
+        ))?;
+        match iterator.next() {
+            Some(((key_pubkey, slot), data)) if key_pubkey == *pubkey => {
+                Ok(Some((slot, deserialize(&data)?)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns up to `limit` recorded states of an account, oldest first, starting at
+    /// `start_slot`
+    pub fn get_account_history(
+        &self,
+        pubkey: &Pubkey,
+        start_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<(Slot, Account)>> {
+        self.account_history_cf
+            .iter(IteratorMode::From(
+                (*pubkey, start_slot),
+                IteratorDirection::Forward,
+            ))?
+            .take_while(|((key_pubkey, _), _)| key_pubkey == pubkey)
+            .take(limit)
+            .map(|((_, slot), data)| Ok((slot, deserialize(&data)?)))
+            .collect()
+    }
+
     /// Returns the entry vector for the slot starting with `shred_start_index`
     pub fn get_slot_entries(&self, slot: Slot, shred_start_index: u64) -> Result<Vec<Entry>> {
         self.get_slot_entries_with_shred_info(slot, shred_start_index, false)
@@ -4008,7 +4087,9 @@ pub mod tests {
         transaction::{Transaction, TransactionError},
     };
     use solana_storage_proto::convert::generated;
-    use solana_transaction_status::{InnerInstructions, Reward, Rewards, TransactionTokenBalance};
+    use solana_transaction_status::{
+        InnerInstructions, Reward, Rewards, TransactionReturnData, TransactionTokenBalance,
+    };
     use std::{sync::mpsc::channel, thread::Builder, time::Duration};
 
     // used for tests only
@@ -6206,6 +6287,7 @@ pub mod tests {
                     pre_token_balances: Some(vec![]),
                     post_token_balances: Some(vec![]),
                     rewards: Some(vec![]),
+                    return_data: None,
                 }
                 .into();
                 ledger
@@ -6222,6 +6304,7 @@ pub mod tests {
                     pre_token_balances: Some(vec![]),
                     post_token_balances: Some(vec![]),
                     rewards: Some(vec![]),
+                    return_data: None,
                 }
                 .into();
                 ledger
@@ -6238,6 +6321,7 @@ pub mod tests {
                     pre_token_balances: Some(vec![]),
                     post_token_balances: Some(vec![]),
                     rewards: Some(vec![]),
+                    return_data: None,
                 }
                 .into();
                 ledger
@@ -6256,6 +6340,7 @@ pub mod tests {
                         pre_token_balances: Some(vec![]),
                         post_token_balances: Some(vec![]),
                         rewards: Some(vec![]),
+                        return_data: None,
                     }),
                 }
             })
@@ -6384,6 +6469,7 @@ pub mod tests {
                 pre_token_balances: Some(pre_token_balances_vec.clone()),
                 post_token_balances: Some(post_token_balances_vec.clone()),
                 rewards: Some(rewards_vec.clone()),
+                return_data: None,
             }
             .into();
             assert!(transaction_status_cf
@@ -6401,6 +6487,7 @@ pub mod tests {
                 pre_token_balances,
                 post_token_balances,
                 rewards,
+                return_data,
             } = transaction_status_cf
                 .get_protobuf_or_bincode::<StoredTransactionStatusMeta>((
                     0,
@@ -6420,6 +6507,7 @@ pub mod tests {
             assert_eq!(pre_token_balances.unwrap(), pre_token_balances_vec);
             assert_eq!(post_token_balances.unwrap(), post_token_balances_vec);
             assert_eq!(rewards.unwrap(), rewards_vec);
+            assert!(return_data.is_none());
 
             // insert value
             let status = TransactionStatusMeta {
@@ -6432,6 +6520,7 @@ pub mod tests {
                 pre_token_balances: Some(pre_token_balances_vec.clone()),
                 post_token_balances: Some(post_token_balances_vec.clone()),
                 rewards: Some(rewards_vec.clone()),
+                return_data: None,
             }
             .into();
             assert!(transaction_status_cf
@@ -6449,6 +6538,7 @@ pub mod tests {
                 pre_token_balances,
                 post_token_balances,
                 rewards,
+                return_data,
             } = transaction_status_cf
                 .get_protobuf_or_bincode::<StoredTransactionStatusMeta>((
                     0,
@@ -6470,6 +6560,7 @@ pub mod tests {
             assert_eq!(pre_token_balances.unwrap(), pre_token_balances_vec);
             assert_eq!(post_token_balances.unwrap(), post_token_balances_vec);
             assert_eq!(rewards.unwrap(), rewards_vec);
+            assert!(return_data.is_none());
         }
         Blockstore::destroy(&blockstore_path).expect("Expected successful database destruction");
     }
@@ -6701,6 +6792,7 @@ pub mod tests {
                 pre_token_balances: Some(vec![]),
                 post_token_balances: Some(vec![]),
                 rewards: Some(vec![]),
+                return_data: None,
             }
             .into();
 
@@ -6897,6 +6989,7 @@ pub mod tests {
                 pre_token_balances: Some(vec![]),
                 post_token_balances: Some(vec![]),
                 rewards: Some(vec![]),
+                return_data: None,
             }
             .into();
 
@@ -7088,6 +7181,7 @@ pub mod tests {
                     pre_token_balances: pre_token_balances.clone(),
                     post_token_balances: post_token_balances.clone(),
                     rewards: rewards.clone(),
+                    return_data: None,
                 }
                 .into();
                 blockstore
@@ -7106,6 +7200,7 @@ pub mod tests {
                         pre_token_balances,
                         post_token_balances,
                         rewards,
+                        return_data: None,
                     }),
                 }
             })
@@ -7191,6 +7286,7 @@ pub mod tests {
                     pre_token_balances: pre_token_balances.clone(),
                     post_token_balances: post_token_balances.clone(),
                     rewards: rewards.clone(),
+                    return_data: None,
                 }
                 .into();
                 blockstore
@@ -7209,6 +7305,7 @@ pub mod tests {
                         pre_token_balances,
                         post_token_balances,
                         rewards,
+                        return_data: None,
                     }),
                 }
             })
@@ -7945,6 +8042,7 @@ pub mod tests {
                     pre_token_balances: Some(vec![]),
                     post_token_balances: Some(vec![]),
                     rewards: Some(vec![]),
+                    return_data: None,
                 }
                 .into();
                 transaction_status_cf
@@ -8009,6 +8107,108 @@ pub mod tests {
         Blockstore::destroy(&blockstore_path).expect("Expected successful database destruction");
     }
 
+    #[test]
+    fn test_account_history() {
+        let blockstore_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(blockstore_path.path()).unwrap();
+
+        let pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+        let account = |lamports| Account {
+            lamports,
+            ..Account::default()
+        };
+        blockstore
+            .write_account_history(5, &[(pubkey, account(1)), (other_pubkey, account(10))])
+            .unwrap();
+        blockstore
+            .write_account_history(8, &[(pubkey, account(2))])
+            .unwrap();
+        blockstore
+            .write_account_history(12, &[(pubkey, account(0))])
+            .unwrap();
+
+        assert_eq!(blockstore.get_account_at_slot(&pubkey, 4).unwrap(), None);
+        assert_eq!(
+            blockstore.get_account_at_slot(&pubkey, 5).unwrap(),
+            Some((5, account(1)))
+        );
+        assert_eq!(
+            blockstore.get_account_at_slot(&pubkey, 11).unwrap(),
+            Some((8, account(2)))
+        );
+        assert_eq!(
+            blockstore.get_account_at_slot(&pubkey, Slot::MAX).unwrap(),
+            Some((12, account(0)))
+        );
+        assert_eq!(
+            blockstore.get_account_at_slot(&other_pubkey, 100).unwrap(),
+            Some((5, account(10)))
+        );
+        assert_eq!(
+            blockstore
+                .get_account_at_slot(&Pubkey::new_unique(), 100)
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(
+            blockstore
+                .get_account_history(&pubkey, 0, usize::MAX)
+                .unwrap(),
+            vec![(5, account(1)), (8, account(2)), (12, account(0))]
+        );
+        assert_eq!(
+            blockstore.get_account_history(&pubkey, 6, 1).unwrap(),
+            vec![(8, account(2))]
+        );
+        assert_eq!(
+            blockstore
+                .get_account_history(&other_pubkey, 6, 10)
+                .unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_account_history_sessions() {
+        let blockstore_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(blockstore_path.path()).unwrap();
+        assert_eq!(blockstore.get_account_history_session(10).unwrap(), None);
+
+        let session = |last_slot| AccountHistorySession {
+            last_slot,
+            owners: None,
+        };
+        let filtered_session = AccountHistorySession {
+            last_slot: 30,
+            owners: Some(vec![Pubkey::new_unique()]),
+        };
+        blockstore
+            .write_account_history_session(5, &session(8))
+            .unwrap();
+        blockstore
+            .write_account_history_session(5, &session(12))
+            .unwrap();
+        blockstore
+            .write_account_history_session(20, &filtered_session)
+            .unwrap();
+
+        assert_eq!(blockstore.get_account_history_session(4).unwrap(), None);
+        assert_eq!(
+            blockstore.get_account_history_session(5).unwrap(),
+            Some((5, session(12)))
+        );
+        assert_eq!(
+            blockstore.get_account_history_session(15).unwrap(),
+            Some((5, session(12)))
+        );
+        assert_eq!(
+            blockstore.get_account_history_session(Slot::MAX).unwrap(),
+            Some((20, filtered_session))
+        );
+    }
+
     #[test]
     fn test_lowest_slot() {
         let blockstore_path = get_tmp_ledger_path!();
@@ -8498,6 +8698,10 @@ pub mod tests {
                     reward_type: Some(RewardType::Rent),
                     commission: None,
                 }]),
+                return_data: Some(TransactionReturnData {
+                    program_id: Pubkey::new_unique(),
+                    data: vec![1, 2, 3],
+                }),
             };
             let deprecated_status: StoredTransactionStatusMeta = status.clone().into();
             let protobuf_status: generated::TransactionStatusMeta = status.into();
@@ -209,6 +209,9 @@ impl Blockstore {
                 // in no spiky periodic huge delete_range for them.
             }
         }
+        // The compaction filter can't tell whether an account was written again after a purged
+        // slot, so the account history is purged explicitly whatever the purge type
+        self.purge_account_history(&mut write_batch, from_slot, to_slot)?;
         delete_range_timer.stop();
         let mut write_timer = Measure::start("write_batch");
         if let Err(e) = self.db.write(write_batch) {
@@ -315,6 +318,72 @@ impl Blockstore {
         Ok(result)
     }
 
+    /// Purges the account history of the slots in \[from_slot,to_slot\), as looked up in the
+    /// AccountHistorySlots column. The history of slots at or before the lowest cleanup slot is
+    /// collapsed into the latest state of each account instead, so that the state at any slot
+    /// that hasn't been cleaned up can still be looked up. Accounts closed by then are dropped
+    /// entirely.
+    fn purge_account_history(
+        &self,
+        batch: &mut WriteBatch,
+        from_slot: Slot,
+        to_slot: Slot, // Exclusive
+    ) -> Result<()> {
+        let lowest_cleanup_slot = *self.lowest_cleanup_slot.read().unwrap();
+        let slot_iterator = self
+            .db
+            .iter::<cf::AccountHistorySlots>(IteratorMode::From(
+                (from_slot, Pubkey::default()),
+                IteratorDirection::Forward,
+            ))?
+            .map(|(index, _)| index)
+            .take_while(|(slot, _)| *slot < to_slot);
+        for (slot, pubkey) in slot_iterator {
+            if slot > lowest_cleanup_slot {
+                batch.delete::<cf::AccountHistory>((pubkey, slot))?;
+                continue;
+            }
+
+            // Expired states are dropped once a later state is expired too
+            let next_slot = self
+                .account_history_cf
+                .iter(IteratorMode::From(
+                    (pubkey, slot.saturating_add(1)),
+                    IteratorDirection::Forward,
+                ))?
+                .next()
+                .map(|(index, _)| index)
+                .filter(|(next_pubkey, _)| *next_pubkey == pubkey)
+                .map(|(_, next_slot)| next_slot);
+            if matches!(next_slot, Some(next_slot) if next_slot <= lowest_cleanup_slot) {
+                batch.delete::<cf::AccountHistory>((pubkey, slot))?;
+                continue;
+            }
+
+            // The latest expired state replaces the one kept by an earlier purge, if any
+            if from_slot > 0 {
+                let kept_slots = self
+                    .account_history_cf
+                    .iter(IteratorMode::From(
+                        (pubkey, from_slot - 1),
+                        IteratorDirection::Reverse,
+                    ))?
+                    .map(|(index, _)| index)
+                    .take_while(|(kept_pubkey, _)| *kept_pubkey == pubkey);
+                for (_, kept_slot) in kept_slots {
+                    batch.delete::<cf::AccountHistory>((pubkey, kept_slot))?;
+                }
+            }
+            if let Some(account) = self.account_history_cf.get((pubkey, slot))? {
+                if account.lamports == 0 {
+                    batch.delete::<cf::AccountHistory>((pubkey, slot))?;
+                }
+            }
+        }
+        self.db
+            .delete_range_cf::<cf::AccountHistorySlots>(batch, from_slot, to_slot)
+    }
+
     /// Purges special columns (using a non-Slot primary-index) exactly, by deserializing each slot
     /// being purged and iterating through all transactions to determine the keys of individual
     /// records. **This method is very slow.**
@@ -394,6 +463,7 @@ impl Blockstore {
 pub mod tests {
     use super::*;
     use crate::{blockstore::tests::make_slot_entries_with_transactions, get_tmp_ledger_path};
+    use assert_matches::assert_matches;
     use bincode::serialize;
     use solana_entry::entry::next_entry_mut;
     use solana_sdk::{
@@ -1202,6 +1272,84 @@ pub mod tests {
         Blockstore::destroy(&blockstore_path).expect("Expected successful database destruction");
     }
 
+    #[test]
+    fn test_purge_account_history() {
+        let blockstore_path = get_tmp_ledger_path!();
+        {
+            let blockstore = Blockstore::open(&blockstore_path).unwrap();
+            let account = |lamports| Account {
+                lamports,
+                ..Account::default()
+            };
+            let written = Pubkey::new_unique();
+            let closed = Pubkey::new_unique();
+            let recent = Pubkey::new_unique();
+            let write_history = || {
+                blockstore
+                    .write_account_history(5, &[(written, account(1)), (closed, account(10))])
+                    .unwrap();
+                blockstore
+                    .write_account_history(7, &[(closed, account(0))])
+                    .unwrap();
+                blockstore
+                    .write_account_history(8, &[(written, account(2))])
+                    .unwrap();
+                blockstore
+                    .write_account_history(12, &[(written, account(3))])
+                    .unwrap();
+                blockstore
+                    .write_account_history(15, &[(recent, account(4))])
+                    .unwrap();
+            };
+            let history = |pubkey| {
+                blockstore
+                    .get_account_history(pubkey, 0, usize::MAX)
+                    .unwrap()
+            };
+
+            // Slots past the lowest cleanup slot are purged exactly
+            write_history();
+            blockstore.purge_slots(12, 20, PurgeType::Exact);
+            assert_eq!(history(&written), vec![(5, account(1)), (8, account(2))]);
+            assert_eq!(history(&closed), vec![(5, account(10)), (7, account(0))]);
+            assert_eq!(history(&recent), vec![]);
+
+            // Expired slots collapse into the latest state of each account that remains open
+            write_history();
+            *blockstore.lowest_cleanup_slot.write().unwrap() = 10;
+            blockstore.purge_slots(0, 10, PurgeType::CompactionFilter);
+            assert_eq!(history(&written), vec![(8, account(2)), (12, account(3))]);
+            assert_eq!(history(&closed), vec![]);
+            assert_eq!(history(&recent), vec![(15, account(4))]);
+
+            assert_matches!(
+                blockstore.get_account_at_slot(&written, 9),
+                Err(BlockstoreError::SlotCleanedUp)
+            );
+            assert_eq!(
+                blockstore.get_account_at_slot(&written, 11).unwrap(),
+                Some((8, account(2)))
+            );
+            assert_eq!(blockstore.get_account_at_slot(&closed, 11).unwrap(), None);
+
+            // A later expired state replaces the one kept by the previous purge
+            *blockstore.lowest_cleanup_slot.write().unwrap() = 13;
+            blockstore.purge_slots(11, 13, PurgeType::CompactionFilter);
+            assert_eq!(history(&written), vec![(12, account(3))]);
+            assert_eq!(history(&recent), vec![(15, account(4))]);
+
+            // Only the slots of the account history that haven't been purged remain indexed
+            let indexed_slots: Vec<_> = blockstore
+                .db
+                .iter::<cf::AccountHistorySlots>(IteratorMode::Start)
+                .unwrap()
+                .map(|((slot, _), _)| slot)
+                .collect();
+            assert_eq!(indexed_slots, vec![15]);
+        }
+        Blockstore::destroy(&blockstore_path).expect("Expected successful database destruction");
+    }
+
     #[test]
     fn test_purge_special_columns_exact_no_sigs() {
         let blockstore_path = get_tmp_ledger_path!();
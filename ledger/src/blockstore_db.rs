@@ -16,6 +16,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use solana_runtime::hardened_unpack::UnpackError;
 use solana_sdk::{
+    account::Account,
     clock::{Slot, UnixTimestamp},
     pubkey::Pubkey,
     signature::Signature,
@@ -75,6 +76,12 @@ const PERF_SAMPLES_CF: &str = "perf_samples";
 const BLOCK_HEIGHT_CF: &str = "block_height";
 /// Column family for ProgramCosts
 const PROGRAM_COSTS_CF: &str = "program_costs";
+/// Column family for AccountHistory
+const ACCOUNT_HISTORY_CF: &str = "account_history";
+/// Column family for AccountHistorySlots
+const ACCOUNT_HISTORY_SLOTS_CF: &str = "account_history_slots";
+/// Column family for AccountHistorySessions
+const ACCOUNT_HISTORY_SESSIONS_CF: &str = "account_history_sessions";
 
 // 1 day is chosen for the same reasoning of DEFAULT_COMPACTION_SLOT_INTERVAL
 const PERIODIC_COMPACTION_SECONDS: u64 = 60 * 60 * 24;
@@ -187,6 +194,19 @@ pub mod columns {
     #[derive(Debug)]
     // The program costs column
     pub struct ProgramCosts;
+
+    #[derive(Debug)]
+    /// The account history column
+    pub struct AccountHistory;
+
+    #[derive(Debug)]
+    /// The account history slots column, indexing the account history by slot so that it can be
+    /// purged without scanning every account
+    pub struct AccountHistorySlots;
+
+    #[derive(Debug)]
+    /// The account history sessions column
+    pub struct AccountHistorySessions;
 }
 
 pub enum AccessType {
@@ -357,6 +377,18 @@ impl Rocks {
             ProgramCosts::NAME,
             get_cf_options::<ProgramCosts>(&access_type, &oldest_slot),
         );
+        let account_history_cf_descriptor = ColumnFamilyDescriptor::new(
+            AccountHistory::NAME,
+            get_cf_options::<AccountHistory>(&access_type, &oldest_slot),
+        );
+        let account_history_slots_cf_descriptor = ColumnFamilyDescriptor::new(
+            AccountHistorySlots::NAME,
+            get_cf_options::<AccountHistorySlots>(&access_type, &oldest_slot),
+        );
+        let account_history_sessions_cf_descriptor = ColumnFamilyDescriptor::new(
+            AccountHistorySessions::NAME,
+            get_cf_options::<AccountHistorySessions>(&access_type, &oldest_slot),
+        );
         // Don't forget to add to both run_purge_with_stats() and
         // compact_storage() in ledger/src/blockstore/blockstore_purge.rs!!
 
@@ -382,6 +414,15 @@ impl Rocks {
             (PerfSamples::NAME, perf_samples_cf_descriptor),
             (BlockHeight::NAME, block_height_cf_descriptor),
             (ProgramCosts::NAME, program_costs_cf_descriptor),
+            (AccountHistory::NAME, account_history_cf_descriptor),
+            (
+                AccountHistorySlots::NAME,
+                account_history_slots_cf_descriptor,
+            ),
+            (
+                AccountHistorySessions::NAME,
+                account_history_sessions_cf_descriptor,
+            ),
         ];
         let cf_names: Vec<_> = cfs.iter().map(|c| c.0).collect();
 
@@ -501,6 +542,9 @@ impl Rocks {
             PerfSamples::NAME,
             BlockHeight::NAME,
             ProgramCosts::NAME,
+            AccountHistory::NAME,
+            AccountHistorySlots::NAME,
+            AccountHistorySessions::NAME,
         ]
     }
 
@@ -772,6 +816,14 @@ impl TypedColumn for columns::BlockHeight {
     type Type = u64;
 }
 
+impl SlotColumn for columns::AccountHistorySessions {}
+impl ColumnName for columns::AccountHistorySessions {
+    const NAME: &'static str = ACCOUNT_HISTORY_SESSIONS_CF;
+}
+impl TypedColumn for columns::AccountHistorySessions {
+    type Type = blockstore_meta::AccountHistorySession;
+}
+
 impl ColumnName for columns::ProgramCosts {
     const NAME: &'static str = PROGRAM_COSTS_CF;
 }
@@ -805,6 +857,75 @@ impl Column for columns::ProgramCosts {
     }
 }
 
+impl ColumnName for columns::AccountHistory {
+    const NAME: &'static str = ACCOUNT_HISTORY_CF;
+}
+impl TypedColumn for columns::AccountHistory {
+    type Type = Account;
+}
+impl Column for columns::AccountHistory {
+    type Index = (Pubkey, Slot);
+
+    fn key((pubkey, slot): (Pubkey, Slot)) -> Vec<u8> {
+        let mut key = vec![0; 32 + 8]; // size_of Pubkey + size_of Slot
+        key[0..32].clone_from_slice(&pubkey.as_ref()[0..32]);
+        BigEndian::write_u64(&mut key[32..40], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Pubkey, Slot) {
+        let pubkey = Pubkey::new(&key[0..32]);
+        let slot = BigEndian::read_u64(&key[32..40]);
+        (pubkey, slot)
+    }
+
+    fn primary_index(_index: Self::Index) -> u64 {
+        unimplemented!()
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.1
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn as_index(slot: u64) -> Self::Index {
+        (Pubkey::default(), slot)
+    }
+}
+
+impl ColumnName for columns::AccountHistorySlots {
+    const NAME: &'static str = ACCOUNT_HISTORY_SLOTS_CF;
+}
+impl Column for columns::AccountHistorySlots {
+    type Index = (Slot, Pubkey);
+
+    fn key((slot, pubkey): (Slot, Pubkey)) -> Vec<u8> {
+        let mut key = vec![0; 8 + 32]; // size_of Slot + size_of Pubkey
+        BigEndian::write_u64(&mut key[0..8], slot);
+        key[8..40].clone_from_slice(&pubkey.as_ref()[0..32]);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Slot, Pubkey) {
+        let slot = BigEndian::read_u64(&key[0..8]);
+        let pubkey = Pubkey::new(&key[8..40]);
+        (slot, pubkey)
+    }
+
+    fn primary_index(_index: Self::Index) -> u64 {
+        unimplemented!()
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn as_index(slot: u64) -> Self::Index {
+        (slot, Pubkey::default())
+    }
+}
+
 impl Column for columns::ShredCode {
     type Index = (u64, u64);
 
@@ -1327,8 +1448,11 @@ fn get_cf_options<C: 'static + Column + ColumnName>(
     options.set_max_bytes_for_level_base(total_size_base);
     options.set_target_file_size_base(file_size_base);
 
-    // TransactionStatusIndex and ProgramCosts must be excluded from LedgerCleanupService's rocksdb
-    // compactions....
+    // TransactionStatusIndex, ProgramCosts and the AccountHistory columns must be excluded
+    // from LedgerCleanupService's rocksdb compactions.... AccountHistory is purged by
+    // Blockstore::run_purge_with_stats() instead, which keeps the latest state of each account
+    // and looks up the slots to purge in AccountHistorySlots before deleting their range. The
+    // AccountHistorySessions are kept, as a session may span any number of slots
     if matches!(access_type, AccessType::PrimaryOnly) && !excludes_from_compaction(C::NAME) {
         options.set_compaction_filter_factory(PurgedSlotFilterFactory::<C> {
             oldest_slot: oldest_slot.clone(),
@@ -1374,6 +1498,9 @@ fn excludes_from_compaction(cf_name: &str) -> bool {
     let no_compaction_cfs: HashSet<&'static str> = vec![
         columns::TransactionStatusIndex::NAME,
         columns::ProgramCosts::NAME,
+        columns::AccountHistory::NAME,
+        columns::AccountHistorySlots::NAME,
+        columns::AccountHistorySessions::NAME,
     ]
     .into_iter()
     .collect();
@@ -1436,11 +1563,16 @@ pub mod tests {
 
     #[test]
     fn test_excludes_from_compaction() {
-        // currently there are two CFs are excluded from compaction:
+        // currently there are three CFs are excluded from compaction:
         assert!(excludes_from_compaction(
             columns::TransactionStatusIndex::NAME
         ));
         assert!(excludes_from_compaction(columns::ProgramCosts::NAME));
+        assert!(excludes_from_compaction(columns::AccountHistory::NAME));
+        assert!(excludes_from_compaction(columns::AccountHistorySlots::NAME));
+        assert!(excludes_from_compaction(
+            columns::AccountHistorySessions::NAME
+        ));
         assert!(!excludes_from_compaction("something else"));
     }
 }
@@ -1,6 +1,6 @@
 use crate::erasure::ErasureConfig;
 use serde::{Deserialize, Serialize};
-use solana_sdk::{clock::Slot, hash::Hash};
+use solana_sdk::{clock::Slot, hash::Hash, pubkey::Pubkey};
 use std::{collections::BTreeSet, ops::RangeBounds};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
@@ -285,6 +285,17 @@ pub struct ProgramCost {
     pub cost: u64,
 }
 
+/// A run of rooted slots whose account history was recorded without gaps, keyed by its first
+/// slot. The state of an account at a slot of the session is known only if it was recorded
+/// during the session, since the accounts before its first slot were never recorded
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct AccountHistorySession {
+    /// The last slot recorded
+    pub last_slot: Slot,
+    /// The owners of the recorded accounts, besides closed accounts; all accounts if `None`
+    pub owners: Option<Vec<Pubkey>>,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -21,6 +21,7 @@ use solana_runtime::{
     bank::{
         Bank, ExecuteTimings, InnerInstructionsList, RentDebits, TransactionBalancesSet,
         TransactionExecutionResult, TransactionLogMessages, TransactionResults,
+        TransactionReturnData,
     },
     bank_forks::BankForks,
     bank_utils,
@@ -167,7 +168,7 @@ fn execute_batch(
 
     let pre_process_units: u64 = aggregate_total_execution_units(timings);
 
-    let (tx_results, balances, inner_instructions, transaction_logs) =
+    let (tx_results, balances, inner_instructions, transaction_logs, transaction_return_data) =
         batch.bank().load_execute_and_commit_transactions(
             batch,
             MAX_PROCESSING_AGE,
@@ -232,6 +233,7 @@ fn execute_batch(
             token_balances,
             inner_instructions,
             transaction_logs,
+            transaction_return_data,
             rent_debits,
         );
     }
@@ -1303,6 +1305,7 @@ pub struct TransactionStatusBatch {
     pub token_balances: TransactionTokenBalancesSet,
     pub inner_instructions: Option<Vec<Option<InnerInstructionsList>>>,
     pub transaction_logs: Option<Vec<Option<TransactionLogMessages>>>,
+    pub transaction_return_data: Vec<Option<TransactionReturnData>>,
     pub rent_debits: Vec<RentDebits>,
 }
 
@@ -1322,6 +1325,7 @@ impl TransactionStatusSender {
         token_balances: TransactionTokenBalancesSet,
         inner_instructions: Vec<Option<InnerInstructionsList>>,
         transaction_logs: Vec<Option<TransactionLogMessages>>,
+        transaction_return_data: Vec<Option<TransactionReturnData>>,
         rent_debits: Vec<RentDebits>,
     ) {
         let slot = bank.slot();
@@ -1340,6 +1344,7 @@ impl TransactionStatusSender {
                 token_balances,
                 inner_instructions,
                 transaction_logs,
+                transaction_return_data,
                 rent_debits,
             }))
         {
@@ -3254,6 +3259,7 @@ pub mod tests {
             _balances,
             _inner_instructions,
             _log_messages,
+            _return_data,
         ) = batch.bank().load_execute_and_commit_transactions(
             &batch,
             MAX_PROCESSING_AGE,
@@ -3,6 +3,7 @@
 #[macro_use]
 extern crate solana_bpf_loader_program;
 
+pub mod account_history;
 pub mod bank_forks_utils;
 pub mod bigtable_upload;
 pub mod bigtable_upload_service;
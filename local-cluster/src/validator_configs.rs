@@ -16,6 +16,7 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         rpc_addrs: config.rpc_addrs,
         pubsub_config: config.pubsub_config.clone(),
         snapshot_config: config.snapshot_config.clone(),
+        snapshot_uploader: config.snapshot_uploader.clone(),
         max_ledger_shreds: config.max_ledger_shreds,
         broadcast_stage_type: config.broadcast_stage_type.clone(),
         enable_partition: config.enable_partition.clone(),
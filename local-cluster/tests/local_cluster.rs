@@ -208,6 +208,7 @@ fn test_local_cluster_signature_subscribe() {
         Some(RpcSignatureSubscribeConfig {
             commitment: Some(CommitmentConfig::processed()),
             enable_received_notification: Some(true),
+            from_slot: None,
         }),
     )
     .unwrap();
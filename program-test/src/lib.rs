@@ -324,6 +324,9 @@ impl solana_sdk::program_stubs::SyscallStubs for SyscallStubs {
         }
 
         invoke_context.record_instruction(instruction);
+        invoke_context
+            .set_return_data(Vec::new())
+            .map_err(|err| ProgramError::try_from(err).unwrap_or_else(|err| panic!("{}", err)))?;
 
         InstructionProcessor::process_cross_program_instruction(
             &message,
@@ -388,6 +391,26 @@ impl solana_sdk::program_stubs::SyscallStubs for SyscallStubs {
     fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
         get_sysvar::<Rent>(&rent::id(), var_addr)
     }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let invoke_context = get_invoke_context();
+        stable_log::program_data(&invoke_context.get_logger(), fields);
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let (program_id, data) = get_invoke_context().get_return_data();
+        if data.is_empty() {
+            None
+        } else {
+            Some((program_id, data.to_vec()))
+        }
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        get_invoke_context()
+            .set_return_data(data.to_vec())
+            .expect("set_return_data");
+    }
 }
 
 pub fn find_file(filename: &str) -> Option<PathBuf> {
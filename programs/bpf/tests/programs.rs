@@ -294,7 +294,7 @@ fn process_transaction_and_record_inner(
     let signature = tx.signatures.get(0).unwrap().clone();
     let txs = vec![tx];
     let tx_batch = bank.prepare_batch(txs).unwrap();
-    let (mut results, _, mut inner_instructions, _transaction_logs) = bank
+    let (mut results, _, mut inner_instructions, _transaction_logs, _return_data) = bank
         .load_execute_and_commit_transactions(
             &tx_batch,
             MAX_PROCESSING_AGE,
@@ -331,6 +331,7 @@ fn execute_transactions(bank: &Bank, txs: Vec<Transaction>) -> Vec<ConfirmedTran
         },
         inner_instructions,
         transaction_logs,
+        transaction_return_data,
     ) = bank.load_execute_and_commit_transactions(
         &batch,
         std::usize::MAX,
@@ -350,6 +351,7 @@ fn execute_transactions(bank: &Bank, txs: Vec<Transaction>) -> Vec<ConfirmedTran
         tx_pre_token_balances.into_iter(),
         tx_post_token_balances.into_iter(),
         transaction_logs.into_iter(),
+        transaction_return_data.into_iter(),
     )
     .map(
         |(
@@ -361,6 +363,7 @@ fn execute_transactions(bank: &Bank, txs: Vec<Transaction>) -> Vec<ConfirmedTran
             pre_token_balances,
             post_token_balances,
             log_messages,
+            return_data,
         )| {
             #[allow(deprecated)]
             let fee_calculator = nonce_rollback
@@ -392,6 +395,7 @@ fn execute_transactions(bank: &Bank, txs: Vec<Transaction>) -> Vec<ConfirmedTran
                 inner_instructions,
                 log_messages,
                 rewards: None,
+                return_data,
             };
 
             ConfirmedTransaction {
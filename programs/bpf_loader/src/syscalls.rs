@@ -23,7 +23,8 @@ use solana_sdk::{
     feature_set::{
         blake3_syscall_enabled, close_upgradeable_program_accounts, disable_fees_sysvar,
         enforce_aligned_host_addrs, libsecp256k1_0_5_upgrade_enabled, mem_overlap_fix,
-        secp256k1_recover_syscall_enabled,
+        return_data_syscall_enabled, secp256k1_recover_syscall_enabled,
+        sol_log_data_syscall_enabled,
     },
     hash::{Hasher, HASH_BYTES},
     ic_msg,
@@ -32,6 +33,7 @@ use solana_sdk::{
     keyed_account::KeyedAccount,
     native_loader,
     process_instruction::{self, stable_log, ComputeMeter, InvokeContext, Logger},
+    program::MAX_RETURN_DATA,
     pubkey::{Pubkey, PubkeyError, MAX_SEEDS},
     rent::Rent,
     secp256k1_recover::{
@@ -81,6 +83,8 @@ pub enum SyscallError {
     TooManyAccounts,
     #[error("Overlapping copy")]
     CopyOverlapping,
+    #[error("Return data too large ({0} > {1})")]
+    ReturnDataTooLarge(u64, u64),
 }
 impl From<SyscallError> for EbpfError<BpfError> {
     fn from(error: SyscallError) -> Self {
@@ -172,6 +176,19 @@ pub fn register_syscalls(
     // Memory allocator
     syscall_registry.register_syscall_by_name(b"sol_alloc_free_", SyscallAllocFree::call)?;
 
+    // Log data
+    if invoke_context.is_feature_active(&sol_log_data_syscall_enabled::id()) {
+        syscall_registry.register_syscall_by_name(b"sol_log_data", SyscallLogData::call)?;
+    }
+
+    // Return data
+    if invoke_context.is_feature_active(&return_data_syscall_enabled::id()) {
+        syscall_registry
+            .register_syscall_by_name(b"sol_set_return_data", SyscallSetReturnData::call)?;
+        syscall_registry
+            .register_syscall_by_name(b"sol_get_return_data", SyscallGetReturnData::call)?;
+    }
+
     Ok(syscall_registry)
 }
 
@@ -345,8 +362,21 @@ pub fn bind_syscall_context_objects<'a>(
         }),
     );
 
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        invoke_context.is_feature_active(&sol_log_data_syscall_enabled::id()),
+        Box::new(SyscallLogData {
+            cost: compute_budget.syscall_base_cost,
+            compute_meter: invoke_context.get_compute_meter(),
+            logger: invoke_context.get_logger(),
+            loader_id,
+        }),
+    );
+
     let is_fee_sysvar_via_syscall_active =
         !invoke_context.is_feature_active(&disable_fees_sysvar::id());
+    let is_return_data_syscall_active =
+        invoke_context.is_feature_active(&return_data_syscall_enabled::id());
 
     let invoke_context = Rc::new(RefCell::new(invoke_context));
 
@@ -396,6 +426,24 @@ pub fn bind_syscall_context_objects<'a>(
         None,
     )?;
 
+    // Return data
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        is_return_data_syscall_active,
+        Box::new(SyscallSetReturnData {
+            invoke_context: invoke_context.clone(),
+            loader_id,
+        }),
+    );
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        is_return_data_syscall_active,
+        Box::new(SyscallGetReturnData {
+            invoke_context: invoke_context.clone(),
+            loader_id,
+        }),
+    );
+
     // Memory allocator
     vm.bind_syscall_context_object(
         Box::new(SyscallAllocFree {
@@ -750,6 +798,60 @@ impl<'a> SyscallObject<BpfError> for SyscallLogPubkey<'a> {
     }
 }
 
+/// Log data fields, each base64 encoded
+pub struct SyscallLogData<'a> {
+    cost: u64,
+    compute_meter: Rc<RefCell<dyn ComputeMeter>>,
+    logger: Rc<RefCell<dyn Logger>>,
+    loader_id: &'a Pubkey,
+}
+impl<'a> SyscallObject<BpfError> for SyscallLogData<'a> {
+    fn call(
+        &mut self,
+        addr: u64,
+        len: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        question_mark!(self.compute_meter.consume(self.cost), result);
+        let untranslated_fields = question_mark!(
+            translate_slice::<&[u8]>(memory_mapping, addr, len, self.loader_id, true),
+            result
+        );
+        question_mark!(
+            self.compute_meter
+                .consume(self.cost.saturating_mul(untranslated_fields.len() as u64)),
+            result
+        );
+        question_mark!(
+            self.compute_meter.consume(
+                untranslated_fields
+                    .iter()
+                    .fold(0, |total, field| total.saturating_add(field.len() as u64))
+            ),
+            result
+        );
+        let mut fields = Vec::with_capacity(untranslated_fields.len());
+        for untranslated_field in untranslated_fields {
+            fields.push(question_mark!(
+                translate_slice::<u8>(
+                    memory_mapping,
+                    untranslated_field.as_ptr() as u64,
+                    untranslated_field.len() as u64,
+                    self.loader_id,
+                    true,
+                ),
+                result
+            ));
+        }
+        stable_log::program_data(&self.logger, &fields);
+        *result = Ok(0);
+    }
+}
+
 /// Dynamic memory allocation syscall called when the BPF program calls
 /// `sol_alloc_free_()`.  The allocator is expected to allocate/free
 /// from/to a given chunk of memory and enforce size restrictions.  The
@@ -2391,6 +2493,12 @@ fn call<'a>(
         )
     };
 
+    // Clear the return data so that the caller only sees what the callee sets
+    syscall
+        .get_context_mut()?
+        .set_return_data(Vec::new())
+        .map_err(SyscallError::InstructionError)?;
+
     // Process instruction
 
     #[allow(clippy::deref_addrof)]
@@ -2463,6 +2571,122 @@ fn call<'a>(
     Ok(SUCCESS)
 }
 
+// Return data handling
+
+/// Set the return data of the running program
+struct SyscallSetReturnData<'a> {
+    invoke_context: Rc<RefCell<&'a mut dyn InvokeContext>>,
+    loader_id: &'a Pubkey,
+}
+impl<'a> SyscallObject<BpfError> for SyscallSetReturnData<'a> {
+    fn call(
+        &mut self,
+        addr: u64,
+        len: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        let mut invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow_mut()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+
+        let compute_budget = invoke_context.get_compute_budget();
+        let cost = len / compute_budget.cpi_bytes_per_unit + compute_budget.syscall_base_cost;
+        question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+        if len > MAX_RETURN_DATA as u64 {
+            *result = Err(SyscallError::ReturnDataTooLarge(len, MAX_RETURN_DATA as u64).into());
+            return;
+        }
+
+        let return_data = if len == 0 {
+            Vec::new()
+        } else {
+            question_mark!(
+                translate_slice::<u8>(memory_mapping, addr, len, self.loader_id, true),
+                result
+            )
+            .to_vec()
+        };
+        question_mark!(
+            invoke_context
+                .set_return_data(return_data)
+                .map_err(SyscallError::InstructionError),
+            result
+        );
+
+        *result = Ok(0);
+    }
+}
+
+/// Get the return data set by the last program to set it, and that program's id
+struct SyscallGetReturnData<'a> {
+    invoke_context: Rc<RefCell<&'a mut dyn InvokeContext>>,
+    loader_id: &'a Pubkey,
+}
+impl<'a> SyscallObject<BpfError> for SyscallGetReturnData<'a> {
+    fn call(
+        &mut self,
+        return_data_addr: u64,
+        len: u64,
+        program_id_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        let invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+
+        let compute_budget = invoke_context.get_compute_budget();
+        question_mark!(
+            invoke_context
+                .get_compute_meter()
+                .consume(compute_budget.syscall_base_cost),
+            result
+        );
+
+        let (program_id, return_data) = invoke_context.get_return_data();
+        let length = len.min(return_data.len() as u64);
+        if length != 0 {
+            let cost = (length + size_of::<Pubkey>() as u64) / compute_budget.cpi_bytes_per_unit;
+            question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+            let return_data_result = question_mark!(
+                translate_slice_mut::<u8>(
+                    memory_mapping,
+                    return_data_addr,
+                    length,
+                    self.loader_id,
+                    true,
+                ),
+                result
+            );
+            return_data_result.copy_from_slice(&return_data[..length as usize]);
+
+            let program_id_result = question_mark!(
+                translate_type_mut::<Pubkey>(memory_mapping, program_id_addr, self.loader_id, true),
+                result
+            );
+            *program_id_result = program_id;
+        }
+
+        // Return the actual length, rather than the length copied, so that the program can tell
+        // when its buffer was too small
+        *result = Ok(return_data.len() as u64);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2982,6 +3206,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_syscall_sol_log_data() {
+        let bytes1 = "Gaggablaghblagh!";
+        let bytes2 = "flurbos";
+
+        #[allow(dead_code)]
+        struct MockSlice {
+            pub addr: u64,
+            pub len: usize,
+        }
+        let fields = [
+            MockSlice {
+                addr: 0x200000000,
+                len: bytes1.len(),
+            },
+            MockSlice {
+                addr: 0x300000000,
+                len: bytes2.len(),
+            },
+        ];
+        let fields_va = 0x100000000;
+        let config = Config::default();
+        let memory_mapping = MemoryMapping::new::<UserError>(
+            vec![
+                MemoryRegion::default(),
+                MemoryRegion {
+                    host_addr: fields.as_ptr() as *const _ as u64,
+                    vm_addr: fields_va,
+                    len: 32,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+                MemoryRegion {
+                    host_addr: bytes1.as_ptr() as *const _ as u64,
+                    vm_addr: fields[0].addr,
+                    len: bytes1.len() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+                MemoryRegion {
+                    host_addr: bytes2.as_ptr() as *const _ as u64,
+                    vm_addr: fields[1].addr,
+                    len: bytes2.len() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+            ],
+            &config,
+        )
+        .unwrap();
+
+        let cost = 100;
+        let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
+            Rc::new(RefCell::new(MockComputeMeter {
+                remaining: cost * 3 + (bytes1.len() + bytes2.len()) as u64,
+            }));
+        let log = Rc::new(RefCell::new(vec![]));
+        let logger: Rc<RefCell<dyn Logger>> =
+            Rc::new(RefCell::new(MockLogger { log: log.clone() }));
+        let mut syscall = SyscallLogData {
+            cost,
+            compute_meter,
+            logger,
+            loader_id: &bpf_loader::id(),
+        };
+
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(1);
+        syscall.call(fields_va, 2, 0, 0, 0, &memory_mapping, &mut result);
+        assert_eq!(result.unwrap(), 0);
+        assert_eq!(
+            log.borrow().as_slice(),
+            &["Program data: R2FnZ2FibGFnaGJsYWdoIQ== Zmx1cmJvcw==".to_string()]
+        );
+
+        // Out of compute units
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(1);
+        syscall.call(fields_va, 2, 0, 0, 0, &memory_mapping, &mut result);
+        assert_eq!(
+            result,
+            Err(EbpfError::UserError(BpfError::SyscallError(
+                SyscallError::InstructionError(InstructionError::ComputationalBudgetExceeded)
+            )))
+        );
+        assert_eq!(log.borrow().len(), 1);
+    }
+
     #[test]
     fn test_syscall_sol_log_u64() {
         let compute_meter: Rc<RefCell<dyn ComputeMeter>> =
@@ -3504,6 +3814,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_syscall_return_data() {
+        let config = Config::default();
+        let program_id = Pubkey::new_unique();
+        let set_data = [1u8, 2, 3];
+        let set_data_va = 0x100000000;
+        let mut got_data = [0u8; 2];
+        let got_data_va = 0x200000000;
+        let mut got_program_id = Pubkey::default();
+        let got_program_id_va = 0x300000000;
+        let memory_mapping = MemoryMapping::new::<UserError>(
+            vec![
+                MemoryRegion::default(),
+                MemoryRegion {
+                    host_addr: set_data.as_ptr() as u64,
+                    vm_addr: set_data_va,
+                    len: set_data.len() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+                MemoryRegion {
+                    host_addr: got_data.as_mut_ptr() as u64,
+                    vm_addr: got_data_va,
+                    len: got_data.len() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: true,
+                },
+                MemoryRegion {
+                    host_addr: &mut got_program_id as *mut _ as u64,
+                    vm_addr: got_program_id_va,
+                    len: size_of::<Pubkey>() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: true,
+                },
+            ],
+            &config,
+        )
+        .unwrap();
+
+        let mut invoke_context = MockInvokeContext::new(vec![]);
+        invoke_context.invoke_stack[0].key = program_id;
+        let invoke_context = Rc::new(RefCell::new(&mut invoke_context as &mut dyn InvokeContext));
+
+        let mut set_syscall = SyscallSetReturnData {
+            invoke_context: invoke_context.clone(),
+            loader_id: &bpf_loader::id(),
+        };
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(1);
+        set_syscall.call(set_data_va, 3, 0, 0, 0, &memory_mapping, &mut result);
+        assert_eq!(result.unwrap(), 0);
+        assert_eq!(
+            invoke_context.borrow().get_return_data(),
+            (program_id, &set_data[..])
+        );
+
+        // The buffer is too small for all of the data, so only part of it is copied but the full
+        // length is returned
+        let mut get_syscall = SyscallGetReturnData {
+            invoke_context: invoke_context.clone(),
+            loader_id: &bpf_loader::id(),
+        };
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        get_syscall.call(
+            got_data_va,
+            got_data.len() as u64,
+            got_program_id_va,
+            0,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(got_data, [1, 2]);
+        assert_eq!(got_program_id, program_id);
+
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        set_syscall.call(
+            set_data_va,
+            MAX_RETURN_DATA as u64 + 1,
+            0,
+            0,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(
+            result,
+            Err(EbpfError::UserError(BpfError::SyscallError(
+                SyscallError::ReturnDataTooLarge(
+                    MAX_RETURN_DATA as u64 + 1,
+                    MAX_RETURN_DATA as u64
+                )
+            )))
+        );
+
+        // Setting empty return data clears it
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(1);
+        set_syscall.call(0, 0, 0, 0, 0, &memory_mapping, &mut result);
+        assert_eq!(result.unwrap(), 0);
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(1);
+        get_syscall.call(
+            got_data_va,
+            got_data.len() as u64,
+            got_program_id_va,
+            0,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(result.unwrap(), 0);
+    }
+
     #[test]
     fn test_overlapping() {
         assert!(!check_overlapping(10, 7, 3));
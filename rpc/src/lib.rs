@@ -4,10 +4,12 @@ pub mod max_slots;
 pub mod optimistically_confirmed_bank_tracker;
 pub mod parsed_token_accounts;
 pub mod rpc;
+pub mod rpc_access_log;
 pub mod rpc_completed_slots_service;
 pub mod rpc_health;
 pub mod rpc_pubsub;
 pub mod rpc_pubsub_service;
+pub mod rpc_rate_limiter;
 pub mod rpc_service;
 pub mod rpc_subscriptions;
 pub mod transaction_status_service;
@@ -15,9 +17,6 @@ pub mod transaction_status_service;
 #[macro_use]
 extern crate log;
 
-#[macro_use]
-extern crate serde_derive;
-
 #[cfg(test)]
 #[macro_use]
 extern crate serde_json;
@@ -2,8 +2,12 @@
 
 use {
     crate::{
-        max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_health::*,
+        max_slots::MaxSlots,
+        optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
+        parsed_token_accounts::*,
+        rpc_access_log::{PendingRpcAccessLogEntries, RpcAccessLogConfig},
+        rpc_health::*,
+        rpc_rate_limiter::RpcRateLimitConfig,
     },
     bincode::{config::Options, serialize},
     jsonrpc_core::{futures::future, types::error, BoxFuture, Error, Metadata, Result},
@@ -22,9 +26,10 @@ use {
         rpc_request::{
             TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
-            MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            NUM_LARGEST_ACCOUNTS,
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE,
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS,
+            MAX_GET_TOKEN_HOLDERS_PAGE_LIMIT, MAX_MULTIPLE_ACCOUNTS, NUM_LARGEST_ACCOUNTS,
         },
         rpc_response::Response as RpcResponse,
         rpc_response::*,
@@ -32,14 +37,15 @@ use {
     solana_faucet::faucet::request_airdrop_transaction,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_ledger::{
-        blockstore::Blockstore, blockstore_db::BlockstoreError, get_tmp_ledger_path,
+        account_history::AccountHistoryConfig, blockstore::Blockstore,
+        blockstore_db::BlockstoreError, get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
     solana_runtime::{
         accounts::AccountAddressFilter,
-        accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey},
+        accounts_index::{AccountIndex, AccountSecondaryIndexes, CustomAccountIndex, IndexKey},
         bank::{Bank, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
@@ -58,7 +64,7 @@ use {
         exit::Exit,
         hash::Hash,
         message::{Message, SanitizedMessage},
-        pubkey::Pubkey,
+        pubkey::{Pubkey, PUBKEY_BYTES},
         signature::{Keypair, Signature, Signer},
         stake::state::StakeState,
         stake_history::StakeHistory,
@@ -70,10 +76,13 @@ use {
         send_transaction_service::{SendTransactionService, TransactionInfo},
         tpu_info::NullTpuInfo,
     },
+    solana_storage_bigtable::LedgerStorageAdapter,
     solana_streamer::socket::SocketAddrSpace,
     solana_transaction_status::{
-        ConfirmedBlock, EncodedConfirmedTransaction, Reward, RewardType,
-        TransactionConfirmationStatus, TransactionStatus, UiConfirmedBlock, UiTransactionEncoding,
+        token_balances::collect_simulated_token_balances, ConfirmedBlock,
+        EncodedConfirmedTransaction, InnerInstructions, Reward, RewardType,
+        TransactionConfirmationStatus, TransactionStatus, UiConfirmedBlock, UiInnerInstructions,
+        UiTransactionEncoding,
     },
     solana_vote_program::vote_state::{VoteState, MAX_LOCKOUT_HISTORY},
     spl_token_v2_0::{
@@ -82,10 +91,11 @@ use {
     },
     std::{
         any::type_name,
-        cmp::{max, min},
+        cmp::{max, min, Reverse},
         collections::{HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -120,6 +130,52 @@ pub enum OptionalContext<T> {
     NoContext(T),
 }
 
+/// Position of a `getProgramAccountsPaginated` scan, handed to clients as an opaque string: the
+/// slot of the bank the previous page was read from and the last pubkey it returned
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ProgramAccountsCursor {
+    slot: Slot,
+    last_pubkey: Pubkey,
+}
+
+impl ProgramAccountsCursor {
+    fn encode(&self) -> String {
+        bs58::encode(serialize(self).unwrap()).into_string()
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        bs58::decode(cursor)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bincode::deserialize(&bytes).ok())
+            .ok_or_else(|| Error::invalid_params("Invalid param: cursor"))
+    }
+}
+
+/// Position of a `getTokenHolders` listing, handed to clients as an opaque string: the slot of
+/// the bank the previous page was read from, and the balance and address of the last holder it
+/// returned
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct TokenHoldersCursor {
+    slot: Slot,
+    last_amount: u64,
+    last_address: Pubkey,
+}
+
+impl TokenHoldersCursor {
+    fn encode(&self) -> String {
+        bs58::encode(serialize(self).unwrap()).into_string()
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        bs58::decode(cursor)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bincode::deserialize(&bytes).ok())
+            .ok_or_else(|| Error::invalid_params("Invalid param: cursor"))
+    }
+}
+
 fn is_finalized(
     block_commitment_cache: &BlockCommitmentCache,
     bank: &Bank,
@@ -142,6 +198,18 @@ pub struct JsonRpcConfig {
     pub account_indexes: AccountSecondaryIndexes,
     pub rpc_threads: usize,
     pub rpc_bigtable_timeout: Option<Duration>,
+    /// Serve and upload long-term ledger storage from this local archive instead of BigTable
+    pub local_ledger_storage_path: Option<PathBuf>,
+    /// Record the accounts written in each rooted slot, for `getAccountInfoAtSlot`
+    pub account_history: Option<AccountHistoryConfig>,
+    /// Serve `getAccountProof` from the Merkle tree of the latest accounts hash published
+    pub enable_rpc_account_proofs: bool,
+    pub rate_limit: RpcRateLimitConfig,
+    pub access_log: RpcAccessLogConfig,
+    /// HTTP header that carries the client's address, as appended by a trusted reverse proxy.
+    /// Rate limits and the access log identify clients by its last entry, or by the peer address
+    /// of the connection when the header is not configured or absent
+    pub client_address_header: Option<String>,
     pub minimal_api: bool,
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
@@ -159,12 +227,14 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Arc<Mutex<Sender<TransactionInfo>>>,
-    bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+    bigtable_ledger_storage: Option<Arc<dyn LedgerStorageAdapter>>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
     max_slots: Arc<MaxSlots>,
     leader_schedule_cache: Arc<LeaderScheduleCache>,
     max_complete_transaction_status_slot: Arc<AtomicU64>,
+    client_address: Option<String>,
+    access_log_entries: Option<PendingRpcAccessLogEntries>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -248,7 +318,7 @@ impl JsonRpcRequestProcessor {
         health: Arc<RpcHealth>,
         cluster_info: Arc<ClusterInfo>,
         genesis_hash: Hash,
-        bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+        bigtable_ledger_storage: Option<Arc<dyn LedgerStorageAdapter>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
         max_slots: Arc<MaxSlots>,
@@ -274,6 +344,8 @@ impl JsonRpcRequestProcessor {
                 max_slots,
                 leader_schedule_cache,
                 max_complete_transaction_status_slot,
+                client_address: None,
+                access_log_entries: None,
             },
             receiver,
         )
@@ -327,9 +399,35 @@ impl JsonRpcRequestProcessor {
             max_slots: Arc::new(MaxSlots::default()),
             leader_schedule_cache: Arc::new(LeaderScheduleCache::new_from_bank(bank)),
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
+            client_address: None,
+            access_log_entries: None,
         }
     }
 
+    /// The address of the client that sent the request being processed, if known
+    pub fn client_address(&self) -> Option<&str> {
+        self.client_address.as_deref()
+    }
+
+    pub(crate) fn with_client_address(mut self, client_address: Option<String>) -> Self {
+        self.client_address = client_address;
+        self
+    }
+
+    /// Collect the access log entries of the calls of the request being processed, to be
+    /// recorded once its response has been written
+    pub(crate) fn with_access_log_entries(
+        mut self,
+        access_log_entries: Option<PendingRpcAccessLogEntries>,
+    ) -> Self {
+        self.access_log_entries = access_log_entries;
+        self
+    }
+
+    pub(crate) fn access_log_entries(&self) -> Option<&PendingRpcAccessLogEntries> {
+        self.access_log_entries.as_ref()
+    }
+
     pub fn get_account_info(
         &self,
         pubkey: &Pubkey,
@@ -344,6 +442,120 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, response))
     }
 
+    pub fn get_account_proof(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Result<RpcResponse<Option<RpcAccountProof>>> {
+        if !self.config.enable_rpc_account_proofs {
+            return Err(RpcCustomError::AccountProofsNotAvailable.into());
+        }
+        let bank = self.bank(Some(CommitmentConfig::finalized()));
+        // Only prove accounts against an accounts hash this node published, which clients can
+        // compare with the accounts hashes of other validators
+        let tree = bank
+            .accounts_hash_tree()
+            .filter(|tree| {
+                tree.slot() <= bank.slot()
+                    && self
+                        .cluster_info
+                        .get_accounts_hash_for_node(&self.cluster_info.id(), |hashes| {
+                            hashes.contains(&(tree.slot(), tree.root()))
+                        })
+                        .unwrap_or(false)
+            })
+            .ok_or(RpcCustomError::AccountsHashNotPublished)?;
+        let value = match bank.get_account_proof(&tree, pubkey) {
+            Some((account, last_write_slot, proof)) => Some(RpcAccountProof {
+                slot: tree.slot(),
+                account: encode_account(&account, pubkey, UiAccountEncoding::Base64, None)?,
+                last_write_slot,
+                proof: proof.levels.iter().map(RpcMerkleProofLevel::from).collect(),
+                accounts_hash: tree.root().to_string(),
+            }),
+            None if tree.get_proof(pubkey).is_some() => {
+                return Err(
+                    RpcCustomError::AccountWrittenSinceAccountsHash { slot: tree.slot() }.into(),
+                );
+            }
+            None => None,
+        };
+        Ok(Response {
+            context: RpcResponseContext { slot: tree.slot() },
+            value,
+        })
+    }
+
+    pub fn get_account_info_at_slot(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> Result<RpcResponse<Option<UiAccount>>> {
+        if self.config.account_history.is_none() {
+            return Err(RpcCustomError::AccountHistoryNotAvailable.into());
+        }
+        let config = config.unwrap_or_default();
+        let encoding = config.encoding.unwrap_or(UiAccountEncoding::Base64);
+        check_slice_and_encoding(&encoding, config.data_slice.is_some())?;
+
+        let bank = self.bank(Some(CommitmentConfig::finalized()));
+        if slot > bank.slot() {
+            return Err(RpcCustomError::BlockNotAvailable { slot }.into());
+        }
+
+        // An account that hasn't been written since `slot` is read from the finalized bank,
+        // otherwise from the history recorded in the session that includes `slot`
+        let account = match bank.get_account_modified_slot(pubkey) {
+            Some((account, modified_slot)) if modified_slot <= slot => Some(account),
+            bank_account => {
+                let not_recorded = || RpcCustomError::AccountHistoryNotRecorded { slot };
+                let (first_slot, session) = self
+                    .blockstore
+                    .get_account_history_session(slot)
+                    .map_err(|err| Error::invalid_params(format!("{:?}", err)))?
+                    .filter(|(_, session)| slot <= session.last_slot)
+                    .ok_or_else(not_recorded)?;
+                let result = self.blockstore.get_account_at_slot(pubkey, slot);
+                self.check_slot_cleaned_up(&result, slot)?;
+                match result.map_err(|err| Error::invalid_params(format!("{:?}", err)))? {
+                    Some((written_slot, account)) if written_slot >= first_slot => {
+                        Some(AccountSharedData::from(account))
+                    }
+                    // With every account recorded up to the finalized bank, an account that
+                    // doesn't exist and was never written in the session never existed in it
+                    None if bank_account.is_none()
+                        && session.owners.is_none()
+                        && session.last_slot >= bank.slot()
+                        && self
+                            .blockstore
+                            .get_account_history(pubkey, first_slot, 1)
+                            .map_err(|err| Error::invalid_params(format!("{:?}", err)))?
+                            .is_empty() =>
+                    {
+                        None
+                    }
+                    _ => return Err(not_recorded().into()),
+                }
+            }
+        };
+        let response = match account.filter(|account| account.lamports() > 0) {
+            Some(account) => Some(
+                if account.owner() == &spl_token_id_v2_0()
+                    && encoding == UiAccountEncoding::JsonParsed
+                {
+                    get_parsed_token_account(bank, pubkey, account)
+                } else {
+                    encode_account(&account, pubkey, encoding, config.data_slice)?
+                },
+            ),
+            None => None,
+        };
+        Ok(Response {
+            context: RpcResponseContext { slot },
+            value: response,
+        })
+    }
+
     pub fn get_multiple_accounts(
         &self,
         pubkeys: Vec<Pubkey>,
@@ -390,6 +602,10 @@ impl JsonRpcRequestProcessor {
                 self.get_filtered_spl_token_accounts_by_owner(&bank, &owner, filters)?
             } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
                 self.get_filtered_spl_token_accounts_by_mint(&bank, &mint, filters)?
+            } else if let Some((index, key)) =
+                get_custom_index_filter(&self.config.account_indexes, program_id, &filters)
+            {
+                self.get_filtered_custom_indexed_accounts(&bank, &index, &key, filters)?
             } else {
                 self.get_filtered_program_accounts(&bank, program_id, filters)?
             }
@@ -413,6 +629,92 @@ impl JsonRpcRequestProcessor {
         })
     }
 
+    pub fn get_program_accounts_paginated(
+        &self,
+        program_id: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+        filters: Vec<RpcFilterType>,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+        let config = config.unwrap_or_default();
+        let cursor = cursor.map(ProgramAccountsCursor::decode).transpose()?;
+        let bank = match &cursor {
+            Some(cursor) => self.bank_for_cursor(cursor.slot, config.commitment)?,
+            None => self.bank(config.commitment),
+        };
+        let encoding = config.encoding.unwrap_or(UiAccountEncoding::Binary);
+        let data_slice_config = config.data_slice;
+        check_slice_and_encoding(&encoding, data_slice_config.is_some())?;
+        let keyed_accounts = bank
+            .get_filtered_program_accounts_paginated(
+                program_id,
+                cursor.as_ref().map(|cursor| &cursor.last_pubkey),
+                limit,
+                |account| {
+                    filters
+                        .iter()
+                        .all(|filter_type| filter_type.allows(account))
+                },
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?;
+        // A short page means the scan reached the end of the accounts index
+        let next_cursor = if keyed_accounts.len() == limit {
+            keyed_accounts.last().map(|(pubkey, _)| {
+                ProgramAccountsCursor {
+                    slot: bank.slot(),
+                    last_pubkey: *pubkey,
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+        let accounts =
+            if program_id == &spl_token_id_v2_0() && encoding == UiAccountEncoding::JsonParsed {
+                get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
+            } else {
+                let mut encoded_accounts = vec![];
+                for (pubkey, account) in keyed_accounts {
+                    encoded_accounts.push(RpcKeyedAccount {
+                        pubkey: pubkey.to_string(),
+                        account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
+                    });
+                }
+                encoded_accounts
+            };
+        Ok(new_response(
+            &bank,
+            RpcProgramAccountsPage {
+                accounts,
+                next_cursor,
+            },
+        ))
+    }
+
+    /// Pages of a paginated scan are read from the bank the cursor was issued at for as long as
+    /// it remains in BankForks. After that, the scan continues on the bank at the requested
+    /// commitment, provided this node has caught up with the cursor.
+    fn bank_for_cursor(
+        &self,
+        cursor_slot: Slot,
+        commitment: Option<CommitmentConfig>,
+    ) -> RpcCustomResult<Arc<Bank>> {
+        if let Some(bank) = self.bank_forks.read().unwrap().get(cursor_slot) {
+            return Ok(bank.clone());
+        }
+        let bank = self.bank(commitment);
+        if bank.slot() < cursor_slot {
+            return Err(RpcCustomError::CursorSlotNotReached {
+                cursor_slot,
+                context_slot: bank.slot(),
+            });
+        }
+        Ok(bank)
+    }
+
     pub async fn get_inflation_reward(
         &self,
         addresses: Vec<Pubkey>,
@@ -1682,6 +1984,117 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, token_balances))
     }
 
+    /// Every page reads all the token accounts of the mint, through the `SplTokenMint` index if
+    /// it is enabled, and returns those that sort after the cursor
+    pub fn get_token_holders(
+        &self,
+        mint: &Pubkey,
+        config: RpcTokenHoldersConfig,
+        limit: usize,
+    ) -> Result<RpcResponse<RpcTokenHoldersPage>> {
+        let cursor = config
+            .cursor
+            .as_deref()
+            .map(TokenHoldersCursor::decode)
+            .transpose()?;
+        let bank = match &cursor {
+            Some(cursor) => self.bank_for_cursor(cursor.slot, config.commitment)?,
+            None => self.bank(config.commitment),
+        };
+        let (mint_owner, decimals) = get_mint_owner_and_decimals(&bank, mint)?;
+        if mint_owner != spl_token_id_v2_0() {
+            return Err(Error::invalid_params(
+                "Invalid param: not a v2.0 Token mint".to_string(),
+            ));
+        }
+        let exclude_zero_balance = config.exclude_zero_balance.unwrap_or_default();
+        let unpack_holder = |account: &AccountSharedData| {
+            TokenAccount::unpack(account.data())
+                .ok()
+                .filter(|token_account| !exclude_zero_balance || token_account.amount > 0)
+        };
+
+        let (holders, has_more) = if config.sort == Some(RpcTokenHoldersSort::Balance) {
+            // Ordering by balance needs every token account of the mint, which only the mint
+            // index can list without scanning every account
+            if !self
+                .config
+                .account_indexes
+                .contains(&AccountIndex::SplTokenMint)
+            {
+                return Err(Error::invalid_params(
+                    "Invalid param: sorting by balance requires the spl-token-mint account index"
+                        .to_string(),
+                ));
+            }
+            let mut holders: Vec<(Pubkey, TokenAccount)> = self
+                .get_filtered_spl_token_accounts_by_mint(&bank, mint, vec![])?
+                .into_iter()
+                .filter_map(|(address, account)| Some((address, unpack_holder(&account)?)))
+                .collect();
+            let sort_key = |address: &Pubkey, amount: u64| (Reverse(amount), *address);
+            holders.sort_unstable_by_key(|(address, token_account)| {
+                sort_key(address, token_account.amount)
+            });
+            let start = cursor
+                .map(|cursor| {
+                    let last_key = sort_key(&cursor.last_address, cursor.last_amount);
+                    holders.partition_point(|(address, token_account)| {
+                        sort_key(address, token_account.amount) <= last_key
+                    })
+                })
+                .unwrap_or_default();
+            let end = min(start.saturating_add(limit), holders.len());
+            let has_more = end < holders.len();
+            holders.truncate(end);
+            holders.drain(..start);
+            (holders, has_more)
+        } else {
+            // Read one more token account than the page holds to find out whether it's the last
+            let mut holders: Vec<(Pubkey, TokenAccount)> = self
+                .get_filtered_spl_token_accounts_by_mint_paginated(
+                    &bank,
+                    mint,
+                    cursor.as_ref().map(|cursor| &cursor.last_address),
+                    limit.saturating_add(1),
+                    |account| unpack_holder(account).is_some(),
+                )?
+                .into_iter()
+                .filter_map(|(address, account)| Some((address, unpack_holder(&account)?)))
+                .collect();
+            let has_more = holders.len() > limit;
+            holders.truncate(limit);
+            (holders, has_more)
+        };
+        let next_cursor = if has_more {
+            holders.last().map(|(last_address, last_token_account)| {
+                TokenHoldersCursor {
+                    slot: bank.slot(),
+                    last_amount: last_token_account.amount,
+                    last_address: *last_address,
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+        let holders = holders
+            .iter()
+            .map(|(address, token_account)| RpcTokenHolder {
+                address: address.to_string(),
+                owner: token_account.owner.to_string(),
+                amount: token_amount_to_ui_amount(token_account.amount, decimals),
+            })
+            .collect();
+        Ok(new_response(
+            &bank,
+            RpcTokenHoldersPage {
+                holders,
+                next_cursor,
+            },
+        ))
+    }
+
     pub fn get_token_accounts_by_owner(
         &self,
         owner: &Pubkey,
@@ -1794,10 +2207,9 @@ impl JsonRpcRequestProcessor {
         filters: Vec<RpcFilterType>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         let filter_closure = |account: &AccountSharedData| {
-            filters.iter().all(|filter_type| match filter_type {
-                RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
-                RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
-            })
+            filters
+                .iter()
+                .all(|filter_type| filter_type.allows(account))
         };
         if self
             .config
@@ -1830,6 +2242,32 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    /// Get the accounts in a custom account index under `key`
+    fn get_filtered_custom_indexed_accounts(
+        &self,
+        bank: &Arc<Bank>,
+        index: &CustomAccountIndex,
+        key: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        if !self.config.account_indexes.include_custom_key(index, key) {
+            return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                index_key: bs58::encode(&key.as_ref()[..index.length]).into_string(),
+            });
+        }
+        bank.get_filtered_indexed_accounts(&IndexKey::Custom(*index, *key), |account| {
+            // Like the other indexes, a custom index may still list accounts that have since
+            // been reassigned or wiped, so the owner and filters are checked again
+            account.owner() == &index.program_id
+                && filters
+                    .iter()
+                    .all(|filter_type| filter_type.allows(account))
+        })
+        .map_err(|e| RpcCustomError::ScanError {
+            message: e.to_string(),
+        })
+    }
+
     /// Get an iterator of spl-token accounts by owner address
     fn get_filtered_spl_token_accounts_by_owner(
         &self,
@@ -1866,10 +2304,9 @@ impl JsonRpcRequestProcessor {
             Ok(bank
                 .get_filtered_indexed_accounts(&IndexKey::SplTokenOwner(*owner_key), |account| {
                     account.owner() == &spl_token_id_v2_0()
-                        && filters.iter().all(|filter_type| match filter_type {
-                            RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
-                            RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
-                        })
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_type.allows(account))
                 })
                 .map_err(|e| RpcCustomError::ScanError {
                     message: e.to_string(),
@@ -1914,10 +2351,9 @@ impl JsonRpcRequestProcessor {
             Ok(bank
                 .get_filtered_indexed_accounts(&IndexKey::SplTokenMint(*mint_key), |account| {
                     account.owner() == &spl_token_id_v2_0()
-                        && filters.iter().all(|filter_type| match filter_type {
-                            RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
-                            RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
-                        })
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_type.allows(account))
                 })
                 .map_err(|e| RpcCustomError::ScanError {
                     message: e.to_string(),
@@ -1927,6 +2363,61 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    /// Like `get_filtered_spl_token_accounts_by_mint`, but returns at most `limit` token
+    /// accounts that pass `filter`, in address order, starting after `start_after`
+    fn get_filtered_spl_token_accounts_by_mint_paginated<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        bank: &Arc<Bank>,
+        mint_key: &Pubkey,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        // As in `get_filtered_spl_token_accounts_by_mint`, the index entries of wiped token
+        // accounts are filtered out by size and mint address
+        let filters = [
+            RpcFilterType::DataSize(TokenAccount::get_packed_len() as u64),
+            RpcFilterType::Memcmp(Memcmp {
+                offset: SPL_TOKEN_ACCOUNT_MINT_OFFSET,
+                bytes: MemcmpEncodedBytes::Binary(mint_key.to_string()),
+                encoding: None,
+            }),
+        ];
+        let filter = |account: &AccountSharedData| {
+            filters
+                .iter()
+                .all(|filter_type| filter_type.allows(account))
+                && filter(account)
+        };
+        let result = if self
+            .config
+            .account_indexes
+            .contains(&AccountIndex::SplTokenMint)
+        {
+            if !self.config.account_indexes.include_key(mint_key) {
+                return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                    index_key: mint_key.to_string(),
+                });
+            }
+            bank.get_filtered_indexed_accounts_paginated(
+                &IndexKey::SplTokenMint(*mint_key),
+                start_after,
+                limit,
+                |account| account.owner() == &spl_token_id_v2_0() && filter(account),
+            )
+        } else {
+            bank.get_filtered_program_accounts_paginated(
+                &spl_token_id_v2_0(),
+                start_after,
+                limit,
+                filter,
+            )
+        };
+        result.map_err(|e| RpcCustomError::ScanError {
+            message: e.to_string(),
+        })
+    }
+
     fn get_latest_blockhash(
         &self,
         commitment: Option<CommitmentConfig>,
@@ -1971,6 +2462,7 @@ fn verify_transaction(
     transaction: &SanitizedTransaction,
     libsecp256k1_0_5_upgrade_enabled: bool,
     libsecp256k1_fail_on_bad_count: bool,
+    ed25519_program_enabled: bool,
 ) -> Result<()> {
     if transaction.verify().is_err() {
         return Err(RpcCustomError::TransactionSignatureVerificationFailure.into());
@@ -1979,6 +2471,7 @@ fn verify_transaction(
     if let Err(e) = transaction.verify_precompiles(
         libsecp256k1_0_5_upgrade_enabled,
         libsecp256k1_fail_on_bad_count,
+        ed25519_program_enabled,
     ) {
         return Err(RpcCustomError::TransactionPrecompileVerificationFailure(e).into());
     }
@@ -2131,51 +2624,92 @@ fn get_spl_token_owner_filter(program_id: &Pubkey, filters: &[RpcFilterType]) ->
         return None;
     }
     let mut data_size_filter: Option<u64> = None;
+    let mut token_account_state_filter = false;
     let mut owner_key: Option<Pubkey> = None;
     for filter in filters {
         match filter {
             RpcFilterType::DataSize(size) => data_size_filter = Some(*size),
-            RpcFilterType::Memcmp(Memcmp {
-                offset: SPL_TOKEN_ACCOUNT_OWNER_OFFSET,
-                bytes: MemcmpEncodedBytes::Binary(bytes),
-                ..
-            }) => {
-                if let Ok(key) = Pubkey::from_str(bytes) {
-                    owner_key = Some(key)
+            RpcFilterType::TokenAccountState(_) => token_account_state_filter = true,
+            RpcFilterType::Memcmp(
+                compare @ Memcmp {
+                    offset: SPL_TOKEN_ACCOUNT_OWNER_OFFSET,
+                    ..
+                },
+            ) => {
+                if let Some(bytes) = compare.bytes() {
+                    if bytes.len() == PUBKEY_BYTES {
+                        owner_key = Some(Pubkey::new(&bytes))
+                    }
                 }
             }
             _ => {}
         }
     }
-    if data_size_filter == Some(TokenAccount::get_packed_len() as u64) {
+    if data_size_filter == Some(TokenAccount::get_packed_len() as u64) || token_account_state_filter
+    {
         owner_key
     } else {
         None
     }
 }
 
+/// Find a custom account index of `program_id` that serves one of the memcmp filters, and the
+/// key to look up in it
+fn get_custom_index_filter(
+    account_indexes: &AccountSecondaryIndexes,
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+) -> Option<(CustomAccountIndex, Pubkey)> {
+    filters.iter().find_map(|filter| match filter {
+        RpcFilterType::Memcmp(compare) => {
+            let bytes = compare.bytes()?;
+            account_indexes
+                .custom_indexes
+                .keys()
+                .find(|index| {
+                    index.program_id == *program_id
+                        && index.offset == compare.offset
+                        && bytes.len() >= index.length
+                })
+                .map(|index| {
+                    (
+                        *index,
+                        CustomAccountIndex::key_from_bytes(&bytes[..index.length]),
+                    )
+                })
+        }
+        _ => None,
+    })
+}
+
 fn get_spl_token_mint_filter(program_id: &Pubkey, filters: &[RpcFilterType]) -> Option<Pubkey> {
     if program_id != &spl_token_id_v2_0() {
         return None;
     }
     let mut data_size_filter: Option<u64> = None;
+    let mut token_account_state_filter = false;
     let mut mint: Option<Pubkey> = None;
     for filter in filters {
         match filter {
             RpcFilterType::DataSize(size) => data_size_filter = Some(*size),
-            RpcFilterType::Memcmp(Memcmp {
-                offset: SPL_TOKEN_ACCOUNT_MINT_OFFSET,
-                bytes: MemcmpEncodedBytes::Binary(bytes),
-                ..
-            }) => {
-                if let Ok(key) = Pubkey::from_str(bytes) {
-                    mint = Some(key)
+            RpcFilterType::TokenAccountState(_) => token_account_state_filter = true,
+            RpcFilterType::Memcmp(
+                compare @ Memcmp {
+                    offset: SPL_TOKEN_ACCOUNT_MINT_OFFSET,
+                    ..
+                },
+            ) => {
+                if let Some(bytes) = compare.bytes() {
+                    if bytes.len() == PUBKEY_BYTES {
+                        mint = Some(Pubkey::new(&bytes))
+                    }
                 }
             }
             _ => {}
         }
     }
-    if data_size_filter == Some(TokenAccount::get_packed_len() as u64) {
+    if data_size_filter == Some(TokenAccount::get_packed_len() as u64) || token_account_state_filter
+    {
         mint
     } else {
         None
@@ -2686,6 +3220,13 @@ pub mod rpc_accounts {
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Option<UiAccount>>>;
 
+        #[rpc(meta, name = "getAccountProof")]
+        fn get_account_proof(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+        ) -> Result<RpcResponse<Option<RpcAccountProof>>>;
+
         #[rpc(meta, name = "getMultipleAccounts")]
         fn get_multiple_accounts(
             &self,
@@ -2702,6 +3243,14 @@ pub mod rpc_accounts {
             config: Option<RpcProgramAccountsConfig>,
         ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>>;
 
+        #[rpc(meta, name = "getProgramAccountsPaginated")]
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>>;
+
         #[rpc(meta, name = "getBlockCommitment")]
         fn get_block_commitment(
             &self,
@@ -2759,6 +3308,14 @@ pub mod rpc_accounts {
             commitment: Option<CommitmentConfig>,
         ) -> Result<RpcResponse<Vec<RpcTokenAccountBalance>>>;
 
+        #[rpc(meta, name = "getTokenHolders")]
+        fn get_token_holders(
+            &self,
+            meta: Self::Metadata,
+            mint_str: String,
+            config: Option<RpcTokenHoldersConfig>,
+        ) -> Result<RpcResponse<RpcTokenHoldersPage>>;
+
         #[rpc(meta, name = "getTokenAccountsByOwner")]
         fn get_token_accounts_by_owner(
             &self,
@@ -2793,6 +3350,16 @@ pub mod rpc_accounts {
             meta.get_account_info(&pubkey, config)
         }
 
+        fn get_account_proof(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+        ) -> Result<RpcResponse<Option<RpcAccountProof>>> {
+            debug!("get_account_proof rpc request received: {:?}", pubkey_str);
+            let pubkey = verify_pubkey(&pubkey_str)?;
+            meta.get_account_proof(&pubkey)
+        }
+
         fn get_multiple_accounts(
             &self,
             meta: Self::Metadata,
@@ -2853,6 +3420,49 @@ pub mod rpc_accounts {
             meta.get_program_accounts(&program_id, config, filters, with_context)
         }
 
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+            debug!(
+                "get_program_accounts_paginated rpc request received: {:?}",
+                program_id_str
+            );
+            let program_id = verify_pubkey(&program_id_str)?;
+            let RpcProgramAccountsPaginatedConfig {
+                filters,
+                account_config,
+                limit,
+                cursor,
+            } = config.unwrap_or_default();
+            let filters = filters.unwrap_or_default();
+            if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                return Err(Error::invalid_params(format!(
+                    "Too many filters provided; max {}",
+                    MAX_GET_PROGRAM_ACCOUNT_FILTERS
+                )));
+            }
+            for filter in &filters {
+                verify_filter(filter)?;
+            }
+            let limit = limit.unwrap_or(MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT);
+            if limit == 0 || limit > MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT {
+                return Err(Error::invalid_params(format!(
+                    "Invalid limit; max {}",
+                    MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT
+                )));
+            }
+            meta.get_program_accounts_paginated(
+                &program_id,
+                Some(account_config),
+                filters,
+                limit,
+                cursor.as_deref(),
+            )
+        }
+
         fn get_block_commitment(
             &self,
             meta: Self::Metadata,
@@ -2933,6 +3543,25 @@ pub mod rpc_accounts {
             meta.get_token_largest_accounts(&mint, commitment)
         }
 
+        fn get_token_holders(
+            &self,
+            meta: Self::Metadata,
+            mint_str: String,
+            config: Option<RpcTokenHoldersConfig>,
+        ) -> Result<RpcResponse<RpcTokenHoldersPage>> {
+            debug!("get_token_holders rpc request received: {:?}", mint_str);
+            let mint = verify_pubkey(&mint_str)?;
+            let config = config.unwrap_or_default();
+            let limit = config.limit.unwrap_or(MAX_GET_TOKEN_HOLDERS_PAGE_LIMIT);
+            if limit == 0 || limit > MAX_GET_TOKEN_HOLDERS_PAGE_LIMIT {
+                return Err(Error::invalid_params(format!(
+                    "Invalid limit; max {}",
+                    MAX_GET_TOKEN_HOLDERS_PAGE_LIMIT
+                )));
+            }
+            meta.get_token_holders(&mint, config, limit)
+        }
+
         fn get_token_accounts_by_owner(
             &self,
             meta: Self::Metadata,
@@ -3053,6 +3682,15 @@ pub mod rpc_full {
             slot: Slot,
         ) -> BoxFuture<Result<Option<UnixTimestamp>>>;
 
+        #[rpc(meta, name = "getAccountInfoAtSlot")]
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoConfig>,
+        ) -> Result<RpcResponse<Option<UiAccount>>>;
+
         #[rpc(meta, name = "getBlocks")]
         fn get_blocks(
             &self,
@@ -3336,6 +3974,7 @@ pub mod rpc_full {
                     &transaction,
                     preflight_bank.libsecp256k1_0_5_upgrade_enabled(),
                     preflight_bank.libsecp256k1_fail_on_bad_count(),
+                    preflight_bank.ed25519_program_enabled(),
                 ) {
                     return Err(e);
                 }
@@ -3363,6 +4002,8 @@ pub mod rpc_full {
                     logs,
                     post_simulation_accounts: _,
                     units_consumed,
+                    inner_instructions: _,
+                    return_data,
                 } = preflight_bank.simulate_transaction(transaction)
                 {
                     match err {
@@ -3380,6 +4021,10 @@ pub mod rpc_full {
                             logs: Some(logs),
                             accounts: None,
                             units_consumed: Some(units_consumed),
+                            inner_instructions: None,
+                            pre_token_balances: None,
+                            post_token_balances: None,
+                            return_data: return_data.map(Into::into),
                         },
                     }
                     .into());
@@ -3426,14 +4071,18 @@ pub mod rpc_full {
                     &transaction,
                     bank.libsecp256k1_0_5_upgrade_enabled(),
                     bank.libsecp256k1_fail_on_bad_count(),
+                    bank.ed25519_program_enabled(),
                 )?;
             }
 
+            let message = transaction.message().clone();
             let TransactionSimulationResult {
                 result,
                 logs,
                 post_simulation_accounts,
                 units_consumed,
+                inner_instructions,
+                return_data,
             } = bank.simulate_transaction(transaction);
 
             let accounts = if let Some(config_accounts) = config.accounts {
@@ -3473,6 +4122,39 @@ pub mod rpc_full {
                 None
             };
 
+            let inner_instructions = if config.inner_instructions {
+                inner_instructions.map(|inner_instructions| {
+                    inner_instructions
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, instructions)| InnerInstructions {
+                            index: index as u8,
+                            instructions,
+                        })
+                        .filter(|i| !i.instructions.is_empty())
+                        .map(|inner_instructions| match message.legacy_message() {
+                            Some(message) => {
+                                UiInnerInstructions::parse(inner_instructions, message)
+                            }
+                            None => inner_instructions.into(),
+                        })
+                        .collect()
+                })
+            } else {
+                None
+            };
+
+            let (pre_token_balances, post_token_balances) = if config.token_balances {
+                let (pre_token_balances, post_token_balances) =
+                    collect_simulated_token_balances(bank, &message, &post_simulation_accounts);
+                (
+                    Some(pre_token_balances.into_iter().map(Into::into).collect()),
+                    Some(post_token_balances.into_iter().map(Into::into).collect()),
+                )
+            } else {
+                (None, None)
+            };
+
             Ok(new_response(
                 bank,
                 RpcSimulateTransactionResult {
@@ -3480,6 +4162,10 @@ pub mod rpc_full {
                     logs: Some(logs),
                     accounts,
                     units_consumed: Some(units_consumed),
+                    inner_instructions,
+                    pre_token_balances,
+                    post_token_balances,
+                    return_data: return_data.map(Into::into),
                 },
             ))
         }
@@ -3543,6 +4229,21 @@ pub mod rpc_full {
             Box::pin(async move { meta.get_block_time(slot).await })
         }
 
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoConfig>,
+        ) -> Result<RpcResponse<Option<UiAccount>>> {
+            debug!(
+                "get_account_info_at_slot rpc request received: {:?} {:?}",
+                pubkey_str, slot
+            );
+            let pubkey = verify_pubkey(&pubkey_str)?;
+            meta.get_account_info_at_slot(&pubkey, slot, config)
+        }
+
         fn get_transaction(
             &self,
             meta: Self::Metadata,
@@ -4187,7 +4888,8 @@ pub fn create_test_transactions_and_populate_blockstore(
 pub mod tests {
     use {
         super::{
-            rpc_accounts::*, rpc_bank::*, rpc_deprecated_v1_8::*, rpc_full::*, rpc_minimal::*, *,
+            rpc_accounts::*, rpc_bank::*, rpc_deprecated_v1_7::*, rpc_deprecated_v1_8::*,
+            rpc_full::*, rpc_minimal::*, *,
         },
         crate::{
             optimistically_confirmed_bank_tracker::{
@@ -4198,15 +4900,31 @@ pub mod tests {
         bincode::deserialize,
         jsonrpc_core::{futures, ErrorCode, MetaIoHandler, Output, Response, Value},
         jsonrpc_core_client::transports::local,
-        solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes},
+        solana_account_decoder::parse_token::UiAccountState,
+        solana_client::{
+            rpc_custom_error::{
+                JSON_RPC_SERVER_ERROR_ACCOUNTS_HASH_NOT_PUBLISHED,
+                JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_ACCOUNT_PROOFS_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_ACCOUNT_WRITTEN_SINCE_ACCOUNTS_HASH,
+                JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_CURSOR_SLOT_NOT_REACHED,
+                JSON_RPC_SERVER_ERROR_KEY_EXCLUDED_FROM_SECONDARY_INDEX,
+                JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED,
+            },
+            rpc_filter::{Memcmp, MemcmpEncodedBytes, MemcmpEncoding},
+        },
         solana_gossip::{contact_info::ContactInfo, socketaddr},
         solana_ledger::{
-            blockstore_meta::PerfSample,
+            blockstore_meta::{AccountHistorySession, PerfSample},
             blockstore_processor::fill_blockstore_slot_with_ticks,
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
+            get_tmp_ledger_path_auto_delete,
         },
+        solana_program_runtime::InstructionProcessor,
         solana_runtime::{
-            accounts_background_service::AbsRequestSender, commitment::BlockCommitment,
+            accounts_background_service::AbsRequestSender, accounts_db::AccountShrinkThreshold,
+            accounts_index::AccountSecondaryIndexesIncludeExclude, commitment::BlockCommitment,
             non_circulating_supply::non_circulating_accounts,
         },
         solana_sdk::{
@@ -4214,14 +4932,18 @@ pub mod tests {
             clock::MAX_RECENT_BLOCKHASHES,
             fee_calculator::DEFAULT_BURN_PERCENT,
             hash::{hash, Hash},
-            instruction::InstructionError,
+            instruction::{AccountMeta, Instruction, InstructionError},
+            keyed_account::keyed_account_at_index,
             message::Message,
-            nonce, rpc_port,
+            nonce,
+            process_instruction::InvokeContext,
+            rpc_port,
             signature::{Keypair, Signer},
-            system_program, system_transaction,
+            system_instruction, system_program, system_transaction,
             timing::slot_duration_from_slots_per_year,
             transaction::{self, Transaction, TransactionError},
         },
+        solana_storage_bigtable::FileLedgerStorage,
         solana_transaction_status::{
             EncodedConfirmedBlock, EncodedTransaction, EncodedTransactionWithStatusMeta,
             TransactionDetails, UiMessage,
@@ -4394,6 +5116,7 @@ pub mod tests {
         let (meta, receiver) = JsonRpcRequestProcessor::new(
             JsonRpcConfig {
                 enable_rpc_transaction_history: true,
+                account_history: Some(AccountHistoryConfig::default()),
                 ..JsonRpcConfig::default()
             },
             None,
@@ -5083,6 +5806,169 @@ pub mod tests {
         result["error"].as_object().unwrap();
     }
 
+    #[test]
+    fn test_rpc_get_account_info_at_slot() {
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+        let request = |pubkey: &Pubkey, slot: Slot| {
+            format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"getAccountInfoAtSlot","params":["{}", {}]}}"#,
+                pubkey, slot
+            )
+        };
+        let get_result = |pubkey: &Pubkey, slot: Slot| -> Value {
+            let res = io.handle_request_sync(&request(pubkey, slot), meta.clone());
+            serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+
+        // Accounts that haven't changed since the slot are read from the bank
+        let result = get_result(&bob_pubkey, 0);
+        assert_eq!(result["result"]["context"]["slot"], 0);
+        assert_eq!(result["result"]["value"]["lamports"], 20);
+
+        // Older states are read from the history recorded in the session that includes the slot
+        let address = solana_sdk::pubkey::new_rand();
+        let closed_address = solana_sdk::pubkey::new_rand();
+        meta.blockstore
+            .write_account_history(
+                0,
+                &[
+                    (
+                        address,
+                        Account {
+                            lamports: 42,
+                            data: vec![1, 2, 3],
+                            ..Account::default()
+                        },
+                    ),
+                    (closed_address, Account::default()),
+                ],
+            )
+            .unwrap();
+        let result = get_result(&address, 0);
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE).code()
+        );
+        let session = |owners| AccountHistorySession {
+            last_slot: bank.slot(),
+            owners,
+        };
+        meta.blockstore
+            .write_account_history_session(0, &session(None))
+            .unwrap();
+        let result = get_result(&address, 0);
+        assert_eq!(result["result"]["value"]["lamports"], 42);
+        assert_eq!(
+            result["result"]["value"]["data"],
+            json!([base64::encode([1, 2, 3]), "base64"]),
+        );
+        let result = get_result(&closed_address, 0);
+        assert_eq!(result["result"]["value"], Value::Null);
+
+        // An account never written in a session that records every account didn't exist, but
+        // may have had an owner that wasn't recorded otherwise
+        let result = get_result(&solana_sdk::pubkey::new_rand(), 0);
+        assert_eq!(result["result"]["value"], Value::Null);
+        meta.blockstore
+            .write_account_history_session(0, &session(Some(vec![system_program::id()])))
+            .unwrap();
+        let result = get_result(&solana_sdk::pubkey::new_rand(), 0);
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE).code()
+        );
+
+        // Slots after the finalized bank are not available
+        let result = get_result(&bob_pubkey, bank.slot() + 1);
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE).code()
+        );
+
+        // Nodes that don't record account history can't serve the method
+        let res = io.handle_request_sync(
+            &request(&bob_pubkey, 0),
+            JsonRpcRequestProcessor::new_from_bank(&bank, SocketAddrSpace::Unspecified),
+        );
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE).code()
+        );
+    }
+
+    #[test]
+    fn test_rpc_get_account_proof() {
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let RpcHandler {
+            io, mut meta, bank, ..
+        } = start_rpc_handler_with_tx(&bob_pubkey);
+        let get_result = |meta: &JsonRpcRequestProcessor, pubkey: &Pubkey| {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"getAccountProof","params":["{}"]}}"#,
+                pubkey
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str::<Value>(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+
+        let result = get_result(&meta, &bob_pubkey);
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_PROOFS_NOT_AVAILABLE).code()
+        );
+
+        // Accounts are only proven against an accounts hash this node published
+        meta.config.enable_rpc_account_proofs = true;
+        bank.enable_accounts_hash_trees();
+        let accounts_hash = bank.update_accounts_hash();
+        let result = get_result(&meta, &bob_pubkey);
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNTS_HASH_NOT_PUBLISHED).code()
+        );
+        meta.cluster_info
+            .push_accounts_hashes(vec![(bank.slot(), accounts_hash)]);
+        meta.cluster_info.flush_push_queue();
+        let result = get_result(&meta, &bob_pubkey);
+        assert_eq!(result["result"]["context"]["slot"], json!(bank.slot()));
+        let proof: RpcAccountProof =
+            serde_json::from_value(result["result"]["value"].clone()).unwrap();
+        assert_eq!(proof.slot, bank.slot());
+        assert_eq!(proof.accounts_hash, accounts_hash.to_string());
+        assert_eq!(proof.last_write_slot, bank.slot());
+        assert_eq!(proof.account.decode::<Account>().unwrap().lamports, 20);
+        assert!(proof.verify(&bob_pubkey));
+        assert!(!proof.verify(&solana_sdk::pubkey::new_rand()));
+
+        let mut tampered = proof.clone();
+        tampered.last_write_slot += 1;
+        assert!(!tampered.verify(&bob_pubkey));
+        let mut tampered = proof;
+        tampered.accounts_hash = Hash::default().to_string();
+        assert!(!tampered.verify(&bob_pubkey));
+
+        // Missing accounts have no proof
+        let result = get_result(&meta, &solana_sdk::pubkey::new_rand());
+        assert_eq!(result["result"]["value"], Value::Null);
+
+        // Accounts written since can't be proven until the next accounts hash
+        bank.store_account(
+            &bob_pubkey,
+            &AccountSharedData::new(30, 0, &Pubkey::default()),
+        );
+        let result = get_result(&meta, &bob_pubkey);
+        assert_eq!(
+            result["error"]["code"],
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_WRITTEN_SINCE_ACCOUNTS_HASH)
+                .code()
+        );
+    }
+
     #[test]
     fn test_rpc_get_multiple_accounts() {
         let bob_pubkey = solana_sdk::pubkey::new_rand();
@@ -5371,6 +6257,82 @@ pub mod tests {
             .expect("actual response deserialization");
         assert_eq!(accounts.len(), 0);
 
+        // Test base-64 memcmp filter; filter on Nonce authority
+        let req = format!(
+            r#"{{
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"getProgramAccounts",
+                "params":["{}",{{"filters": [
+                    {{
+                        "memcmp": {{"offset": 8,"bytes": "{}","encoding": "base64"}}
+                    }}
+                ]}}]
+            }}"#,
+            system_program::id(),
+            base64::encode(authority.as_ref()),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let accounts: Vec<RpcKeyedAccount> = serde_json::from_value(json["result"].clone())
+            .expect("actual response deserialization");
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].pubkey, nonce_keypair1.pubkey().to_string());
+
+        // Test lamports filter
+        let req = format!(
+            r#"{{
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"getProgramAccounts",
+                "params":["{}",{{"filters": [
+                    {{
+                        "lamports": {{"min": 100000, "max": 100000}}
+                    }}
+                ]}}]
+            }}"#,
+            system_program::id(),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let accounts: Vec<RpcKeyedAccount> = serde_json::from_value(json["result"].clone())
+            .expect("actual response deserialization");
+        assert_eq!(accounts.len(), 2);
+
+        let req = format!(
+            r#"{{
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"getProgramAccounts",
+                "params":["{}",{{"filters": [
+                    {{
+                        "lamports": {{"min": 2, "max": 1}}
+                    }}
+                ]}}]
+            }}"#,
+            system_program::id(),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["error"]["code"], ErrorCode::InvalidParams.code());
+
+        // Test zeroed data filter; only the nonce accounts have non-zero data
+        let req = format!(
+            r#"{{
+                "jsonrpc":"2.0",
+                "id":1,
+                "method":"getProgramAccounts",
+                "params":["{}",{{"filters": ["zeroedData", {{"dataSize": {}}}]}}]
+            }}"#,
+            system_program::id(),
+            nonce::State::size(),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let accounts: Vec<RpcKeyedAccount> = serde_json::from_value(json["result"].clone())
+            .expect("actual response deserialization");
+        assert_eq!(accounts.len(), 0);
+
         // Test multiple filters
         let req = format!(
             r#"{{
@@ -5420,6 +6382,96 @@ pub mod tests {
         assert_eq!(accounts.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_paginated() {
+        let RpcHandler { io, meta, .. } =
+            start_rpc_handler_with_tx(&solana_sdk::pubkey::new_rand());
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getProgramAccounts","params":["{}"]}}"#,
+            system_program::id(),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let accounts: Vec<RpcKeyedAccount> = serde_json::from_value(json["result"].clone())
+            .expect("actual response deserialization");
+        let mut expected_pubkeys: Vec<Pubkey> = accounts
+            .iter()
+            .map(|keyed_account| keyed_account.pubkey.parse().unwrap())
+            .collect();
+        expected_pubkeys.sort();
+        assert!(expected_pubkeys.len() > 2);
+
+        // Walk all pages, two accounts at a time
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            let req = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getProgramAccountsPaginated",
+                "params": [
+                    system_program::id().to_string(),
+                    {"limit": 2, "cursor": cursor},
+                ],
+            })
+            .to_string();
+            let res = io.handle_request_sync(&req, meta.clone());
+            let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+            let page: RpcProgramAccountsPage =
+                serde_json::from_value(json["result"]["value"].clone())
+                    .expect("actual response deserialization");
+            assert!(page.accounts.len() <= 2);
+            pubkeys.extend(
+                page.accounts
+                    .iter()
+                    .map(|keyed_account| keyed_account.pubkey.parse::<Pubkey>().unwrap()),
+            );
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pubkeys, expected_pubkeys);
+
+        // Invalid limit
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getProgramAccountsPaginated","params":["{}",{{"limit": {}}}]}}"#,
+            system_program::id(),
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT + 1,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["error"]["code"], ErrorCode::InvalidParams.code());
+
+        // Invalid cursor
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getProgramAccountsPaginated","params":["{}",{{"cursor": "notacursor"}}]}}"#,
+            system_program::id(),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["error"]["code"], ErrorCode::InvalidParams.code());
+
+        // Cursor issued at a slot this node has not reached yet
+        let cursor = ProgramAccountsCursor {
+            slot: 1_000,
+            last_pubkey: Pubkey::default(),
+        }
+        .encode();
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getProgramAccountsPaginated","params":["{}",{{"cursor": "{}"}}]}}"#,
+            system_program::id(),
+            cursor,
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(
+            json["error"]["code"],
+            JSON_RPC_SERVER_ERROR_CURSOR_SLOT_NOT_REACHED
+        );
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let RpcHandler {
@@ -5688,6 +6740,189 @@ pub mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction_inner_instructions_and_token_balances() {
+        let RpcHandler {
+            io,
+            meta,
+            blockhash,
+            alice,
+            bank,
+            ..
+        } = start_rpc_handler_with_tx(&solana_sdk::pubkey::new_rand());
+
+        let mut account_data = vec![0; TokenAccount::get_packed_len()];
+        let mint = SplTokenPubkey::new(&[2; 32]);
+        let owner = SplTokenPubkey::new(&[3; 32]);
+        let token_account = TokenAccount {
+            mint,
+            owner,
+            amount: 420,
+            state: TokenAccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        TokenAccount::pack(token_account, &mut account_data).unwrap();
+        let token_account = AccountSharedData::from(Account {
+            lamports: 111,
+            data: account_data.to_vec(),
+            owner: spl_token_id_v2_0(),
+            ..Account::default()
+        });
+        let token_account_pubkey = solana_sdk::pubkey::new_rand();
+        bank.store_account(&token_account_pubkey, &token_account);
+
+        let mut mint_data = vec![0; Mint::get_packed_len()];
+        let mint_state = Mint {
+            mint_authority: COption::Some(owner),
+            supply: 500,
+            decimals: 2,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        Mint::pack(mint_state, &mut mint_data).unwrap();
+        let mint_account = AccountSharedData::from(Account {
+            lamports: 111,
+            data: mint_data.to_vec(),
+            owner: spl_token_id_v2_0(),
+            ..Account::default()
+        });
+        bank.store_account(&Pubkey::from_str(&mint.to_string()).unwrap(), &mint_account);
+
+        // Credit the token account; the token program is referenced so that token balances are
+        // collected
+        let mut instruction =
+            system_instruction::transfer(&alice.pubkey(), &token_account_pubkey, 1);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(spl_token_id_v2_0(), false));
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&alice.pubkey()),
+            &[&alice],
+            blockhash,
+        );
+        let tx_serialized_encoded = bs58::encode(serialize(&tx).unwrap()).into_string();
+
+        bank.freeze(); // Ensure the root bank is frozen, `start_rpc_handler_with_tx()` doesn't do this
+
+        let expected_token_balances = json!([{
+            "accountIndex": 1,
+            "mint": mint.to_string(),
+            "uiTokenAmount": {
+                "uiAmount": 4.2,
+                "decimals": 2,
+                "amount": "420",
+                "uiAmountString": "4.2",
+            },
+        }]);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{}", {{"innerInstructions": true, "tokenBalances": true}}]}}"#,
+            tx_serialized_encoded,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let value = &json["result"]["value"];
+        assert_eq!(value["err"], Value::Null);
+        assert_eq!(value["innerInstructions"], json!([]));
+        assert_eq!(value["preTokenBalances"], expected_token_balances);
+        assert_eq!(value["postTokenBalances"], expected_token_balances);
+
+        // Neither is reported unless requested
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{}"]}}"#,
+            tx_serialized_encoded,
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let value = json["result"]["value"].as_object().unwrap();
+        assert!(!value.contains_key("innerInstructions"));
+        assert!(!value.contains_key("preTokenBalances"));
+        assert!(!value.contains_key("postTokenBalances"));
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_parsed_inner_instructions() {
+        // Transfers the lamports in its instruction data from its first account to its second by
+        // invoking the system program, which is its third account
+        fn process_instruction(
+            _program_id: &Pubkey,
+            data: &[u8],
+            invoke_context: &mut dyn InvokeContext,
+        ) -> std::result::Result<(), InstructionError> {
+            let keyed_accounts = invoke_context.get_keyed_accounts()?;
+            let from = *keyed_account_at_index(keyed_accounts, 0)?.unsigned_key();
+            let to = *keyed_account_at_index(keyed_accounts, 1)?.unsigned_key();
+            let lamports =
+                bincode::deserialize(data).map_err(|_| InstructionError::InvalidArgument)?;
+            InstructionProcessor::native_invoke(
+                invoke_context,
+                system_instruction::transfer(&from, &to, lamports),
+                &[0, 1, 2],
+                &[],
+            )
+        }
+
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(TEST_MINT_LAMPORTS);
+        let program_id = solana_sdk::pubkey::new_rand();
+        let mut bank = Bank::new_for_tests(&genesis_config);
+        bank.add_builtin("mock_cpi_program", program_id, process_instruction);
+        bank.freeze();
+        let bank = Arc::new(bank);
+        let meta = JsonRpcRequestProcessor::new_from_bank(&bank, SocketAddrSpace::Unspecified);
+        let mut io = MetaIoHandler::default();
+        io.extend_with(rpc_full::FullImpl.to_delegate());
+
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let instruction = Instruction::new_with_bincode(
+            program_id,
+            &42u64,
+            vec![
+                AccountMeta::new(mint_keypair.pubkey(), true),
+                AccountMeta::new(bob_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&mint_keypair.pubkey()),
+            &[&mint_keypair],
+            bank.last_blockhash(),
+        );
+        let tx_serialized_encoded = bs58::encode(serialize(&tx).unwrap()).into_string();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{}", {{"innerInstructions": true}}]}}"#,
+            tx_serialized_encoded,
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let value = &json["result"]["value"];
+        assert_eq!(value["err"], Value::Null);
+        assert_eq!(
+            value["innerInstructions"],
+            json!([{
+                "index": 0,
+                "instructions": [{
+                    "parsed": {
+                        "info": {
+                            "destination": bob_pubkey.to_string(),
+                            "lamports": 42,
+                            "source": mint_keypair.pubkey().to_string(),
+                        },
+                        "type": "transfer",
+                    },
+                    "program": "system",
+                    "programId": system_program::id().to_string(),
+                }],
+            }])
+        );
+    }
+
     #[test]
     #[should_panic]
     fn test_rpc_simulate_transaction_panic_on_unfrozen_bank() {
@@ -6614,6 +7849,70 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_get_confirmed_block_from_file_ledger_storage() {
+        let bob_pubkey = solana_sdk::pubkey::new_rand();
+        let RpcHandler {
+            mut io,
+            mut meta,
+            block_commitment_cache,
+            confirmed_block_signatures,
+            ..
+        } = start_rpc_handler_with_tx(&bob_pubkey);
+        io.extend_with(DeprecatedV1_7Impl.to_delegate());
+
+        // Store the block of slot 0 as slot 5, a root that the blockstore has no data for
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let ledger_storage_path = get_tmp_ledger_path_auto_delete!();
+        let ledger_storage = FileLedgerStorage::new(ledger_storage_path.path(), false).unwrap();
+        let confirmed_block = meta.blockstore.get_rooted_block(0, true).unwrap();
+        runtime
+            .block_on(ledger_storage.upload_confirmed_block(5, confirmed_block))
+            .unwrap();
+        meta.bigtable_ledger_storage = Some(Arc::new(ledger_storage));
+        meta.blockstore.set_roots(vec![4, 5, 7].iter()).unwrap();
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_highest_confirmed_root(7);
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getConfirmedBlock","params":[5]}"#;
+        let res = runtime.block_on(io.handle_request(req, meta.clone()));
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let confirmed_block: Option<EncodedConfirmedBlock> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        let confirmed_block = confirmed_block.unwrap();
+        assert_eq!(confirmed_block.transactions.len(), 3);
+        let signatures: Vec<_> = confirmed_block
+            .transactions
+            .into_iter()
+            .map(
+                |EncodedTransactionWithStatusMeta { transaction, .. }| match transaction {
+                    EncodedTransaction::Json(transaction) => transaction.signatures[0].clone(),
+                    _ => panic!("unexpected encoding"),
+                },
+            )
+            .collect();
+        assert_eq!(
+            signatures[..2],
+            confirmed_block_signatures
+                .iter()
+                .map(|signature| signature.to_string())
+                .collect::<Vec<_>>()
+        );
+
+        // Roots missing from both the blockstore and the file ledger storage
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getConfirmedBlock","params":[4]}"#;
+        let res = runtime.block_on(io.handle_request(req, meta));
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["code"],
+            JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+        );
+    }
+
     #[test]
     fn test_get_blocks() {
         let bob_pubkey = solana_sdk::pubkey::new_rand();
@@ -7502,6 +8801,162 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_get_token_holders() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let mut account_indexes = AccountSecondaryIndexes::default();
+        account_indexes.indexes.insert(AccountIndex::SplTokenMint);
+        let bank = Arc::new(Bank::new_with_paths_for_tests(
+            &genesis_config,
+            Vec::new(),
+            &[],
+            None,
+            None,
+            account_indexes.clone(),
+            false,
+            AccountShrinkThreshold::default(),
+            false,
+        ));
+        let mut io = MetaIoHandler::default();
+        io.extend_with(rpc_accounts::AccountsDataImpl.to_delegate());
+        let unindexed_meta =
+            JsonRpcRequestProcessor::new_from_bank(&bank, SocketAddrSpace::Unspecified);
+        let mut meta = unindexed_meta.clone();
+        meta.config.account_indexes = account_indexes;
+
+        let mint = SplTokenPubkey::new(&[2; 32]);
+        let mut mint_data = vec![0; Mint::get_packed_len()];
+        let mint_state = Mint {
+            mint_authority: COption::None,
+            supply: 100,
+            decimals: 2,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        Mint::pack(mint_state, &mut mint_data).unwrap();
+        let mint_account = AccountSharedData::from(Account {
+            lamports: 111,
+            data: mint_data,
+            owner: spl_token_id_v2_0(),
+            ..Account::default()
+        });
+        bank.store_account(&Pubkey::new(&mint.to_bytes()), &mint_account);
+
+        let store_token_account = |mint: SplTokenPubkey, amount: u64| {
+            let owner = solana_sdk::pubkey::new_rand();
+            let mut account_data = vec![0; TokenAccount::get_packed_len()];
+            let token_account = TokenAccount {
+                mint,
+                owner: SplTokenPubkey::new(&owner.to_bytes()),
+                amount,
+                state: TokenAccountState::Initialized,
+                ..TokenAccount::default()
+            };
+            TokenAccount::pack(token_account, &mut account_data).unwrap();
+            let address = solana_sdk::pubkey::new_rand();
+            bank.store_account(
+                &address,
+                &AccountSharedData::from(Account {
+                    lamports: 111,
+                    data: account_data,
+                    owner: spl_token_id_v2_0(),
+                    ..Account::default()
+                }),
+            );
+            (address, owner, amount)
+        };
+        let mut holders: Vec<_> = [10, 0, 42, 10, 7]
+            .iter()
+            .map(|amount| store_token_account(mint, *amount))
+            .collect();
+        store_token_account(SplTokenPubkey::new(&[5; 32]), 1_000);
+
+        let get_all_holders = |meta: &JsonRpcRequestProcessor, config: Value| {
+            let mut all_holders = vec![];
+            let mut config = config;
+            loop {
+                let req = json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "getTokenHolders",
+                    "params": [mint.to_string(), config],
+                })
+                .to_string();
+                let res = io.handle_request_sync(&req, meta.clone());
+                let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+                let page: RpcTokenHoldersPage =
+                    serde_json::from_value(json["result"]["value"].clone())
+                        .expect("actual response deserialization");
+                assert!(page.holders.len() <= 2);
+                all_holders.extend(page.holders.into_iter().map(|holder| {
+                    (
+                        holder.address.parse::<Pubkey>().unwrap(),
+                        holder.owner.parse::<Pubkey>().unwrap(),
+                        holder.amount.amount.parse::<u64>().unwrap(),
+                    )
+                }));
+                match page.next_cursor {
+                    Some(cursor) => config["cursor"] = json!(cursor),
+                    None => return all_holders,
+                }
+            }
+        };
+
+        // Holders are listed by address by default, paging over the mint index or, without it,
+        // over every account
+        holders.sort_by_key(|(address, _, _)| *address);
+        assert_eq!(get_all_holders(&meta, json!({"limit": 2})), holders);
+        assert_eq!(
+            get_all_holders(&unindexed_meta, json!({"limit": 2})),
+            holders
+        );
+        let mut nonzero_holders = holders.clone();
+        nonzero_holders.retain(|(_, _, amount)| *amount > 0);
+        assert_eq!(
+            get_all_holders(&meta, json!({"limit": 2, "excludeZeroBalance": true})),
+            nonzero_holders
+        );
+
+        // Sorted by descending balance, without empty token accounts
+        holders.sort_by_key(|(address, _, amount)| (Reverse(*amount), *address));
+        holders.pop();
+        assert_eq!(
+            get_all_holders(
+                &meta,
+                json!({"limit": 2, "sort": "balance", "excludeZeroBalance": true})
+            ),
+            holders
+        );
+
+        // Sorting by balance requires the mint index
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenHolders","params":["{}",{{"sort":"balance"}}]}}"#,
+            mint,
+        );
+        let res = io.handle_request_sync(&req, unindexed_meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["error"]["code"], ErrorCode::InvalidParams.code());
+
+        // Not a mint
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenHolders","params":["{}"]}}"#,
+            holders[0].0,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["error"]["code"], ErrorCode::InvalidParams.code());
+
+        // Invalid limit
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenHolders","params":["{}",{{"limit": {}}}]}}"#,
+            mint,
+            MAX_GET_TOKEN_HOLDERS_PAGE_LIMIT + 1,
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(json["error"]["code"], ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     fn test_token_parsing() {
         let RpcHandler { io, meta, bank, .. } =
@@ -7639,6 +9094,23 @@ pub mod tests {
             owner
         );
 
+        // Base-64 owner bytes and a token account state filter in place of the data size filter
+        assert_eq!(
+            get_spl_token_owner_filter(
+                &Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap(),
+                &[
+                    RpcFilterType::Memcmp(Memcmp {
+                        offset: 32,
+                        bytes: MemcmpEncodedBytes::Binary(base64::encode(owner.as_ref())),
+                        encoding: Some(MemcmpEncoding::Base64),
+                    }),
+                    RpcFilterType::TokenAccountState(UiAccountState::Initialized),
+                ],
+            )
+            .unwrap(),
+            owner
+        );
+
         // Filtering on mint instead of owner
         assert!(get_spl_token_owner_filter(
             &Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap(),
@@ -7668,6 +9140,108 @@ pub mod tests {
         .is_none());
     }
 
+    #[test]
+    fn test_get_program_accounts_custom_index() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let program_id = Pubkey::new_unique();
+        let custom_index = CustomAccountIndex {
+            program_id,
+            offset: 4,
+            length: 32,
+        };
+        let mut account_indexes = AccountSecondaryIndexes::default();
+        account_indexes.custom_indexes.insert(custom_index, None);
+        let bank = Arc::new(Bank::new_with_paths_for_tests(
+            &genesis_config,
+            Vec::new(),
+            &[],
+            None,
+            None,
+            account_indexes.clone(),
+            false,
+            AccountShrinkThreshold::default(),
+            false,
+        ));
+        let store_account = |market: &Pubkey| {
+            let mut data = vec![0; 40];
+            data[4..36].copy_from_slice(market.as_ref());
+            let pubkey = Pubkey::new_unique();
+            bank.store_account(
+                &pubkey,
+                &AccountSharedData::from(Account {
+                    lamports: 1,
+                    data,
+                    owner: program_id,
+                    ..Account::default()
+                }),
+            );
+            pubkey
+        };
+        let market = Pubkey::new_unique();
+        let open_orders = store_account(&market);
+        store_account(&Pubkey::new_unique());
+
+        let mut meta = JsonRpcRequestProcessor::new_from_bank(&bank, SocketAddrSpace::Unspecified);
+        meta.config.account_indexes = account_indexes;
+        let market_filters = |offset| {
+            vec![RpcFilterType::Memcmp(Memcmp {
+                offset,
+                bytes: MemcmpEncodedBytes::Binary(market.to_string()),
+                encoding: None,
+            })]
+        };
+        assert_eq!(
+            get_custom_index_filter(
+                &meta.config.account_indexes,
+                &program_id,
+                &market_filters(4)
+            ),
+            Some((custom_index, market))
+        );
+        assert_eq!(
+            get_custom_index_filter(
+                &meta.config.account_indexes,
+                &program_id,
+                &market_filters(5)
+            ),
+            None
+        );
+        assert_eq!(
+            get_custom_index_filter(
+                &meta.config.account_indexes,
+                &Pubkey::new_unique(),
+                &market_filters(4)
+            ),
+            None
+        );
+
+        let accounts = match meta
+            .get_program_accounts(&program_id, None, market_filters(4), false)
+            .unwrap()
+        {
+            OptionalContext::NoContext(accounts) => accounts,
+            OptionalContext::Context(_) => panic!("unexpected context"),
+        };
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].pubkey, open_orders.to_string());
+
+        // Keys excluded from the index are rejected
+        meta.config.account_indexes.custom_indexes.insert(
+            custom_index,
+            Some(AccountSecondaryIndexesIncludeExclude {
+                exclude: true,
+                keys: [market].iter().cloned().collect(),
+            }),
+        );
+        let error = meta
+            .get_program_accounts(&program_id, None, market_filters(4), false)
+            .unwrap_err();
+        assert_eq!(
+            error.code,
+            ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_KEY_EXCLUDED_FROM_SECONDARY_INDEX)
+        );
+    }
+
     #[test]
     fn test_rpc_single_gossip() {
         let exit = Arc::new(AtomicBool::new(false));
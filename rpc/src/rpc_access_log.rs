@@ -0,0 +1,327 @@
+//! The `rpc_access_log` module records each JSON RPC call, with its latency and the sizes of
+//! its params and HTTP response, as a metrics datapoint and optionally as a line of a JSON access
+//! log.
+
+use {
+    crate::rpc::JsonRpcRequestProcessor,
+    crossbeam_channel::{bounded, Receiver, Sender, TrySendError},
+    jsonrpc_core::{
+        futures::future::Either,
+        middleware::{Middleware, NoopCallFuture, NoopFuture},
+        Call, Output,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        fs::{File, OpenOptions},
+        io::{self, BufWriter, Write},
+        path::PathBuf,
+        sync::{Arc, Mutex},
+        thread::Builder,
+        time::{Instant, SystemTime, UNIX_EPOCH},
+    },
+};
+
+// Entries waiting to be written to the access log; calls made while the queue is full are
+// not logged
+const ACCESS_LOG_QUEUE_SIZE: usize = 10_000;
+
+#[derive(Debug, Default, Clone)]
+pub struct RpcAccessLogConfig {
+    /// Report an `rpc-call` datapoint for each call
+    pub enable_metrics: bool,
+    /// Append a line of JSON describing each call to this file
+    pub log_path: Option<PathBuf>,
+}
+
+impl RpcAccessLogConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enable_metrics || self.log_path.is_some()
+    }
+}
+
+/// A line of the access log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccessLogEntry {
+    /// Milliseconds since the UNIX epoch at which the call completed
+    pub timestamp: u64,
+    pub client: Option<String>,
+    pub method: String,
+    /// Length in bytes of the serialized params
+    pub params_size: usize,
+    /// Length in bytes of the HTTP response body, which the calls of a batch request share;
+    /// `None` for calls made outside of an HTTP request or whose response failed
+    pub http_response_size: Option<usize>,
+    pub latency_us: u64,
+    /// JSON RPC error code of a failed call
+    pub error_code: Option<i64>,
+    /// Why the HTTP response of the call failed, such as the client disconnecting
+    pub http_error: Option<String>,
+}
+
+impl RpcAccessLogEntry {
+    fn report(&self) {
+        datapoint_info!(
+            "rpc-call",
+            ("method", self.method, String),
+            ("client", self.client.as_deref().unwrap_or_default(), String),
+            ("params_size", self.params_size, i64),
+            (
+                "http_response_size",
+                self.http_response_size.unwrap_or_default(),
+                i64
+            ),
+            ("latency_us", self.latency_us, i64),
+            ("error_code", self.error_code.unwrap_or_default(), i64),
+            ("http_error", self.http_error.is_some(), bool),
+        );
+    }
+}
+
+fn write_entry(writer: &mut impl Write, entry: &RpcAccessLogEntry) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, entry)?;
+    writer.write_all(b"\n")
+}
+
+fn write_log(receiver: Receiver<RpcAccessLogEntry>, file: File) {
+    let mut writer = BufWriter::new(file);
+    while let Ok(entry) = receiver.recv() {
+        // Flush once the queued entries have been written
+        let result = write_entry(&mut writer, &entry)
+            .and_then(|()| {
+                receiver
+                    .try_iter()
+                    .try_for_each(|entry| write_entry(&mut writer, &entry))
+            })
+            .and_then(|()| writer.flush());
+        if let Err(err) = result {
+            warn!("Failed to write RPC access log: {}", err);
+        }
+    }
+}
+
+/// The entries of the calls of an HTTP request, recorded once the size of its response is known
+#[derive(Debug, Default, Clone)]
+pub(crate) struct PendingRpcAccessLogEntries(Arc<Mutex<Vec<RpcAccessLogEntry>>>);
+
+impl PendingRpcAccessLogEntries {
+    fn push(&self, entry: RpcAccessLogEntry) {
+        self.0.lock().unwrap().push(entry);
+    }
+}
+
+#[derive(Clone)]
+pub struct RpcAccessLog {
+    enable_metrics: bool,
+    log_sender: Option<Sender<RpcAccessLogEntry>>,
+}
+
+impl RpcAccessLog {
+    /// Opens the access log, if any, and starts a thread that writes to it. The thread exits
+    /// once the `RpcAccessLog` and its clones are dropped
+    pub fn new(config: &RpcAccessLogConfig) -> io::Result<Self> {
+        let log_sender = match &config.log_path {
+            Some(log_path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(log_path)?;
+                let (sender, receiver) = bounded(ACCESS_LOG_QUEUE_SIZE);
+                Builder::new()
+                    .name("solana-rpc-access-log".to_string())
+                    .spawn(move || write_log(receiver, file))?;
+                Some(sender)
+            }
+            None => None,
+        };
+        Ok(Self {
+            enable_metrics: config.enable_metrics,
+            log_sender,
+        })
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enable_metrics || self.log_sender.is_some()
+    }
+
+    /// Records the entries of an HTTP request once its response body of `http_response` bytes
+    /// has been built, or with the error that failed the response
+    pub(crate) fn record_pending(
+        &self,
+        entries: &PendingRpcAccessLogEntries,
+        http_response: Result<usize, String>,
+    ) {
+        for mut entry in entries.0.lock().unwrap().drain(..) {
+            match &http_response {
+                Ok(http_response_size) => entry.http_response_size = Some(*http_response_size),
+                Err(err) => entry.http_error = Some(err.clone()),
+            }
+            self.record(entry);
+        }
+    }
+
+    fn record(&self, entry: RpcAccessLogEntry) {
+        if self.enable_metrics {
+            entry.report();
+        }
+        if let Some(log_sender) = &self.log_sender {
+            if let Err(TrySendError::Full(_)) = log_sender.try_send(entry) {
+                inc_new_counter_info!("rpc-access-log-dropped", 1);
+            }
+        }
+    }
+}
+
+impl Middleware<JsonRpcRequestProcessor> for RpcAccessLog {
+    type Future = NoopFuture;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: std::future::Future<Output = Option<Output>> + Send + 'static,
+    {
+        if !self.is_enabled() {
+            return Either::Right(next(call, meta));
+        }
+        let (method, params) = match &call {
+            Call::MethodCall(method_call) => (method_call.method.clone(), &method_call.params),
+            Call::Notification(notification) => (notification.method.clone(), &notification.params),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let params_size = serialized_size(params);
+        let client = meta.client_address().map(str::to_string);
+        let pending_entries = meta.access_log_entries().cloned();
+
+        let access_log = self.clone();
+        let start = Instant::now();
+        let output = next(call, meta);
+        Either::Left(Box::pin(async move {
+            let output = output.await;
+            let latency_us = start.elapsed().as_micros() as u64;
+            let error_code = match &output {
+                Some(Output::Failure(failure)) => Some(failure.error.code.code()),
+                _ => None,
+            };
+            let entry = RpcAccessLogEntry {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+                client,
+                method,
+                params_size,
+                // Filled in by `record_pending` once the response body has been built
+                http_response_size: None,
+                latency_us,
+                error_code,
+                http_error: None,
+            };
+            // Calls made outside of an HTTP request have no response body to measure
+            match pending_entries {
+                Some(pending_entries) => pending_entries.push(entry),
+                None => access_log.record(entry),
+            }
+            output
+        }))
+    }
+}
+
+fn serialized_size(value: &jsonrpc_core::Params) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        jsonrpc_core::{futures::future, Error, MetaIoHandler, Value},
+        solana_ledger::{genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete},
+        solana_runtime::bank::Bank,
+        solana_streamer::socket::SocketAddrSpace,
+        std::{fs, thread::sleep, time::Duration},
+    };
+
+    #[test]
+    fn test_access_log_middleware() {
+        let genesis = create_genesis_config(100);
+        let bank = Arc::new(Bank::new_for_tests(&genesis.genesis_config));
+        let pending_entries = PendingRpcAccessLogEntries::default();
+        let meta = JsonRpcRequestProcessor::new_from_bank(&bank, SocketAddrSpace::Unspecified)
+            .with_client_address(Some("10.0.0.1".to_string()))
+            .with_access_log_entries(Some(pending_entries.clone()));
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let log_path = ledger_path.path().join("access.log");
+        let access_log = RpcAccessLog::new(&RpcAccessLogConfig {
+            enable_metrics: true,
+            log_path: Some(log_path.clone()),
+        })
+        .unwrap();
+        let mut io = MetaIoHandler::with_middleware(access_log.clone());
+        io.add_method_with_meta("getSlot", |_params, _meta: JsonRpcRequestProcessor| {
+            future::ready(Ok(Value::from(0)))
+        });
+        io.add_method_with_meta("getBalance", |_params, _meta: JsonRpcRequestProcessor| {
+            future::ready(Err(Error::invalid_params("Invalid param")))
+        });
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"getSlot"}"#;
+        let response = io.handle_request_sync(request, meta.clone()).unwrap();
+        access_log.record_pending(&pending_entries, Ok(response.len()));
+        let failing_request = r#"{"jsonrpc":"2.0","id":2,"method":"getBalance","params":["x"]}"#;
+        let failing_response = io
+            .handle_request_sync(failing_request, meta.clone())
+            .unwrap();
+        access_log.record_pending(&pending_entries, Ok(failing_response.len()));
+        io.handle_request_sync(request, meta).unwrap();
+        access_log.record_pending(&pending_entries, Err("connection closed".to_string()));
+        // Closes the log, so the writer thread exits once the entries are written
+        drop(io);
+        drop(access_log);
+
+        let mut entries = vec![];
+        for _ in 0..100 {
+            entries = fs::read_to_string(&log_path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<RpcAccessLogEntry>(line).unwrap())
+                .collect::<Vec<_>>();
+            if entries.len() == 3 {
+                break;
+            }
+            sleep(Duration::from_millis(10));
+        }
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].method, "getSlot");
+        assert_eq!(entries[0].client.as_deref(), Some("10.0.0.1"));
+        assert_eq!(
+            entries[0].params_size,
+            serialized_size(&jsonrpc_core::Params::None)
+        );
+        assert_eq!(entries[0].http_response_size, Some(response.len()));
+        assert_eq!(entries[0].error_code, None);
+        assert_eq!(entries[0].http_error, None);
+
+        assert_eq!(entries[1].method, "getBalance");
+        assert_eq!(entries[1].params_size, r#"["x"]"#.len());
+        assert_eq!(entries[1].http_response_size, Some(failing_response.len()));
+        assert_eq!(
+            entries[1].error_code,
+            Some(jsonrpc_core::ErrorCode::InvalidParams.code())
+        );
+
+        // Calls whose HTTP response failed are logged with the error
+        assert_eq!(entries[2].method, "getSlot");
+        assert_eq!(entries[2].http_response_size, None);
+        assert_eq!(entries[2].http_error.as_deref(), Some("connection closed"));
+    }
+}
@@ -5,29 +5,34 @@ use solana_runtime::bank_forks::BankForks;
 #[cfg(test)]
 use std::sync::RwLock;
 use {
-    crate::rpc_subscriptions::{RpcSubscriptions, RpcVote},
+    crate::rpc_subscriptions::{RpcSubscriptions, TransactionSubscriptionFilter},
     jsonrpc_core::{Error, ErrorCode, Result},
     jsonrpc_derive::rpc,
     jsonrpc_pubsub::{typed::Subscriber, Session, SubscriptionId},
     solana_account_decoder::UiAccount,
     solana_client::{
         rpc_config::{
-            RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
-            RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+            RpcAccountInfoConfig, RpcBlockConfig, RpcBlockSubscribeFilter,
+            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcSubscriptionReplayConfig,
+            RpcTransactionLogsConfig, RpcTransactionLogsFilter, RpcTransactionSubscribeConfig,
+            RpcTransactionSubscribeFilter,
         },
         rpc_response::{
-            Response as RpcResponse, RpcKeyedAccount, RpcLogsResponse, RpcSignatureResult,
-            SlotInfo, SlotUpdate,
+            Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
     std::{
+        collections::BTreeSet,
         str::FromStr,
         sync::{atomic, Arc},
     },
 };
 
 pub const MAX_ACTIVE_SUBSCRIPTIONS: usize = 100_000;
+// Limit on the number of addresses in each account list of a `transactionSubscribe` filter
+pub const MAX_TRANSACTION_SUBSCRIBE_FILTER_ADDRESSES: usize = 256;
 
 // Suppress needless_return due to
 //   https://github.com/paritytech/jsonrpc/blob/2d38e6424d8461cdf72e78425ce67d51af9c6586/derive/src/lib.rs#L204
@@ -103,6 +108,51 @@ pub trait RpcSolPubSub {
     )]
     fn logs_unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
 
+    // Get notification when a block is confirmed or finalized, optionally only for blocks that
+    // mention an account or program
+    #[pubsub(subscription = "blockNotification", subscribe, name = "blockSubscribe")]
+    fn block_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcBlockUpdate>>,
+        filter: RpcBlockSubscribeFilter,
+        config: Option<RpcBlockConfig>,
+    );
+
+    // Unsubscribe from block notification subscription.
+    #[pubsub(
+        subscription = "blockNotification",
+        unsubscribe,
+        name = "blockUnsubscribe"
+    )]
+    fn block_unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+
+    // Get notification of each confirmed or finalized transaction that matches the filter
+    #[pubsub(
+        subscription = "transactionNotification",
+        subscribe,
+        name = "transactionSubscribe"
+    )]
+    fn transaction_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    );
+
+    // Unsubscribe from transaction notification subscription.
+    #[pubsub(
+        subscription = "transactionNotification",
+        unsubscribe,
+        name = "transactionUnsubscribe"
+    )]
+    fn transaction_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when signature is verified
     // Accepts signature parameter as base-58 encoded string
     #[pubsub(
@@ -130,9 +180,15 @@ pub trait RpcSolPubSub {
         id: SubscriptionId,
     ) -> Result<bool>;
 
-    // Get notification when slot is encountered
+    // Get notification when slot is encountered, optionally replaying the notifications since
+    // an earlier slot first
     #[pubsub(subscription = "slotNotification", subscribe, name = "slotSubscribe")]
-    fn slot_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<SlotInfo>);
+    fn slot_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<SlotInfo>,
+        config: Option<RpcSubscriptionReplayConfig>,
+    );
 
     // Unsubscribe from slot notification subscription.
     #[pubsub(
@@ -178,9 +234,15 @@ pub trait RpcSolPubSub {
     )]
     fn vote_unsubscribe(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
 
-    // Get notification when a new root is set
+    // Get notification when a new root is set, optionally replaying the notifications since
+    // an earlier slot first
     #[pubsub(subscription = "rootNotification", subscribe, name = "rootSubscribe")]
-    fn root_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<Slot>);
+    fn root_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<Slot>,
+        config: Option<RpcSubscriptionReplayConfig>,
+    );
 
     // Unsubscribe from slot notification subscription.
     #[pubsub(
@@ -247,6 +309,47 @@ fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
     })
 }
 
+fn address_set(addresses: Option<Vec<String>>, thing: &str) -> Result<BTreeSet<Pubkey>> {
+    let addresses = addresses.unwrap_or_default();
+    if addresses.len() > MAX_TRANSACTION_SUBSCRIBE_FILTER_ADDRESSES {
+        return Err(Error::invalid_params(format!(
+            "Invalid Request: Too many {} addresses provided; max {}",
+            thing, MAX_TRANSACTION_SUBSCRIBE_FILTER_ADDRESSES
+        )));
+    }
+    addresses
+        .iter()
+        .map(|address| param::<Pubkey>(address, thing))
+        .collect()
+}
+
+fn verify_transaction_filter(
+    filter: RpcTransactionSubscribeFilter,
+) -> Result<TransactionSubscriptionFilter> {
+    Ok(TransactionSubscriptionFilter {
+        vote: filter.vote.unwrap_or(false),
+        account_include: address_set(filter.account_include, "accountInclude")?,
+        account_exclude: address_set(filter.account_exclude, "accountExclude")?,
+        account_required: address_set(filter.account_required, "accountRequired")?,
+    })
+}
+
+fn verify_filters(config: &Option<RpcProgramAccountsConfig>) -> Result<()> {
+    let filters = config
+        .as_ref()
+        .and_then(|config| config.filters.as_ref())
+        .map(|filters| filters.as_slice())
+        .unwrap_or_default();
+    for filter in filters {
+        filter.verify().map_err(|e| Error {
+            code: ErrorCode::InvalidParams,
+            message: format!("Invalid Request: Invalid filter provided: {}", e),
+            data: None,
+        })?;
+    }
+    Ok(())
+}
+
 impl RpcSolPubSub for RpcSolPubSubImpl {
     type Metadata = Arc<Session>;
 
@@ -301,7 +404,9 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
             subscriber.reject(err).unwrap_or_default();
             return;
         }
-        match param::<Pubkey>(&pubkey_str, "pubkey") {
+        match param::<Pubkey>(&pubkey_str, "pubkey")
+            .and_then(|pubkey| verify_filters(&config).map(|_| pubkey))
+        {
             Ok(pubkey) => {
                 let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
                 let sub_id = SubscriptionId::Number(id as u64);
@@ -373,10 +478,14 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
 
         let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
         let sub_id = SubscriptionId::Number(id as u64);
+        let (commitment, from_slot) = config
+            .map(|config| (config.commitment, config.from_slot))
+            .unwrap_or_default();
         self.subscriptions.add_logs_subscription(
             address,
             include_votes,
-            config.and_then(|config| config.commitment),
+            commitment,
+            from_slot,
             sub_id,
             subscriber,
         )
@@ -395,6 +504,114 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         }
     }
 
+    fn block_subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcBlockUpdate>>,
+        filter: RpcBlockSubscribeFilter,
+        config: Option<RpcBlockConfig>,
+    ) {
+        info!("block_subscribe");
+        if let Err(err) = self.check_subscription_count() {
+            subscriber.reject(err).unwrap_or_default();
+            return;
+        }
+        let commitment = config
+            .and_then(|config| config.commitment)
+            .unwrap_or_default();
+        if !commitment.is_at_least_confirmed() {
+            subscriber
+                .reject(Error::invalid_params(
+                    "Method does not support commitment below `confirmed`",
+                ))
+                .unwrap_or_default();
+            return;
+        }
+        let address = match filter {
+            RpcBlockSubscribeFilter::All => None,
+            RpcBlockSubscribeFilter::MentionsAccountOrProgram(address) => {
+                match param::<Pubkey>(&address, "mentionsAccountOrProgram") {
+                    Ok(address) => Some(address),
+                    Err(e) => {
+                        subscriber.reject(e).unwrap_or_default();
+                        return;
+                    }
+                }
+            }
+        };
+
+        let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
+        let sub_id = SubscriptionId::Number(id as u64);
+        info!("block_subscribe: address={:?} id={:?}", address, sub_id);
+        self.subscriptions
+            .add_block_subscription(address, config, sub_id, subscriber)
+    }
+
+    fn block_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        info!("block_unsubscribe: id={:?}", id);
+        if self.subscriptions.remove_block_subscription(&id) {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: Subscription id does not exist".into(),
+                data: None,
+            })
+        }
+    }
+
+    fn transaction_subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) {
+        info!("transaction_subscribe");
+        if let Err(err) = self.check_subscription_count() {
+            subscriber.reject(err).unwrap_or_default();
+            return;
+        }
+        let commitment = config
+            .and_then(|config| config.commitment)
+            .unwrap_or_default();
+        if !commitment.is_at_least_confirmed() {
+            subscriber
+                .reject(Error::invalid_params(
+                    "Method does not support commitment below `confirmed`",
+                ))
+                .unwrap_or_default();
+            return;
+        }
+        match verify_transaction_filter(filter) {
+            Ok(filter) => {
+                let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
+                let sub_id = SubscriptionId::Number(id as u64);
+                info!("transaction_subscribe: filter={:?} id={:?}", filter, sub_id);
+                self.subscriptions
+                    .add_transaction_subscription(filter, config, sub_id, subscriber)
+            }
+            Err(e) => subscriber.reject(e).unwrap_or_default(),
+        }
+    }
+
+    fn transaction_unsubscribe(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        info!("transaction_unsubscribe: id={:?}", id);
+        if self.subscriptions.remove_transaction_subscription(&id) {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: Subscription id does not exist".into(),
+                data: None,
+            })
+        }
+    }
+
     fn signature_subscribe(
         &self,
         _meta: Self::Metadata,
@@ -443,7 +660,12 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         }
     }
 
-    fn slot_subscribe(&self, _meta: Self::Metadata, subscriber: Subscriber<SlotInfo>) {
+    fn slot_subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<SlotInfo>,
+        config: Option<RpcSubscriptionReplayConfig>,
+    ) {
         info!("slot_subscribe");
         if let Err(err) = self.check_subscription_count() {
             subscriber.reject(err).unwrap_or_default();
@@ -452,7 +674,11 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
         let sub_id = SubscriptionId::Number(id as u64);
         info!("slot_subscribe: id={:?}", sub_id);
-        self.subscriptions.add_slot_subscription(sub_id, subscriber);
+        self.subscriptions.add_slot_subscription(
+            config.and_then(|config| config.from_slot),
+            sub_id,
+            subscriber,
+        );
     }
 
     fn slot_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
@@ -527,7 +753,12 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         }
     }
 
-    fn root_subscribe(&self, _meta: Self::Metadata, subscriber: Subscriber<Slot>) {
+    fn root_subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<Slot>,
+        config: Option<RpcSubscriptionReplayConfig>,
+    ) {
         info!("root_subscribe");
         if let Err(err) = self.check_subscription_count() {
             subscriber.reject(err).unwrap_or_default();
@@ -536,7 +767,11 @@ impl RpcSolPubSub for RpcSolPubSubImpl {
         let id = self.uid.fetch_add(1, atomic::Ordering::Relaxed);
         let sub_id = SubscriptionId::Number(id as u64);
         info!("root_subscribe: id={:?}", sub_id);
-        self.subscriptions.add_root_subscription(sub_id, subscriber);
+        self.subscriptions.add_root_subscription(
+            config.and_then(|config| config.from_slot),
+            sub_id,
+            subscriber,
+        );
     }
 
     fn root_unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
@@ -691,6 +926,7 @@ mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::finalized()),
                 enable_received_notification: Some(true),
+                from_slot: None,
             }),
         );
         let received_slot = 1;
@@ -724,6 +960,7 @@ mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::confirmed()),
                 enable_received_notification: Some(true),
+                from_slot: None,
             }),
         );
         let received_slot = 2;
@@ -1040,6 +1277,40 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_program_subscribe_filters() {
+        let session = create_session();
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(Bank::new_for_tests(
+            &genesis_config,
+        ))));
+
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default_with_bank_forks(bank_forks);
+
+        io.extend_with(rpc.to_delegate());
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"programSubscribe","params":["{}",{{"filters":[{{"memcmp":{{"offset":0,"bytes":"{}","encoding":"base64"}}}},{{"lamports":{{"min":1}}}},"zeroedData"]}}]}}"#,
+            system_program::id(),
+            base64::encode([1, 2, 3]),
+        );
+        let res = io.handle_request_sync(&req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+        let expected: Response = serde_json::from_str(expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        // Test bad filter
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"programSubscribe","params":["{}",{{"filters":[{{"lamports":{{"min":2,"max":1}}}}]}}]}}"#,
+            system_program::id(),
+        );
+        let res = io.handle_request_sync(&req, session);
+        let result: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(result["error"]["code"], ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     #[should_panic]
     fn test_account_commitment_not_fulfilled() {
@@ -1180,7 +1451,7 @@ mod tests {
         let rpc = RpcSolPubSubImpl::default_with_bank_forks(bank_forks);
         let session = create_session();
         let (subscriber, _id_receiver, receiver) = Subscriber::new_test("slotNotification");
-        rpc.slot_subscribe(session, subscriber);
+        rpc.slot_subscribe(session, subscriber, None);
 
         rpc.subscriptions.notify_slot(0, 0, 0);
         // Test slot confirmation notification
@@ -1199,6 +1470,34 @@ mod tests {
         assert_eq!(expected, response);
     }
 
+    #[test]
+    #[serial]
+    fn test_slot_subscribe_from_slot() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let session = create_session();
+        let mut io = PubSubHandler::default();
+        let rpc = RpcSolPubSubImpl::default_with_bank_forks(bank_forks);
+        io.extend_with(rpc.to_delegate());
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"slotSubscribe"}"#;
+        let res = io.handle_request_sync(req, session.clone());
+        let expected = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+        let expected: Response = serde_json::from_str(expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(expected, result);
+
+        // No slots have been buffered
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"slotSubscribe","params":[{"fromSlot":5}]}"#;
+        let res = io.handle_request_sync(req, session);
+        let result: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(
+            result["error"]["code"],
+            solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_REPLAY_SLOT_NOT_AVAILABLE
+        );
+    }
+
     #[test]
     #[serial]
     fn test_slot_unsubscribe() {
@@ -1208,7 +1507,7 @@ mod tests {
         let rpc = RpcSolPubSubImpl::default_with_bank_forks(bank_forks);
         let session = create_session();
         let (subscriber, _id_receiver, receiver) = Subscriber::new_test("slotNotification");
-        rpc.slot_subscribe(session, subscriber);
+        rpc.slot_subscribe(session, subscriber, None);
         rpc.subscriptions.notify_slot(0, 0, 0);
         let (response, _) = robust_poll_or_panic(receiver);
         let expected_res = SlotInfo {
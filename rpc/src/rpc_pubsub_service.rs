@@ -21,6 +21,8 @@ use {
 #[derive(Debug, Clone)]
 pub struct PubSubConfig {
     pub enable_vote_subscription: bool,
+    pub enable_block_subscription: bool,
+    pub enable_transaction_subscription: bool,
 
     // See the corresponding fields in
     // https://github.com/paritytech/ws-rs/blob/be4d47575bae55c60d9f51b47480d355492a94fc/src/lib.rs#L131
@@ -30,17 +32,22 @@ pub struct PubSubConfig {
     pub max_in_buffer_capacity: usize,
     pub max_out_buffer_capacity: usize,
     pub max_active_subscriptions: usize,
+    // Number of slots of notifications kept for subscriptions that start from an earlier slot
+    pub max_replay_slots: usize,
 }
 
 impl Default for PubSubConfig {
     fn default() -> Self {
         Self {
             enable_vote_subscription: false,
+            enable_block_subscription: false,
+            enable_transaction_subscription: false,
             max_connections: 1000, // Arbitrary, default of 100 is too low
             max_fragment_size: 50 * 1024, // 50KB
             max_in_buffer_capacity: 50 * 1024, // 50KB
             max_out_buffer_capacity: 15 * 1024 * 1024, // max account size (10MB), then 5MB extra for base64 encoding overhead/etc
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
+            max_replay_slots: 64,
         }
     }
 }
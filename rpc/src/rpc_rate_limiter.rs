@@ -0,0 +1,470 @@
+//! The `rpc_rate_limiter` module implements admission control for the JSON RPC service: token
+//! bucket rate limits per client and per method, and a cap on concurrently executing heavy
+//! methods.
+
+use {
+    crate::rpc::JsonRpcRequestProcessor,
+    jsonrpc_core::{
+        futures::future::{self, Either},
+        middleware::{Middleware, NoopCallFuture, NoopFuture},
+        Call, Output,
+    },
+    solana_client::rpc_custom_error::RpcCustomError,
+    std::{
+        collections::{HashMap, HashSet},
+        fmt,
+        str::FromStr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+// How often idle buckets are dropped from the limiter
+const BUCKET_PRUNE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A token bucket limit: `requests_per_second` tokens are added to the bucket each second, up
+/// to `burst` tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_second: u64,
+    pub burst: u64,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: u64) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second,
+        }
+    }
+
+    // Time for an empty bucket to fill up again
+    fn refill_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.burst as f64 / self.requests_per_second as f64)
+    }
+}
+
+/// Parses `REQUESTS_PER_SECOND[:BURST]`
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or_else(|| format!("Invalid rate limit {}: expected a positive integer", s))
+        };
+        match s.split_once(':') {
+            Some((requests_per_second, burst)) => Ok(Self {
+                requests_per_second: parse(requests_per_second)?,
+                burst: parse(burst)?,
+            }),
+            None => Ok(Self::new(parse(s)?)),
+        }
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.requests_per_second, self.burst)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RpcRateLimitConfig {
+    /// Limit on the requests of each client, across all methods. Each call consumes its
+    /// method's weight in tokens
+    pub client_limit: Option<RateLimit>,
+    /// Limits on calls of specific methods by each client
+    pub method_limits: HashMap<String, RateLimit>,
+    /// Tokens consumed from `client_limit` by each call of a method; methods not listed here
+    /// consume one token. Methods weighing more than one token are considered heavy
+    pub method_weights: HashMap<String, u64>,
+    /// Maximum number of heavy method calls executing at once, across all clients
+    pub max_concurrent_heavy_calls: Option<usize>,
+}
+
+impl RpcRateLimitConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.client_limit.is_some()
+            || !self.method_limits.is_empty()
+            || self.max_concurrent_heavy_calls.is_some()
+    }
+
+    fn method_weight(&self, method: &str) -> u64 {
+        self.method_weights.get(method).copied().unwrap_or(1)
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * limit.requests_per_second as f64).min(limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    fn can_consume(&mut self, limit: &RateLimit, cost: u64, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        // A call weighing more than the burst size may proceed on a full bucket
+        let cost = cost.min(limit.burst) as f64;
+        if self.tokens >= cost {
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (cost - self.tokens) / limit.requests_per_second as f64,
+            ))
+        }
+    }
+
+    fn consume(&mut self, limit: &RateLimit, cost: u64) {
+        self.tokens -= cost.min(limit.burst) as f64;
+    }
+}
+
+#[derive(Default)]
+struct Buckets {
+    clients: HashMap<String, TokenBucket>,
+    methods: HashMap<(String, String), TokenBucket>,
+    last_prune: Option<Instant>,
+}
+
+/// Held while a heavy method call executes
+pub struct HeavyCallGuard(Arc<AtomicUsize>);
+
+impl Drop for HeavyCallGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub struct RpcRateLimiter {
+    config: RpcRateLimitConfig,
+    heavy_methods: HashSet<String>,
+    buckets: Mutex<Buckets>,
+    heavy_calls: Arc<AtomicUsize>,
+}
+
+impl RpcRateLimiter {
+    pub fn new(config: RpcRateLimitConfig) -> Self {
+        let heavy_methods = config
+            .method_weights
+            .iter()
+            .filter(|(_, weight)| **weight > 1)
+            .map(|(method, _)| method.clone())
+            .collect();
+        Self {
+            config,
+            heavy_methods,
+            buckets: Mutex::default(),
+            heavy_calls: Arc::default(),
+        }
+    }
+
+    /// Admit a call of `method` by `client`, or fail with `RpcCustomError::RateLimited`. Heavy
+    /// calls are counted against `max_concurrent_heavy_calls` until the returned guard is
+    /// dropped.
+    pub fn check(
+        &self,
+        client: &str,
+        method: &str,
+    ) -> Result<Option<HeavyCallGuard>, RpcCustomError> {
+        let now = Instant::now();
+        let rate_limited = |retry_after: Option<Duration>| {
+            inc_new_counter_info!("rpc-rate-limited", 1);
+            RpcCustomError::RateLimited {
+                method: method.to_string(),
+                retry_after_ms: retry_after.map(|retry_after| retry_after.as_millis() as u64 + 1),
+            }
+        };
+
+        let guard = match self.config.max_concurrent_heavy_calls {
+            Some(max_concurrent_heavy_calls) if self.heavy_methods.contains(method) => {
+                let heavy_calls = self.heavy_calls.fetch_add(1, Ordering::Relaxed);
+                let guard = HeavyCallGuard(self.heavy_calls.clone());
+                if heavy_calls >= max_concurrent_heavy_calls {
+                    return Err(rate_limited(None));
+                }
+                Some(guard)
+            }
+            _ => None,
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        self.prune(&mut buckets, now);
+        let Buckets {
+            clients, methods, ..
+        } = &mut *buckets;
+
+        // Both buckets must have enough tokens before either is charged
+        let weight = self.config.method_weight(method);
+        let mut client_bucket = self.config.client_limit.map(|limit| {
+            let bucket = clients
+                .entry(client.to_string())
+                .or_insert_with(|| TokenBucket::new(&limit, now));
+            (limit, bucket)
+        });
+        let mut method_bucket = self.config.method_limits.get(method).map(|limit| {
+            let bucket = methods
+                .entry((client.to_string(), method.to_string()))
+                .or_insert_with(|| TokenBucket::new(limit, now));
+            (*limit, bucket)
+        });
+        if let Some((limit, bucket)) = &mut client_bucket {
+            bucket
+                .can_consume(limit, weight, now)
+                .map_err(|retry_after| rate_limited(Some(retry_after)))?;
+        }
+        if let Some((limit, bucket)) = &mut method_bucket {
+            bucket
+                .can_consume(limit, 1, now)
+                .map_err(|retry_after| rate_limited(Some(retry_after)))?;
+        }
+        if let Some((limit, bucket)) = client_bucket {
+            bucket.consume(&limit, weight);
+        }
+        if let Some((limit, bucket)) = method_bucket {
+            bucket.consume(&limit, 1);
+        }
+        Ok(guard)
+    }
+
+    // Drop buckets that have refilled completely, as they are equivalent to new ones
+    fn prune(&self, buckets: &mut Buckets, now: Instant) {
+        if buckets
+            .last_prune
+            .map(|last_prune| now.duration_since(last_prune) < BUCKET_PRUNE_INTERVAL)
+            .unwrap_or(false)
+        {
+            return;
+        }
+        buckets.last_prune = Some(now);
+
+        if let Some(limit) = &self.config.client_limit {
+            let refill_duration = limit.refill_duration();
+            buckets.clients.retain(|_, bucket| {
+                now.saturating_duration_since(bucket.last_refill) < refill_duration
+            });
+        }
+        let method_limits = &self.config.method_limits;
+        buckets.methods.retain(|(_, method), bucket| {
+            method_limits
+                .get(method)
+                .map(|limit| {
+                    now.saturating_duration_since(bucket.last_refill) < limit.refill_duration()
+                })
+                .unwrap_or(false)
+        });
+    }
+}
+
+impl Middleware<JsonRpcRequestProcessor> for RpcRateLimiter {
+    type Future = NoopFuture;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: std::future::Future<Output = Option<Output>> + Send + 'static,
+    {
+        if !self.config.is_enabled() {
+            return Either::Right(next(call, meta));
+        }
+        let (method, id, jsonrpc) = match &call {
+            Call::MethodCall(method_call) => (
+                &method_call.method,
+                Some(method_call.id.clone()),
+                method_call.jsonrpc,
+            ),
+            Call::Notification(notification) => (&notification.method, None, notification.jsonrpc),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+
+        match self.check(meta.client_address().unwrap_or_default(), method) {
+            Ok(None) => Either::Right(next(call, meta)),
+            Ok(Some(guard)) => {
+                let output = next(call, meta);
+                Either::Left(Box::pin(async move {
+                    let output = output.await;
+                    drop(guard);
+                    output
+                }))
+            }
+            Err(err) => {
+                debug!("RPC call rejected: {}", err);
+                Either::Left(Box::pin(future::ready(
+                    id.map(|id| Output::from(Err(err.into()), id, jsonrpc)),
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        jsonrpc_core::{types::error, MetaIoHandler, Value},
+        solana_ledger::genesis_utils::create_genesis_config,
+        solana_runtime::bank::Bank,
+        solana_streamer::socket::SocketAddrSpace,
+        std::thread::sleep,
+    };
+
+    fn method_limit(method: &str, limit: RateLimit) -> HashMap<String, RateLimit> {
+        vec![(method.to_string(), limit)].into_iter().collect()
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        assert_eq!("10".parse(), Ok(RateLimit::new(10)));
+        assert_eq!(
+            "10:25".parse(),
+            Ok(RateLimit {
+                requests_per_second: 10,
+                burst: 25
+            })
+        );
+        assert!("0".parse::<RateLimit>().is_err());
+        assert!("10:".parse::<RateLimit>().is_err());
+        assert!("ten".parse::<RateLimit>().is_err());
+        assert_eq!(RateLimit::new(3).to_string(), "3:3");
+    }
+
+    #[test]
+    fn test_client_limit() {
+        let rate_limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            client_limit: Some(RateLimit {
+                requests_per_second: 1,
+                burst: 4,
+            }),
+            method_weights: vec![("getProgramAccounts".to_string(), 3)]
+                .into_iter()
+                .collect(),
+            ..RpcRateLimitConfig::default()
+        });
+
+        assert!(rate_limiter.check("a", "getProgramAccounts").is_ok());
+        assert!(rate_limiter.check("a", "getSlot").is_ok());
+        match rate_limiter.check("a", "getSlot") {
+            Err(RpcCustomError::RateLimited {
+                method,
+                retry_after_ms,
+            }) => {
+                assert_eq!(method, "getSlot");
+                assert!(retry_after_ms.unwrap() <= 1001);
+            }
+            _ => panic!("expected the call to be rate limited"),
+        }
+
+        // Clients are limited independently
+        assert!(rate_limiter.check("b", "getSlot").is_ok());
+    }
+
+    #[test]
+    fn test_method_limit() {
+        let rate_limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            client_limit: Some(RateLimit::new(100)),
+            method_limits: method_limit("getProgramAccounts", RateLimit::new(1)),
+            ..RpcRateLimitConfig::default()
+        });
+
+        assert!(rate_limiter.check("a", "getProgramAccounts").is_ok());
+        assert!(rate_limiter.check("a", "getProgramAccounts").is_err());
+        assert!(rate_limiter.check("a", "sendTransaction").is_ok());
+        assert!(rate_limiter.check("b", "getProgramAccounts").is_ok());
+    }
+
+    #[test]
+    fn test_refill() {
+        let rate_limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            client_limit: Some(RateLimit::new(20)),
+            ..RpcRateLimitConfig::default()
+        });
+
+        for _ in 0..20 {
+            assert!(rate_limiter.check("a", "getSlot").is_ok());
+        }
+        assert!(rate_limiter.check("a", "getSlot").is_err());
+        sleep(Duration::from_millis(100));
+        assert!(rate_limiter.check("a", "getSlot").is_ok());
+    }
+
+    #[test]
+    fn test_max_concurrent_heavy_calls() {
+        let rate_limiter = RpcRateLimiter::new(RpcRateLimitConfig {
+            method_weights: vec![("getProgramAccounts".to_string(), 10)]
+                .into_iter()
+                .collect(),
+            max_concurrent_heavy_calls: Some(1),
+            ..RpcRateLimitConfig::default()
+        });
+
+        let guard = rate_limiter.check("a", "getProgramAccounts").unwrap();
+        assert!(guard.is_some());
+        assert!(matches!(
+            rate_limiter.check("b", "getProgramAccounts"),
+            Err(RpcCustomError::RateLimited {
+                retry_after_ms: None,
+                ..
+            })
+        ));
+        assert!(rate_limiter.check("b", "getSlot").unwrap().is_none());
+        drop(guard);
+        assert!(rate_limiter.check("b", "getProgramAccounts").is_ok());
+    }
+
+    #[test]
+    fn test_rate_limiter_middleware() {
+        let genesis = create_genesis_config(100);
+        let bank = Arc::new(Bank::new_for_tests(&genesis.genesis_config));
+        let meta = JsonRpcRequestProcessor::new_from_bank(&bank, SocketAddrSpace::Unspecified);
+
+        let mut io = MetaIoHandler::with_middleware(RpcRateLimiter::new(RpcRateLimitConfig {
+            method_limits: method_limit("getSlot", RateLimit::new(1)),
+            ..RpcRateLimitConfig::default()
+        }));
+        io.add_method_with_meta("getSlot", |_params, _meta: JsonRpcRequestProcessor| {
+            future::ready(Ok(Value::from(0)))
+        });
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"getSlot"}"#;
+        let response: Value =
+            serde_json::from_str(&io.handle_request_sync(request, meta.clone()).unwrap()).unwrap();
+        assert_eq!(response["result"], 0);
+
+        let response: Value =
+            serde_json::from_str(&io.handle_request_sync(request, meta).unwrap()).unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            error::ErrorCode::ServerError(
+                solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_RATE_LIMITED
+            )
+            .code()
+        );
+    }
+}
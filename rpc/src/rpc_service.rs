@@ -9,12 +9,21 @@ use {
             rpc_accounts::*, rpc_bank::*, rpc_deprecated_v1_7::*, rpc_deprecated_v1_8::*,
             rpc_full::*, rpc_minimal::*, rpc_obsolete_v1_7::*, *,
         },
+        rpc_access_log::{PendingRpcAccessLogEntries, RpcAccessLog},
         rpc_health::*,
+        rpc_rate_limiter::RpcRateLimiter,
     },
     jsonrpc_core::{futures::prelude::*, MetaIoHandler},
     jsonrpc_http_server::{
-        hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation, RequestMiddleware,
-        RequestMiddlewareAction, ServerBuilder,
+        cors::AccessControlAllowHeaders,
+        hyper::{
+            self,
+            body::HttpBody,
+            server::conn::AddrStream,
+            service::{make_service_fn, service_fn, Service},
+        },
+        AccessControlAllowOrigin, RequestMiddleware, RequestMiddlewareAction, RestApi, Rpc,
+        ServerHandler,
     },
     regex::Regex,
     solana_client::rpc_cache::LargestAccountsCache,
@@ -35,19 +44,57 @@ use {
         native_token::lamports_to_sol, pubkey::Pubkey,
     },
     solana_send_transaction_service::send_transaction_service::SendTransactionService,
+    solana_storage_bigtable::{FileLedgerStorage, LedgerStorage, LedgerStorageAdapter},
     std::{
         collections::HashSet,
+        convert::Infallible,
         net::SocketAddr,
         path::{Path, PathBuf},
         sync::atomic::{AtomicBool, AtomicU64, Ordering},
         sync::{mpsc::channel, Arc, Mutex, RwLock},
         thread::{self, Builder, JoinHandle},
     },
+    tokio::sync::oneshot,
     tokio_util::codec::{BytesCodec, FramedRead},
 };
 
 const LARGEST_ACCOUNTS_CACHE_DURATION: u64 = 60 * 60 * 2;
 
+// The address of the client: the last entry of `header`, when behind a trusted reverse proxy,
+// or else the peer of the connection. `X-Forwarded-For` style headers are appended to by each
+// proxy, so only the last entry is known to come from the trusted proxy; the earlier entries
+// are whatever the client sent
+fn client_address(
+    req: &hyper::Request<hyper::Body>,
+    header: Option<&hyper::header::HeaderName>,
+) -> Option<String> {
+    let forwarded_address = header
+        .and_then(|header| req.headers().get(header))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(str::trim)
+        .filter(|address| !address.is_empty());
+    match forwarded_address {
+        Some(address) => Some(address.to_string()),
+        None => req
+            .extensions()
+            .get::<SocketAddr>()
+            .map(|peer_address| peer_address.ip().to_string()),
+    }
+}
+
+// Stops the HTTP server, from `JsonRpcService::exit` or the validator exit
+#[derive(Clone)]
+struct CloseHandle(Arc<Mutex<Option<oneshot::Sender<()>>>>);
+
+impl CloseHandle {
+    fn close(&self) {
+        if let Some(sender) = self.0.lock().unwrap().take() {
+            let _ = sender.send(());
+        }
+    }
+}
+
 pub struct JsonRpcService {
     thread_hdl: JoinHandle<()>,
 
@@ -327,13 +374,21 @@ impl JsonRpcService {
 
         let (bigtable_ledger_storage, _bigtable_ledger_upload_service) =
             if config.enable_bigtable_ledger_storage || config.enable_bigtable_ledger_upload {
-                runtime
-                    .block_on(solana_storage_bigtable::LedgerStorage::new(
-                        !config.enable_bigtable_ledger_upload,
-                        config.rpc_bigtable_timeout,
-                    ))
+                let read_only = !config.enable_bigtable_ledger_upload;
+                let ledger_storage = match &config.local_ledger_storage_path {
+                    Some(path) => FileLedgerStorage::new(path, read_only)
+                        .map(|storage| Arc::new(storage) as Arc<dyn LedgerStorageAdapter>),
+                    None => runtime
+                        .block_on(LedgerStorage::new(read_only, config.rpc_bigtable_timeout))
+                        .map(|storage| Arc::new(storage) as Arc<dyn LedgerStorageAdapter>),
+                };
+                let ledger_storage_name = match &config.local_ledger_storage_path {
+                    Some(path) => format!("File ledger storage at {}", path.display()),
+                    None => "BigTable ledger storage".to_string(),
+                };
+                ledger_storage
                     .map(|bigtable_ledger_storage| {
-                        info!("BigTable ledger storage initialized");
+                        info!("{} initialized", ledger_storage_name);
 
                         let bigtable_ledger_upload_service = if config.enable_bigtable_ledger_upload
                         {
@@ -354,13 +409,24 @@ impl JsonRpcService {
                         )
                     })
                     .unwrap_or_else(|err| {
-                        error!("Failed to initialize BigTable ledger storage: {:?}", err);
+                        error!("Failed to initialize {}: {:?}", ledger_storage_name, err);
                         (None, None)
                     })
             } else {
                 (None, None)
             };
 
+        let rate_limiter = RpcRateLimiter::new(config.rate_limit.clone());
+        let access_log = RpcAccessLog::new(&config.access_log).unwrap_or_else(|err| {
+            panic!(
+                "Failed to open RPC access log {:?}: {}",
+                config.access_log.log_path, err
+            )
+        });
+        let client_address_header = config.client_address_header.as_ref().map(|header| {
+            hyper::header::HeaderName::from_bytes(header.as_bytes())
+                .expect("invalid client address header")
+        });
         let minimal_api = config.minimal_api;
         let obsolete_v1_7_api = config.obsolete_v1_7_api;
         let (request_processor, receiver) = JsonRpcRequestProcessor::new(
@@ -397,11 +463,14 @@ impl JsonRpcService {
 
         let ledger_path = ledger_path.to_path_buf();
 
-        let (close_handle_sender, close_handle_receiver) = channel();
+        let (close_sender, close_receiver) = oneshot::channel();
+        let close_handle = CloseHandle(Arc::new(Mutex::new(Some(close_sender))));
+        let (bind_result_sender, bind_result_receiver) = channel();
         let thread_hdl = Builder::new()
             .name("solana-jsonrpc".to_string())
             .spawn(move || {
-                let mut io = MetaIoHandler::default();
+                // Calls rejected by the rate limiter are logged too
+                let mut io = MetaIoHandler::with_middleware((access_log.clone(), rate_limiter));
 
                 io.extend_with(rpc_minimal::MinimalImpl.to_delegate());
                 if !minimal_api {
@@ -421,38 +490,105 @@ impl JsonRpcService {
                     bank_forks.clone(),
                     health.clone(),
                 );
-                let server = ServerBuilder::with_meta_extractor(
-                    io,
-                    move |_req: &hyper::Request<hyper::Body>| request_processor.clone(),
-                )
-                .event_loop_executor(runtime.handle().clone())
-                .threads(1)
-                .cors(DomainsValidation::AllowOnly(vec![
-                    AccessControlAllowOrigin::Any,
-                ]))
-                .cors_max_age(86400)
-                .request_middleware(request_middleware)
-                .max_request_body_size(MAX_REQUEST_PAYLOAD_SIZE)
-                .start_http(&rpc_addr);
-
-                if let Err(e) = server {
-                    warn!(
-                        "JSON RPC service unavailable error: {:?}. \n\
-                           Also, check that port {} is not already in use by another application",
-                        e,
-                        rpc_addr.port()
-                    );
-                    return;
-                }
-
-                let server = server.unwrap();
-                close_handle_sender.send(server.close_handle()).unwrap();
-                server.wait();
+                let rpc = Rpc {
+                    handler: Arc::new(io),
+                    extractor: Arc::new(move |req: &hyper::Request<hyper::Body>| {
+                        request_processor
+                            .clone()
+                            .with_client_address(client_address(
+                                req,
+                                client_address_header.as_ref(),
+                            ))
+                            .with_access_log_entries(
+                                req.extensions().get::<PendingRpcAccessLogEntries>().cloned(),
+                            )
+                    }),
+                };
+                let request_middleware: Arc<dyn RequestMiddleware> = Arc::new(request_middleware);
+
+                // The server is run here rather than by `jsonrpc_http_server::ServerBuilder`,
+                // which doesn't expose the peer address of the connection to the meta extractor
+                runtime.block_on(async move {
+                    let server = match hyper::Server::try_bind(&rpc_addr) {
+                        Ok(server) => server,
+                        Err(e) => {
+                            warn!(
+                                "JSON RPC service unavailable error: {:?}. \n\
+                                   Also, check that port {} is not already in use by another application",
+                                e,
+                                rpc_addr.port()
+                            );
+                            return;
+                        }
+                    };
+                    bind_result_sender.send(()).unwrap();
+
+                    let make_service = make_service_fn(|conn: &AddrStream| {
+                        let peer_address = conn.remote_addr();
+                        let mut handler = ServerHandler::new(
+                            rpc.downgrade(),
+                            Some(vec![AccessControlAllowOrigin::Any]),
+                            Some(86400),
+                            AccessControlAllowHeaders::Any,
+                            None,
+                            request_middleware.clone(),
+                            RestApi::Disabled,
+                            None,
+                            MAX_REQUEST_PAYLOAD_SIZE,
+                            true,
+                        );
+                        let access_log = access_log.clone();
+                        async move {
+                            Ok::<_, Infallible>(service_fn(
+                                move |mut req: hyper::Request<hyper::Body>| {
+                                    req.extensions_mut().insert(peer_address);
+                                    let pending_entries = if access_log.is_enabled() {
+                                        let pending_entries = PendingRpcAccessLogEntries::default();
+                                        req.extensions_mut().insert(pending_entries.clone());
+                                        Some(pending_entries)
+                                    } else {
+                                        None
+                                    };
+                                    let response = handler.call(req);
+                                    let access_log = access_log.clone();
+                                    async move {
+                                        let response = response.await;
+                                        if let Some(pending_entries) = pending_entries {
+                                            let http_response = response
+                                                .as_ref()
+                                                .map(|response| {
+                                                    HttpBody::size_hint(response.body())
+                                                        .exact()
+                                                        .unwrap_or_default()
+                                                        as usize
+                                                })
+                                                .map_err(ToString::to_string);
+                                            access_log
+                                                .record_pending(&pending_entries, http_response);
+                                        }
+                                        response
+                                    }
+                                },
+                            ))
+                        }
+                    });
+                    let server = server
+                        .http1_keepalive(true)
+                        .tcp_nodelay(true)
+                        .tcp_sleep_on_accept_errors(true)
+                        .serve(make_service)
+                        .with_graceful_shutdown(async {
+                            let _ = close_receiver.await;
+                        });
+                    if let Err(err) = server.await {
+                        error!("Error running JSON RPC server: {:?}", err);
+                    }
+                });
                 exit_bigtable_ledger_upload_service.store(true, Ordering::Relaxed);
             })
             .unwrap();
 
-        let close_handle = close_handle_receiver.recv().unwrap();
+        bind_result_receiver.recv().unwrap();
         let close_handle_ = close_handle.clone();
         validator_exit
             .write()
@@ -481,7 +617,11 @@ impl JsonRpcService {
 mod tests {
     use {
         super::*,
-        crate::rpc::create_validator_exit,
+        crate::{
+            rpc::create_validator_exit,
+            rpc_access_log::{RpcAccessLogConfig, RpcAccessLogEntry},
+        },
+        solana_client::rpc_client::RpcClient,
         solana_gossip::{
             contact_info::ContactInfo,
             crds_value::{CrdsData, CrdsValue, SnapshotHash},
@@ -503,8 +643,11 @@ mod tests {
         },
         solana_streamer::socket::SocketAddrSpace,
         std::{
-            io::Write,
-            net::{IpAddr, Ipv4Addr},
+            fs,
+            io::{Read, Write},
+            net::{IpAddr, Ipv4Addr, TcpStream},
+            thread::sleep,
+            time::Duration,
         },
         tokio::runtime::Runtime,
     };
@@ -566,10 +709,141 @@ mod tests {
                 .get_balance(&mint_keypair.pubkey(), None)
                 .value
         );
+
+        let rpc_client =
+            RpcClient::new_socket(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), rpc_addr.port()));
+        assert_eq!(
+            10_000,
+            rpc_client.get_balance(&mint_keypair.pubkey()).unwrap()
+        );
+
         rpc_service.exit();
         rpc_service.join().unwrap();
     }
 
+    #[test]
+    fn test_rpc_access_log_http_response_size() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let exit = Arc::new(AtomicBool::new(false));
+        let validator_exit = create_validator_exit(&exit);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::default(),
+            Arc::new(Keypair::new()),
+            SocketAddrSpace::Unspecified,
+        ));
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let rpc_addr = SocketAddr::new(
+            ip_addr,
+            solana_net_utils::find_available_port_in_range(ip_addr, (10000, 65535)).unwrap(),
+        );
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Arc::new(Blockstore::open(&ledger_path).unwrap());
+        let log_path = ledger_path.join("access.log");
+        let block_commitment_cache = Arc::new(RwLock::new(BlockCommitmentCache::default()));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let mut rpc_service = JsonRpcService::new(
+            rpc_addr,
+            JsonRpcConfig {
+                access_log: RpcAccessLogConfig {
+                    enable_metrics: false,
+                    log_path: Some(log_path.clone()),
+                },
+                ..JsonRpcConfig::default()
+            },
+            None,
+            bank_forks,
+            block_commitment_cache,
+            blockstore,
+            cluster_info,
+            None,
+            Hash::default(),
+            &PathBuf::from("farf"),
+            validator_exit,
+            None,
+            Arc::new(AtomicBool::new(false)),
+            optimistically_confirmed_bank,
+            1000,
+            1,
+            Arc::new(MaxSlots::default()),
+            Arc::new(LeaderScheduleCache::default()),
+            Arc::new(AtomicU64::default()),
+        );
+
+        let request = r#"[{"jsonrpc":"2.0","id":1,"method":"getSlot"},{"jsonrpc":"2.0","id":2,"method":"getEpochInfo"}]"#;
+        let mut stream = TcpStream::connect(rpc_addr).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            rpc_addr,
+            request.len(),
+            request
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        assert!(body.starts_with('['));
+
+        rpc_service.exit();
+        rpc_service.join().unwrap();
+
+        let mut entries = vec![];
+        for _ in 0..100 {
+            entries = fs::read_to_string(&log_path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<RpcAccessLogEntry>(line).unwrap())
+                .collect::<Vec<_>>();
+            if entries.len() == 2 {
+                break;
+            }
+            sleep(Duration::from_millis(10));
+        }
+        assert_eq!(entries.len(), 2);
+        for entry in entries {
+            assert_eq!(entry.client.as_deref(), Some("127.0.0.1"));
+            assert_eq!(entry.http_response_size, Some(body.len()));
+        }
+    }
+
+    #[test]
+    fn test_client_address() {
+        let header = hyper::header::HeaderName::from_static("x-forwarded-for");
+        let peer_address: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+        let request = |forwarded_for: Option<&str>| {
+            let mut request = hyper::Request::builder();
+            if let Some(forwarded_for) = forwarded_for {
+                request = request.header(&header, forwarded_for);
+            }
+            let mut request = request.body(hyper::Body::empty()).unwrap();
+            request.extensions_mut().insert(peer_address);
+            request
+        };
+
+        // The peer address identifies the client unless a trusted proxy header is configured
+        assert_eq!(
+            client_address(&request(Some("192.168.0.1")), None).as_deref(),
+            Some("10.0.0.1")
+        );
+        assert_eq!(
+            client_address(&request(None), Some(&header)).as_deref(),
+            Some("10.0.0.1")
+        );
+        // Only the last entry, appended by the proxy, is trusted
+        assert_eq!(
+            client_address(&request(Some("192.168.0.1, 172.16.0.1")), Some(&header)).as_deref(),
+            Some("172.16.0.1")
+        );
+        assert_eq!(
+            client_address(&request(Some("172.16.0.1")), Some(&header)).as_deref(),
+            Some("172.16.0.1")
+        );
+    }
+
     fn create_bank_forks() -> Arc<RwLock<BankForks>> {
         let GenesisConfigInfo {
             mut genesis_config, ..
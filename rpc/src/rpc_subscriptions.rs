@@ -4,6 +4,7 @@ use {
     crate::{
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::{get_parsed_token_account, get_parsed_token_accounts},
+        rpc_pubsub_service::PubSubConfig,
     },
     core::hash::Hash,
     jsonrpc_pubsub::{
@@ -13,45 +14,55 @@ use {
     serde::Serialize,
     solana_account_decoder::{parse_token::spl_token_id_v2_0, UiAccount, UiAccountEncoding},
     solana_client::{
-        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig},
+        rpc_config::{
+            RpcAccountInfoConfig, RpcBlockConfig, RpcProgramAccountsConfig,
+            RpcSignatureSubscribeConfig, RpcTransactionSubscribeConfig,
+        },
+        rpc_custom_error::RpcCustomError,
         rpc_filter::RpcFilterType,
         rpc_response::{
-            ProcessedSignatureResult, ReceivedSignatureResult, Response, RpcKeyedAccount,
-            RpcLogsResponse, RpcResponseContext, RpcSignatureResult, SlotInfo, SlotUpdate,
+            ProcessedSignatureResult, ReceivedSignatureResult, Response, RpcBlockUpdate,
+            RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
         },
     },
+    solana_ledger::blockstore::Blockstore,
     solana_measure::measure::Measure,
     solana_runtime::{
         bank::{
-            Bank, TransactionLogCollectorConfig, TransactionLogCollectorFilter, TransactionLogInfo,
+            Bank, TransactionLogCollector, TransactionLogCollectorConfig,
+            TransactionLogCollectorFilter, TransactionLogInfo,
         },
         bank_forks::BankForks,
         commitment::{BlockCommitmentCache, CommitmentSlots},
+        status_cache::MAX_CACHE_ENTRIES,
     },
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
-        clock::{Slot, UnixTimestamp},
+        clock::Slot,
         commitment_config::CommitmentConfig,
         pubkey::Pubkey,
         signature::Signature,
         timing::timestamp,
-        transaction,
+        transaction::{self, Transaction},
     },
+    solana_transaction_status::{ConfirmedBlock, TransactionDetails, UiTransactionEncoding},
     solana_vote_program::vote_state::Vote,
     std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeSet, HashMap, HashSet, VecDeque},
         iter,
         sync::{
-            atomic::{AtomicBool, Ordering},
+            atomic::{AtomicBool, AtomicU64, Ordering},
             mpsc::{Receiver, RecvTimeoutError, SendError, Sender},
         },
         sync::{Arc, Mutex, RwLock},
         thread::{Builder, JoinHandle},
-        time::Duration,
+        time::{Duration, Instant},
     },
 };
 
 const RECEIVE_DELAY_MILLIS: u64 = 100;
+const SUBSCRIPTION_COUNTS_REPORT_INTERVAL: Duration = Duration::from_secs(10);
 
 trait BankGetTransactionLogsAdapter {
     fn get_transaction_logs_adapter(
@@ -65,22 +76,21 @@ impl BankGetTransactionLogsAdapter for Bank {
         &self,
         config: &(Option<Pubkey>, bool),
     ) -> Option<Vec<TransactionLogInfo>> {
-        let mut logs = self.get_transaction_logs(config.0.as_ref());
-
-        if config.0.is_none() && !config.1 {
-            // Filter out votes if the subscriber doesn't want them
-            logs = logs.map(|logs| logs.into_iter().filter(|log| !log.is_vote).collect());
-        }
-        logs
+        get_transaction_logs(&self.transaction_log_collector.read().unwrap(), config)
     }
 }
 
-// A more human-friendly version of Vote, with the bank state signature base58 encoded.
-#[derive(Serialize, Deserialize, Debug)]
-pub struct RpcVote {
-    pub slots: Vec<Slot>,
-    pub hash: String,
-    pub timestamp: Option<UnixTimestamp>,
+fn get_transaction_logs(
+    transaction_log_collector: &TransactionLogCollector,
+    config: &(Option<Pubkey>, bool),
+) -> Option<Vec<TransactionLogInfo>> {
+    let mut logs = transaction_log_collector.get_logs_for_address(config.0.as_ref());
+
+    if config.0.is_none() && !config.1 {
+        // Filter out votes if the subscriber doesn't want them
+        logs = logs.map(|logs| logs.into_iter().filter(|log| !log.is_vote).collect());
+    }
+    logs
 }
 
 enum NotificationEntry {
@@ -148,6 +158,67 @@ type RpcSignatureSubscriptions = RwLock<
         HashMap<SubscriptionId, SubscriptionData<Response<RpcSignatureResult>, bool>>,
     >,
 >;
+#[derive(Clone)]
+struct BlockConfig {
+    encoding: UiTransactionEncoding,
+    transaction_details: TransactionDetails,
+    show_rewards: bool,
+}
+// Block subscriptions are keyed by the account that notified blocks must mention, if any
+type RpcBlockSubscriptions = RwLock<
+    HashMap<
+        Option<Pubkey>,
+        HashMap<SubscriptionId, SubscriptionData<Response<RpcBlockUpdate>, BlockConfig>>,
+    >,
+>;
+/// The transactions a `transactionSubscribe` subscription is notified of
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionFilter {
+    pub vote: bool,
+    pub account_include: BTreeSet<Pubkey>,
+    pub account_exclude: BTreeSet<Pubkey>,
+    pub account_required: BTreeSet<Pubkey>,
+}
+
+impl TransactionSubscriptionFilter {
+    fn matches(&self, transaction: &Transaction) -> bool {
+        let account_keys = &transaction.message.account_keys;
+        if !self.vote && is_vote_transaction(transaction) {
+            return false;
+        }
+        (self.account_include.is_empty()
+            || account_keys
+                .iter()
+                .any(|key| self.account_include.contains(key)))
+            && !account_keys
+                .iter()
+                .any(|key| self.account_exclude.contains(key))
+            && self
+                .account_required
+                .iter()
+                .all(|key| account_keys.contains(key))
+    }
+}
+
+fn is_vote_transaction(transaction: &Transaction) -> bool {
+    transaction.message.instructions.iter().any(|instruction| {
+        transaction
+            .message
+            .account_keys
+            .get(instruction.program_id_index as usize)
+            == Some(&solana_vote_program::id())
+    })
+}
+
+type RpcTransactionSubscriptions = RwLock<
+    HashMap<
+        TransactionSubscriptionFilter,
+        HashMap<
+            SubscriptionId,
+            SubscriptionData<Response<RpcTransactionUpdate>, UiTransactionEncoding>,
+        >,
+    >,
+>;
 type RpcSlotSubscriptions = RwLock<HashMap<SubscriptionId, Sink<SlotInfo>>>;
 type RpcSlotUpdateSubscriptions = RwLock<HashMap<SubscriptionId, Sink<Arc<SlotUpdate>>>>;
 type RpcVoteSubscriptions = RwLock<HashMap<SubscriptionId, Sink<RpcVote>>>;
@@ -333,10 +404,9 @@ fn filter_program_results(
     let filters = config.filters;
     let accounts_is_empty = accounts.is_empty();
     let keyed_accounts = accounts.into_iter().filter(move |(_, account)| {
-        filters.iter().all(|filter_type| match filter_type {
-            RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
-            RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
-        })
+        filters
+            .iter()
+            .all(|filter_type| filter_type.allows(account))
     });
     let accounts: Box<dyn Iterator<Item = RpcKeyedAccount>> = if program_id == &spl_token_id_v2_0()
         && encoding == UiAccountEncoding::JsonParsed
@@ -354,6 +424,14 @@ fn filter_program_results(
     (accounts, last_notified_slot)
 }
 
+fn logs_response(log: TransactionLogInfo) -> RpcLogsResponse {
+    RpcLogsResponse {
+        signature: log.signature.to_string(),
+        err: log.result.err(),
+        logs: log.log_messages,
+    }
+}
+
 fn filter_logs_results(
     logs: Option<Vec<TransactionLogInfo>>,
     _address: &(Option<Pubkey>, bool),
@@ -364,16 +442,22 @@ fn filter_logs_results(
     match logs {
         None => (Box::new(iter::empty()), last_notified_slot),
         Some(logs) => (
-            Box::new(logs.into_iter().map(|log| RpcLogsResponse {
-                signature: log.signature.to_string(),
-                err: log.result.err(),
-                logs: log.log_messages,
-            })),
+            Box::new(logs.into_iter().map(logs_response)),
             last_notified_slot,
         ),
     }
 }
 
+fn block_mentions(block: &ConfirmedBlock, address: &Pubkey) -> bool {
+    block.transactions.iter().any(|transaction_with_meta| {
+        transaction_with_meta
+            .transaction
+            .message
+            .account_keys
+            .contains(address)
+    })
+}
+
 fn total_nested_subscriptions<K, L, V>(
     subscription_map: &RwLock<HashMap<K, HashMap<L, V>>>,
 ) -> usize {
@@ -384,6 +468,192 @@ fn total_nested_subscriptions<K, L, V>(
         .fold(0, |acc, x| acc + x.1.len())
 }
 
+type LogsHistory = VecDeque<(Slot, Arc<RwLock<TransactionLogCollector>>)>;
+
+fn push_bounded<T>(buffer: &mut VecDeque<T>, max_len: usize, value: T) {
+    if max_len == 0 {
+        return;
+    }
+    if buffer.len() == max_len {
+        buffer.pop_front();
+    }
+    buffer.push_back(value);
+}
+
+fn check_replay_slot(
+    from_slot: Slot,
+    first_available_slot: Option<Slot>,
+) -> jsonrpc_core::Result<()> {
+    match first_available_slot {
+        Some(first_available_slot) if from_slot >= first_available_slot => Ok(()),
+        _ => Err(RpcCustomError::ReplaySlotNotAvailable {
+            slot: from_slot,
+            first_available_slot,
+        }
+        .into()),
+    }
+}
+
+/// The most recent notifications, which subscriptions that start from an earlier slot are
+/// replayed from. Each buffer holds the notifications of at most `max_slots` slots
+#[derive(Default)]
+struct NotificationHistory {
+    max_slots: usize,
+    slots: VecDeque<SlotInfo>,
+    roots: VecDeque<Slot>,
+    // The transaction logs of the slots that logs subscribers were notified of, by commitment
+    processed_logs: LogsHistory,
+    confirmed_logs: LogsHistory,
+    finalized_logs: LogsHistory,
+    // Banks only collect the logs of addresses that have subscriptions, so the address of a
+    // removed logs subscription is still collected until the slot it was removed at has been
+    // evicted from the logs buffers
+    retained_logs_keys: HashMap<(Option<Pubkey>, bool), Slot>,
+    // The first slot whose logs have been collected for each logs key that is still collected,
+    // keyed like `logs_collection_key()`. Logs are only replayed from that slot, since the slots
+    // before it are missing the key's logs
+    logs_collected_since: HashMap<(Option<Pubkey>, bool), Slot>,
+}
+
+// Logs of an address are collected whether or not votes are, so a subscription's key is
+// collected under the key of its address alone
+fn logs_collection_key(key: &(Option<Pubkey>, bool)) -> (Option<Pubkey>, bool) {
+    match key {
+        (Some(address), _) => (Some(*address), false),
+        (None, include_votes) => (None, *include_votes),
+    }
+}
+
+impl NotificationHistory {
+    fn new(max_slots: usize) -> Self {
+        Self {
+            max_slots,
+            ..Self::default()
+        }
+    }
+
+    fn logs_history(&self, commitment: &CommitmentConfig) -> &LogsHistory {
+        if commitment.is_finalized() {
+            &self.finalized_logs
+        } else if commitment.is_confirmed() {
+            &self.confirmed_logs
+        } else {
+            &self.processed_logs
+        }
+    }
+
+    fn record_slot(&mut self, slot_info: SlotInfo) {
+        push_bounded(&mut self.slots, self.max_slots, slot_info);
+    }
+
+    fn record_root(&mut self, root: Slot) {
+        push_bounded(&mut self.roots, self.max_slots, root);
+    }
+
+    /// Records the logs of `bank`, unless they were already recorded at `commitment`. Returns
+    /// true if any retained logs keys expired as a result
+    fn record_logs(&mut self, commitment: &CommitmentConfig, bank: &Bank) -> bool {
+        let logs_history = if commitment.is_finalized() {
+            &mut self.finalized_logs
+        } else if commitment.is_confirmed() {
+            &mut self.confirmed_logs
+        } else {
+            &mut self.processed_logs
+        };
+        if logs_history.iter().any(|(slot, _)| *slot == bank.slot()) {
+            return false;
+        }
+        push_bounded(
+            logs_history,
+            self.max_slots,
+            (bank.slot(), bank.transaction_log_collector.clone()),
+        );
+
+        let first_slot = [
+            &self.processed_logs,
+            &self.confirmed_logs,
+            &self.finalized_logs,
+        ]
+        .iter()
+        .map(|logs_history| logs_history.front().map(|(slot, _)| *slot))
+        .min()
+        .flatten();
+        match first_slot {
+            Some(first_slot) => {
+                let num_retained_logs_keys = self.retained_logs_keys.len();
+                self.retained_logs_keys
+                    .retain(|_, removed_slot| *removed_slot >= first_slot);
+                self.retained_logs_keys.len() != num_retained_logs_keys
+            }
+            None => false,
+        }
+    }
+
+    fn retain_logs_key(&mut self, key: (Option<Pubkey>, bool), removed_slot: Slot) {
+        if self.max_slots > 0 {
+            self.retained_logs_keys.insert(key, removed_slot);
+        }
+    }
+
+    fn replay_slots(&self, from_slot: Slot) -> jsonrpc_core::Result<Vec<SlotInfo>> {
+        check_replay_slot(
+            from_slot,
+            self.slots.front().map(|slot_info| slot_info.slot),
+        )?;
+        Ok(self
+            .slots
+            .iter()
+            .filter(|slot_info| slot_info.slot >= from_slot)
+            .cloned()
+            .collect())
+    }
+
+    fn replay_roots(&self, from_slot: Slot) -> jsonrpc_core::Result<Vec<Slot>> {
+        check_replay_slot(from_slot, self.roots.front().cloned())?;
+        Ok(self
+            .roots
+            .iter()
+            .filter(|root| **root >= from_slot)
+            .cloned()
+            .collect())
+    }
+
+    fn replay_logs(
+        &self,
+        commitment: &CommitmentConfig,
+        address_with_enable_votes_flag: &(Option<Pubkey>, bool),
+        from_slot: Slot,
+    ) -> jsonrpc_core::Result<Vec<Response<RpcLogsResponse>>> {
+        let logs_history = self.logs_history(commitment);
+        let first_collected_slot = self
+            .logs_collected_since
+            .get(&logs_collection_key(address_with_enable_votes_flag));
+        let first_available_slot = match (logs_history.front(), first_collected_slot) {
+            (Some((first_slot, _)), Some(first_collected_slot)) => {
+                Some(std::cmp::max(*first_slot, *first_collected_slot))
+            }
+            _ => None,
+        };
+        check_replay_slot(from_slot, first_available_slot)?;
+        Ok(logs_history
+            .iter()
+            .filter(|(slot, _)| *slot >= from_slot)
+            .flat_map(|(slot, transaction_log_collector)| {
+                get_transaction_logs(
+                    &transaction_log_collector.read().unwrap(),
+                    address_with_enable_votes_flag,
+                )
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |log| Response {
+                    context: RpcResponseContext { slot: *slot },
+                    value: logs_response(log),
+                })
+            })
+            .collect())
+    }
+}
+
 #[derive(Clone)]
 struct Subscriptions {
     account_subscriptions: Arc<RpcAccountSubscriptions>,
@@ -394,10 +664,17 @@ struct Subscriptions {
     gossip_logs_subscriptions: Arc<RpcLogsSubscriptions>,
     gossip_program_subscriptions: Arc<RpcProgramSubscriptions>,
     gossip_signature_subscriptions: Arc<RpcSignatureSubscriptions>,
+    block_subscriptions: Arc<RpcBlockSubscriptions>,
+    gossip_block_subscriptions: Arc<RpcBlockSubscriptions>,
+    transaction_subscriptions: Arc<RpcTransactionSubscriptions>,
+    gossip_transaction_subscriptions: Arc<RpcTransactionSubscriptions>,
     slot_subscriptions: Arc<RpcSlotSubscriptions>,
     slots_updates_subscriptions: Arc<RpcSlotUpdateSubscriptions>,
     vote_subscriptions: Arc<RpcVoteSubscriptions>,
     root_subscriptions: Arc<RpcRootSubscriptions>,
+    // Held while notifications are recorded and sent, so that a subscription replaying the
+    // history is added either before or after each notification
+    history: Arc<RwLock<NotificationHistory>>,
 }
 
 impl Subscriptions {
@@ -411,11 +688,73 @@ impl Subscriptions {
         total += total_nested_subscriptions(&self.gossip_logs_subscriptions);
         total += total_nested_subscriptions(&self.gossip_program_subscriptions);
         total += total_nested_subscriptions(&self.gossip_signature_subscriptions);
+        total += total_nested_subscriptions(&self.block_subscriptions);
+        total += total_nested_subscriptions(&self.gossip_block_subscriptions);
+        total += total_nested_subscriptions(&self.transaction_subscriptions);
+        total += total_nested_subscriptions(&self.gossip_transaction_subscriptions);
         total += self.slot_subscriptions.read().unwrap().len();
         total += self.vote_subscriptions.read().unwrap().len();
         total += self.root_subscriptions.read().unwrap().len();
         total
     }
+
+    fn report_counts(&self) {
+        datapoint_info!(
+            "rpc-subscription-counts",
+            (
+                "account",
+                total_nested_subscriptions(&self.account_subscriptions)
+                    + total_nested_subscriptions(&self.gossip_account_subscriptions),
+                i64
+            ),
+            (
+                "program",
+                total_nested_subscriptions(&self.program_subscriptions)
+                    + total_nested_subscriptions(&self.gossip_program_subscriptions),
+                i64
+            ),
+            (
+                "logs",
+                total_nested_subscriptions(&self.logs_subscriptions)
+                    + total_nested_subscriptions(&self.gossip_logs_subscriptions),
+                i64
+            ),
+            (
+                "signature",
+                total_nested_subscriptions(&self.signature_subscriptions)
+                    + total_nested_subscriptions(&self.gossip_signature_subscriptions),
+                i64
+            ),
+            (
+                "block",
+                total_nested_subscriptions(&self.block_subscriptions)
+                    + total_nested_subscriptions(&self.gossip_block_subscriptions),
+                i64
+            ),
+            (
+                "transaction",
+                total_nested_subscriptions(&self.transaction_subscriptions)
+                    + total_nested_subscriptions(&self.gossip_transaction_subscriptions),
+                i64
+            ),
+            ("slot", self.slot_subscriptions.read().unwrap().len(), i64),
+            (
+                "slots_updates",
+                self.slots_updates_subscriptions.read().unwrap().len(),
+                i64
+            ),
+            ("vote", self.vote_subscriptions.read().unwrap().len(), i64),
+            ("root", self.root_subscriptions.read().unwrap().len(), i64),
+        );
+    }
+}
+
+/// The blockstore that block and transaction notifications are read from, along with the highest
+/// slot whose transaction statuses have been written to it by the `TransactionStatusService`
+#[derive(Clone)]
+pub struct BlockSubscriptionSource {
+    pub blockstore: Arc<Blockstore>,
+    pub max_complete_transaction_status_slot: Arc<AtomicU64>,
 }
 
 pub struct RpcSubscriptions {
@@ -427,6 +766,8 @@ pub struct RpcSubscriptions {
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     exit: Arc<AtomicBool>,
     enable_vote_subscription: bool,
+    enable_block_subscription: bool,
+    enable_transaction_subscription: bool,
 }
 
 impl Drop for RpcSubscriptions {
@@ -459,6 +800,29 @@ impl RpcSubscriptions {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         enable_vote_subscription: bool,
+    ) -> Self {
+        Self::new_with_config(
+            exit,
+            bank_forks,
+            block_commitment_cache,
+            optimistically_confirmed_bank,
+            &PubSubConfig {
+                enable_vote_subscription,
+                ..PubSubConfig::default()
+            },
+            None,
+        )
+    }
+
+    /// Block and transaction subscriptions are only accepted if they are enabled in `config`
+    /// and a `block_source` is provided
+    pub fn new_with_config(
+        exit: &Arc<AtomicBool>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+        optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
+        config: &PubSubConfig,
+        block_source: Option<BlockSubscriptionSource>,
     ) -> Self {
         let (notification_sender, notification_receiver): (
             Sender<NotificationEntry>,
@@ -473,6 +837,10 @@ impl RpcSubscriptions {
         let gossip_logs_subscriptions = Arc::new(RpcLogsSubscriptions::default());
         let gossip_program_subscriptions = Arc::new(RpcProgramSubscriptions::default());
         let gossip_signature_subscriptions = Arc::new(RpcSignatureSubscriptions::default());
+        let block_subscriptions = Arc::new(RpcBlockSubscriptions::default());
+        let gossip_block_subscriptions = Arc::new(RpcBlockSubscriptions::default());
+        let transaction_subscriptions = Arc::new(RpcTransactionSubscriptions::default());
+        let gossip_transaction_subscriptions = Arc::new(RpcTransactionSubscriptions::default());
         let slot_subscriptions = Arc::new(RpcSlotSubscriptions::default());
         let slots_updates_subscriptions = Arc::new(RpcSlotUpdateSubscriptions::default());
         let vote_subscriptions = Arc::new(RpcVoteSubscriptions::default());
@@ -491,13 +859,23 @@ impl RpcSubscriptions {
             gossip_logs_subscriptions,
             gossip_program_subscriptions,
             gossip_signature_subscriptions,
+            block_subscriptions,
+            gossip_block_subscriptions,
+            transaction_subscriptions,
+            gossip_transaction_subscriptions,
             slot_subscriptions,
             slots_updates_subscriptions,
             vote_subscriptions,
             root_subscriptions,
+            history: Arc::new(RwLock::new(NotificationHistory::new(
+                config.max_replay_slots,
+            ))),
         };
         let _subscriptions = subscriptions.clone();
 
+        let enable_block_subscription = config.enable_block_subscription && block_source.is_some();
+        let enable_transaction_subscription =
+            config.enable_transaction_subscription && block_source.is_some();
         let notifier = RpcNotifier {};
         let t_cleanup = Builder::new()
             .name("solana-rpc-notifications".to_string())
@@ -508,6 +886,7 @@ impl RpcSubscriptions {
                     notification_receiver,
                     _subscriptions,
                     _bank_forks,
+                    block_source,
                 );
             })
             .unwrap();
@@ -520,7 +899,9 @@ impl RpcSubscriptions {
             block_commitment_cache,
             optimistically_confirmed_bank,
             exit: exit.clone(),
-            enable_vote_subscription,
+            enable_vote_subscription: config.enable_vote_subscription,
+            enable_block_subscription,
+            enable_transaction_subscription,
         }
     }
 
@@ -624,17 +1005,8 @@ impl RpcSubscriptions {
         self.subscriptions.total()
     }
 
-    pub fn add_account_subscription(
-        &self,
-        pubkey: Pubkey,
-        config: Option<RpcAccountInfoConfig>,
-        sub_id: SubscriptionId,
-        subscriber: Subscriber<Response<UiAccount>>,
-    ) {
-        let config = config.unwrap_or_default();
-        let commitment = config.commitment.unwrap_or_default();
-
-        let slot = if commitment.is_finalized() {
+    fn commitment_slot(&self, commitment: &CommitmentConfig) -> Slot {
+        if commitment.is_finalized() {
             self.block_commitment_cache
                 .read()
                 .unwrap()
@@ -647,7 +1019,20 @@ impl RpcSubscriptions {
                 .slot()
         } else {
             self.block_commitment_cache.read().unwrap().slot()
-        };
+        }
+    }
+
+    pub fn add_account_subscription(
+        &self,
+        pubkey: Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+        sub_id: SubscriptionId,
+        subscriber: Subscriber<Response<UiAccount>>,
+    ) {
+        let config = config.unwrap_or_default();
+        let commitment = config.commitment.unwrap_or_default();
+
+        let slot = self.commitment_slot(&commitment);
 
         let last_notified_slot = if let Some((_account, slot)) = self
             .bank_forks
@@ -742,16 +1127,33 @@ impl RpcSubscriptions {
         }
     }
 
+    /// If `from_slot` is provided, the logs notified since then are replayed before any new
+    /// ones. Logs are only collected while a subscription with the same filter is active or has
+    /// just been removed, so the subscription is rejected if they were not collected from
+    /// `from_slot` on
     pub fn add_logs_subscription(
         &self,
         address: Option<Pubkey>,
         include_votes: bool,
         commitment: Option<CommitmentConfig>,
+        from_slot: Option<Slot>,
         sub_id: SubscriptionId,
         subscriber: Subscriber<Response<RpcLogsResponse>>,
     ) {
         let commitment = commitment.unwrap_or_default();
+        let key = (address, include_votes);
 
+        let mut history = self.subscriptions.history.write().unwrap();
+        let replayed_logs = match from_slot
+            .map(|from_slot| history.replay_logs(&commitment, &key, from_slot))
+            .transpose()
+        {
+            Ok(replayed_logs) => replayed_logs.unwrap_or_default(),
+            Err(err) => {
+                let _ = subscriber.reject(err);
+                return;
+            }
+        };
         {
             let mut subscriptions = if commitment.is_confirmed() {
                 self.subscriptions
@@ -763,49 +1165,66 @@ impl RpcSubscriptions {
             };
             add_subscription(
                 &mut subscriptions,
-                (address, include_votes),
+                key,
                 commitment,
-                sub_id,
+                sub_id.clone(),
                 subscriber,
                 0, // last_notified_slot is not utilized for logs subscriptions
                 None,
             );
+            let sink = &subscriptions[&key][&sub_id].sink;
+            for logs in replayed_logs {
+                inc_new_counter_info!("rpc-subscription-replay-logs", 1);
+                RpcNotifier.notify(logs, sink);
+            }
         }
-        self.update_bank_transaction_log_keys();
+        Self::update_bank_transaction_log_keys(&self.subscriptions, &mut history, &self.bank_forks);
     }
 
     pub fn remove_logs_subscription(&self, id: &SubscriptionId) -> bool {
-        let mut removed = {
-            let mut subscriptions = self.subscriptions.logs_subscriptions.write().unwrap();
-            remove_subscription(&mut subscriptions, id)
-        };
-
-        if !removed {
-            removed = {
-                let mut subscriptions = self
-                    .subscriptions
-                    .gossip_logs_subscriptions
-                    .write()
-                    .unwrap();
-                remove_subscription(&mut subscriptions, id)
-            };
+        let mut history = self.subscriptions.history.write().unwrap();
+        let mut removed_key = None;
+        for subscriptions in [
+            &self.subscriptions.logs_subscriptions,
+            &self.subscriptions.gossip_logs_subscriptions,
+        ]
+        .iter()
+        {
+            let mut subscriptions = subscriptions.write().unwrap();
+            removed_key = subscriptions
+                .iter()
+                .find(|(_, subscriptions)| subscriptions.contains_key(id))
+                .map(|(key, _)| *key);
+            if removed_key.is_some() {
+                remove_subscription(&mut subscriptions, id);
+                break;
+            }
         }
 
-        if removed {
-            self.update_bank_transaction_log_keys();
+        match removed_key {
+            Some(key) => {
+                let slot = self.block_commitment_cache.read().unwrap().slot();
+                history.retain_logs_key(key, slot);
+                Self::update_bank_transaction_log_keys(
+                    &self.subscriptions,
+                    &mut history,
+                    &self.bank_forks,
+                );
+                true
+            }
+            None => false,
         }
-        removed
     }
 
-    fn update_bank_transaction_log_keys(&self) {
+    fn update_bank_transaction_log_keys(
+        subscriptions: &Subscriptions,
+        history: &mut NotificationHistory,
+        bank_forks: &Arc<RwLock<BankForks>>,
+    ) {
         // Grab a write lock for both `logs_subscriptions` and `gossip_logs_subscriptions`, to
         // ensure `Bank::transaction_log_collector_config` is updated atomically.
-        let logs_subscriptions = self.subscriptions.logs_subscriptions.write().unwrap();
-        let gossip_logs_subscriptions = self
-            .subscriptions
-            .gossip_logs_subscriptions
-            .write()
-            .unwrap();
+        let logs_subscriptions = subscriptions.logs_subscriptions.write().unwrap();
+        let gossip_logs_subscriptions = subscriptions.gossip_logs_subscriptions.write().unwrap();
 
         let mut config = TransactionLogCollectorConfig::default();
 
@@ -815,6 +1234,7 @@ impl RpcSubscriptions {
         for (address, with_votes) in logs_subscriptions
             .keys()
             .chain(gossip_logs_subscriptions.keys())
+            .chain(history.retained_logs_keys.keys())
         {
             match address {
                 None => {
@@ -840,14 +1260,38 @@ impl RpcSubscriptions {
             TransactionLogCollectorFilter::None
         };
 
-        *self
-            .bank_forks
-            .read()
-            .unwrap()
+        let mut collected_keys: HashSet<_> = config
+            .mentioned_addresses
+            .iter()
+            .map(|address| (Some(*address), false))
+            .collect();
+        match config.filter {
+            TransactionLogCollectorFilter::AllWithVotes => {
+                collected_keys.insert((None, true));
+                collected_keys.insert((None, false));
+            }
+            TransactionLogCollectorFilter::All => {
+                collected_keys.insert((None, false));
+            }
+            _ => (),
+        }
+
+        let bank_forks = bank_forks.read().unwrap();
+        *bank_forks
             .root_bank()
             .transaction_log_collector_config
             .write()
             .unwrap() = config;
+
+        // The banks that are already executing transactions may have missed the logs of the
+        // newly collected keys, which are only complete from the slot after them
+        let next_slot = bank_forks.working_bank().slot() + 1;
+        history
+            .logs_collected_since
+            .retain(|key, _| collected_keys.contains(key));
+        for key in collected_keys {
+            history.logs_collected_since.entry(key).or_insert(next_slot);
+        }
     }
 
     pub fn add_signature_subscription(
@@ -857,12 +1301,58 @@ impl RpcSubscriptions {
         sub_id: SubscriptionId,
         subscriber: Subscriber<Response<RpcSignatureResult>>,
     ) {
-        let (commitment, enable_received_notification) = signature_subscribe_config
-            .map(|config| (config.commitment, config.enable_received_notification))
+        let (commitment, enable_received_notification, from_slot) = signature_subscribe_config
+            .map(|config| {
+                (
+                    config.commitment,
+                    config.enable_received_notification,
+                    config.from_slot,
+                )
+            })
             .unwrap_or_default();
 
         let commitment = commitment.unwrap_or_default();
 
+        let history = self.subscriptions.history.read().unwrap();
+        if let Some(from_slot) = from_slot {
+            let slot = self.commitment_slot(&commitment);
+            // Signature statuses are looked up in the status cache rather than the history
+            let max_slots = history.max_slots.min(MAX_CACHE_ENTRIES);
+            let first_available_slot = if max_slots > 0 {
+                Some(slot.saturating_sub(max_slots as u64))
+            } else {
+                None
+            };
+            if let Err(err) = check_replay_slot(from_slot, first_available_slot) {
+                let _ = subscriber.reject(err);
+                return;
+            }
+
+            let status = self
+                .bank_forks
+                .read()
+                .unwrap()
+                .get(slot)
+                .and_then(|bank| bank.get_signature_status_slot(&signature));
+            if let Some((processed_slot, result)) = status {
+                if processed_slot >= from_slot {
+                    // The subscription is complete once notified, so it is never added
+                    let sink = subscriber.assign_id(sub_id).unwrap();
+                    inc_new_counter_info!("rpc-subscription-replay-signature", 1);
+                    RpcNotifier.notify(
+                        Response {
+                            context: RpcResponseContext { slot },
+                            value: RpcSignatureResult::ProcessedSignature(
+                                ProcessedSignatureResult { err: result.err() },
+                            ),
+                        },
+                        &sink,
+                    );
+                    return;
+                }
+            }
+        }
+
         let mut subscriptions = if commitment.is_confirmed() {
             self.subscriptions
                 .gossip_signature_subscriptions
@@ -897,6 +1387,146 @@ impl RpcSubscriptions {
         }
     }
 
+    pub fn add_block_subscription(
+        &self,
+        address: Option<Pubkey>,
+        config: Option<RpcBlockConfig>,
+        sub_id: SubscriptionId,
+        subscriber: Subscriber<Response<RpcBlockUpdate>>,
+    ) {
+        if !self.enable_block_subscription {
+            let _ = subscriber.reject(jsonrpc_core::Error::new(
+                jsonrpc_core::ErrorCode::MethodNotFound,
+            ));
+            return;
+        }
+        let config = config.unwrap_or_default();
+        let commitment = config.commitment.unwrap_or_default();
+
+        // Only blocks after the current one at the requested commitment are notified
+        let (last_notified_slot, mut subscriptions) = if commitment.is_confirmed() {
+            (
+                self.optimistically_confirmed_bank
+                    .read()
+                    .unwrap()
+                    .bank
+                    .slot(),
+                self.subscriptions
+                    .gossip_block_subscriptions
+                    .write()
+                    .unwrap(),
+            )
+        } else {
+            (
+                self.block_commitment_cache
+                    .read()
+                    .unwrap()
+                    .highest_confirmed_root(),
+                self.subscriptions.block_subscriptions.write().unwrap(),
+            )
+        };
+
+        add_subscription(
+            &mut subscriptions,
+            address,
+            commitment,
+            sub_id,
+            subscriber,
+            last_notified_slot,
+            Some(BlockConfig {
+                encoding: config.encoding.unwrap_or(UiTransactionEncoding::Json),
+                transaction_details: config.transaction_details.unwrap_or_default(),
+                show_rewards: config.rewards.unwrap_or(true),
+            }),
+        );
+    }
+
+    pub fn remove_block_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self.subscriptions.block_subscriptions.write().unwrap();
+        if remove_subscription(&mut subscriptions, id) {
+            true
+        } else {
+            let mut subscriptions = self
+                .subscriptions
+                .gossip_block_subscriptions
+                .write()
+                .unwrap();
+            remove_subscription(&mut subscriptions, id)
+        }
+    }
+
+    pub fn add_transaction_subscription(
+        &self,
+        filter: TransactionSubscriptionFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+        sub_id: SubscriptionId,
+        subscriber: Subscriber<Response<RpcTransactionUpdate>>,
+    ) {
+        if !self.enable_transaction_subscription {
+            let _ = subscriber.reject(jsonrpc_core::Error::new(
+                jsonrpc_core::ErrorCode::MethodNotFound,
+            ));
+            return;
+        }
+        let config = config.unwrap_or_default();
+        let commitment = config.commitment.unwrap_or_default();
+
+        // Only transactions of blocks after the current one at the requested commitment are
+        // notified
+        let (last_notified_slot, mut subscriptions) = if commitment.is_confirmed() {
+            (
+                self.optimistically_confirmed_bank
+                    .read()
+                    .unwrap()
+                    .bank
+                    .slot(),
+                self.subscriptions
+                    .gossip_transaction_subscriptions
+                    .write()
+                    .unwrap(),
+            )
+        } else {
+            (
+                self.block_commitment_cache
+                    .read()
+                    .unwrap()
+                    .highest_confirmed_root(),
+                self.subscriptions
+                    .transaction_subscriptions
+                    .write()
+                    .unwrap(),
+            )
+        };
+
+        add_subscription(
+            &mut subscriptions,
+            filter,
+            commitment,
+            sub_id,
+            subscriber,
+            last_notified_slot,
+            Some(config.encoding.unwrap_or(UiTransactionEncoding::Json)),
+        );
+    }
+
+    pub fn remove_transaction_subscription(&self, id: &SubscriptionId) -> bool {
+        let mut subscriptions = self
+            .subscriptions
+            .transaction_subscriptions
+            .write()
+            .unwrap();
+        if remove_subscription(&mut subscriptions, id) {
+            true
+        } else {
+            let mut subscriptions = self
+                .subscriptions
+                .gossip_transaction_subscriptions
+                .write()
+                .unwrap();
+            remove_subscription(&mut subscriptions, id)
+        }
+    }
+
     /// Notify subscribers of changes to any accounts or new signatures since
     /// the bank's last checkpoint.
     pub fn notify_subscribers(&self, commitment_slots: CommitmentSlots) {
@@ -913,8 +1543,28 @@ impl RpcSubscriptions {
         self.enqueue_notification(NotificationEntry::SlotUpdate(slot_update));
     }
 
-    pub fn add_slot_subscription(&self, sub_id: SubscriptionId, subscriber: Subscriber<SlotInfo>) {
+    pub fn add_slot_subscription(
+        &self,
+        from_slot: Option<Slot>,
+        sub_id: SubscriptionId,
+        subscriber: Subscriber<SlotInfo>,
+    ) {
+        let history = self.subscriptions.history.read().unwrap();
+        let replayed_slots = match from_slot
+            .map(|from_slot| history.replay_slots(from_slot))
+            .transpose()
+        {
+            Ok(replayed_slots) => replayed_slots.unwrap_or_default(),
+            Err(err) => {
+                let _ = subscriber.reject(err);
+                return;
+            }
+        };
         let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        for slot_info in replayed_slots {
+            inc_new_counter_info!("rpc-subscription-replay-slot", 1);
+            RpcNotifier.notify(slot_info, &sink);
+        }
         let mut subscriptions = self.subscriptions.slot_subscriptions.write().unwrap();
         subscriptions.insert(sub_id, sink);
     }
@@ -981,8 +1631,28 @@ impl RpcSubscriptions {
         self.enqueue_notification(NotificationEntry::Vote(vote.clone()));
     }
 
-    pub fn add_root_subscription(&self, sub_id: SubscriptionId, subscriber: Subscriber<Slot>) {
+    pub fn add_root_subscription(
+        &self,
+        from_slot: Option<Slot>,
+        sub_id: SubscriptionId,
+        subscriber: Subscriber<Slot>,
+    ) {
+        let history = self.subscriptions.history.read().unwrap();
+        let replayed_roots = match from_slot
+            .map(|from_slot| history.replay_roots(from_slot))
+            .transpose()
+        {
+            Ok(replayed_roots) => replayed_roots.unwrap_or_default(),
+            Err(err) => {
+                let _ = subscriber.reject(err);
+                return;
+            }
+        };
         let sink = subscriber.assign_id(sub_id.clone()).unwrap();
+        for root in replayed_roots {
+            inc_new_counter_info!("rpc-subscription-replay-root", 1);
+            RpcNotifier.notify(root, &sink);
+        }
         let mut subscriptions = self.subscriptions.root_subscriptions.write().unwrap();
         subscriptions.insert(sub_id, sink);
     }
@@ -1026,14 +1696,22 @@ impl RpcSubscriptions {
         notification_receiver: Receiver<NotificationEntry>,
         subscriptions: Subscriptions,
         bank_forks: Arc<RwLock<BankForks>>,
+        block_source: Option<BlockSubscriptionSource>,
     ) {
+        let mut last_counts_report = Instant::now();
         loop {
             if exit.load(Ordering::Relaxed) {
                 break;
             }
+            if last_counts_report.elapsed() >= SUBSCRIPTION_COUNTS_REPORT_INTERVAL {
+                subscriptions.report_counts();
+                last_counts_report = Instant::now();
+            }
             match notification_receiver.recv_timeout(Duration::from_millis(RECEIVE_DELAY_MILLIS)) {
                 Ok(notification_entry) => match notification_entry {
                     NotificationEntry::Slot(slot_info) => {
+                        let mut history = subscriptions.history.write().unwrap();
+                        history.record_slot(slot_info);
                         let subscriptions = subscriptions.slot_subscriptions.read().unwrap();
                         let num_subscriptions = subscriptions.len();
                         if num_subscriptions > 0 {
@@ -1082,6 +1760,8 @@ impl RpcSubscriptions {
                         }
                     }
                     NotificationEntry::Root(root) => {
+                        let mut history = subscriptions.history.write().unwrap();
+                        history.record_root(root);
                         let subscriptions = subscriptions.root_subscriptions.read().unwrap();
                         let num_subscriptions = subscriptions.len();
                         if num_subscriptions > 0 {
@@ -1096,6 +1776,19 @@ impl RpcSubscriptions {
                         }
                     }
                     NotificationEntry::Bank(commitment_slots) => {
+                        let mut history = subscriptions.history.write().unwrap();
+                        Self::record_logs(
+                            &mut history,
+                            &[
+                                (CommitmentConfig::processed(), commitment_slots.slot),
+                                (
+                                    CommitmentConfig::finalized(),
+                                    commitment_slots.highest_confirmed_root,
+                                ),
+                            ],
+                            &subscriptions,
+                            &bank_forks,
+                        );
                         RpcSubscriptions::notify_accounts_logs_programs_signatures(
                             &subscriptions.account_subscriptions,
                             &subscriptions.logs_subscriptions,
@@ -1105,15 +1798,42 @@ impl RpcSubscriptions {
                             &commitment_slots,
                             &notifier,
                             "bank",
-                        )
+                        );
+                        if let Some(block_source) = &block_source {
+                            Self::notify_blocks_and_transactions(
+                                &subscriptions.block_subscriptions,
+                                &subscriptions.transaction_subscriptions,
+                                block_source,
+                                &bank_forks,
+                                commitment_slots.highest_confirmed_root,
+                                &notifier,
+                            );
+                        }
                     }
                     NotificationEntry::Gossip(slot) => {
+                        let mut history = subscriptions.history.write().unwrap();
+                        Self::record_logs(
+                            &mut history,
+                            &[(CommitmentConfig::confirmed(), slot)],
+                            &subscriptions,
+                            &bank_forks,
+                        );
                         Self::process_gossip_notification(
                             slot,
                             &notifier,
                             &subscriptions,
                             &bank_forks,
                         );
+                        if let Some(block_source) = &block_source {
+                            Self::notify_blocks_and_transactions(
+                                &subscriptions.gossip_block_subscriptions,
+                                &subscriptions.gossip_transaction_subscriptions,
+                                block_source,
+                                &bank_forks,
+                                slot,
+                                &notifier,
+                            );
+                        }
                     }
                     NotificationEntry::SignaturesReceived(slot_signatures) => {
                         RpcSubscriptions::process_signatures_received(
@@ -1139,6 +1859,24 @@ impl RpcSubscriptions {
         }
     }
 
+    fn record_logs(
+        history: &mut NotificationHistory,
+        notified_slots: &[(CommitmentConfig, Slot)],
+        subscriptions: &Subscriptions,
+        bank_forks: &Arc<RwLock<BankForks>>,
+    ) {
+        let mut expired_retained_logs_keys = false;
+        for (commitment, slot) in notified_slots {
+            let bank = bank_forks.read().unwrap().get(*slot).cloned();
+            if let Some(bank) = bank {
+                expired_retained_logs_keys |= history.record_logs(commitment, &bank);
+            }
+        }
+        if expired_retained_logs_keys {
+            Self::update_bank_transaction_log_keys(subscriptions, history, bank_forks);
+        }
+    }
+
     fn process_gossip_notification(
         slot: Slot,
         notifier: &RpcNotifier,
@@ -1278,6 +2016,163 @@ impl RpcSubscriptions {
         }
     }
 
+    /// Notify block and transaction subscribers of the blocks up to `slot` that they haven't
+    /// been notified of. A block is only read once its transaction statuses have been written
+    /// to the blockstore
+    fn notify_blocks_and_transactions(
+        block_subscriptions: &Arc<RpcBlockSubscriptions>,
+        transaction_subscriptions: &Arc<RpcTransactionSubscriptions>,
+        block_source: &BlockSubscriptionSource,
+        bank_forks: &Arc<RwLock<BankForks>>,
+        slot: Slot,
+        notifier: &RpcNotifier,
+    ) {
+        let block_subscriptions = block_subscriptions.read().unwrap();
+        let transaction_subscriptions = transaction_subscriptions.read().unwrap();
+        if block_subscriptions.is_empty() && transaction_subscriptions.is_empty() {
+            return;
+        }
+        let bank = match bank_forks.read().unwrap().get(slot).cloned() {
+            Some(bank) => bank,
+            None => return,
+        };
+        let max_complete_transaction_status_slot = block_source
+            .max_complete_transaction_status_slot
+            .load(Ordering::SeqCst);
+        let ancestors: Vec<_> = bank
+            .status_cache_ancestors()
+            .into_iter()
+            .filter(|ancestor| *ancestor <= slot)
+            .collect();
+
+        let mut blocks: HashMap<Slot, Option<Arc<ConfirmedBlock>>> = HashMap::new();
+        let mut get_block = |slot: Slot| {
+            blocks
+                .entry(slot)
+                .or_insert_with(|| {
+                    let mut block = block_source
+                        .blockstore
+                        .get_complete_block(slot, false)
+                        .map_err(|err| warn!("Failed to read block {}: {:?}", slot, err))
+                        .ok()?;
+                    if let Some(bank) = bank_forks.read().unwrap().get(slot) {
+                        block
+                            .block_time
+                            .get_or_insert_with(|| bank.clock().unix_timestamp);
+                        block
+                            .block_height
+                            .get_or_insert_with(|| bank.block_height());
+                    }
+                    Some(Arc::new(block))
+                })
+                .clone()
+        };
+        // Calls `notify_slot` with each unnotified ancestor in order, stopping at the first one
+        // whose transaction statuses haven't been written yet
+        let for_each_unnotified_slot =
+            |last_notified_slot: &RwLock<Slot>, notify_slot: &mut dyn FnMut(Slot)| {
+                let mut w_last_notified_slot = last_notified_slot.write().unwrap();
+                let unnotified_slot = *w_last_notified_slot + 1;
+                for ancestor in ancestors.iter().filter(|s| **s >= unnotified_slot) {
+                    if *ancestor > max_complete_transaction_status_slot {
+                        break;
+                    }
+                    notify_slot(*ancestor);
+                    *w_last_notified_slot = *ancestor;
+                }
+            };
+
+        let mut num_blocks_notified = 0;
+        for (address, hashmap) in block_subscriptions.iter() {
+            for SubscriptionData {
+                sink,
+                last_notified_slot,
+                config,
+                ..
+            } in hashmap.values()
+            {
+                let config = config.as_ref().unwrap();
+                for_each_unnotified_slot(last_notified_slot, &mut |slot| {
+                    let value = match get_block(slot) {
+                        Some(block)
+                            if address
+                                .as_ref()
+                                .map(|address| !block_mentions(&block, address))
+                                .unwrap_or(false) =>
+                        {
+                            return;
+                        }
+                        Some(block) => RpcBlockUpdate {
+                            slot,
+                            block: Some(ConfirmedBlock::clone(&block).configure(
+                                config.encoding,
+                                config.transaction_details,
+                                config.show_rewards,
+                            )),
+                            err: None,
+                        },
+                        None => RpcBlockUpdate {
+                            slot,
+                            block: None,
+                            err: Some(RpcBlockUpdateError::BlockStoreError),
+                        },
+                    };
+                    notifier.notify(
+                        Response {
+                            context: RpcResponseContext { slot },
+                            value,
+                        },
+                        sink,
+                    );
+                    num_blocks_notified += 1;
+                });
+            }
+        }
+        inc_new_counter_info!("rpc-subscription-notify-block", num_blocks_notified);
+
+        let mut num_transactions_notified = 0;
+        for (filter, hashmap) in transaction_subscriptions.iter() {
+            for SubscriptionData {
+                sink,
+                last_notified_slot,
+                config: encoding,
+                ..
+            } in hashmap.values()
+            {
+                let encoding = encoding.unwrap();
+                for_each_unnotified_slot(last_notified_slot, &mut |slot| {
+                    let block = match get_block(slot) {
+                        Some(block) => block,
+                        None => return,
+                    };
+                    for transaction_with_meta in block
+                        .transactions
+                        .iter()
+                        .filter(|transaction| filter.matches(&transaction.transaction))
+                    {
+                        notifier.notify(
+                            Response {
+                                context: RpcResponseContext { slot },
+                                value: RpcTransactionUpdate {
+                                    signature: transaction_with_meta.transaction.signatures[0]
+                                        .to_string(),
+                                    transaction: transaction_with_meta.clone().encode(encoding),
+                                    block_time: block.block_time,
+                                },
+                            },
+                            sink,
+                        );
+                        num_transactions_notified += 1;
+                    }
+                });
+            }
+        }
+        inc_new_counter_info!(
+            "rpc-subscription-notify-transaction",
+            num_transactions_notified
+        );
+    }
+
     fn process_signatures_received(
         (received_slot, signatures): &(Slot, Vec<Signature>),
         signature_subscriptions: &Arc<RpcSignatureSubscriptions>,
@@ -1330,17 +2225,23 @@ impl RpcSubscriptions {
 pub(crate) mod tests {
     use {
         super::*,
-        crate::optimistically_confirmed_bank_tracker::{
-            BankNotification, OptimisticallyConfirmedBank, OptimisticallyConfirmedBankTracker,
+        crate::{
+            optimistically_confirmed_bank_tracker::{
+                BankNotification, OptimisticallyConfirmedBank, OptimisticallyConfirmedBankTracker,
+            },
+            rpc::create_test_transactions_and_populate_blockstore,
         },
-        jsonrpc_core::futures::StreamExt,
+        jsonrpc_core::futures::{executor::block_on, StreamExt},
         jsonrpc_pubsub::typed::Subscriber,
         serial_test::serial,
+        solana_ledger::get_tmp_ledger_path_auto_delete,
         solana_runtime::{
+            bank::ExecuteTimings,
             commitment::BlockCommitment,
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
         },
         solana_sdk::{
+            clock::MAX_PROCESSING_AGE,
             message::Message,
             signature::{Keypair, Signer},
             stake, system_instruction, system_program, system_transaction,
@@ -2139,6 +3040,7 @@ pub(crate) mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::processed()),
                 enable_received_notification: Some(false),
+                from_slot: None,
             }),
             SubscriptionId::Number(1),
             past_bank_sub1,
@@ -2148,6 +3050,7 @@ pub(crate) mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::finalized()),
                 enable_received_notification: Some(false),
+                from_slot: None,
             }),
             SubscriptionId::Number(2),
             past_bank_sub2,
@@ -2157,6 +3060,7 @@ pub(crate) mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::processed()),
                 enable_received_notification: Some(false),
+                from_slot: None,
             }),
             SubscriptionId::Number(3),
             processed_sub,
@@ -2166,6 +3070,7 @@ pub(crate) mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::processed()),
                 enable_received_notification: Some(false),
+                from_slot: None,
             }),
             SubscriptionId::Number(4),
             Subscriber::new_test("signatureNotification").0,
@@ -2176,6 +3081,7 @@ pub(crate) mod tests {
             Some(RpcSignatureSubscribeConfig {
                 commitment: Some(CommitmentConfig::processed()),
                 enable_received_notification: Some(true),
+                from_slot: None,
             }),
             SubscriptionId::Number(5),
             processed_sub3,
@@ -2265,6 +3171,251 @@ pub(crate) mod tests {
         );
     }
 
+    struct BlockSubscriptionsSetup {
+        subscriptions: RpcSubscriptions,
+        mint_keypair: Keypair,
+        alice: Keypair,
+        // Signers of the transactions of slots 1 and 2, see
+        // `create_test_transactions_and_populate_blockstore()`
+        keypairs: Vec<Keypair>,
+        signatures1: Vec<Signature>,
+        signatures2: Vec<Signature>,
+    }
+
+    // Slots 1 and 2 are written to the blockstore with their transaction statuses. Only `alice`
+    // is mentioned by slot 1 alone
+    fn setup_block_subscriptions(
+        blockstore: Arc<Blockstore>,
+        config: &PubSubConfig,
+    ) -> BlockSubscriptionsSetup {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let bank0 = bank_forks.read().unwrap().get(0).unwrap().clone();
+        bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(&bank0, &Pubkey::default(), 1));
+        let bank1 = bank_forks.read().unwrap().get(1).unwrap().clone();
+
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let alice = Keypair::new();
+        let keypairs: Vec<_> = (0..5).map(|_| Keypair::new()).collect();
+        let signatures1 = create_test_transactions_and_populate_blockstore(
+            vec![&mint_keypair, &alice, &keypairs[0], &keypairs[1]],
+            0,
+            bank1.clone(),
+            blockstore.clone(),
+            max_complete_transaction_status_slot.clone(),
+        );
+        bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(&bank1, &Pubkey::default(), 2));
+        let bank2 = bank_forks.read().unwrap().get(2).unwrap().clone();
+        let signatures2 = create_test_transactions_and_populate_blockstore(
+            vec![&mint_keypair, &keypairs[2], &keypairs[3], &keypairs[4]],
+            1,
+            bank2,
+            blockstore.clone(),
+            max_complete_transaction_status_slot.clone(),
+        );
+        // Normally advanced once the statuses of a frozen bank have been written
+        max_complete_transaction_status_slot.store(2, Ordering::SeqCst);
+
+        let subscriptions = RpcSubscriptions::new_with_config(
+            &Arc::new(AtomicBool::new(false)),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks),
+            config,
+            Some(BlockSubscriptionSource {
+                blockstore,
+                max_complete_transaction_status_slot,
+            }),
+        );
+        BlockSubscriptionsSetup {
+            subscriptions,
+            mint_keypair,
+            alice,
+            keypairs,
+            signatures1,
+            signatures2,
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_block_subscribe() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let BlockSubscriptionsSetup {
+            subscriptions,
+            alice,
+            signatures1,
+            signatures2,
+            ..
+        } = setup_block_subscriptions(
+            blockstore,
+            &PubSubConfig {
+                enable_block_subscription: true,
+                ..PubSubConfig::default()
+            },
+        );
+        let config = RpcBlockConfig {
+            transaction_details: Some(TransactionDetails::Signatures),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcBlockConfig::default()
+        };
+        let (all_sub, _id_receiver, all_recv) = Subscriber::new_test("blockNotification");
+        subscriptions.add_block_subscription(
+            None,
+            Some(config),
+            SubscriptionId::Number(0),
+            all_sub,
+        );
+        let (alice_sub, _id_receiver, alice_recv) = Subscriber::new_test("blockNotification");
+        subscriptions.add_block_subscription(
+            Some(alice.pubkey()),
+            Some(config),
+            SubscriptionId::Number(1),
+            alice_sub,
+        );
+        assert_eq!(
+            total_nested_subscriptions(&subscriptions.subscriptions.gossip_block_subscriptions),
+            2
+        );
+
+        // Confirming slot 2 notifies its unnotified ancestors in order
+        subscriptions.notify_gossip_subscribers(2);
+        let notification_slot_and_signatures = |response: String| {
+            let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+            let value = &response["params"]["result"]["value"];
+            assert_eq!(value["err"], serde_json::Value::Null);
+            assert!(value["block"].get("rewards").is_none());
+            let signatures: Vec<String> =
+                serde_json::from_value(value["block"]["signatures"].clone()).unwrap();
+            (value["slot"].as_u64().unwrap(), signatures)
+        };
+        let (response, all_recv) = robust_poll_or_panic(all_recv);
+        let (slot, signatures) = notification_slot_and_signatures(response);
+        assert_eq!(slot, 1);
+        assert!(signatures1
+            .iter()
+            .all(|signature| signatures.contains(&signature.to_string())));
+        let (response, _) = robust_poll_or_panic(all_recv);
+        let (slot, signatures) = notification_slot_and_signatures(response);
+        assert_eq!(slot, 2);
+        assert!(signatures2
+            .iter()
+            .all(|signature| signatures.contains(&signature.to_string())));
+
+        // Only slot 1 mentions alice
+        let (response, mut alice_recv) = robust_poll_or_panic(alice_recv);
+        assert_eq!(notification_slot_and_signatures(response).0, 1);
+        std::thread::sleep(Duration::from_millis(RECEIVE_DELAY_MILLIS));
+        assert!(alice_recv.try_next().is_err());
+
+        assert!(subscriptions.remove_block_subscription(&SubscriptionId::Number(0)));
+        assert!(subscriptions.remove_block_subscription(&SubscriptionId::Number(1)));
+        assert!(!subscriptions.remove_block_subscription(&SubscriptionId::Number(1)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_transaction_subscribe() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let BlockSubscriptionsSetup {
+            subscriptions,
+            mint_keypair,
+            alice,
+            keypairs,
+            signatures1,
+            signatures2,
+        } = setup_block_subscriptions(
+            blockstore,
+            &PubSubConfig {
+                enable_transaction_subscription: true,
+                ..PubSubConfig::default()
+            },
+        );
+        let config = RpcTransactionSubscribeConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+        };
+        let filters = vec![
+            TransactionSubscriptionFilter {
+                account_include: vec![alice.pubkey()].into_iter().collect(),
+                ..TransactionSubscriptionFilter::default()
+            },
+            TransactionSubscriptionFilter {
+                account_required: vec![mint_keypair.pubkey(), keypairs[2].pubkey()]
+                    .into_iter()
+                    .collect(),
+                ..TransactionSubscriptionFilter::default()
+            },
+            // Leaves the successful transfers from the mint
+            TransactionSubscriptionFilter {
+                account_include: vec![mint_keypair.pubkey()].into_iter().collect(),
+                account_exclude: vec![keypairs[0].pubkey(), keypairs[3].pubkey()]
+                    .into_iter()
+                    .collect(),
+                ..TransactionSubscriptionFilter::default()
+            },
+        ];
+        let expected_notifications = vec![
+            vec![(1, signatures1[0])],
+            vec![(2, signatures2[0])],
+            vec![(1, signatures1[0]), (2, signatures2[0])],
+        ];
+
+        let receivers: Vec<_> = filters
+            .into_iter()
+            .enumerate()
+            .map(|(i, filter)| {
+                let (sub, _id_receiver, recv) = Subscriber::new_test("transactionNotification");
+                subscriptions.add_transaction_subscription(
+                    filter,
+                    Some(config),
+                    SubscriptionId::Number(i as u64),
+                    sub,
+                );
+                recv
+            })
+            .collect();
+        subscriptions.notify_gossip_subscribers(2);
+
+        for (recv, expected_notifications) in receivers.into_iter().zip(expected_notifications) {
+            let mut recv = recv;
+            for (expected_slot, expected_signature) in expected_notifications {
+                let (response, next_recv) = robust_poll_or_panic(recv);
+                recv = next_recv;
+                let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+                let result = &response["params"]["result"];
+                assert_eq!(result["context"]["slot"], expected_slot);
+                assert_eq!(result["value"]["signature"], expected_signature.to_string());
+                assert_eq!(
+                    result["value"]["transaction"]["meta"]["err"],
+                    serde_json::Value::Null
+                );
+                assert_eq!(result["value"]["transaction"]["transaction"][1], "base64");
+            }
+            std::thread::sleep(Duration::from_millis(RECEIVE_DELAY_MILLIS));
+            assert!(recv.try_next().is_err());
+        }
+
+        for i in 0..3 {
+            assert!(subscriptions.remove_transaction_subscription(&SubscriptionId::Number(i)));
+        }
+        assert_eq!(subscriptions.total(), 0);
+    }
+
     #[test]
     #[serial]
     fn test_check_slot_subscribe() {
@@ -2283,7 +3434,7 @@ pub(crate) mod tests {
             Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
             optimistically_confirmed_bank,
         );
-        subscriptions.add_slot_subscription(sub_id.clone(), subscriber);
+        subscriptions.add_slot_subscription(None, sub_id.clone(), subscriber);
 
         assert!(subscriptions
             .subscriptions
@@ -2334,7 +3485,7 @@ pub(crate) mod tests {
             Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
             optimistically_confirmed_bank,
         );
-        subscriptions.add_root_subscription(sub_id.clone(), subscriber);
+        subscriptions.add_root_subscription(None, sub_id.clone(), subscriber);
 
         assert!(subscriptions
             .subscriptions
@@ -2366,6 +3517,295 @@ pub(crate) mod tests {
             .contains_key(&sub_id));
     }
 
+    #[test]
+    #[serial]
+    fn test_replay_slot_and_root_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let subscriptions = RpcSubscriptions::new_with_config(
+            &exit,
+            bank_forks,
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+            &PubSubConfig {
+                max_replay_slots: 2,
+                ..PubSubConfig::default()
+            },
+            None,
+        );
+
+        // Nothing has been buffered yet
+        let (subscriber, id_receiver, _transport_receiver) =
+            Subscriber::new_test("rootNotification");
+        subscriptions.add_root_subscription(Some(0), SubscriptionId::Number(0), subscriber);
+        let err = block_on(id_receiver).unwrap().unwrap_err();
+        assert_eq!(
+            err.code,
+            jsonrpc_core::ErrorCode::ServerError(
+                solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_REPLAY_SLOT_NOT_AVAILABLE
+            )
+        );
+
+        let (subscriber, _id_receiver, mut live_receiver) =
+            Subscriber::new_test("rootNotification");
+        subscriptions.add_root_subscription(None, SubscriptionId::Number(1), subscriber);
+        let (slot_subscriber, _id_receiver, mut live_slot_receiver) =
+            Subscriber::new_test("slotNotification");
+        subscriptions.add_slot_subscription(None, SubscriptionId::Number(2), slot_subscriber);
+        subscriptions.notify_roots(vec![1, 2, 3]);
+        for slot in 1..=3 {
+            subscriptions.notify_slot(slot, slot - 1, 0);
+        }
+        // Wait for the notifications to be buffered
+        for _ in 1..=3 {
+            live_receiver = robust_poll_or_panic(live_receiver).1;
+            live_slot_receiver = robust_poll_or_panic(live_slot_receiver).1;
+        }
+
+        // Only the last two roots are kept
+        let (subscriber, id_receiver, _transport_receiver) =
+            Subscriber::new_test("rootNotification");
+        subscriptions.add_root_subscription(Some(1), SubscriptionId::Number(3), subscriber);
+        let err = block_on(id_receiver).unwrap().unwrap_err();
+        assert_eq!(
+            err.message,
+            "Notifications from slot 1 are no longer available for replay. First available slot: 2"
+        );
+        assert!(!subscriptions
+            .subscriptions
+            .root_subscriptions
+            .read()
+            .unwrap()
+            .contains_key(&SubscriptionId::Number(3)));
+
+        let (subscriber, _id_receiver, mut transport_receiver) =
+            Subscriber::new_test("rootNotification");
+        subscriptions.add_root_subscription(Some(2), SubscriptionId::Number(4), subscriber);
+        for expected_root in 2..=3 {
+            let (response, receiver) = robust_poll_or_panic(transport_receiver);
+            transport_receiver = receiver;
+            let expected = format!(
+                r#"{{"jsonrpc":"2.0","method":"rootNotification","params":{{"result":{},"subscription":4}}}}"#,
+                expected_root
+            );
+            assert_eq!(expected, response);
+        }
+
+        // The replayed subscription then receives new roots
+        subscriptions.notify_roots(vec![4]);
+        let (response, _) = robust_poll_or_panic(transport_receiver);
+        assert_eq!(
+            response,
+            r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":4,"subscription":4}}"#
+        );
+
+        let (subscriber, _id_receiver, transport_receiver) =
+            Subscriber::new_test("slotNotification");
+        subscriptions.add_slot_subscription(Some(3), SubscriptionId::Number(5), subscriber);
+        let (response, _) = robust_poll_or_panic(transport_receiver);
+        let expected_res = SlotInfo {
+            slot: 3,
+            parent: 2,
+            root: 0,
+        };
+        let expected_res_str =
+            serde_json::to_string(&serde_json::to_value(expected_res).unwrap()).unwrap();
+        assert_eq!(
+            response,
+            format!(
+                r#"{{"jsonrpc":"2.0","method":"slotNotification","params":{{"result":{},"subscription":5}}}}"#,
+                expected_res_str
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_replay_signature_subscribe() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let transaction = system_transaction::transfer(
+            &mint_keypair,
+            &solana_sdk::pubkey::new_rand(),
+            1,
+            bank.last_blockhash(),
+        );
+        bank.process_transaction(&transaction).unwrap();
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let subscriptions = RpcSubscriptions::default_with_bank_forks(bank_forks);
+
+        let config = RpcSignatureSubscribeConfig {
+            commitment: Some(CommitmentConfig::processed()),
+            enable_received_notification: None,
+            from_slot: Some(0),
+        };
+        let (subscriber, _id_receiver, transport_receiver) =
+            Subscriber::new_test("signatureNotification");
+        subscriptions.add_signature_subscription(
+            transaction.signatures[0],
+            Some(config.clone()),
+            SubscriptionId::Number(0),
+            subscriber,
+        );
+        let (response, _) = robust_poll_or_panic(transport_receiver);
+        assert_eq!(
+            response,
+            r#"{"jsonrpc":"2.0","method":"signatureNotification","params":{"result":{"context":{"slot":0},"value":{"err":null}},"subscription":0}}"#
+        );
+        assert_eq!(subscriptions.total(), 0);
+
+        // Signatures that haven't been processed are subscribed to as usual
+        let (subscriber, _id_receiver, _transport_receiver) =
+            Subscriber::new_test("signatureNotification");
+        subscriptions.add_signature_subscription(
+            Signature::default(),
+            Some(config),
+            SubscriptionId::Number(1),
+            subscriber,
+        );
+        assert_eq!(subscriptions.total(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_replay_logs_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank0 = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank0)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let subscriptions = RpcSubscriptions::new_with_config(
+            &exit,
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+            &PubSubConfig::default(),
+            None,
+        );
+        let (slot_subscriber, _id_receiver, mut slot_receiver) =
+            Subscriber::new_test("slotNotification");
+        subscriptions.add_slot_subscription(None, SubscriptionId::Number(0), slot_subscriber);
+
+        let (subscriber, _id_receiver, transport_receiver) =
+            Subscriber::new_test("logsNotification");
+        let mut transport_receiver = Some(transport_receiver);
+        subscriptions.add_logs_subscription(
+            None,
+            false,
+            Some(CommitmentConfig::processed()),
+            None,
+            SubscriptionId::Number(1),
+            subscriber,
+        );
+
+        let mut signatures = vec![];
+        for slot in 1..=2 {
+            let parent = bank_forks.read().unwrap().get(slot - 1).unwrap().clone();
+            let bank = Bank::new_from_parent(&parent, &Pubkey::default(), slot);
+            let transaction = system_transaction::transfer(
+                &mint_keypair,
+                &solana_sdk::pubkey::new_rand(),
+                1,
+                bank.last_blockhash(),
+            );
+            {
+                // Log messages are only recorded when requested
+                let batch = bank.prepare_batch(vec![transaction.clone()]).unwrap();
+                let _ = bank.load_execute_and_commit_transactions(
+                    &batch,
+                    MAX_PROCESSING_AGE,
+                    false,
+                    false,
+                    true,
+                    &mut ExecuteTimings::default(),
+                );
+            }
+            signatures.push(transaction.signatures[0].to_string());
+            bank_forks.write().unwrap().insert(bank);
+            subscriptions.notify_subscribers(CommitmentSlots {
+                slot,
+                ..CommitmentSlots::default()
+            });
+            // Notifications are processed in order, so the bank notification has been processed
+            // once the slot notification is received
+            subscriptions.notify_slot(slot, slot - 1, 0);
+            slot_receiver = robust_poll_or_panic(slot_receiver).1;
+
+            if slot == 1 {
+                let (response, _) = robust_poll_or_panic(transport_receiver.take().unwrap());
+                let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+                assert_eq!(
+                    response["params"]["result"]["value"]["signature"],
+                    signatures[0]
+                );
+                // The logs of the removed subscription's filter are still collected
+                assert!(subscriptions.remove_logs_subscription(&SubscriptionId::Number(1)));
+            }
+        }
+
+        let (subscriber, id_receiver, _transport_receiver) =
+            Subscriber::new_test("logsNotification");
+        subscriptions.add_logs_subscription(
+            None,
+            false,
+            Some(CommitmentConfig::processed()),
+            Some(0),
+            SubscriptionId::Number(2),
+            subscriber,
+        );
+        assert!(block_on(id_receiver).unwrap().is_err());
+
+        // The logs of other filters were not collected, so they can't be replayed either
+        for (address, include_votes) in [(Some(mint_keypair.pubkey()), false), (None, true)] {
+            let (subscriber, id_receiver, _transport_receiver) =
+                Subscriber::new_test("logsNotification");
+            subscriptions.add_logs_subscription(
+                address,
+                include_votes,
+                Some(CommitmentConfig::processed()),
+                Some(1),
+                SubscriptionId::Number(4),
+                subscriber,
+            );
+            assert!(block_on(id_receiver).unwrap().is_err());
+        }
+
+        let (subscriber, _id_receiver, mut transport_receiver) =
+            Subscriber::new_test("logsNotification");
+        subscriptions.add_logs_subscription(
+            None,
+            false,
+            Some(CommitmentConfig::processed()),
+            Some(1),
+            SubscriptionId::Number(3),
+            subscriber,
+        );
+        for (slot, signature) in (1..=2).zip(signatures) {
+            let (response, receiver) = robust_poll_or_panic(transport_receiver);
+            transport_receiver = receiver;
+            let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+            assert_eq!(response["params"]["subscription"], 3);
+            assert_eq!(response["params"]["result"]["context"]["slot"], slot);
+            assert_eq!(
+                response["params"]["result"]["value"]["signature"],
+                signature
+            );
+        }
+    }
+
     #[test]
     #[serial]
     fn test_add_and_remove_subscription() {
@@ -2678,7 +4118,14 @@ pub(crate) mod tests {
         let (subscriber, _id_receiver, _transport_receiver) =
             Subscriber::new_test("logsNotification");
         let logs_sub_id = SubscriptionId::Number(2u64);
-        subscriptions.add_logs_subscription(None, false, None, logs_sub_id.clone(), subscriber);
+        subscriptions.add_logs_subscription(
+            None,
+            false,
+            None,
+            None,
+            logs_sub_id.clone(),
+            subscriber,
+        );
         assert_eq!(subscriptions.total(), 3);
 
         let (subscriber, _id_receiver, _transport_receiver) =
@@ -2695,7 +4142,7 @@ pub(crate) mod tests {
         let (subscriber, _id_receiver, _transport_receiver) =
             Subscriber::new_test("slotNotification");
         let slot_sub_id = SubscriptionId::Number(4u64);
-        subscriptions.add_slot_subscription(slot_sub_id.clone(), subscriber);
+        subscriptions.add_slot_subscription(None, slot_sub_id.clone(), subscriber);
         assert_eq!(subscriptions.total(), 5);
 
         let (subscriber, _id_receiver, _transport_receiver) =
@@ -2707,7 +4154,7 @@ pub(crate) mod tests {
         let (subscriber, _id_receiver, _transport_receiver) =
             Subscriber::new_test("rootNotification");
         let root_sub_id = SubscriptionId::Number(6u64);
-        subscriptions.add_root_subscription(root_sub_id.clone(), subscriber);
+        subscriptions.add_root_subscription(None, root_sub_id.clone(), subscriber);
         assert_eq!(subscriptions.total(), 7);
 
         // Add duplicate account subscription to ensure totals include all subscriptions on all keys
@@ -64,6 +64,7 @@ impl TransactionStatusService {
                 token_balances,
                 inner_instructions,
                 transaction_logs,
+                transaction_return_data,
                 rent_debits,
             }) => {
                 let slot = bank.slot();
@@ -90,6 +91,7 @@ impl TransactionStatusService {
                     post_token_balances,
                     inner_instructions,
                     log_messages,
+                    return_data,
                     rent_debits,
                 ) in izip!(
                     transactions,
@@ -100,6 +102,7 @@ impl TransactionStatusService {
                     token_balances.post_token_balances,
                     inner_instructions_iter,
                     transaction_logs_iter,
+                    transaction_return_data,
                     rent_debits,
                 ) {
                     if Bank::can_commit(&status) {
@@ -157,6 +160,7 @@ impl TransactionStatusService {
                                     pre_token_balances,
                                     post_token_balances,
                                     rewards,
+                                    return_data,
                                 },
                             )
                             .expect("Expect database write to succeed");
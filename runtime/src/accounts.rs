@@ -748,6 +748,33 @@ impl Accounts {
         )
     }
 
+    /// Load at most `limit` accounts owned by `program_id` that pass `filter`, in pubkey order,
+    /// starting after `start_after`
+    pub fn load_by_program_with_filter_paginated<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+    ) -> ScanResult<Vec<(Pubkey, AccountSharedData)>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        self.accounts_db.ordered_scan_accounts(
+            ancestors,
+            bank_id,
+            start_after,
+            |collector: &mut Vec<(Pubkey, AccountSharedData)>, some_account_tuple| {
+                Self::load_while_filtering(collector, some_account_tuple, |account| {
+                    account.owner() == program_id && filter(account)
+                });
+                collector.len() < limit
+            },
+        )
+    }
+
     pub fn load_by_index_key_with_filter<F: Fn(&AccountSharedData) -> bool>(
         &self,
         ancestors: &Ancestors,
@@ -769,6 +796,32 @@ impl Accounts {
             .map(|result| result.0)
     }
 
+    pub fn load_by_index_key_with_filter_paginated<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: &IndexKey,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+    ) -> ScanResult<Vec<(Pubkey, AccountSharedData)>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        self.accounts_db.ordered_index_scan_accounts(
+            ancestors,
+            bank_id,
+            *index_key,
+            start_after,
+            |collector: &mut Vec<(Pubkey, AccountSharedData)>, some_account_tuple| {
+                Self::load_while_filtering(collector, some_account_tuple, |account| {
+                    filter(account)
+                });
+                collector.len() < limit
+            },
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.accounts_db.account_indexes.include_key(key)
     }
@@ -791,6 +844,33 @@ impl Accounts {
         )
     }
 
+    /// Load at most `limit` accounts, with the slot each was last written in, in pubkey order,
+    /// starting after `start_after`
+    pub fn load_all_paginated(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+    ) -> ScanResult<Vec<(Pubkey, AccountSharedData, Slot)>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        self.accounts_db.ordered_scan_accounts(
+            ancestors,
+            bank_id,
+            start_after,
+            |collector: &mut Vec<(Pubkey, AccountSharedData, Slot)>, some_account_tuple| {
+                if let Some((pubkey, account, slot)) = some_account_tuple
+                    .filter(|(_, account, _)| Self::is_loadable(account.lamports()))
+                {
+                    collector.push((*pubkey, account, slot))
+                }
+                collector.len() < limit
+            },
+        )
+    }
+
     pub fn load_to_collect_rent_eagerly<R: RangeBounds<Pubkey> + std::fmt::Debug>(
         &self,
         ancestors: &Ancestors,
@@ -1671,6 +1751,115 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_by_program_with_filter_paginated() {
+        let accounts = Accounts::new_with_config_for_tests(
+            Vec::new(),
+            &ClusterType::Development,
+            AccountSecondaryIndexes::default(),
+            false,
+            AccountShrinkThreshold::default(),
+        );
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let program_id = Pubkey::new(&[2; 32]);
+
+        // Load accounts owned by various programs into AccountsDb
+        let mut pubkeys = vec![];
+        for i in 0..10 {
+            let pubkey = solana_sdk::pubkey::new_rand();
+            let account = AccountSharedData::new(1, i % 2, &program_id);
+            accounts.store_slow_uncached(0, &pubkey, &account);
+            pubkeys.push(pubkey);
+        }
+        let other_account = AccountSharedData::new(1, 0, &Pubkey::new(&[3; 32]));
+        accounts.store_slow_uncached(0, &solana_sdk::pubkey::new_rand(), &other_account);
+        pubkeys.sort();
+
+        let mut loaded = vec![];
+        let mut start_after = None;
+        loop {
+            let page = accounts
+                .load_by_program_with_filter_paginated(
+                    &ancestors,
+                    0,
+                    &program_id,
+                    start_after.as_ref(),
+                    3,
+                    |_| true,
+                )
+                .unwrap();
+            assert!(page.len() <= 3);
+            if page.is_empty() {
+                break;
+            }
+            start_after = page.last().map(|(pubkey, _)| *pubkey);
+            loaded.extend(page.into_iter().map(|(pubkey, _)| pubkey));
+        }
+        assert_eq!(loaded, pubkeys);
+
+        let loaded = accounts
+            .load_by_program_with_filter_paginated(
+                &ancestors,
+                0,
+                &program_id,
+                None,
+                10,
+                |account| account.data().is_empty(),
+            )
+            .unwrap();
+        assert_eq!(loaded.len(), 5);
+        assert!(loaded.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let loaded = accounts
+            .load_by_program_with_filter_paginated(&ancestors, 0, &program_id, None, 0, |_| true)
+            .unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn test_load_all_paginated() {
+        let accounts = Accounts::new_with_config_for_tests(
+            Vec::new(),
+            &ClusterType::Development,
+            AccountSecondaryIndexes::default(),
+            false,
+            AccountShrinkThreshold::default(),
+        );
+        let ancestors = vec![(0, 0), (1, 1)].into_iter().collect();
+
+        let mut pubkeys = vec![];
+        for slot in 0..2 {
+            for _ in 0..4 {
+                let pubkey = solana_sdk::pubkey::new_rand();
+                let account = AccountSharedData::new(1, 0, &Pubkey::default());
+                accounts.store_slow_uncached(slot, &pubkey, &account);
+                pubkeys.push((pubkey, slot));
+            }
+        }
+        // Closed accounts are not loaded
+        let closed_account = AccountSharedData::new(0, 0, &Pubkey::default());
+        accounts.store_slow_uncached(1, &solana_sdk::pubkey::new_rand(), &closed_account);
+        pubkeys.sort();
+
+        let mut loaded = vec![];
+        let mut start_after = None;
+        loop {
+            let page = accounts
+                .load_all_paginated(&ancestors, 0, start_after.as_ref(), 3)
+                .unwrap();
+            assert!(page.len() <= 3);
+            if page.is_empty() {
+                break;
+            }
+            start_after = page.last().map(|(pubkey, _, _)| *pubkey);
+            loaded.extend(page.into_iter().map(|(pubkey, _, slot)| (pubkey, slot)));
+        }
+        assert_eq!(loaded, pubkeys);
+
+        let loaded = accounts.load_all_paginated(&ancestors, 0, None, 0).unwrap();
+        assert!(loaded.is_empty());
+    }
+
     #[test]
     fn test_accounts_account_not_found() {
         let accounts = Accounts::new_with_config_for_tests(
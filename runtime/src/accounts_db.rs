@@ -21,7 +21,10 @@
 use crate::{
     accounts_background_service::{DroppedSlotsSender, SendDroppedBankCallback},
     accounts_cache::{AccountsCache, CachedAccount, SlotCache},
-    accounts_hash::{AccountsHash, CalculateHashIntermediate, HashStats, PreviousPass},
+    accounts_hash::{
+        AccountsHash, AccountsHashTree, CalculateHashIntermediate, HashStats, PreviousPass,
+        MERKLE_FANOUT,
+    },
     accounts_index::{
         AccountIndexGetResult, AccountSecondaryIndexes, AccountsIndex, AccountsIndexConfig,
         AccountsIndexRootsStats, IndexKey, IsCached, RefCount, ScanResult, SlotList, SlotSlice,
@@ -982,6 +985,13 @@ pub struct AccountsDb {
     /// Zero-lamport accounts that are *not* purged during clean because they need to stay alive
     /// for incremental snapshot support.
     zero_lamport_accounts_to_purge_after_full_snapshot: DashSet<(Slot, Pubkey)>,
+
+    /// Whether to build the Merkle tree of each accounts hash calculated by
+    /// `update_accounts_hash_with_index_option()`, see `enable_accounts_hash_trees()`
+    is_accounts_hash_tree_enabled: AtomicBool,
+
+    /// The Merkle tree of the latest accounts hash calculated, when enabled
+    accounts_hash_tree: RwLock<Option<Arc<AccountsHashTree>>>,
 }
 
 #[derive(Debug, Default)]
@@ -1428,6 +1438,8 @@ impl AccountsDb {
             shrink_ratio: AccountShrinkThreshold::default(),
             dirty_stores: DashMap::default(),
             zero_lamport_accounts_to_purge_after_full_snapshot: DashSet::default(),
+            is_accounts_hash_tree_enabled: AtomicBool::default(),
+            accounts_hash_tree: RwLock::default(),
         }
     }
 
@@ -2722,6 +2734,73 @@ impl AccountsDb {
         Ok(collector)
     }
 
+    /// Like `scan_accounts()`, but visits accounts in pubkey order, starting after
+    /// `start_after`, and stops as soon as `scan_func` returns false
+    pub fn ordered_scan_accounts<F, A>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        start_after: Option<&Pubkey>,
+        scan_func: F,
+    ) -> ScanResult<A>
+    where
+        F: Fn(&mut A, Option<(&Pubkey, AccountSharedData, Slot)>) -> bool,
+        A: Default,
+    {
+        let mut collector = A::default();
+
+        // This can error out if the slots being scanned over are aborted
+        self.accounts_index.ordered_scan_accounts(
+            ancestors,
+            bank_id,
+            start_after,
+            |pubkey, (account_info, slot)| {
+                let account_slot = self
+                    .get_account_accessor(slot, pubkey, account_info.store_id, account_info.offset)
+                    .get_loaded_account()
+                    .map(|loaded_account| (pubkey, loaded_account.take_account(), slot));
+                scan_func(&mut collector, account_slot)
+            },
+        )?;
+
+        Ok(collector)
+    }
+
+    /// Like `ordered_scan_accounts`, but only visits the accounts under `index_key` in the
+    /// secondary index, falling back to scanning every account if the key isn't indexed
+    pub fn ordered_index_scan_accounts<F, A>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: IndexKey,
+        start_after: Option<&Pubkey>,
+        scan_func: F,
+    ) -> ScanResult<A>
+    where
+        F: Fn(&mut A, Option<(&Pubkey, AccountSharedData, Slot)>) -> bool,
+        A: Default,
+    {
+        if !self.account_indexes.include_index_key(&index_key) {
+            return self.ordered_scan_accounts(ancestors, bank_id, start_after, scan_func);
+        }
+
+        let mut collector = A::default();
+        self.accounts_index.ordered_index_scan_accounts(
+            ancestors,
+            bank_id,
+            index_key,
+            start_after,
+            |pubkey, (account_info, slot)| {
+                let account_slot = self
+                    .get_account_accessor(slot, pubkey, account_info.store_id, account_info.offset)
+                    .get_loaded_account()
+                    .map(|loaded_account| (pubkey, loaded_account.take_account(), slot));
+                scan_func(&mut collector, account_slot)
+            },
+        )?;
+        Ok(collector)
+    }
+
     pub fn unchecked_scan_accounts<F, A>(
         &self,
         metric_name: &'static str,
@@ -2801,12 +2880,7 @@ impl AccountsDb {
         F: Fn(&mut A, Option<(&Pubkey, AccountSharedData, Slot)>),
         A: Default,
     {
-        let key = match &index_key {
-            IndexKey::ProgramId(key) => key,
-            IndexKey::SplTokenMint(key) => key,
-            IndexKey::SplTokenOwner(key) => key,
-        };
-        if !self.account_indexes.include_key(key) {
+        if !self.account_indexes.include_index_key(&index_key) {
             // the requested key was not indexed in the secondary index, so do a normal scan
             let used_index = false;
             let scan_result = self.scan_accounts(ancestors, bank_id, scan_func)?;
@@ -4682,16 +4756,9 @@ impl AccountsDb {
         AccountsHash::checked_cast_for_capitalization(balances.map(|b| b as u128).sum::<u128>())
     }
 
-    fn calculate_accounts_hash(
-        &self,
-        slot: Slot,
-        ancestors: &Ancestors,
-        check_hash: bool,
-    ) -> Result<(Hash, u64), BankHashVerificationError> {
-        use BankHashVerificationError::*;
-        let mut collect = Measure::start("collect");
-        let keys: Vec<_> = self
-            .accounts_index
+    /// Every key of the accounts index, in order
+    fn get_sorted_index_keys(&self) -> Vec<Pubkey> {
+        self.accounts_index
             .account_maps
             .iter()
             .map(|map| {
@@ -4700,7 +4767,18 @@ impl AccountsDb {
                 keys
             })
             .flatten()
-            .collect();
+            .collect()
+    }
+
+    fn calculate_accounts_hash(
+        &self,
+        slot: Slot,
+        ancestors: &Ancestors,
+        check_hash: bool,
+    ) -> Result<(Hash, u64), BankHashVerificationError> {
+        use BankHashVerificationError::*;
+        let mut collect = Measure::start("collect");
+        let keys = self.get_sorted_index_keys();
         collect.stop();
 
         let mut scan = Measure::start("scan");
@@ -5060,12 +5138,80 @@ impl AccountsDb {
                 slots_per_epoch,
             )
             .unwrap(); // unwrap here will never fail since check_hash = false
-        let mut bank_hashes = self.bank_hashes.write().unwrap();
-        let mut bank_hash_info = bank_hashes.get_mut(&slot).unwrap();
-        bank_hash_info.snapshot_hash = hash;
+        {
+            let mut bank_hashes = self.bank_hashes.write().unwrap();
+            let mut bank_hash_info = bank_hashes.get_mut(&slot).unwrap();
+            bank_hash_info.snapshot_hash = hash;
+        }
+        if self.is_accounts_hash_tree_enabled.load(Ordering::Relaxed) {
+            self.update_accounts_hash_tree(slot, ancestors, hash);
+        }
         (hash, total_lamports)
     }
 
+    /// Build the Merkle tree of each accounts hash calculated from now on, keeping the latest
+    /// one to prove accounts against. The tree holds the pubkey and hash of every account
+    pub fn enable_accounts_hash_trees(&self) {
+        self.is_accounts_hash_tree_enabled
+            .store(true, Ordering::Relaxed);
+    }
+
+    /// The Merkle tree of the latest accounts hash calculated since
+    /// `enable_accounts_hash_trees()` was called
+    pub fn accounts_hash_tree(&self) -> Option<Arc<AccountsHashTree>> {
+        self.accounts_hash_tree.read().unwrap().clone()
+    }
+
+    /// Build the Merkle tree of the accounts of `slot`, and keep it in place of the previous one
+    /// if its root is `accounts_hash`, as just calculated for `slot`
+    fn update_accounts_hash_tree(&self, slot: Slot, ancestors: &Ancestors, accounts_hash: Hash) {
+        let mut build_time = Measure::start("build_accounts_hash_tree");
+        let keys = self.get_sorted_index_keys();
+        let get_hashes = || {
+            keys.par_iter()
+                .filter_map(|key| {
+                    if let AccountIndexGetResult::Found(lock, index) =
+                        self.accounts_index.get(key, Some(ancestors), Some(slot))
+                    {
+                        let (slot, account_info) = &lock.slot_list()[index];
+                        if account_info.lamports == 0 {
+                            return None;
+                        }
+                        self.get_account_accessor(
+                            *slot,
+                            key,
+                            account_info.store_id,
+                            account_info.offset,
+                        )
+                        .get_loaded_account()
+                        .map(|loaded_account| (*key, loaded_account.loaded_hash()))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let hashes = self.thread_pool_clean.install(get_hashes);
+        let tree = AccountsHashTree::new(slot, hashes, MERKLE_FANOUT);
+        build_time.stop();
+        if tree.root() != accounts_hash {
+            warn!(
+                "accounts hash tree of slot {} doesn't match its accounts hash: {}, expected: {}",
+                slot,
+                tree.root(),
+                accounts_hash
+            );
+            return;
+        }
+        datapoint_info!(
+            "accounts_hash_tree",
+            ("slot", slot, i64),
+            ("num_accounts", tree.num_accounts(), i64),
+            ("build_us", build_time.as_us(), i64),
+        );
+        *self.accounts_hash_tree.write().unwrap() = Some(Arc::new(tree));
+    }
+
     fn scan_snapshot_stores_with_cache(
         storage: &SortedStorages,
         mut stats: &mut crate::accounts_hash::HashStats,
@@ -2,6 +2,8 @@ use log::*;
 use rayon::prelude::*;
 use solana_measure::measure::Measure;
 use solana_sdk::{
+    account_proof::{MerkleProof, MerkleProofLevel},
+    clock::Slot,
     hash::{Hash, Hasher},
     pubkey::Pubkey,
 };
@@ -195,6 +197,90 @@ impl CumulativeOffsets {
     }
 }
 
+/// The Merkle tree of an accounts hash, kept to prove that accounts are part of it without
+/// hashing every account again
+#[derive(Debug)]
+pub struct AccountsHashTree {
+    slot: Slot,
+    fanout: usize,
+    /// The pubkeys of the leaves, in order
+    pubkeys: Vec<Pubkey>,
+    /// The nodes of each level, from the account hashes up to the root
+    levels: Vec<Vec<Hash>>,
+}
+
+impl AccountsHashTree {
+    /// Build the tree of the accounts hash of `slot` from the hash of each account, sorted by
+    /// pubkey. Its root is the same as `AccountsHash::compute_merkle_root()`
+    pub fn new(slot: Slot, hashes: Vec<(Pubkey, Hash)>, fanout: usize) -> Self {
+        let (pubkeys, leaves): (Vec<Pubkey>, Vec<Hash>) = hashes.into_iter().unzip();
+        let mut levels = vec![leaves];
+        loop {
+            let level = levels.last().unwrap();
+            if level.is_empty() || (level.len() == 1 && levels.len() > 1) {
+                break;
+            }
+            let next_level = level
+                .par_chunks(fanout)
+                .map(|chunk| {
+                    let mut hasher = Hasher::default();
+                    chunk.iter().for_each(|hash| hasher.hash(hash.as_ref()));
+                    hasher.result()
+                })
+                .collect();
+            levels.push(next_level);
+        }
+        Self {
+            slot,
+            fanout,
+            pubkeys,
+            levels,
+        }
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    pub fn num_accounts(&self) -> usize {
+        self.pubkeys.len()
+    }
+
+    pub fn root(&self) -> Hash {
+        if self.levels.len() > 1 {
+            self.levels.last().unwrap()[0]
+        } else {
+            Hasher::default().result()
+        }
+    }
+
+    /// The hash of the account at `pubkey` and its proof against the root, or `None` if the
+    /// account isn't part of the accounts hash
+    pub fn get_proof(&self, pubkey: &Pubkey) -> Option<(Hash, MerkleProof)> {
+        let mut index = self.pubkeys.binary_search(pubkey).ok()?;
+        let leaf = self.levels[0][index];
+        let levels = self.levels[..self.levels.len() - 1]
+            .iter()
+            .map(|level| {
+                let start = index - index % self.fanout;
+                let end = std::cmp::min(start + self.fanout, level.len());
+                let siblings = level[start..index]
+                    .iter()
+                    .chain(&level[index + 1..end])
+                    .cloned()
+                    .collect();
+                let proof_level = MerkleProofLevel {
+                    index: index - start,
+                    siblings,
+                };
+                index /= self.fanout;
+                proof_level
+            })
+            .collect();
+        Some((leaf, MerkleProof { levels }))
+    }
+}
+
 #[derive(Debug)]
 pub struct AccountsHash {
     pub dummy: i32,
@@ -1715,6 +1801,38 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_accounts_hash_tree() {
+        for fanout in [2, 3, MERKLE_FANOUT] {
+            for count in [
+                0,
+                1,
+                2,
+                fanout,
+                fanout + 1,
+                fanout * fanout,
+                fanout * fanout + 3,
+            ] {
+                let mut hashes: Vec<_> = (0..count)
+                    .map(|_| (Pubkey::new_unique(), Hash::new_unique()))
+                    .collect();
+                AccountsHash::sort_hashes_by_pubkey(&mut hashes);
+                let root = AccountsHash::compute_merkle_root(hashes.clone(), fanout);
+                let tree = AccountsHashTree::new(5, hashes.clone(), fanout);
+                assert_eq!(tree.slot(), 5);
+                assert_eq!(tree.num_accounts(), count);
+                assert_eq!(tree.root(), root);
+                for (pubkey, hash) in &hashes {
+                    let (leaf, proof) = tree.get_proof(pubkey).unwrap();
+                    assert_eq!(leaf, *hash);
+                    assert!(proof.verify(*hash, &root), "{} {}", fanout, count);
+                    assert!(!proof.verify(Hash::new_unique(), &root));
+                }
+                assert_eq!(tree.get_proof(&Pubkey::new_unique()), None);
+            }
+        }
+    }
+
     #[test]
     fn test_accountsdb_compute_merkle_root() {
         solana_logger::setup();
@@ -6,6 +6,7 @@ use crate::{
     secondary_index::*,
 };
 use bv::BitVec;
+use dashmap::{mapref::one::Ref, DashMap};
 use log::*;
 use ouroboros::self_referencing;
 use solana_measure::measure::Measure;
@@ -58,7 +59,8 @@ pub enum ScanError {
 
 enum ScanTypes<R: RangeBounds<Pubkey>> {
     Unindexed(Option<R>),
-    Indexed(IndexKey),
+    // With a range, only the indexed keys in the range are scanned, in order
+    Indexed(IndexKey, Option<R>),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -66,6 +68,7 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    Custom(CustomAccountIndex, Pubkey),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -75,12 +78,41 @@ pub enum AccountIndex {
     SplTokenOwner,
 }
 
+/// An index on the `length` bytes at `offset` in the data of the accounts owned by
+/// `program_id`. `length` is at most `PUBKEY_BYTES`; shorter keys are padded with zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomAccountIndex {
+    pub program_id: Pubkey,
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl CustomAccountIndex {
+    pub fn key_from_bytes(bytes: &[u8]) -> Pubkey {
+        let mut key = [0; PUBKEY_BYTES];
+        key[..bytes.len()].copy_from_slice(bytes);
+        Pubkey::new_from_array(key)
+    }
+
+    /// The key of an account with `data` in this index, if the data is long enough
+    pub fn key_from_data(&self, data: &[u8]) -> Option<Pubkey> {
+        let end = self.offset.checked_add(self.length)?;
+        data.get(self.offset..end).map(Self::key_from_bytes)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct AccountSecondaryIndexesIncludeExclude {
     pub exclude: bool,
     pub keys: HashSet<Pubkey>,
 }
 
+impl AccountSecondaryIndexesIncludeExclude {
+    fn include_key(&self, key: &Pubkey) -> bool {
+        self.exclude ^ self.keys.contains(key)
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct AccountsIndexConfig {
     pub bins: Option<usize>,
@@ -90,21 +122,42 @@ pub struct AccountsIndexConfig {
 pub struct AccountSecondaryIndexes {
     pub keys: Option<AccountSecondaryIndexesIncludeExclude>,
     pub indexes: HashSet<AccountIndex>,
+    /// Custom indexes, each with its own set of included or excluded keys
+    pub custom_indexes: HashMap<CustomAccountIndex, Option<AccountSecondaryIndexesIncludeExclude>>,
 }
 
 impl AccountSecondaryIndexes {
     pub fn is_empty(&self) -> bool {
-        self.indexes.is_empty()
+        self.indexes.is_empty() && self.custom_indexes.is_empty()
     }
     pub fn contains(&self, index: &AccountIndex) -> bool {
         self.indexes.contains(index)
     }
     pub fn include_key(&self, key: &Pubkey) -> bool {
         match &self.keys {
-            Some(options) => options.exclude ^ options.keys.contains(key),
+            Some(options) => options.include_key(key),
             None => true, // include all keys
         }
     }
+    pub fn contains_custom(&self, index: &CustomAccountIndex) -> bool {
+        self.custom_indexes.contains_key(index)
+    }
+    pub fn include_custom_key(&self, index: &CustomAccountIndex, key: &Pubkey) -> bool {
+        match self.custom_indexes.get(index) {
+            Some(Some(options)) => options.include_key(key),
+            Some(None) => true,
+            None => false,
+        }
+    }
+    /// Whether the accounts of `index_key` are found in the index, assuming it is enabled
+    pub fn include_index_key(&self, index_key: &IndexKey) -> bool {
+        match index_key {
+            IndexKey::ProgramId(key)
+            | IndexKey::SplTokenMint(key)
+            | IndexKey::SplTokenOwner(key) => self.include_key(key),
+            IndexKey::Custom(index, key) => self.include_custom_key(index, key),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -784,6 +837,8 @@ pub struct AccountsIndex<T> {
     program_id_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    // Created as the first account is added to each index
+    custom_indexes: DashMap<CustomAccountIndex, SecondaryIndex<DashMapSecondaryIndexEntry>>,
     roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -818,6 +873,7 @@ impl<T: IsCached> AccountsIndex<T> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            custom_indexes: DashMap::default(),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -845,6 +901,7 @@ impl<T: IsCached> AccountsIndex<T> {
         AccountsIndexIterator::new(self, range, collect_all_unsorted)
     }
 
+    /// `func` returns whether the scan should continue
     fn do_checked_scan_accounts<F, R>(
         &self,
         metric_name: &'static str,
@@ -855,7 +912,7 @@ impl<T: IsCached> AccountsIndex<T> {
         collect_all_unsorted: bool,
     ) -> Result<(), ScanError>
     where
-        F: FnMut(&Pubkey, (&T, Slot)),
+        F: FnMut(&Pubkey, (&T, Slot)) -> bool,
         R: RangeBounds<Pubkey> + std::fmt::Debug,
     {
         {
@@ -1014,33 +1071,48 @@ impl<T: IsCached> AccountsIndex<T> {
                     collect_all_unsorted,
                 );
             }
-            ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
+            ScanTypes::Indexed(IndexKey::ProgramId(program_id), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.program_id_index,
                     &program_id,
+                    range,
                     Some(max_root),
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_mint_index,
                     &mint_key,
+                    range,
                     Some(max_root),
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_owner_index,
                     &owner_key,
+                    range,
                     Some(max_root),
                 );
             }
+            ScanTypes::Indexed(IndexKey::Custom(index, key), range) => {
+                if let Some(custom_index) = self.custom_indexes.get(&index) {
+                    self.do_scan_secondary_index(
+                        ancestors,
+                        func,
+                        custom_index.value(),
+                        &key,
+                        range,
+                        Some(max_root),
+                    );
+                }
+            }
         }
 
         {
@@ -1074,7 +1146,7 @@ impl<T: IsCached> AccountsIndex<T> {
         &self,
         metric_name: &'static str,
         ancestors: &Ancestors,
-        func: F,
+        mut func: F,
         range: Option<R>,
         collect_all_unsorted: bool,
     ) where
@@ -1084,7 +1156,10 @@ impl<T: IsCached> AccountsIndex<T> {
         self.do_scan_accounts(
             metric_name,
             ancestors,
-            func,
+            |pubkey, index| {
+                func(pubkey, index);
+                true
+            },
             range,
             None,
             collect_all_unsorted,
@@ -1094,6 +1169,7 @@ impl<T: IsCached> AccountsIndex<T> {
     // Scan accounts and return latest version of each account that is either:
     // 1) rooted or
     // 2) present in ancestors
+    // The scan stops early once `func` returns false
     fn do_scan_accounts<F, R>(
         &self,
         metric_name: &'static str,
@@ -1103,7 +1179,7 @@ impl<T: IsCached> AccountsIndex<T> {
         max_root: Option<Slot>,
         collect_all_unsorted: bool,
     ) where
-        F: FnMut(&Pubkey, (&T, Slot)),
+        F: FnMut(&Pubkey, (&T, Slot)) -> bool,
         R: RangeBounds<Pubkey> + std::fmt::Debug,
     {
         // TODO: expand to use mint index to find the `pubkey_list` below more efficiently
@@ -1115,7 +1191,7 @@ impl<T: IsCached> AccountsIndex<T> {
         let mut read_lock_elapsed = 0;
         let mut iterator_elapsed = 0;
         let mut iterator_timer = Measure::start("iterator_elapsed");
-        for pubkey_list in self.iter(range, collect_all_unsorted) {
+        'outer: for pubkey_list in self.iter(range, collect_all_unsorted) {
             iterator_timer.stop();
             iterator_elapsed += iterator_timer.as_us();
             for (pubkey, list) in pubkey_list {
@@ -1129,9 +1205,12 @@ impl<T: IsCached> AccountsIndex<T> {
                     latest_slot_timer.stop();
                     latest_slot_elapsed += latest_slot_timer.as_us();
                     let mut load_account_timer = Measure::start("load_account");
-                    func(&pubkey, (&list_r[index].1, list_r[index].0));
+                    let keep_scanning = func(&pubkey, (&list_r[index].1, list_r[index].0));
                     load_account_timer.stop();
                     load_account_elapsed += load_account_timer.as_us();
+                    if !keep_scanning {
+                        break 'outer;
+                    }
                 }
             }
             iterator_timer = Measure::start("iterator_elapsed");
@@ -1153,6 +1232,7 @@ impl<T: IsCached> AccountsIndex<T> {
 
     fn do_scan_secondary_index<
         F,
+        R,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
         &self,
@@ -1160,20 +1240,29 @@ impl<T: IsCached> AccountsIndex<T> {
         mut func: F,
         index: &SecondaryIndex<SecondaryIndexEntryType>,
         index_key: &Pubkey,
+        range: Option<R>,
         max_root: Option<Slot>,
     ) where
-        F: FnMut(&Pubkey, (&T, Slot)),
+        F: FnMut(&Pubkey, (&T, Slot)) -> bool,
+        R: RangeBounds<Pubkey>,
     {
-        for pubkey in index.get(index_key) {
+        let mut pubkeys = index.get(index_key);
+        if let Some(range) = range {
+            pubkeys.retain(|pubkey| range.contains(pubkey));
+            pubkeys.sort_unstable();
+        }
+        for pubkey in pubkeys {
             // Maybe these reads from the AccountsIndex can be batched every time it
             // grabs the read lock as well...
             if let AccountIndexGetResult::Found(list_r, index) =
                 self.get(&pubkey, Some(ancestors), max_root)
             {
-                func(
+                if !func(
                     &pubkey,
                     (&list_r.slot_list()[index].1, list_r.slot_list()[index].0),
-                );
+                ) {
+                    break;
+                }
             }
         }
     }
@@ -1252,7 +1341,7 @@ impl<T: IsCached> AccountsIndex<T> {
         &self,
         ancestors: &Ancestors,
         scan_bank_id: BankId,
-        func: F,
+        mut func: F,
     ) -> Result<(), ScanError>
     where
         F: FnMut(&Pubkey, (&T, Slot)),
@@ -1263,12 +1352,46 @@ impl<T: IsCached> AccountsIndex<T> {
             "",
             ancestors,
             scan_bank_id,
-            func,
+            |pubkey, index| {
+                func(pubkey, index);
+                true
+            },
             ScanTypes::Unindexed(None::<Range<Pubkey>>),
             collect_all_unsorted,
         )
     }
 
+    /// call func with every pubkey and index visible from a given set of ancestors, in pubkey
+    /// order, starting with the first pubkey after `start_after`. The scan stops as soon as
+    /// func returns false.
+    pub(crate) fn ordered_scan_accounts<F>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        start_after: Option<&Pubkey>,
+        func: F,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)) -> bool,
+    {
+        let collect_all_unsorted = false;
+        let range = (
+            start_after
+                .map(|pubkey| Excluded(*pubkey))
+                .unwrap_or(Unbounded),
+            Unbounded,
+        );
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            func,
+            ScanTypes::Unindexed(Some(range)),
+            collect_all_unsorted,
+        )
+    }
+
     pub(crate) fn unchecked_scan_accounts<F>(
         &self,
         metric_name: &'static str,
@@ -1315,20 +1438,54 @@ impl<T: IsCached> AccountsIndex<T> {
         ancestors: &Ancestors,
         scan_bank_id: BankId,
         index_key: IndexKey,
-        func: F,
+        mut func: F,
     ) -> Result<(), ScanError>
     where
         F: FnMut(&Pubkey, (&T, Slot)),
     {
         let collect_all_unsorted = false;
 
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            |pubkey, index| {
+                func(pubkey, index);
+                true
+            },
+            ScanTypes::<Range<Pubkey>>::Indexed(index_key, None),
+            collect_all_unsorted,
+        )
+    }
+
+    /// call func with every pubkey and index visible from a given set of ancestors, in pubkey
+    /// order starting after `start_after`, until func returns false
+    pub(crate) fn ordered_index_scan_accounts<F>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        index_key: IndexKey,
+        start_after: Option<&Pubkey>,
+        func: F,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)) -> bool,
+    {
+        let collect_all_unsorted = false;
+        let range = (
+            start_after
+                .map(|pubkey| Excluded(*pubkey))
+                .unwrap_or(Unbounded),
+            Unbounded,
+        );
         // Pass "" not to log metrics, so RPC doesn't get spammy
         self.do_checked_scan_accounts(
             "",
             ancestors,
             scan_bank_id,
             func,
-            ScanTypes::<Range<Pubkey>>::Indexed(index_key),
+            ScanTypes::Indexed(index_key, Some(range)),
             collect_all_unsorted,
         )
     }
@@ -1534,6 +1691,30 @@ impl<T: IsCached> AccountsIndex<T> {
                 }
             }
         }
+
+        for index in account_indexes
+            .custom_indexes
+            .keys()
+            .filter(|index| index.program_id == *account_owner)
+        {
+            if let Some(key) = index.key_from_data(account_data) {
+                if account_indexes.include_custom_key(index, &key) {
+                    self.get_or_create_custom_index(index).insert(&key, pubkey);
+                }
+            }
+        }
+    }
+
+    fn get_or_create_custom_index(
+        &self,
+        index: &CustomAccountIndex,
+    ) -> Ref<'_, CustomAccountIndex, SecondaryIndex<DashMapSecondaryIndexEntry>> {
+        self.custom_indexes.get(index).unwrap_or_else(|| {
+            self.custom_indexes
+                .entry(*index)
+                .or_insert_with(|| SecondaryIndex::new("custom_index_stats"))
+                .downgrade()
+        })
     }
 
     fn get_account_maps_write_lock(&self, pubkey: &Pubkey) -> AccountMapsWriteLock<T> {
@@ -1697,6 +1878,12 @@ impl<T: IsCached> AccountsIndex<T> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        for index in account_indexes.custom_indexes.keys() {
+            if let Some(custom_index) = self.custom_indexes.get(index) {
+                custom_index.remove_by_inner_key(inner_key);
+            }
+        }
     }
 
     fn purge_older_root_entries(
@@ -1949,6 +2136,7 @@ pub mod tests {
         AccountSecondaryIndexes {
             indexes: account_indexes,
             keys: None,
+            custom_indexes: HashMap::new(),
         }
     }
 
@@ -1958,6 +2146,7 @@ pub mod tests {
         AccountSecondaryIndexes {
             indexes: account_indexes,
             keys: None,
+            custom_indexes: HashMap::new(),
         }
     }
 
@@ -3255,6 +3444,97 @@ pub mod tests {
         run_test_scan_accounts(ITER_BATCH_SIZE * 10 + 1);
     }
 
+    #[test]
+    fn test_ordered_scan_accounts() {
+        let (index, mut pubkeys) = setup_accounts_index_keys(3 * ITER_BATCH_SIZE);
+        pubkeys.sort();
+        let ancestors = Ancestors::default();
+
+        // Pages of any size visit every key exactly once, in order
+        for page_size in [
+            1,
+            ITER_BATCH_SIZE - 1,
+            ITER_BATCH_SIZE,
+            2 * ITER_BATCH_SIZE + 1,
+        ] {
+            let mut scanned_keys = vec![];
+            loop {
+                let mut page = vec![];
+                index
+                    .ordered_scan_accounts(&ancestors, 0, scanned_keys.last(), |pubkey, _index| {
+                        page.push(*pubkey);
+                        page.len() < page_size
+                    })
+                    .unwrap();
+                if page.is_empty() {
+                    break;
+                }
+                assert!(page.len() <= page_size);
+                scanned_keys.extend(page);
+            }
+            assert_eq!(scanned_keys, pubkeys);
+        }
+    }
+
+    #[test]
+    fn test_ordered_index_scan_accounts() {
+        let (key_start, key_end, secondary_indexes) = create_dashmap_secondary_index_state();
+        let index = AccountsIndex::<bool>::default_for_tests();
+        let mint_key = Pubkey::new_unique();
+        let other_mint_key = Pubkey::new_unique();
+        let mut pubkeys = vec![];
+        for i in 0..20 {
+            let account_key = solana_sdk::pubkey::new_rand();
+            let index_key = if i % 4 == 0 {
+                other_mint_key
+            } else {
+                pubkeys.push(account_key);
+                mint_key
+            };
+            let mut account_data = vec![0; inline_spl_token_v2_0::state::Account::get_packed_len()];
+            account_data[key_start..key_end].clone_from_slice(&(index_key.to_bytes()));
+            index.upsert(
+                0,
+                &account_key,
+                &inline_spl_token_v2_0::id(),
+                &account_data,
+                &secondary_indexes,
+                true,
+                &mut vec![],
+                UPSERT_PREVIOUS_SLOT_ENTRY_WAS_CACHED_FALSE,
+            );
+        }
+        index.add_root(0, false);
+        pubkeys.sort();
+        let ancestors = Ancestors::default();
+
+        // Pages of any size visit every key under the index key exactly once, in order
+        for page_size in [1, 4, pubkeys.len()] {
+            let mut scanned_keys = vec![];
+            loop {
+                let mut page = vec![];
+                index
+                    .ordered_index_scan_accounts(
+                        &ancestors,
+                        0,
+                        IndexKey::SplTokenMint(mint_key),
+                        scanned_keys.last(),
+                        |pubkey, _index| {
+                            page.push(*pubkey);
+                            page.len() < page_size
+                        },
+                    )
+                    .unwrap();
+                if page.is_empty() {
+                    break;
+                }
+                assert!(page.len() <= page_size);
+                scanned_keys.extend(page);
+            }
+            assert_eq!(scanned_keys, pubkeys);
+        }
+    }
+
     #[test]
     fn test_accounts_iter_finished() {
         let (index, _) = setup_accounts_index_keys(0);
@@ -3964,6 +4244,103 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_custom_secondary_index() {
+        let index = AccountsIndex::<bool>::default_for_tests();
+        let program_id = Pubkey::new_unique();
+        let custom_index = CustomAccountIndex {
+            program_id,
+            offset: 8,
+            length: 4,
+        };
+        let mut secondary_indexes = AccountSecondaryIndexes::default();
+        secondary_indexes.custom_indexes.insert(custom_index, None);
+        let account_key = Pubkey::new_unique();
+        let mut account_data = vec![0; 16];
+        account_data[8..12].copy_from_slice(&[1, 2, 3, 4]);
+        let index_key = CustomAccountIndex::key_from_bytes(&[1, 2, 3, 4]);
+        assert_eq!(custom_index.key_from_data(&account_data), Some(index_key));
+
+        // Wrong program id
+        index.update_secondary_indexes(
+            &account_key,
+            &Pubkey::default(),
+            &account_data,
+            &secondary_indexes,
+        );
+        assert!(index.custom_indexes.is_empty());
+
+        // Account data too short for the key
+        index.update_secondary_indexes(
+            &account_key,
+            &program_id,
+            &account_data[..11],
+            &secondary_indexes,
+        );
+        assert!(index.custom_indexes.is_empty());
+
+        // Excluded
+        secondary_indexes.custom_indexes.insert(
+            custom_index,
+            Some(AccountSecondaryIndexesIncludeExclude {
+                keys: [index_key].iter().cloned().collect::<HashSet<_>>(),
+                exclude: true,
+            }),
+        );
+        assert!(!secondary_indexes.include_index_key(&IndexKey::Custom(custom_index, index_key)));
+        index.update_secondary_indexes(
+            &account_key,
+            &program_id,
+            &account_data,
+            &secondary_indexes,
+        );
+        assert!(index.custom_indexes.is_empty());
+
+        // Included, and the keys of the other indexes do not apply
+        secondary_indexes.custom_indexes.insert(custom_index, None);
+        secondary_indexes.keys = Some(AccountSecondaryIndexesIncludeExclude {
+            keys: [index_key].iter().cloned().collect::<HashSet<_>>(),
+            exclude: true,
+        });
+        assert!(secondary_indexes.include_index_key(&IndexKey::Custom(custom_index, index_key)));
+        index.upsert(
+            0,
+            &account_key,
+            &program_id,
+            &account_data,
+            &secondary_indexes,
+            true,
+            &mut vec![],
+            UPSERT_PREVIOUS_SLOT_ENTRY_WAS_CACHED_FALSE,
+        );
+        check_secondary_index_mapping_correct(
+            index.custom_indexes.get(&custom_index).unwrap().value(),
+            &[index_key],
+            &account_key,
+        );
+        let mut found = vec![];
+        index
+            .index_scan_accounts(
+                &Ancestors::from(vec![0]),
+                BankId::default(),
+                IndexKey::Custom(custom_index, index_key),
+                |pubkey, _| found.push(*pubkey),
+            )
+            .unwrap();
+        assert_eq!(found, vec![account_key]);
+
+        index
+            .get_account_write_entry(&account_key)
+            .unwrap()
+            .slot_list_mut(|slot_list| slot_list.clear());
+
+        // Everything should be deleted
+        index.handle_dead_keys(&[&account_key], &secondary_indexes);
+        let custom_secondary_index = index.custom_indexes.get(&custom_index).unwrap();
+        assert!(custom_secondary_index.index.is_empty());
+        assert!(custom_secondary_index.reverse_index.is_empty());
+    }
+
     fn run_test_secondary_indexes_same_slot_and_forks<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
@@ -17,7 +17,7 @@ use std::{
     borrow::Borrow,
     fs::{remove_file, OpenOptions},
     io,
-    io::{Seek, SeekFrom, Write},
+    io::{Read, Seek, SeekFrom, Write},
     mem,
     path::{Path, PathBuf},
     sync::atomic::{AtomicUsize, Ordering},
@@ -337,6 +337,38 @@ impl AppendVec {
         Ok((new, num_accounts))
     }
 
+    /// Read the `current_len` bytes in use of a storage from `reader` into anonymous memory,
+    /// such as a storage streamed from a snapshot archive
+    pub fn new_from_reader<R: Read>(
+        reader: &mut R,
+        current_len: usize,
+    ) -> io::Result<(Self, usize)> {
+        let file_size = std::cmp::max(current_len, 1);
+        AppendVec::sanitize_len_and_size(current_len, file_size)?;
+
+        let mut map = MmapMut::map_anon(file_size)?;
+        reader.read_exact(&mut map[..current_len])?;
+
+        let new = AppendVec {
+            path: PathBuf::default(),
+            map,
+            append_lock: Mutex::new(()),
+            current_len: AtomicUsize::new(current_len),
+            file_size: file_size as u64,
+            remove_on_drop: false,
+        };
+
+        let (sanitized, num_accounts) = new.sanitize_layout_and_length();
+        if !sanitized {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "incorrect layout/length/data",
+            ));
+        }
+
+        Ok((new, num_accounts))
+    }
+
     fn sanitize_layout_and_length(&self) -> (bool, usize) {
         let mut offset = 0;
 
@@ -777,6 +809,29 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_append_vec_new_from_reader() {
+        let path = get_append_vec_path("test_new_from_reader");
+        let av = AppendVec::new(&path.path, true, 1024 * 1024);
+        let accounts: Vec<_> = (0..3).map(create_test_account).collect();
+        for account in &accounts {
+            av.append_account_test(account).unwrap();
+        }
+        av.flush().unwrap();
+
+        let data = std::fs::read(&path.path).unwrap();
+        let (av_from_reader, num_accounts) =
+            AppendVec::new_from_reader(&mut &data[..], av.len()).unwrap();
+        assert_eq!(num_accounts, accounts.len());
+        assert_eq!(av_from_reader.len(), av.len());
+        assert_eq!(av_from_reader.get_account_test(0), av.get_account_test(0));
+
+        // The reader must hold the whole length in use
+        assert!(AppendVec::new_from_reader(&mut &data[..av.len() - 1], av.len()).is_err());
+        // and the length must end on an account
+        assert!(AppendVec::new_from_reader(&mut &data[..], av.len() - 1).is_err());
+    }
+
     #[test]
     fn test_new_from_file_crafted_zero_lamport_account() {
         let file = get_append_vec_path("test_append");
@@ -38,7 +38,8 @@ use crate::{
         AccountAddressFilter, Accounts, TransactionAccounts, TransactionLoadResult,
         TransactionLoaders,
     },
-    accounts_db::{AccountShrinkThreshold, ErrorCounters, SnapshotStorages},
+    accounts_db::{AccountShrinkThreshold, AccountsDb, ErrorCounters, SnapshotStorages},
+    accounts_hash::AccountsHashTree,
     accounts_index::{
         AccountSecondaryIndexes, AccountsIndexConfig, IndexKey, ScanResult,
         ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS, ACCOUNTS_INDEX_CONFIG_FOR_TESTING,
@@ -76,6 +77,7 @@ use solana_sdk::{
         create_account_shared_data_with_fields as create_account, from_account, Account,
         AccountSharedData, InheritableAccountFields, ReadableAccount, WritableAccount,
     },
+    account_proof::MerkleProof,
     account_utils::StateMut,
     clock::{
         BankId, Epoch, Slot, SlotCount, SlotIndex, UnixTimestamp, DEFAULT_TICKS_PER_SECOND,
@@ -529,6 +531,8 @@ pub struct TransactionSimulationResult {
     pub logs: TransactionLogMessages,
     pub post_simulation_accounts: Vec<(Pubkey, AccountSharedData)>,
     pub units_consumed: u64,
+    pub inner_instructions: Option<InnerInstructionsList>,
+    pub return_data: Option<TransactionReturnData>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -560,6 +564,13 @@ pub type InnerInstructionsList = Vec<InnerInstructions>;
 /// A list of log messages emitted during a transaction
 pub type TransactionLogMessages = Vec<String>;
 
+/// The return data left by the last instruction of a transaction, and the program that set it
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TransactionReturnData {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+}
+
 #[derive(Serialize, Deserialize, AbiExample, AbiEnumVisitor, Debug, PartialEq)]
 pub enum TransactionLogCollectorFilter {
     All,
@@ -599,6 +610,21 @@ pub struct TransactionLogCollector {
     pub mentioned_address_map: HashMap<Pubkey, Vec<usize>>,
 }
 
+impl TransactionLogCollector {
+    pub fn get_logs_for_address(
+        &self,
+        address: Option<&Pubkey>,
+    ) -> Option<Vec<TransactionLogInfo>> {
+        match address {
+            None => Some(self.logs.clone()),
+            Some(address) => self
+                .mentioned_address_map
+                .get(address)
+                .map(|log_indices| log_indices.iter().map(|i| self.logs[*i].clone()).collect()),
+        }
+    }
+}
+
 pub trait NonceRollbackInfo {
     fn nonce_address(&self) -> &Pubkey;
     fn nonce_account(&self) -> &AccountSharedData;
@@ -2856,8 +2882,9 @@ impl Bank {
         let (
             loaded_transactions,
             executed,
-            _inner_instructions,
+            inner_instructions,
             logs,
+            return_data,
             _retryable_transactions,
             _transaction_count,
             _signature_count,
@@ -2867,13 +2894,15 @@ impl Bank {
             // for processing. During forwarding, the transaction could expire if the
             // delay is not accounted for.
             MAX_PROCESSING_AGE - MAX_TRANSACTION_FORWARDING_DELAY,
-            false,
+            true,
             true,
             &mut timings,
         );
 
         let result = executed[0].0.clone().map(|_| ());
         let logs = logs.get(0).cloned().flatten().unwrap_or_default();
+        let inner_instructions = inner_instructions.into_iter().next().flatten();
+        let return_data = return_data.into_iter().next().flatten();
         let post_simulation_accounts = loaded_transactions
             .into_iter()
             .next()
@@ -2898,6 +2927,8 @@ impl Bank {
             logs,
             post_simulation_accounts,
             units_consumed,
+            inner_instructions,
+            return_data,
         }
     }
 
@@ -3285,6 +3316,7 @@ impl Bank {
         Vec<TransactionExecutionResult>,
         Vec<Option<InnerInstructionsList>>,
         Vec<Option<TransactionLogMessages>>,
+        Vec<Option<TransactionReturnData>>,
         Vec<usize>,
         u64,
         u64,
@@ -3335,6 +3367,8 @@ impl Bank {
             Vec::with_capacity(sanitized_txs.len());
         let mut transaction_log_messages: Vec<Option<Vec<String>>> =
             Vec::with_capacity(sanitized_txs.len());
+        let mut transaction_return_data: Vec<Option<TransactionReturnData>> =
+            Vec::with_capacity(sanitized_txs.len());
 
         let executed: Vec<TransactionExecutionResult> = loaded_txs
             .iter_mut()
@@ -3343,6 +3377,7 @@ impl Bank {
                 (Err(e), _nonce_rollback) => {
                     transaction_log_messages.push(None);
                     inner_instructions.push(None);
+                    transaction_return_data.push(None);
                     (Err(e.clone()), None)
                 }
                 (Ok(loaded_transaction), nonce_rollback) => {
@@ -3398,6 +3433,7 @@ impl Bank {
                             )
                         };
 
+                        let mut return_data = None;
                         if let Some(legacy_message) = tx.message().legacy_message() {
                             process_result = self.message_processor.process_message(
                                 legacy_message,
@@ -3415,6 +3451,7 @@ impl Bank {
                                 &self.ancestors,
                                 blockhash,
                                 fee_calculator,
+                                &mut return_data,
                             );
                         } else {
                             // TODO: support versioned messages
@@ -3426,6 +3463,7 @@ impl Bank {
                             instruction_recorders,
                             tx.message(),
                         ));
+                        transaction_return_data.push(return_data);
 
                         if let Err(e) = Self::refcells_to_accounts(
                             &mut loaded_transaction.accounts,
@@ -3443,6 +3481,7 @@ impl Bank {
                     } else {
                         transaction_log_messages.push(None);
                         inner_instructions.push(None);
+                        transaction_return_data.push(None);
                     }
 
                     let nonce_rollback =
@@ -3555,6 +3594,7 @@ impl Bank {
             executed,
             inner_instructions,
             transaction_log_messages,
+            transaction_return_data,
             retryable_txs,
             tx_count,
             signature_count,
@@ -4288,6 +4328,7 @@ impl Bank {
         TransactionBalancesSet,
         Vec<Option<InnerInstructionsList>>,
         Vec<Option<TransactionLogMessages>>,
+        Vec<Option<TransactionReturnData>>,
     ) {
         let pre_balances = if collect_balances {
             self.collect_balances(batch)
@@ -4300,6 +4341,7 @@ impl Bank {
             executed,
             inner_instructions,
             transaction_logs,
+            transaction_return_data,
             _,
             tx_count,
             signature_count,
@@ -4329,6 +4371,7 @@ impl Bank {
             TransactionBalancesSet::new(pre_balances, post_balances),
             inner_instructions,
             transaction_logs,
+            transaction_return_data,
         )
     }
 
@@ -4699,6 +4742,25 @@ impl Bank {
         )
     }
 
+    /// Get at most `limit` accounts owned by `program_id` that pass `filter`, in pubkey order,
+    /// starting after `start_after`
+    pub fn get_filtered_program_accounts_paginated<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        program_id: &Pubkey,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+    ) -> ScanResult<Vec<(Pubkey, AccountSharedData)>> {
+        self.rc.accounts.load_by_program_with_filter_paginated(
+            &self.ancestors,
+            self.bank_id,
+            program_id,
+            start_after,
+            limit,
+            filter,
+        )
+    }
+
     pub fn get_filtered_indexed_accounts<F: Fn(&AccountSharedData) -> bool>(
         &self,
         index_key: &IndexKey,
@@ -4712,6 +4774,25 @@ impl Bank {
         )
     }
 
+    /// Get at most `limit` accounts under `index_key` in the secondary index that pass `filter`,
+    /// in pubkey order, starting after `start_after`
+    pub fn get_filtered_indexed_accounts_paginated<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        index_key: &IndexKey,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        filter: F,
+    ) -> ScanResult<Vec<(Pubkey, AccountSharedData)>> {
+        self.rc.accounts.load_by_index_key_with_filter_paginated(
+            &self.ancestors,
+            self.bank_id,
+            index_key,
+            start_after,
+            limit,
+            filter,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
@@ -4722,6 +4803,18 @@ impl Bank {
         self.rc.accounts.load_all(&self.ancestors, self.bank_id)
     }
 
+    /// Get at most `limit` accounts, with the slot each was last written in, in pubkey order,
+    /// starting after `start_after`
+    pub fn get_all_accounts_with_modified_slots_paginated(
+        &self,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+    ) -> ScanResult<Vec<(Pubkey, AccountSharedData, Slot)>> {
+        self.rc
+            .accounts
+            .load_all_paginated(&self.ancestors, self.bank_id, start_after, limit)
+    }
+
     pub fn get_program_accounts_modified_since_parent(
         &self,
         program_id: &Pubkey,
@@ -4735,26 +4828,41 @@ impl Bank {
         &self,
         address: Option<&Pubkey>,
     ) -> Option<Vec<TransactionLogInfo>> {
-        let transaction_log_collector = self.transaction_log_collector.read().unwrap();
-
-        match address {
-            None => Some(transaction_log_collector.logs.clone()),
-            Some(address) => transaction_log_collector
-                .mentioned_address_map
-                .get(address)
-                .map(|log_indices| {
-                    log_indices
-                        .iter()
-                        .map(|i| transaction_log_collector.logs[*i].clone())
-                        .collect()
-                }),
-        }
+        self.transaction_log_collector
+            .read()
+            .unwrap()
+            .get_logs_for_address(address)
     }
 
     pub fn get_all_accounts_modified_since_parent(&self) -> Vec<(Pubkey, AccountSharedData)> {
         self.rc.accounts.load_by_program_slot(self.slot(), None)
     }
 
+    /// Build the Merkle tree of each accounts hash calculated from now on, for snapshots and
+    /// accounts hash verification, so that accounts can be proven against the latest one with
+    /// `get_account_proof()`. Applies to every bank sharing this bank's accounts
+    pub fn enable_accounts_hash_trees(&self) {
+        self.rc.accounts.accounts_db.enable_accounts_hash_trees();
+    }
+
+    /// The Merkle tree of the latest accounts hash calculated, if trees are enabled
+    pub fn accounts_hash_tree(&self) -> Option<Arc<AccountsHashTree>> {
+        self.rc.accounts.accounts_db.accounts_hash_tree()
+    }
+
+    /// Proof that the account at `pubkey`, as of this bank, is part of the accounts hash of
+    /// `tree`, along with the account and the slot it was last written in. `None` if the account
+    /// didn't exist in the tree's slot, or has been written since
+    pub fn get_account_proof(
+        &self,
+        tree: &AccountsHashTree,
+        pubkey: &Pubkey,
+    ) -> Option<(AccountSharedData, Slot, MerkleProof)> {
+        let (leaf, proof) = tree.get_proof(pubkey)?;
+        let (account, slot) = self.get_account_modified_slot(pubkey)?;
+        (AccountsDb::hash_account(slot, &account, pubkey) == leaf).then(|| (account, slot, proof))
+    }
+
     // if you want get_account_modified_since_parent without fixed_root, please define so...
     fn get_account_modified_since_parent_with_fixed_root(
         &self,
@@ -4957,7 +5065,11 @@ impl Bank {
         }
 
         if !skip_verification {
-            sanitized_tx.verify_precompiles(self.libsecp256k1_0_5_upgrade_enabled(), true)?;
+            sanitized_tx.verify_precompiles(
+                self.libsecp256k1_0_5_upgrade_enabled(),
+                true,
+                self.ed25519_program_enabled(),
+            )?;
         }
 
         Ok(sanitized_tx)
@@ -5402,6 +5514,11 @@ impl Bank {
             .is_active(&feature_set::libsecp256k1_fail_on_bad_count::id())
     }
 
+    pub fn ed25519_program_enabled(&self) -> bool {
+        self.feature_set
+            .is_active(&feature_set::ed25519_program_enabled::id())
+    }
+
     pub fn merge_nonce_error_into_system_error(&self) -> bool {
         self.feature_set
             .is_active(&feature_set::merge_nonce_error_into_system_error::id())
@@ -5760,6 +5877,7 @@ pub(crate) mod tests {
     use solana_sdk::sysvar::fees::Fees;
     use solana_sdk::{
         account::Account,
+        account_proof::verify_account_proof,
         clock::{DEFAULT_SLOTS_PER_EPOCH, DEFAULT_TICKS_PER_SLOT},
         compute_budget::ComputeBudgetInstruction,
         epoch_schedule::MINIMUM_SLOTS_PER_EPOCH,
@@ -8801,6 +8919,80 @@ pub(crate) mod tests {
         assert!(bank2.verify_bank_hash(true));
     }
 
+    #[test]
+    fn test_bank_account_proof() {
+        let (genesis_config, mint_keypair) = create_genesis_config(2_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let keypair = Keypair::new();
+        bank0
+            .transfer(1_000, &mint_keypair, &keypair.pubkey())
+            .unwrap();
+        bank0.freeze();
+
+        // The keypair's account is closed in bank1
+        let bank1 = Arc::new(new_from_parent(&bank0));
+        let pubkey = solana_sdk::pubkey::new_rand();
+        bank1.transfer(1_000, &keypair, &pubkey).unwrap();
+        bank1.freeze();
+
+        // Trees are only built once enabled
+        bank1.update_accounts_hash();
+        assert!(bank1.accounts_hash_tree().is_none());
+        bank1.enable_accounts_hash_trees();
+        let accounts_hash = bank1.update_accounts_hash();
+        let tree = bank1.accounts_hash_tree().unwrap();
+        assert_eq!(tree.slot(), bank1.slot());
+        assert_eq!(tree.root(), accounts_hash);
+
+        let (account, slot, proof) = bank1.get_account_proof(&tree, &pubkey).unwrap();
+        assert_eq!(Some(&account), bank1.get_account(&pubkey).as_ref());
+        assert_eq!(slot, bank1.slot());
+        assert!(verify_account_proof(
+            &pubkey,
+            &account,
+            slot,
+            &proof,
+            &accounts_hash
+        ));
+        assert!(!verify_account_proof(
+            &pubkey,
+            &account,
+            bank0.slot(),
+            &proof,
+            &accounts_hash
+        ));
+
+        // Accounts that weren't written in the slot are proven with the slot they were last
+        // written in
+        let (mint_account, mint_slot, mint_proof) = bank1
+            .get_account_proof(&tree, &mint_keypair.pubkey())
+            .unwrap();
+        assert_eq!(mint_slot, bank0.slot());
+        assert!(verify_account_proof(
+            &mint_keypair.pubkey(),
+            &mint_account,
+            mint_slot,
+            &mint_proof,
+            &accounts_hash
+        ));
+
+        // Closed and missing accounts have no proof
+        assert_eq!(bank1.get_account(&keypair.pubkey()), None);
+        assert!(bank1.get_account_proof(&tree, &keypair.pubkey()).is_none());
+        assert!(bank1
+            .get_account_proof(&tree, &solana_sdk::pubkey::new_rand())
+            .is_none());
+
+        // Accounts written after the tree's slot can't be proven against it
+        let bank2 = new_from_parent(&bank1);
+        bank2.transfer(1, &mint_keypair, &pubkey).unwrap();
+        assert_eq!(bank2.accounts_hash_tree().unwrap().slot(), bank1.slot());
+        assert!(bank2.get_account_proof(&tree, &pubkey).is_none());
+        assert!(bank2
+            .get_account_proof(&tree, &mint_keypair.pubkey())
+            .is_none());
+    }
+
     #[test]
     fn test_bank_hash_internal_state_verify() {
         solana_logger::setup();
@@ -10683,18 +10875,24 @@ pub(crate) mod tests {
         let txs = vec![tx0, tx1, tx2];
 
         let lock_result = bank0.prepare_batch(txs).unwrap();
-        let (transaction_results, transaction_balances_set, inner_instructions, transaction_logs) =
-            bank0.load_execute_and_commit_transactions(
-                &lock_result,
-                MAX_PROCESSING_AGE,
-                true,
-                false,
-                false,
-                &mut ExecuteTimings::default(),
-            );
+        let (
+            transaction_results,
+            transaction_balances_set,
+            inner_instructions,
+            transaction_logs,
+            transaction_return_data,
+        ) = bank0.load_execute_and_commit_transactions(
+            &lock_result,
+            MAX_PROCESSING_AGE,
+            true,
+            false,
+            false,
+            &mut ExecuteTimings::default(),
+        );
 
         assert!(inner_instructions.iter().all(Option::is_none));
         assert!(transaction_logs.iter().all(Option::is_none));
+        assert!(transaction_return_data.iter().all(Option::is_none));
 
         assert_eq!(inner_instructions.len(), 3);
         assert_eq!(transaction_logs.len(), 3);
@@ -14211,6 +14409,66 @@ pub(crate) mod tests {
         bank.process_transaction(&tx).unwrap();
     }
 
+    #[test]
+    fn test_transaction_return_data() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config_with_leader(
+            1_000_000_000_000_000,
+            &Pubkey::new_unique(),
+            bootstrap_validator_stake_lamports(),
+        );
+        let mut bank = Bank::new_for_tests(&genesis_config);
+
+        fn mock_ix_processor(
+            _pubkey: &Pubkey,
+            data: &[u8],
+            invoke_context: &mut dyn InvokeContext,
+        ) -> std::result::Result<(), InstructionError> {
+            if !data.is_empty() {
+                invoke_context.set_return_data(data.to_vec())?;
+            }
+            Ok(())
+        }
+        let program_id = solana_sdk::pubkey::new_rand();
+        bank.add_builtin("mock_program", program_id, mock_ix_processor);
+
+        let return_data_of = |instructions: &[Instruction]| {
+            let message = Message::new(instructions, Some(&mint_keypair.pubkey()));
+            let tx = Transaction::new(&[&mint_keypair], message, bank.last_blockhash());
+            let batch = bank.prepare_batch(vec![tx]).unwrap();
+            let (results, _, _, _, mut return_data) = bank.load_execute_and_commit_transactions(
+                &batch,
+                MAX_PROCESSING_AGE,
+                false,
+                false,
+                false,
+                &mut ExecuteTimings::default(),
+            );
+            assert_eq!(results.execution_results[0].0, Ok(()));
+            return_data.remove(0)
+        };
+
+        assert_eq!(
+            return_data_of(&[Instruction::new_with_bytes(program_id, &[1, 2, 3], vec![])]),
+            Some(TransactionReturnData {
+                program_id,
+                data: vec![1, 2, 3],
+            })
+        );
+
+        // Only the return data left by the last instruction is reported
+        assert_eq!(
+            return_data_of(&[
+                Instruction::new_with_bytes(program_id, &[4], vec![]),
+                Instruction::new_with_bytes(program_id, &[], vec![]),
+            ]),
+            None
+        );
+    }
+
     #[test]
     fn test_verify_and_hash_transaction_sig_len() {
         let GenesisConfigInfo {
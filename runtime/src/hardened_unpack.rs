@@ -90,22 +90,25 @@ pub enum UnpackPath<'a> {
     Invalid,
 }
 
-fn unpack_archive<'a, A: Read, C>(
+/// Call `visitor` with each entry of `archive` that `entry_checker` accepts, along with the
+/// directory it returned, after checking the entry's path and the sizes and count of the
+/// accepted entries against the limits
+fn visit_archive<'a, A: Read, C, V>(
     archive: &mut Archive<A>,
     apparent_limit_size: u64,
     actual_limit_size: u64,
     limit_count: u64,
     mut entry_checker: C,
+    mut visitor: V,
 ) -> Result<()>
 where
     C: FnMut(&[&str], tar::EntryType) -> UnpackPath<'a>,
+    V: FnMut(&'a Path, &mut tar::Entry<A>) -> Result<()>,
 {
     let mut apparent_total_size: u64 = 0;
     let mut actual_total_size: u64 = 0;
     let mut total_count: u64 = 0;
 
-    let mut total_entries = 0;
-    let mut last_log_update = Instant::now();
     for entry in archive.entries()? {
         let mut entry = entry?;
         let path = entry.path()?;
@@ -161,29 +164,58 @@ where
         )?;
         total_count = checked_total_count_increment(total_count, limit_count)?;
 
-        let target = sanitize_path(&entry.path()?, unpack_dir)?; // ? handles file system errors
-        if target.is_none() {
-            continue; // skip it
-        }
-        let target = target.unwrap();
-
-        let unpack = entry.unpack(target);
-        check_unpack_result(unpack.map(|_unpack| true)?, path_str)?;
-
-        // Sanitize permissions.
-        let mode = match entry.header().entry_type() {
-            GNUSparse | Regular => 0o644,
-            _ => 0o755,
-        };
-        set_perms(&unpack_dir.join(entry.path()?), mode)?;
-
-        total_entries += 1;
-        let now = Instant::now();
-        if now.duration_since(last_log_update).as_secs() >= 10 {
-            info!("unpacked {} entries so far...", total_entries);
-            last_log_update = now;
-        }
+        visitor(unpack_dir, &mut entry)?;
     }
+    Ok(())
+}
+
+fn unpack_archive<'a, A: Read, C>(
+    archive: &mut Archive<A>,
+    apparent_limit_size: u64,
+    actual_limit_size: u64,
+    limit_count: u64,
+    entry_checker: C,
+) -> Result<()>
+where
+    C: FnMut(&[&str], tar::EntryType) -> UnpackPath<'a>,
+{
+    let mut total_entries = 0;
+    let mut last_log_update = Instant::now();
+    visit_archive(
+        archive,
+        apparent_limit_size,
+        actual_limit_size,
+        limit_count,
+        entry_checker,
+        |unpack_dir, entry| {
+            let target = sanitize_path(&entry.path()?, unpack_dir)?; // ? handles file system errors
+            if target.is_none() {
+                return Ok(()); // skip it
+            }
+            let target = target.unwrap();
+
+            let unpack = entry.unpack(target);
+            check_unpack_result(
+                unpack.map(|_unpack| true)?,
+                entry.path()?.display().to_string(),
+            )?;
+
+            // Sanitize permissions.
+            let mode = match entry.header().entry_type() {
+                GNUSparse | Regular => 0o644,
+                _ => 0o755,
+            };
+            set_perms(&unpack_dir.join(entry.path()?), mode)?;
+
+            total_entries += 1;
+            let now = Instant::now();
+            if now.duration_since(last_log_update).as_secs() >= 10 {
+                info!("unpacked {} entries so far...", total_entries);
+                last_log_update = now;
+            }
+            Ok(())
+        },
+    )?;
     info!("unpacked {} entries total", total_entries);
 
     return Ok(());
@@ -341,6 +373,38 @@ pub fn unpack_snapshot<A: Read>(
     .map(|_| unpacked_append_vec_map)
 }
 
+/// Call `visitor` with each entry of a snapshot archive, without unpacking the archive. Entries
+/// are checked as by `unpack_snapshot()`
+pub fn visit_snapshot_archive<A: Read, V>(archive: &mut Archive<A>, mut visitor: V) -> Result<()>
+where
+    V: FnMut(&[&str], &mut tar::Entry<A>) -> Result<()>,
+{
+    visit_archive(
+        archive,
+        MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
+        MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
+        MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT,
+        |parts, kind| {
+            if is_valid_snapshot_archive_entry(parts, kind) {
+                UnpackPath::Valid(Path::new(""))
+            } else {
+                UnpackPath::Invalid
+            }
+        },
+        |_, entry| {
+            let path = entry.path()?.into_owned();
+            let parts: Vec<_> = path
+                .components()
+                .filter_map(|part| match part {
+                    Normal(part) => part.to_str(),
+                    _ => None,
+                })
+                .collect();
+            visitor(&parts, entry)
+        },
+    )
+}
+
 fn all_digits(v: &str) -> bool {
     if v.is_empty() {
         return false;
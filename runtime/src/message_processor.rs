@@ -1,6 +1,7 @@
 use crate::{
-    accounts::Accounts, ancestors::Ancestors, instruction_recorder::InstructionRecorder,
-    log_collector::LogCollector, rent_collector::RentCollector,
+    accounts::Accounts, ancestors::Ancestors, bank::TransactionReturnData,
+    instruction_recorder::InstructionRecorder, log_collector::LogCollector,
+    rent_collector::RentCollector,
 };
 use log::*;
 use serde::{Deserialize, Serialize};
@@ -66,6 +67,7 @@ pub struct ThisInvokeContext<'a> {
     sysvars: RefCell<Vec<(Pubkey, Option<Rc<Vec<u8>>>)>>,
     blockhash: &'a Hash,
     fee_calculator: &'a FeeCalculator,
+    return_data: (Pubkey, Vec<u8>),
 }
 impl<'a> ThisInvokeContext<'a> {
     #[allow(clippy::too_many_arguments)]
@@ -121,6 +123,7 @@ impl<'a> ThisInvokeContext<'a> {
             sysvars: RefCell::new(vec![]),
             blockhash,
             fee_calculator,
+            return_data: (Pubkey::default(), Vec::new()),
         };
         invoke_context
             .invoke_stack
@@ -312,6 +315,13 @@ impl<'a> InvokeContext for ThisInvokeContext<'a> {
     fn get_fee_calculator(&self) -> &FeeCalculator {
         self.fee_calculator
     }
+    fn set_return_data(&mut self, data: Vec<u8>) -> Result<(), InstructionError> {
+        self.return_data = (*self.get_caller()?, data);
+        Ok(())
+    }
+    fn get_return_data(&self) -> (Pubkey, &[u8]) {
+        (self.return_data.0, &self.return_data.1)
+    }
 }
 pub struct ThisLogger {
     log_collector: Option<Rc<LogCollector>>,
@@ -473,6 +483,7 @@ impl MessageProcessor {
         ancestors: &Ancestors,
         blockhash: &Hash,
         fee_calculator: &FeeCalculator,
+        return_data: &mut Option<TransactionReturnData>,
     ) -> Result<(), InstructionError> {
         // Fixup the special instructions key if present
         // before the account pre-values are taken care of
@@ -519,11 +530,22 @@ impl MessageProcessor {
             fee_calculator,
         );
 
-        self.instruction_processor.process_instruction(
+        let result = self.instruction_processor.process_instruction(
             program_id,
             &instruction.data,
             &mut invoke_context,
-        )?;
+        );
+        // Keep the return data of a failed instruction too, it helps debugging a simulation
+        let (return_data_program_id, return_data_bytes) = invoke_context.get_return_data();
+        *return_data = if return_data_bytes.is_empty() {
+            None
+        } else {
+            Some(TransactionReturnData {
+                program_id: return_data_program_id,
+                data: return_data_bytes.to_vec(),
+            })
+        };
+        result?;
         Self::verify(
             message,
             instruction,
@@ -544,6 +566,7 @@ impl MessageProcessor {
     /// Process a message.
     /// This method calls each instruction in the message over the set of loaded Accounts
     /// The accounts are committed back to the bank only if every instruction succeeds
+    /// `return_data` is set to the return data left by the last instruction executed
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::type_complexity)]
     pub fn process_message(
@@ -563,6 +586,7 @@ impl MessageProcessor {
         ancestors: &Ancestors,
         blockhash: Hash,
         fee_calculator: FeeCalculator,
+        return_data: &mut Option<TransactionReturnData>,
     ) -> Result<(), TransactionError> {
         for (instruction_index, instruction) in message.instructions.iter().enumerate() {
             let mut time = Measure::start("execute_instruction");
@@ -589,6 +613,7 @@ impl MessageProcessor {
                     ancestors,
                     &blockhash,
                     &fee_calculator,
+                    return_data,
                 )
                 .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err));
             time.stop();
@@ -851,6 +876,7 @@ mod tests {
             &ancestors,
             Hash::default(),
             FeeCalculator::default(),
+            &mut None,
         );
         assert_eq!(result, Ok(()));
         assert_eq!(accounts[0].1.borrow().lamports(), 100);
@@ -881,6 +907,7 @@ mod tests {
             &ancestors,
             Hash::default(),
             FeeCalculator::default(),
+            &mut None,
         );
         assert_eq!(
             result,
@@ -915,6 +942,7 @@ mod tests {
             &ancestors,
             Hash::default(),
             FeeCalculator::default(),
+            &mut None,
         );
         assert_eq!(
             result,
@@ -1041,6 +1069,7 @@ mod tests {
             &ancestors,
             Hash::default(),
             FeeCalculator::default(),
+            &mut None,
         );
         assert_eq!(
             result,
@@ -1075,6 +1104,7 @@ mod tests {
             &ancestors,
             Hash::default(),
             FeeCalculator::default(),
+            &mut None,
         );
         assert_eq!(result, Ok(()));
 
@@ -1107,6 +1137,7 @@ mod tests {
             &ancestors,
             Hash::default(),
             FeeCalculator::default(),
+            &mut None,
         );
         assert_eq!(result, Ok(()));
         assert_eq!(accounts[0].1.borrow().lamports(), 80);
@@ -0,0 +1,480 @@
+//! Parallel compression and decompression of snapshot archives in the zstd seekable format.
+//!
+//! The data is cut into chunks that are compressed on a thread pool as independent zstd frames,
+//! followed by a seek table in a skippable frame. Any zstd decoder reads the frames back as one
+//! stream and skips the seek table, so the archives stay readable by nodes that decompress them
+//! on a single thread, while `SeekableZstdDecoder` uses the seek table to decompress many frames
+//! at once. The format is described in
+//! https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md
+use {
+    rayon::{prelude::*, ThreadPool},
+    solana_rayon_threadlimit::get_thread_count,
+    std::{
+        collections::VecDeque,
+        convert::TryFrom,
+        io::{self, Read, Seek, SeekFrom, Write},
+        mem,
+    },
+};
+
+const SKIPPABLE_FRAME_MAGIC_NUMBER: u32 = 0x184D_2A5E;
+const SEEKABLE_MAGIC_NUMBER: u32 = 0x8F92_EAB1;
+/// Skippable magic number and frame size
+const SKIPPABLE_FRAME_HEADER_SIZE: u64 = 8;
+/// Number of frames, seek table descriptor and seekable magic number
+const SEEK_TABLE_FOOTER_SIZE: u64 = 9;
+/// Compressed and decompressed size of a frame, without the optional checksum
+const SEEK_TABLE_ENTRY_SIZE: u64 = 8;
+const SEEK_TABLE_DESCRIPTOR_CHECKSUM_FLAG: u8 = 0x80;
+
+/// Bytes of data compressed into each frame. Larger than the window of the default compression
+/// level, so that cutting the data into frames hardly costs any compression
+const DEFAULT_FRAME_SIZE: usize = 4 * 1024 * 1024;
+/// Frames with more data than this are rejected, so a seek table cannot make the decoder
+/// allocate an arbitrary amount of memory
+const MAX_FRAME_DECOMPRESSED_SIZE: u32 = 64 * 1024 * 1024;
+/// Frames are compressed and decompressed in batches of at most this much data, however many
+/// threads there are, so the memory buffered by the encoder, or that a seek table can make the
+/// decoder allocate, does not grow with the thread count
+const MAX_BATCH_DECOMPRESSED_SIZE: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeekTableEntry {
+    pub compressed_size: u32,
+    pub decompressed_size: u32,
+}
+
+fn build_thread_pool(name: &'static str) -> ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(get_thread_count())
+        .thread_name(move |i| format!("{}-{}", name, i))
+        .build()
+        .unwrap()
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Compresses the data written to it into zstd frames of a fixed size on a thread pool, and
+/// writes them to `writer` in order. `finish()` must be called to write the last frame and the
+/// seek table.
+pub struct SeekableZstdEncoder<W: Write> {
+    writer: W,
+    level: i32,
+    frame_size: usize,
+    /// Frames waiting to be compressed together
+    frames: Vec<Vec<u8>>,
+    /// Number of frames compressed together, to keep all threads busy
+    frames_per_batch: usize,
+    seek_table: Vec<SeekTableEntry>,
+    thread_pool: ThreadPool,
+}
+
+impl<W: Write> SeekableZstdEncoder<W> {
+    pub fn new(writer: W, level: i32) -> Self {
+        Self::with_frame_size(writer, level, DEFAULT_FRAME_SIZE)
+    }
+
+    fn with_frame_size(writer: W, level: i32, frame_size: usize) -> Self {
+        assert!(frame_size > 0 && frame_size <= MAX_FRAME_DECOMPRESSED_SIZE as usize);
+        let thread_pool = build_thread_pool("solana-zstd-compress");
+        let frames_per_batch = std::cmp::min(
+            thread_pool.current_num_threads() * 2,
+            MAX_BATCH_DECOMPRESSED_SIZE as usize / frame_size,
+        )
+        .max(1);
+        Self {
+            writer,
+            level,
+            frame_size,
+            frames: vec![],
+            frames_per_batch,
+            seek_table: vec![],
+            thread_pool,
+        }
+    }
+
+    fn compress_frames(&mut self) -> io::Result<()> {
+        let level = self.level;
+        let frames = mem::take(&mut self.frames);
+        let compressed_frames = self.thread_pool.install(|| {
+            frames
+                .par_iter()
+                .map(|frame| zstd::block::compress(frame, level))
+                .collect::<io::Result<Vec<_>>>()
+        })?;
+        for (frame, compressed_frame) in frames.iter().zip(compressed_frames) {
+            self.writer.write_all(&compressed_frame)?;
+            self.seek_table.push(SeekTableEntry {
+                compressed_size: u32::try_from(compressed_frame.len())
+                    .map_err(|err| invalid_data(err.to_string()))?,
+                decompressed_size: frame.len() as u32,
+            });
+        }
+        Ok(())
+    }
+
+    /// Compress the remaining data and write the seek table
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.frames.last().map(|frame| frame.is_empty()) == Some(true) {
+            self.frames.pop();
+        }
+        self.compress_frames()?;
+
+        let frame_size =
+            self.seek_table.len() as u64 * SEEK_TABLE_ENTRY_SIZE + SEEK_TABLE_FOOTER_SIZE;
+        let mut seek_table =
+            Vec::with_capacity((SKIPPABLE_FRAME_HEADER_SIZE + frame_size) as usize);
+        seek_table.extend_from_slice(&SKIPPABLE_FRAME_MAGIC_NUMBER.to_le_bytes());
+        seek_table.extend_from_slice(
+            &u32::try_from(frame_size)
+                .map_err(|err| invalid_data(err.to_string()))?
+                .to_le_bytes(),
+        );
+        for entry in &self.seek_table {
+            seek_table.extend_from_slice(&entry.compressed_size.to_le_bytes());
+            seek_table.extend_from_slice(&entry.decompressed_size.to_le_bytes());
+        }
+        seek_table.extend_from_slice(&(self.seek_table.len() as u32).to_le_bytes());
+        seek_table.push(0); // no checksums
+        seek_table.extend_from_slice(&SEEKABLE_MAGIC_NUMBER.to_le_bytes());
+        self.writer.write_all(&seek_table)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for SeekableZstdEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self
+            .frames
+            .last()
+            .map(|frame| frame.len() == self.frame_size)
+            .unwrap_or(true)
+        {
+            if self.frames.len() == self.frames_per_batch {
+                self.compress_frames()?;
+            }
+            self.frames.push(Vec::with_capacity(self.frame_size));
+        }
+        let frame = self.frames.last_mut().unwrap();
+        let len = std::cmp::min(buf.len(), self.frame_size - frame.len());
+        frame.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Frames are only compressed once they are full, or by `finish()`
+        Ok(())
+    }
+}
+
+/// Read the seek table at the end of `reader`, or `None` if it does not end with one, such as a
+/// zstd archive compressed as a single stream.
+///
+/// The seek table is checked to describe exactly the rest of the data, and frames that would
+/// decompress to more than `MAX_FRAME_DECOMPRESSED_SIZE` are rejected.
+pub fn read_seek_table<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Vec<SeekTableEntry>>> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    if len < SKIPPABLE_FRAME_HEADER_SIZE + SEEK_TABLE_FOOTER_SIZE {
+        return Ok(None);
+    }
+    let mut footer = [0u8; SEEK_TABLE_FOOTER_SIZE as usize];
+    reader.seek(SeekFrom::Start(len - SEEK_TABLE_FOOTER_SIZE))?;
+    reader.read_exact(&mut footer)?;
+    let read_u32 = |bytes: &[u8]| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if read_u32(&footer[5..9]) != SEEKABLE_MAGIC_NUMBER {
+        return Ok(None);
+    }
+
+    let num_frames = u64::from(read_u32(&footer[0..4]));
+    let descriptor = footer[4];
+    let entry_size = if descriptor & SEEK_TABLE_DESCRIPTOR_CHECKSUM_FLAG != 0 {
+        SEEK_TABLE_ENTRY_SIZE + 4
+    } else {
+        SEEK_TABLE_ENTRY_SIZE
+    };
+    let frame_size = num_frames * entry_size + SEEK_TABLE_FOOTER_SIZE;
+    let seek_table_size = SKIPPABLE_FRAME_HEADER_SIZE + frame_size;
+    if seek_table_size > len {
+        return Err(invalid_data(format!(
+            "seek table of {} frames is larger than the archive",
+            num_frames
+        )));
+    }
+    let mut seek_table = vec![0u8; seek_table_size as usize];
+    reader.seek(SeekFrom::Start(len - seek_table_size))?;
+    reader.read_exact(&mut seek_table)?;
+    if read_u32(&seek_table[0..4]) != SKIPPABLE_FRAME_MAGIC_NUMBER
+        || u64::from(read_u32(&seek_table[4..8])) != frame_size
+    {
+        return Err(invalid_data("invalid seek table frame header".to_string()));
+    }
+
+    let entries: Vec<_> = seek_table[SKIPPABLE_FRAME_HEADER_SIZE as usize..]
+        .chunks_exact(entry_size as usize)
+        .take(num_frames as usize)
+        .map(|entry| SeekTableEntry {
+            compressed_size: read_u32(&entry[0..4]),
+            decompressed_size: read_u32(&entry[4..8]),
+        })
+        .collect();
+    if let Some(entry) = entries
+        .iter()
+        .find(|entry| entry.decompressed_size > MAX_FRAME_DECOMPRESSED_SIZE)
+    {
+        return Err(invalid_data(format!(
+            "frame of {} bytes is larger than limit: {}",
+            entry.decompressed_size, MAX_FRAME_DECOMPRESSED_SIZE
+        )));
+    }
+    let compressed_size: u64 = entries
+        .iter()
+        .map(|entry| u64::from(entry.compressed_size))
+        .sum();
+    if compressed_size + seek_table_size != len {
+        return Err(invalid_data(format!(
+            "seek table describes {} bytes of frames, but the archive has {}",
+            compressed_size,
+            len - seek_table_size
+        )));
+    }
+    Ok(Some(entries))
+}
+
+/// Decompresses the frames listed in a seek table on a thread pool, and reads them back in order.
+/// `reader` must be positioned at the first frame.
+pub struct SeekableZstdDecoder<R: Read> {
+    reader: R,
+    /// Frames left to read from `reader`
+    seek_table: VecDeque<SeekTableEntry>,
+    /// Number of frames decompressed together, to keep all threads busy
+    frames_per_batch: usize,
+    /// Most data decompressed together, whatever the number of frames
+    max_batch_decompressed_size: u64,
+    /// Decompressed frames that have not been read yet, the first one from `offset`
+    frames: VecDeque<Vec<u8>>,
+    offset: usize,
+    thread_pool: ThreadPool,
+}
+
+impl<R: Read> SeekableZstdDecoder<R> {
+    pub fn new(reader: R, seek_table: Vec<SeekTableEntry>) -> Self {
+        // Decompressing must not wait on the global thread pool, whose threads may be the ones
+        // blocked on reading the decompressed data
+        let thread_pool = build_thread_pool("solana-zstd-decompress");
+        let frames_per_batch = thread_pool.current_num_threads() * 2;
+        Self {
+            reader,
+            seek_table: seek_table.into(),
+            frames_per_batch,
+            max_batch_decompressed_size: MAX_BATCH_DECOMPRESSED_SIZE,
+            frames: VecDeque::new(),
+            offset: 0,
+            thread_pool,
+        }
+    }
+
+    fn decompress_frames(&mut self) -> io::Result<()> {
+        // At least one frame, which `read_seek_table()` checked is not larger than
+        // `MAX_FRAME_DECOMPRESSED_SIZE`
+        let mut batch_decompressed_size = 0;
+        let num_frames = self
+            .seek_table
+            .iter()
+            .take(self.frames_per_batch)
+            .take_while(|entry| {
+                batch_decompressed_size += u64::from(entry.decompressed_size);
+                batch_decompressed_size <= self.max_batch_decompressed_size
+            })
+            .count()
+            .max(1);
+        let mut compressed_frames = Vec::with_capacity(num_frames);
+        for entry in self.seek_table.drain(..num_frames) {
+            let mut compressed_frame = vec![0u8; entry.compressed_size as usize];
+            self.reader.read_exact(&mut compressed_frame)?;
+            compressed_frames.push((compressed_frame, entry.decompressed_size as usize));
+        }
+        let frames = self.thread_pool.install(|| {
+            compressed_frames
+                .par_iter()
+                .map(|(compressed_frame, decompressed_size)| {
+                    let frame = zstd::block::decompress(compressed_frame, *decompressed_size)?;
+                    if frame.len() != *decompressed_size {
+                        return Err(invalid_data(format!(
+                            "frame decompressed to {} bytes instead of {}",
+                            frame.len(),
+                            decompressed_size
+                        )));
+                    }
+                    Ok(frame)
+                })
+                .collect::<io::Result<Vec<_>>>()
+        })?;
+        self.frames.extend(frames);
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for SeekableZstdDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self
+            .frames
+            .front()
+            .map(|frame| self.offset == frame.len())
+            .unwrap_or(true)
+        {
+            if self.frames.pop_front().is_some() {
+                self.offset = 0;
+            } else if self.seek_table.is_empty() {
+                return Ok(0);
+            } else {
+                self.decompress_frames()?;
+            }
+        }
+        let frame = self.frames.front().unwrap();
+        let len = std::cmp::min(buf.len(), frame.len() - self.offset);
+        buf[..len].copy_from_slice(&frame[self.offset..self.offset + len]);
+        self.offset += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Cursor};
+
+    fn test_data(len: usize) -> Vec<u8> {
+        // Compressible, but not trivially so
+        (0..len)
+            .map(|i| (i % 251) as u8 ^ (i / 4096) as u8)
+            .collect()
+    }
+
+    fn compress(data: &[u8], frame_size: usize) -> Vec<u8> {
+        let mut encoder = SeekableZstdEncoder::with_frame_size(vec![], 0, frame_size);
+        // Write in pieces that do not line up with the frames
+        for piece in data.chunks(1000) {
+            encoder.write_all(piece).unwrap();
+        }
+        encoder.finish().unwrap()
+    }
+
+    fn decompress(archive: &[u8]) -> Vec<u8> {
+        let mut reader = Cursor::new(archive);
+        let seek_table = read_seek_table(&mut reader).unwrap().unwrap();
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        let mut data = vec![];
+        SeekableZstdDecoder::new(reader, seek_table)
+            .read_to_end(&mut data)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_seekable_zstd_roundtrip() {
+        for len in [0, 1, 4096, 4096 * 25 + 7] {
+            let data = test_data(len);
+            let archive = compress(&data, 4096);
+
+            let seek_table = read_seek_table(&mut Cursor::new(&archive))
+                .unwrap()
+                .unwrap();
+            assert_eq!(seek_table.len(), data.chunks(4096).count());
+            assert_eq!(
+                seek_table
+                    .iter()
+                    .map(|entry| entry.decompressed_size as usize)
+                    .sum::<usize>(),
+                len
+            );
+            assert_eq!(decompress(&archive), data);
+
+            // Still a regular zstd stream
+            let mut data_from_stream = vec![];
+            zstd::stream::read::Decoder::new(&archive[..])
+                .unwrap()
+                .read_to_end(&mut data_from_stream)
+                .unwrap();
+            assert_eq!(data_from_stream, data);
+        }
+    }
+
+    #[test]
+    fn test_seekable_zstd_encoder_batch_size() {
+        for frame_size in [
+            4096,
+            DEFAULT_FRAME_SIZE,
+            MAX_FRAME_DECOMPRESSED_SIZE as usize,
+        ] {
+            let encoder = SeekableZstdEncoder::with_frame_size(vec![], 0, frame_size);
+            assert!(encoder.frames_per_batch >= 1);
+            assert!(
+                encoder.frames_per_batch as u64 * frame_size as u64 <= MAX_BATCH_DECOMPRESSED_SIZE
+            );
+        }
+    }
+
+    #[test]
+    fn test_seekable_zstd_batch_size() {
+        let data = test_data(4096 * 10);
+        let archive = compress(&data, 4096);
+        let mut reader = Cursor::new(&archive);
+        let seek_table = read_seek_table(&mut reader).unwrap().unwrap();
+        reader.seek(SeekFrom::Start(0)).unwrap();
+
+        // However many frames could be decompressed together, batches stay under the limit
+        let mut decoder = SeekableZstdDecoder::new(reader, seek_table);
+        decoder.frames_per_batch = 8;
+        decoder.max_batch_decompressed_size = 3 * 4096 + 1;
+        let mut byte = [0u8; 1];
+        decoder.read_exact(&mut byte).unwrap();
+        assert_eq!(decoder.frames.len(), 3);
+
+        let mut rest = vec![];
+        decoder.read_to_end(&mut rest).unwrap();
+        assert_eq!(byte[0], data[0]);
+        assert_eq!(rest, data[1..]);
+    }
+
+    #[test]
+    fn test_read_seek_table_of_zstd_stream() {
+        let archive = zstd::stream::encode_all(&test_data(100_000)[..], 0).unwrap();
+        assert_eq!(read_seek_table(&mut Cursor::new(&archive)).unwrap(), None);
+        assert_eq!(read_seek_table(&mut Cursor::new(vec![])).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_seek_table_invalid() {
+        let data = test_data(4096 * 3);
+        let archive = compress(&data, 4096);
+        let seek_table_offset = archive.len()
+            - (SKIPPABLE_FRAME_HEADER_SIZE + SEEK_TABLE_FOOTER_SIZE) as usize
+            - 3 * SEEK_TABLE_ENTRY_SIZE as usize;
+
+        // Frames that do not add up to the archive
+        let mut archive_with_extra_data = vec![0u8; 10];
+        archive_with_extra_data.extend_from_slice(&archive);
+        assert!(read_seek_table(&mut Cursor::new(&archive_with_extra_data)).is_err());
+
+        // A frame larger than the limit
+        let mut archive_with_large_frame = archive.clone();
+        let decompressed_size_offset = seek_table_offset + SKIPPABLE_FRAME_HEADER_SIZE as usize + 4;
+        archive_with_large_frame[decompressed_size_offset..decompressed_size_offset + 4]
+            .copy_from_slice(&(MAX_FRAME_DECOMPRESSED_SIZE + 1).to_le_bytes());
+        assert!(read_seek_table(&mut Cursor::new(&archive_with_large_frame)).is_err());
+
+        // A frame that decompresses to less than the seek table says
+        let mut archive_with_wrong_size = archive;
+        archive_with_wrong_size[decompressed_size_offset..decompressed_size_offset + 4]
+            .copy_from_slice(&4097u32.to_le_bytes());
+        let mut reader = Cursor::new(&archive_with_wrong_size);
+        let seek_table = read_seek_table(&mut reader).unwrap().unwrap();
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        let mut data = vec![];
+        assert!(SeekableZstdDecoder::new(reader, seek_table)
+            .read_to_end(&mut data)
+            .is_err());
+    }
+}
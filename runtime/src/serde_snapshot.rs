@@ -249,6 +249,41 @@ where
     })
 }
 
+/// The slot and length in use of each storage of a snapshot, keyed by the storage's file name
+pub(crate) type SnapshotStorageLengths = HashMap<String, (Slot, usize)>;
+
+/// Deserialize the bank fields of a snapshot, and the lengths of its storages, without
+/// reconstructing the bank
+pub(crate) fn snapshot_manifest_from_stream<R>(
+    serde_style: SerdeStyle,
+    stream: &mut BufReader<R>,
+) -> Result<(BankFieldsToDeserialize, SnapshotStorageLengths), Error>
+where
+    R: Read,
+{
+    macro_rules! INTO {
+        ($x:ident) => {{
+            let (bank_fields, AccountsDbFields(storages, ..)) =
+                $x::deserialize_bank_fields(stream)?;
+            let storage_lengths = storages
+                .iter()
+                .flat_map(|(slot, storage_entries)| {
+                    storage_entries.iter().map(move |storage_entry| {
+                        (
+                            AppendVec::file_name(*slot, storage_entry.id()),
+                            (*slot, storage_entry.current_len()),
+                        )
+                    })
+                })
+                .collect();
+            Ok((bank_fields, storage_lengths))
+        }};
+    }
+    match serde_style {
+        SerdeStyle::Newer => INTO!(TypeContextFuture),
+    }
+}
+
 pub(crate) fn bank_to_stream<W>(
     serde_style: SerdeStyle,
     stream: &mut BufWriter<W>,
@@ -2,11 +2,16 @@ use {
     crate::{
         accounts_db::AccountShrinkThreshold,
         accounts_index::{AccountSecondaryIndexes, AccountsIndexConfig},
+        append_vec::{AppendVec, StoredAccountMeta, StoredMetaWriteVersion},
         bank::{Bank, BankSlotDelta, Builtins},
-        hardened_unpack::{unpack_snapshot, ParallelSelector, UnpackError, UnpackedAppendVecMap},
+        hardened_unpack::{
+            unpack_snapshot, visit_snapshot_archive, ParallelSelector, UnpackError,
+            UnpackedAppendVecMap,
+        },
+        seekable_zstd::{read_seek_table, SeekableZstdDecoder, SeekableZstdEncoder},
         serde_snapshot::{
-            bank_from_streams, bank_to_stream, SerdeStyle, SnapshotStorage, SnapshotStorages,
-            SnapshotStreams,
+            bank_from_streams, bank_to_stream, snapshot_manifest_from_stream, SerdeStyle,
+            SnapshotStorage, SnapshotStorageLengths, SnapshotStorages, SnapshotStreams,
         },
         shared_buffer_reader::{SharedBuffer, SharedBufferReader},
         snapshot_archive_info::{
@@ -27,11 +32,11 @@ use {
     solana_measure::measure::Measure,
     solana_sdk::{clock::Slot, genesis_config::GenesisConfig, hash::Hash, pubkey::Pubkey},
     std::{
-        cmp::{max, Ordering},
-        collections::HashSet,
+        cmp::{max, Ordering, Reverse},
+        collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet},
         fmt,
         fs::{self, File},
-        io::{BufReader, BufWriter, Error as IoError, ErrorKind, Read, Seek, Write},
+        io::{BufReader, BufWriter, Error as IoError, ErrorKind, Read, Seek, SeekFrom, Write},
         path::{Path, PathBuf},
         process::ExitStatus,
         str::FromStr,
@@ -46,6 +51,7 @@ pub const SNAPSHOT_STATUS_CACHE_FILE_NAME: &str = "status_cache";
 
 pub const MAX_BANK_SNAPSHOTS: usize = 8; // Save some snapshots but not too many
 const MAX_SNAPSHOT_DATA_FILE_SIZE: u64 = 32 * 1024 * 1024 * 1024; // 32 GiB
+const MAX_SNAPSHOT_VERSION_FILE_SIZE: u64 = 1024; // 1 KiB
 const VERSION_STRING_V1_2_0: &str = "1.2.0";
 const DEFAULT_SNAPSHOT_VERSION: SnapshotVersion = SnapshotVersion::V1_2_0;
 pub(crate) const TMP_BANK_SNAPSHOT_PREFIX: &str = "tmp-bank-snapshot-";
@@ -53,6 +59,9 @@ pub const TMP_SNAPSHOT_ARCHIVE_PREFIX: &str = "tmp-snapshot-archive-";
 pub const DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN: usize = 2;
 pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz)$";
 pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz)$";
+/// The file kept next to uploaded snapshot archives that lists the latest ones, so that nodes can
+/// find them without knowing their slots and hashes
+pub const LATEST_SNAPSHOT_ARCHIVES_FILE_NAME: &str = "latest-snapshot-archives";
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum SnapshotVersion {
@@ -354,7 +363,7 @@ pub fn archive_snapshot_package(
                 encoder.finish()?;
             }
             ArchiveFormat::TarZstd => {
-                let mut encoder = zstd::stream::Encoder::new(archive_file, 0)?;
+                let mut encoder = SeekableZstdEncoder::new(archive_file, 0);
                 do_archive_files(&mut encoder)?;
                 encoder.finish()?;
             }
@@ -1207,6 +1216,106 @@ where
     incremental_snapshot_archives.into_iter().rev().next()
 }
 
+/// Select the snapshot archives that `purge_old_snapshot_archives()` removes from a set of full
+/// and incremental snapshot archives, so the same retention can be applied to archives that are not
+/// in a local directory
+pub fn get_snapshot_archives_to_purge(
+    mut full_snapshot_archives: Vec<FullSnapshotArchiveInfo>,
+    incremental_snapshot_archives: Vec<IncrementalSnapshotArchiveInfo>,
+    maximum_snapshots_to_retain: usize,
+) -> (
+    Vec<FullSnapshotArchiveInfo>,
+    Vec<IncrementalSnapshotArchiveInfo>,
+) {
+    full_snapshot_archives.sort_unstable();
+    full_snapshot_archives.reverse();
+    // Only keep incremental snapshots for the latest full snapshot
+    // bprumo TODO issue #18639: As an option to further reduce the number of incremental
+    // snapshots, only a subset of the incremental snapshots for the lastest full snapshot could be
+    // kept.  This could reuse maximum_snapshots_to_retain, or use a new field just for incremental
+    // snapshots.
+    // In case there are incremental snapshots but no full snapshots, make sure all the incremental
+    // snapshots are purged.
+    let last_full_snapshot_slot = full_snapshot_archives
+        .first()
+        .map(|archive_info| archive_info.slot())
+        .unwrap_or(Slot::MAX);
+    // Keep the oldest snapshot so we can always play the ledger from it.
+    full_snapshot_archives.pop();
+    let max_snaps = max(1, maximum_snapshots_to_retain);
+    let full_snapshot_archives_to_purge =
+        full_snapshot_archives.into_iter().skip(max_snaps).collect();
+    let incremental_snapshot_archives_to_purge = incremental_snapshot_archives
+        .into_iter()
+        .filter(|archive_info| archive_info.base_slot() < last_full_snapshot_slot)
+        .collect();
+    (
+        full_snapshot_archives_to_purge,
+        incremental_snapshot_archives_to_purge,
+    )
+}
+
+/// The contents of a latest snapshot archives file, which lists the file name of the full snapshot
+/// archive and, if there is one, of the incremental snapshot archive based on it, one per line
+pub fn latest_snapshot_archives_file_contents(
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+) -> Result<String> {
+    std::iter::once(full_snapshot_archive_info.path())
+        .chain(incremental_snapshot_archive_info.map(|archive_info| archive_info.path()))
+        .map(|path| path_to_file_name_str(path).map(|file_name| format!("{}\n", file_name)))
+        .collect()
+}
+
+/// Parse a latest snapshot archives file into the archives it lists, with the paths they have once
+/// downloaded into `snapshot_archives_dir`
+pub fn parse_latest_snapshot_archives_file(
+    contents: &str,
+    snapshot_archives_dir: &Path,
+) -> Result<(
+    FullSnapshotArchiveInfo,
+    Option<IncrementalSnapshotArchiveInfo>,
+)> {
+    // The file may come from anywhere, so its file names must not lead out of the directory
+    let archive_path = |file_name: &str| {
+        if Path::new(file_name)
+            .file_name()
+            .and_then(|name| name.to_str())
+            != Some(file_name)
+        {
+            return Err(SnapshotError::ParseSnapshotArchiveFileNameError(
+                file_name.to_string(),
+            ));
+        }
+        Ok(snapshot_archives_dir.join(file_name))
+    };
+    let mut file_names = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(archive_path(
+        file_names.next().ok_or(SnapshotError::NoSnapshotArchives)?,
+    )?)?;
+    let incremental_snapshot_archive_info = file_names
+        .next()
+        .map(|file_name| {
+            archive_path(file_name).and_then(IncrementalSnapshotArchiveInfo::new_from_path)
+        })
+        .transpose()?;
+    if let Some(incremental_snapshot_archive_info) = &incremental_snapshot_archive_info {
+        if incremental_snapshot_archive_info.base_slot() != full_snapshot_archive_info.slot() {
+            return Err(SnapshotError::MismatchedBaseSlot(
+                full_snapshot_archive_info.slot(),
+                incremental_snapshot_archive_info.base_slot(),
+            ));
+        }
+    }
+    Ok((
+        full_snapshot_archive_info,
+        incremental_snapshot_archive_info,
+    ))
+}
+
 pub fn purge_old_snapshot_archives<P>(snapshot_archives_dir: P, maximum_snapshots_to_retain: usize)
 where
     P: AsRef<Path>,
@@ -1216,13 +1325,13 @@ where
         snapshot_archives_dir.as_ref().display(),
         maximum_snapshots_to_retain
     );
-    let mut snapshot_archives = get_full_snapshot_archives(&snapshot_archives_dir);
-    snapshot_archives.sort_unstable();
-    snapshot_archives.reverse();
-    // Keep the oldest snapshot so we can always play the ledger from it.
-    snapshot_archives.pop();
-    let max_snaps = max(1, maximum_snapshots_to_retain);
-    for old_archive in snapshot_archives.into_iter().skip(max_snaps) {
+    let (full_snapshot_archives_to_purge, incremental_snapshot_archives_to_purge) =
+        get_snapshot_archives_to_purge(
+            get_full_snapshot_archives(&snapshot_archives_dir),
+            get_incremental_snapshot_archives(&snapshot_archives_dir),
+            maximum_snapshots_to_retain,
+        );
+    for old_archive in full_snapshot_archives_to_purge {
         trace!(
             "Purging old full snapshot archive: {}",
             old_archive.path().display()
@@ -1230,31 +1339,18 @@ where
         fs::remove_file(old_archive.path())
             .unwrap_or_else(|err| info!("Failed to remove old full snapshot archive: {}", err));
     }
-
-    // Only keep incremental snapshots for the latest full snapshot
-    // bprumo TODO issue #18639: As an option to further reduce the number of incremental
-    // snapshots, only a subset of the incremental snapshots for the lastest full snapshot could be
-    // kept.  This could reuse maximum_snapshots_to_retain, or use a new field just for incremental
-    // snapshots.
-    // In case there are incremental snapshots but no full snapshots, make sure all the incremental
-    // snapshots are purged.
-    let last_full_snapshot_slot =
-        get_highest_full_snapshot_archive_slot(&snapshot_archives_dir).unwrap_or(Slot::MAX);
-    get_incremental_snapshot_archives(&snapshot_archives_dir)
-        .iter()
-        .filter(|archive_info| archive_info.base_slot() < last_full_snapshot_slot)
-        .for_each(|old_archive| {
-            trace!(
-                "Purging old incremental snapshot archive: {}",
-                old_archive.path().display()
-            );
-            fs::remove_file(old_archive.path()).unwrap_or_else(|err| {
-                info!("Failed to remove old incremental snapshot archive: {}", err)
-            })
+    for old_archive in incremental_snapshot_archives_to_purge {
+        trace!(
+            "Purging old incremental snapshot archive: {}",
+            old_archive.path().display()
+        );
+        fs::remove_file(old_archive.path()).unwrap_or_else(|err| {
+            info!("Failed to remove old incremental snapshot archive: {}", err)
         });
+    }
 }
 
-fn unpack_snapshot_local<T: 'static + Read + std::marker::Send, F: Fn() -> T>(
+fn unpack_snapshot_local<T: 'static + Read + std::marker::Send, F: FnOnce() -> T>(
     reader: F,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
@@ -1312,12 +1408,20 @@ fn untar_snapshot_in<P: AsRef<Path>>(
             account_paths,
             parallel_divisions,
         )?,
-        ArchiveFormat::TarZstd => unpack_snapshot_local(
-            || zstd::stream::read::Decoder::new(BufReader::new(open_file())).unwrap(),
-            unpack_dir,
-            account_paths,
-            parallel_divisions,
-        )?,
+        ArchiveFormat::TarZstd => match read_seek_table(&mut open_file())? {
+            Some(seek_table) => unpack_snapshot_local(
+                || SeekableZstdDecoder::new(BufReader::new(open_file()), seek_table),
+                unpack_dir,
+                account_paths,
+                parallel_divisions,
+            )?,
+            None => unpack_snapshot_local(
+                || zstd::stream::read::Decoder::new(BufReader::new(open_file())).unwrap(),
+                unpack_dir,
+                account_paths,
+                parallel_divisions,
+            )?,
+        },
         ArchiveFormat::Tar => unpack_snapshot_local(
             || BufReader::new(open_file()),
             unpack_dir,
@@ -1328,6 +1432,303 @@ fn untar_snapshot_in<P: AsRef<Path>>(
     Ok(account_paths_map)
 }
 
+fn open_snapshot_archive(
+    snapshot_archive_path: &Path,
+    archive_format: ArchiveFormat,
+) -> Result<Archive<Box<dyn Read>>> {
+    let file = BufReader::new(File::open(snapshot_archive_path)?);
+    let reader: Box<dyn Read> = match archive_format {
+        ArchiveFormat::TarBzip2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarGzip => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarZstd => match read_seek_table(&mut File::open(snapshot_archive_path)?)? {
+            Some(seek_table) => Box::new(SeekableZstdDecoder::new(file, seek_table)),
+            None => Box::new(zstd::stream::read::Decoder::new(file)?),
+        },
+        ArchiveFormat::Tar => Box::new(file),
+    };
+    Ok(Archive::new(reader))
+}
+
+/// Reads the accounts of a full or incremental snapshot archive as a stream, without unpacking
+/// the archive or rebuilding a bank from it.
+///
+/// Archives store the storages before the manifest that gives the length in use of each of
+/// them, so opening the reader reads through the archive once for the manifest, and each scan
+/// of the accounts reads through it again.
+#[derive(Debug)]
+pub struct SnapshotArchiveReader {
+    snapshot_archive_path: PathBuf,
+    archive_format: ArchiveFormat,
+    snapshot_version: SnapshotVersion,
+    slot: Slot,
+    hash: Hash,
+    capitalization: u64,
+    /// Slot and length in use of each storage, keyed by file name
+    storage_lengths: SnapshotStorageLengths,
+}
+
+impl SnapshotArchiveReader {
+    pub fn open(
+        snapshot_archive_path: impl AsRef<Path>,
+        archive_format: ArchiveFormat,
+    ) -> Result<Self> {
+        let snapshot_archive_path = snapshot_archive_path.as_ref().to_path_buf();
+        let mut snapshot_version = None;
+        let mut manifest = None;
+        let mut archive = open_snapshot_archive(&snapshot_archive_path, archive_format)?;
+        visit_snapshot_archive(&mut archive, |parts, entry| {
+            match parts {
+                ["version"] => {
+                    let mut version = String::new();
+                    entry
+                        .take(MAX_SNAPSHOT_VERSION_FILE_SIZE)
+                        .read_to_string(&mut version)?;
+                    snapshot_version = Some(version.trim().to_string());
+                }
+                ["snapshots", dir, file] if dir == file => {
+                    let mut stream = BufReader::new(entry);
+                    manifest = Some(
+                        snapshot_manifest_from_stream(SerdeStyle::Newer, &mut stream).map_err(
+                            |err| {
+                                UnpackError::Archive(format!("invalid snapshot manifest: {}", err))
+                            },
+                        )?,
+                    );
+                }
+                _ => {}
+            }
+            Ok(())
+        })?;
+
+        let snapshot_version = snapshot_version
+            .ok_or_else(|| get_io_error("No version file found in snapshot archive"))?;
+        let snapshot_version =
+            SnapshotVersion::maybe_from_string(&snapshot_version).ok_or_else(|| {
+                get_io_error(&format!(
+                    "unsupported snapshot version: {}",
+                    snapshot_version
+                ))
+            })?;
+        let (bank_fields, storage_lengths) =
+            manifest.ok_or_else(|| get_io_error("No snapshots found in snapshot archive"))?;
+        Ok(Self {
+            snapshot_archive_path,
+            archive_format,
+            snapshot_version,
+            slot: bank_fields.slot,
+            hash: bank_fields.hash,
+            capitalization: bank_fields.capitalization,
+            storage_lengths,
+        })
+    }
+
+    pub fn snapshot_version(&self) -> SnapshotVersion {
+        self.snapshot_version
+    }
+
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// The bank hash of the snapshot's slot
+    pub fn hash(&self) -> &Hash {
+        &self.hash
+    }
+
+    pub fn capitalization(&self) -> u64 {
+        self.capitalization
+    }
+
+    /// Call `scanner` with each account stored in the archive, along with the slot of its
+    /// storage. An account stored in several storages is passed once for each
+    pub fn scan_stored_accounts<F>(&self, mut scanner: F) -> Result<()>
+    where
+        F: FnMut(Slot, &StoredAccountMeta),
+    {
+        let mut archive = open_snapshot_archive(&self.snapshot_archive_path, self.archive_format)?;
+        visit_snapshot_archive(&mut archive, |parts, entry| {
+            if let ["accounts", file] = parts {
+                let (slot, current_len) = self.storage_lengths.get(*file).ok_or_else(|| {
+                    UnpackError::Archive(format!("storage not in snapshot manifest: {}", file))
+                })?;
+                let (append_vec, _num_accounts) = AppendVec::new_from_reader(entry, *current_len)?;
+                let mut offset = 0;
+                while let Some((account, next_offset)) = append_vec.get_account(offset) {
+                    scanner(*slot, &account);
+                    offset = next_offset;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Map the latest version of each account stored in the archive with `map_account`, and
+    /// return it along with the slot of its storage. Accounts with no lamports are included,
+    /// since they remove the account from the snapshot this archive is based on, if any
+    pub fn latest_accounts<T, F>(&self, mut map_account: F) -> Result<HashMap<Pubkey, (Slot, T)>>
+    where
+        F: FnMut(&StoredAccountMeta) -> T,
+    {
+        let mut accounts = HashMap::<Pubkey, (Slot, StoredMetaWriteVersion, T)>::new();
+        self.scan_stored_accounts(|slot, account| {
+            let version = (slot, account.meta.write_version);
+            match accounts.entry(account.meta.pubkey) {
+                Entry::Occupied(mut entry) => {
+                    let (latest_slot, latest_write_version, _) = entry.get();
+                    if version > (*latest_slot, *latest_write_version) {
+                        entry.insert((slot, version.1, map_account(account)));
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((slot, version.1, map_account(account)));
+                }
+            }
+        })?;
+        Ok(accounts
+            .into_iter()
+            .map(|(pubkey, (slot, _write_version, account))| (pubkey, (slot, account)))
+            .collect())
+    }
+
+    /// Call `scanner` with the latest version of each account stored in the archive, along with
+    /// the slot of its storage, at the cost of reading through the archive twice.
+    ///
+    /// Rather than a map of every account, only the versions superseded by a later one are held
+    /// in memory, see `stale_account_versions()`. Storages that have been cleaned and shrunk
+    /// hold few of them, but the versions of all the stored accounts are spilled to temporary
+    /// files while looking for them, taking `ACCOUNT_VERSION_SIZE` bytes of disk per account.
+    pub fn scan_latest_accounts<F>(&self, mut scanner: F) -> Result<()>
+    where
+        F: FnMut(Slot, &StoredAccountMeta),
+    {
+        let stale_versions = self.stale_account_versions(ACCOUNT_VERSIONS_SORT_BUFFER_LEN)?;
+        self.scan_stored_accounts(|slot, account| {
+            if !stale_versions.contains(&(account.meta.pubkey, slot, account.meta.write_version)) {
+                scanner(slot, account);
+            }
+        })
+    }
+
+    /// The versions of the accounts stored in the archive that a later version of the same
+    /// account supersedes. The versions of all the stored accounts are sorted in runs of
+    /// `sort_buffer_len`, spilled to temporary files and merged in pubkey order
+    fn stale_account_versions(&self, sort_buffer_len: usize) -> Result<HashSet<AccountVersion>> {
+        let mut runs = vec![];
+        let mut versions = vec![];
+        let mut spill_result = Ok(());
+        self.scan_stored_accounts(|slot, account| {
+            if spill_result.is_err() {
+                return;
+            }
+            versions.push((account.meta.pubkey, slot, account.meta.write_version));
+            if versions.len() >= sort_buffer_len {
+                spill_result = spill_account_versions(&mut versions).map(|run| runs.push(run));
+            }
+        })?;
+        spill_result?;
+
+        let mut stale_versions = StaleAccountVersions::default();
+        if runs.is_empty() {
+            versions.sort_unstable();
+            versions
+                .into_iter()
+                .for_each(|version| stale_versions.push(version));
+            return Ok(stale_versions.stale);
+        }
+        if !versions.is_empty() {
+            runs.push(spill_account_versions(&mut versions)?);
+        }
+        let mut heads = BinaryHeap::new();
+        for (index, run) in runs.iter_mut().enumerate() {
+            if let Some(version) = run.next().transpose()? {
+                heads.push(Reverse((version, index)));
+            }
+        }
+        while let Some(Reverse((version, index))) = heads.pop() {
+            if let Some(next_version) = runs[index].next().transpose()? {
+                heads.push(Reverse((next_version, index)));
+            }
+            stale_versions.push(version);
+        }
+        Ok(stale_versions.stale)
+    }
+}
+
+/// The pubkey of a stored account, the slot of its storage and its write version, which order
+/// the versions of an account from the oldest to the latest
+type AccountVersion = (Pubkey, Slot, StoredMetaWriteVersion);
+
+/// Bytes taken by an `AccountVersion` in a run file
+const ACCOUNT_VERSION_SIZE: usize = 32 + 8 + 8;
+
+/// Account versions sorted in memory at a time by `SnapshotArchiveReader::scan_latest_accounts()`
+/// before they are spilled to a run file
+const ACCOUNT_VERSIONS_SORT_BUFFER_LEN: usize = 4 * 1024 * 1024;
+
+/// Collects the stale versions out of account versions pushed in order
+#[derive(Default)]
+struct StaleAccountVersions {
+    previous: Option<AccountVersion>,
+    stale: HashSet<AccountVersion>,
+}
+
+impl StaleAccountVersions {
+    fn push(&mut self, version: AccountVersion) {
+        if let Some(previous) = self.previous.replace(version) {
+            if previous.0 == version.0 {
+                self.stale.insert(previous);
+            }
+        }
+    }
+}
+
+/// Sort `versions` and write them to a temporary file, leaving `versions` empty
+fn spill_account_versions(versions: &mut Vec<AccountVersion>) -> Result<AccountVersionRun> {
+    versions.sort_unstable();
+    let mut file = tempfile::tempfile()?;
+    let mut writer = BufWriter::new(&mut file);
+    for (pubkey, slot, write_version) in versions.drain(..) {
+        writer.write_all(pubkey.as_ref())?;
+        writer.write_all(&slot.to_le_bytes())?;
+        writer.write_all(&write_version.to_le_bytes())?;
+    }
+    writer.flush()?;
+    drop(writer);
+    file.seek(SeekFrom::Start(0))?;
+    Ok(AccountVersionRun {
+        reader: BufReader::new(file),
+    })
+}
+
+/// Sorted account versions spilled to a temporary file, read back in order
+struct AccountVersionRun {
+    reader: BufReader<File>,
+}
+
+impl Iterator for AccountVersionRun {
+    type Item = Result<AccountVersion>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = [0u8; ACCOUNT_VERSION_SIZE];
+        match self.reader.read_exact(&mut buf) {
+            Ok(()) => {
+                let mut slot = [0u8; 8];
+                let mut write_version = [0u8; 8];
+                slot.copy_from_slice(&buf[32..40]);
+                write_version.copy_from_slice(&buf[40..]);
+                Some(Ok((
+                    Pubkey::new(&buf[..32]),
+                    Slot::from_le_bytes(slot),
+                    StoredMetaWriteVersion::from_le_bytes(write_version),
+                )))
+            }
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => None,
+            Err(err) => Some(Err(err.into())),
+        }
+    }
+}
+
 fn verify_unpacked_snapshots_dir_and_version(
     unpacked_snapshots_dir_and_version: &UnpackedSnapshotsDirAndVersion,
 ) -> Result<(SnapshotVersion, BankSnapshotInfo)> {
@@ -1736,9 +2137,11 @@ pub fn package_and_archive_incremental_snapshot(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{accounts_db::AccountStorageEntry, snapshot_archive_info::SnapshotArchiveInfo};
     use assert_matches::assert_matches;
     use bincode::{deserialize_from, serialize_into};
     use solana_sdk::{
+        account::ReadableAccount,
         genesis_config::create_genesis_config,
         signature::{Keypair, Signer},
         system_transaction,
@@ -2390,6 +2793,193 @@ mod tests {
         assert!(remaining_incremental_snapshot_archives.is_empty());
     }
 
+    #[test]
+    fn test_get_snapshot_archives_to_purge() {
+        // The archives only need a file name, they do not have to exist
+        let full_snapshot_archives = [1, 3, 50, 70]
+            .iter()
+            .map(|slot| {
+                FullSnapshotArchiveInfo::new_from_path(PathBuf::from(format!(
+                    "snapshot-{}-{}.tar.zst",
+                    slot,
+                    Hash::default()
+                )))
+                .unwrap()
+            })
+            .collect();
+        let incremental_snapshot_archives = [(50, 60), (70, 80), (70, 90)]
+            .iter()
+            .map(|(base_slot, slot)| {
+                IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(format!(
+                    "incremental-snapshot-{}-{}-{}.tar.zst",
+                    base_slot,
+                    slot,
+                    Hash::default()
+                )))
+                .unwrap()
+            })
+            .collect();
+
+        let (full_snapshot_archives_to_purge, incremental_snapshot_archives_to_purge) =
+            get_snapshot_archives_to_purge(
+                full_snapshot_archives,
+                incremental_snapshot_archives,
+                2,
+            );
+        assert_eq!(
+            full_snapshot_archives_to_purge
+                .iter()
+                .map(|archive_info| archive_info.slot())
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(
+            incremental_snapshot_archives_to_purge
+                .iter()
+                .map(|archive_info| archive_info.slot())
+                .collect::<Vec<_>>(),
+            vec![60]
+        );
+    }
+
+    #[test]
+    fn test_latest_snapshot_archives_file() {
+        let snapshot_archives_dir = Path::new("/snapshots");
+        let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(
+            snapshot_archives_dir.join(format!("snapshot-100-{}.tar.zst", Hash::default())),
+        )
+        .unwrap();
+        let incremental_snapshot_archive_info =
+            IncrementalSnapshotArchiveInfo::new_from_path(snapshot_archives_dir.join(format!(
+                "incremental-snapshot-100-150-{}.tar.zst",
+                Hash::default()
+            )))
+            .unwrap();
+
+        for incremental_snapshot_archive_info in [None, Some(&incremental_snapshot_archive_info)] {
+            let contents = latest_snapshot_archives_file_contents(
+                &full_snapshot_archive_info,
+                incremental_snapshot_archive_info,
+            )
+            .unwrap();
+            assert_eq!(
+                parse_latest_snapshot_archives_file(&contents, snapshot_archives_dir).unwrap(),
+                (
+                    full_snapshot_archive_info.clone(),
+                    incremental_snapshot_archive_info.cloned()
+                )
+            );
+        }
+
+        // The incremental snapshot archive must be based on the full snapshot archive
+        let contents = format!(
+            "snapshot-200-{0}.tar.zst\nincremental-snapshot-100-150-{0}.tar.zst\n",
+            Hash::default()
+        );
+        assert_matches!(
+            parse_latest_snapshot_archives_file(&contents, snapshot_archives_dir),
+            Err(SnapshotError::MismatchedBaseSlot(200, 100))
+        );
+        // File names can't lead out of the snapshot archives directory
+        let contents = format!("../snapshot-100-{}.tar.zst\n", Hash::default());
+        assert!(parse_latest_snapshot_archives_file(&contents, snapshot_archives_dir).is_err());
+        assert!(parse_latest_snapshot_archives_file("", snapshot_archives_dir).is_err());
+    }
+
+    /// Test roundtrip of a snapshot package to a seekable zstd archive with several frames, which
+    /// is unpacked in parallel, then back again
+    #[test]
+    fn test_roundtrip_snapshot_package_to_and_from_tar_zstd_archive() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let accounts_dir = temp_dir.path().join("accounts");
+        let snapshots_dir = temp_dir.path().join("snapshots");
+        let snapshot_archives_dir = temp_dir.path().join("snapshots_output");
+        fs::create_dir_all(&accounts_dir).unwrap();
+
+        // Storages large enough for the archive to span several frames
+        let storage_entries: Vec<_> = (0..5)
+            .map(|i| {
+                Arc::new(AccountStorageEntry::new(
+                    &accounts_dir,
+                    0,
+                    i,
+                    3 * 1024 * 1024,
+                ))
+            })
+            .collect();
+
+        // Create a directory of hard links to some fake snapshots
+        let link_snapshots_dir = tempfile::tempdir_in(temp_dir.path()).unwrap();
+        for i in 0..5 {
+            let snapshot_file_name = format!("{}", i);
+            let snapshot_dir = snapshots_dir.join(&snapshot_file_name);
+            fs::create_dir_all(&snapshot_dir).unwrap();
+            let snapshot_path = snapshot_dir.join(&snapshot_file_name);
+            fs::write(&snapshot_path, b"Hello, world!").unwrap();
+
+            let link_snapshot_dir = link_snapshots_dir.path().join(&snapshot_file_name);
+            fs::create_dir_all(&link_snapshot_dir).unwrap();
+            fs::hard_link(&snapshot_path, link_snapshot_dir.join(&snapshot_file_name)).unwrap();
+        }
+
+        let slot = 42;
+        let hash = Hash::default();
+        let archive_format = ArchiveFormat::TarZstd;
+        let snapshot_archive_path =
+            build_full_snapshot_archive_path(snapshot_archives_dir, slot, &hash, archive_format);
+        let snapshot_package = SnapshotPackage {
+            snapshot_archive_info: SnapshotArchiveInfo {
+                path: snapshot_archive_path.clone(),
+                slot,
+                hash,
+                archive_format,
+            },
+            block_height: slot,
+            slot_deltas: vec![],
+            snapshot_links: link_snapshots_dir,
+            snapshot_storages: vec![storage_entries],
+            snapshot_version: SnapshotVersion::default(),
+            snapshot_type: SnapshotType::FullSnapshot,
+        };
+        archive_snapshot_package(
+            &snapshot_package,
+            DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+
+        // The status cache is added by the packager, so add an empty one to compare against
+        let slot_deltas: Vec<BankSlotDelta> = vec![];
+        serialize_snapshot_data_file(
+            &snapshots_dir.join(SNAPSHOT_STATUS_CACHE_FILE_NAME),
+            |stream| {
+                serialize_into(stream, &slot_deltas)?;
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        let seek_table = read_seek_table(&mut File::open(&snapshot_archive_path).unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(seek_table.len() > 1);
+
+        let unpack_dir = tempfile::TempDir::new().unwrap();
+        untar_snapshot_in(
+            &snapshot_archive_path,
+            unpack_dir.path(),
+            &[unpack_dir.path().to_path_buf()],
+            archive_format,
+            4,
+        )
+        .unwrap();
+        assert!(
+            !dir_diff::is_different(&snapshots_dir, unpack_dir.path().join("snapshots")).unwrap()
+        );
+        assert!(
+            !dir_diff::is_different(&accounts_dir, unpack_dir.path().join("accounts")).unwrap()
+        );
+    }
+
     /// Test roundtrip of bank to a full snapshot, then back again.  This test creates the simplest
     /// bank possible, so the contents of the snapshot archive will be quite minimal.
     #[test]
@@ -2942,4 +3532,113 @@ mod tests {
             "Ensure Account1 has not been brought back from the dead"
         );
     }
+
+    #[test]
+    fn test_snapshot_archive_reader() {
+        solana_logger::setup();
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        bank0.transfer(1, &mint_keypair, &key1.pubkey()).unwrap();
+        bank0.transfer(2, &mint_keypair, &key2.pubkey()).unwrap();
+        while !bank0.is_complete() {
+            bank0.register_tick(&Hash::new_unique());
+        }
+
+        let slot = 1;
+        let bank1 = Arc::new(Bank::new_from_parent(&bank0, &collector, slot));
+        bank1.transfer(3, &mint_keypair, &key1.pubkey()).unwrap();
+        while !bank1.is_complete() {
+            bank1.register_tick(&Hash::new_unique());
+        }
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            snapshot_archives_dir.path(),
+            ArchiveFormat::TarGzip,
+            std::usize::MAX,
+        )
+        .unwrap();
+
+        let reader = SnapshotArchiveReader::open(
+            full_snapshot_archive_info.path(),
+            full_snapshot_archive_info.archive_format(),
+        )
+        .unwrap();
+        assert_eq!(reader.snapshot_version(), SnapshotVersion::default());
+        assert_eq!(reader.slot(), bank1.slot());
+        assert_eq!(reader.hash(), &bank1.hash());
+        assert_eq!(reader.capitalization(), bank1.capitalization());
+
+        let accounts = reader
+            .latest_accounts(|account| account.clone_account())
+            .unwrap();
+        assert_eq!(accounts[&key1.pubkey()].0, 1);
+        assert_eq!(accounts[&key1.pubkey()].1.lamports(), 4);
+        assert_eq!(accounts[&key2.pubkey()].0, 0);
+        assert_eq!(accounts[&key2.pubkey()].1.lamports(), 2);
+        for (pubkey, (_slot, account)) in &accounts {
+            if account.lamports() > 0 {
+                assert_eq!(bank1.get_account(pubkey).as_ref(), Some(account));
+            }
+        }
+        let mut num_stored_accounts = 0;
+        reader
+            .scan_stored_accounts(|_slot, _account| num_stored_accounts += 1)
+            .unwrap();
+        assert!(num_stored_accounts >= accounts.len());
+        let stale_versions = reader
+            .stale_account_versions(ACCOUNT_VERSIONS_SORT_BUFFER_LEN)
+            .unwrap();
+        assert_eq!(stale_versions.len(), num_stored_accounts - accounts.len());
+        // Spilling every version to its own run finds the same stale versions
+        assert_eq!(reader.stale_account_versions(1).unwrap(), stale_versions);
+        let mut latest_accounts = HashMap::new();
+        reader
+            .scan_latest_accounts(|slot, account| {
+                assert!(latest_accounts
+                    .insert(account.meta.pubkey, (slot, account.clone_account()))
+                    .is_none());
+            })
+            .unwrap();
+        assert_eq!(latest_accounts, accounts);
+
+        // An incremental snapshot only stores the accounts written since its base
+        let slot = slot + 1;
+        let bank2 = Arc::new(Bank::new_from_parent(&bank1, &collector, slot));
+        bank2.transfer(5, &mint_keypair, &key2.pubkey()).unwrap();
+        while !bank2.is_complete() {
+            bank2.register_tick(&Hash::new_unique());
+        }
+        let incremental_snapshot_archive_info = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank2,
+            bank1.slot(),
+            None,
+            snapshot_archives_dir.path(),
+            ArchiveFormat::TarZstd,
+            std::usize::MAX,
+        )
+        .unwrap();
+
+        let reader = SnapshotArchiveReader::open(
+            incremental_snapshot_archive_info.path(),
+            incremental_snapshot_archive_info.archive_format(),
+        )
+        .unwrap();
+        assert_eq!(reader.slot(), bank2.slot());
+        assert_eq!(reader.capitalization(), bank2.capitalization());
+        let accounts = reader
+            .latest_accounts(|account| account.account_meta.lamports)
+            .unwrap();
+        assert_eq!(accounts[&key2.pubkey()], (2, 7));
+        assert!(accounts.values().all(|(slot, _)| *slot > bank1.slot()));
+    }
 }
@@ -29,6 +29,14 @@ void sol_log_64_(uint64_t, uint64_t, uint64_t, uint64_t, uint64_t);
 void sol_log_compute_units_();
 #define sol_log_compute_units() sol_log_compute_units_()
 
+/**
+ * Prints the base64 representation of some arrays to stdout
+ *
+ * @param fields The arrays to print
+ * @param fields_len Number of arrays
+ */
+void sol_log_data(SolBytes *fields, uint64_t fields_len);
+
 /**
  * Prints the hexadecimal representation of an array
  *
@@ -1,5 +1,6 @@
 use {
     crate::{
+        ed25519_program,
         fee_calculator::FeeCalculator,
         hash::Hash,
         instruction::{CompiledInstruction, Instruction},
@@ -294,21 +295,22 @@ impl SanitizedMessage {
         })
     }
 
-    /// Calculate the total fees for a transaction given a fee calculator
+    /// Calculate the total fees for a transaction given a fee calculator. Signatures verified by
+    /// the secp256k1 and ed25519 programs are charged like transaction signatures
     pub fn calculate_fee(&self, fee_calculator: &FeeCalculator) -> u64 {
-        let mut num_secp256k1_signatures: u64 = 0;
+        let mut num_precompile_signatures: u64 = 0;
         for (program_id, instruction) in self.program_instructions_iter() {
-            if secp256k1_program::check_id(program_id) {
+            if secp256k1_program::check_id(program_id) || ed25519_program::check_id(program_id) {
                 if let Some(num_signatures) = instruction.data.get(0) {
-                    num_secp256k1_signatures =
-                        num_secp256k1_signatures.saturating_add(u64::from(*num_signatures));
+                    num_precompile_signatures =
+                        num_precompile_signatures.saturating_add(u64::from(*num_signatures));
                 }
             }
         }
 
         fee_calculator.lamports_per_signature.saturating_mul(
             u64::from(self.header().num_required_signatures)
-                .saturating_add(num_secp256k1_signatures),
+                .saturating_add(num_precompile_signatures),
         )
     }
 }
@@ -319,7 +321,7 @@ mod tests {
     use crate::{
         instruction::{AccountMeta, Instruction},
         message::v0,
-        secp256k1_program, system_instruction,
+        system_instruction,
     };
 
     #[test]
@@ -596,4 +598,60 @@ mod tests {
         .unwrap();
         assert_eq!(message.calculate_fee(&FeeCalculator::new(1)), 11);
     }
+
+    #[test]
+    fn test_calculate_fee_ed25519() {
+        let key0 = Pubkey::new_unique();
+        let key1 = Pubkey::new_unique();
+        let ix0 = system_instruction::transfer(&key0, &key1, 1);
+
+        let mut ed25519_instruction1 = Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data: vec![],
+        };
+        let mut ed25519_instruction2 = Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data: vec![1],
+        };
+
+        let message = SanitizedMessage::try_from(Message::new(
+            &[
+                ix0.clone(),
+                ed25519_instruction1.clone(),
+                ed25519_instruction2.clone(),
+            ],
+            Some(&key0),
+        ))
+        .unwrap();
+        assert_eq!(message.calculate_fee(&FeeCalculator::new(1)), 2);
+
+        ed25519_instruction1.data = vec![0];
+        ed25519_instruction2.data = vec![10];
+        let message = SanitizedMessage::try_from(Message::new(
+            &[ix0.clone(), ed25519_instruction1, ed25519_instruction2],
+            Some(&key0),
+        ))
+        .unwrap();
+        assert_eq!(message.calculate_fee(&FeeCalculator::new(1)), 11);
+
+        // Signatures verified by both precompiles add up
+        let secp_instruction = Instruction {
+            program_id: secp256k1_program::id(),
+            accounts: vec![],
+            data: vec![3],
+        };
+        let ed25519_instruction = Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data: vec![4],
+        };
+        let message = SanitizedMessage::try_from(Message::new(
+            &[ix0, secp_instruction, ed25519_instruction],
+            Some(&key0),
+        ))
+        .unwrap();
+        assert_eq!(message.calculate_fee(&FeeCalculator::new(1)), 8);
+    }
 }
@@ -0,0 +1,175 @@
+//! The `account_proof` module verifies that an account is part of the state of a bank, from a
+//! Merkle proof of the account's hash against the bank's accounts hash, which hashes every
+//! account of the bank in pubkey order.
+
+#![cfg(feature = "full")]
+
+use crate::{
+    account::ReadableAccount,
+    blake3,
+    clock::Slot,
+    hash::{Hash, Hasher},
+    pubkey::Pubkey,
+};
+
+/// The hash of an account last written in `slot`, as included in the accounts hash of the banks
+/// that descend from the slot. Accounts with no lamports, which have been closed, hash to
+/// `Hash::default()`.
+pub fn hash_account<T: ReadableAccount>(slot: Slot, account: &T, pubkey: &Pubkey) -> Hash {
+    if account.lamports() == 0 {
+        return Hash::default();
+    }
+    let executable = [account.executable() as u8];
+    let hash = blake3::hashv(&[
+        &account.lamports().to_le_bytes(),
+        &slot.to_le_bytes(),
+        &account.rent_epoch().to_le_bytes(),
+        account.data(),
+        &executable,
+        account.owner().as_ref(),
+        pubkey.as_ref(),
+    ]);
+    Hash::new_from_array(hash.0)
+}
+
+/// One level of a path from a leaf to the root of a Merkle tree, whose nodes hash groups of
+/// consecutive nodes of the level below
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProofLevel {
+    /// Position of the node on the path within its group
+    pub index: usize,
+    /// The other nodes of the group, in order
+    pub siblings: Vec<Hash>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub levels: Vec<MerkleProofLevel>,
+}
+
+impl MerkleProof {
+    /// The root of the tree with `leaf` on this path, or `None` if the proof is malformed
+    pub fn root(&self, leaf: Hash) -> Option<Hash> {
+        self.levels.iter().try_fold(leaf, |node, level| {
+            if level.index > level.siblings.len() {
+                return None;
+            }
+            let (before, after) = level.siblings.split_at(level.index);
+            let mut hasher = Hasher::default();
+            before
+                .iter()
+                .chain(std::iter::once(&node))
+                .chain(after)
+                .for_each(|hash| hasher.hash(hash.as_ref()));
+            Some(hasher.result())
+        })
+    }
+
+    pub fn verify(&self, leaf: Hash, root: &Hash) -> bool {
+        !self.levels.is_empty() && self.root(leaf).as_ref() == Some(root)
+    }
+}
+
+/// Verify that `account`, last written in `slot`, is the state of `pubkey` in the bank whose
+/// accounts hash is `accounts_hash`. Closed accounts are not part of the accounts hash.
+pub fn verify_account_proof<T: ReadableAccount>(
+    pubkey: &Pubkey,
+    account: &T,
+    slot: Slot,
+    proof: &MerkleProof,
+    accounts_hash: &Hash,
+) -> bool {
+    account.lamports() > 0 && proof.verify(hash_account(slot, account, pubkey), accounts_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{account::Account, hash::hashv},
+    };
+
+    #[test]
+    fn test_merkle_proof() {
+        let leaf = Hash::new_unique();
+        let siblings = vec![Hash::new_unique(), Hash::new_unique()];
+        let node = hashv(&[siblings[0].as_ref(), leaf.as_ref(), siblings[1].as_ref()]);
+        let other = Hash::new_unique();
+        let root = hashv(&[other.as_ref(), node.as_ref()]);
+        let proof = MerkleProof {
+            levels: vec![
+                MerkleProofLevel { index: 1, siblings },
+                MerkleProofLevel {
+                    index: 1,
+                    siblings: vec![other],
+                },
+            ],
+        };
+        assert!(proof.verify(leaf, &root));
+        assert!(!proof.verify(Hash::new_unique(), &root));
+        assert!(!MerkleProof::default().verify(root, &root));
+
+        let mut malformed = proof;
+        malformed.levels[1].index = 2;
+        assert_eq!(malformed.root(leaf), None);
+    }
+
+    #[test]
+    fn test_verify_account_proof() {
+        let pubkey = Pubkey::new_unique();
+        let account = Account::new(42, 3, &Pubkey::new_unique());
+        let slot = 7;
+        let leaf = hash_account(slot, &account, &pubkey);
+        assert_ne!(leaf, hash_account(slot + 1, &account, &pubkey));
+        assert_eq!(
+            hash_account(slot, &Account::default(), &pubkey),
+            Hash::default()
+        );
+
+        let sibling = Hash::new_unique();
+        let proof = MerkleProof {
+            levels: vec![MerkleProofLevel {
+                index: 0,
+                siblings: vec![sibling],
+            }],
+        };
+        let accounts_hash = hashv(&[leaf.as_ref(), sibling.as_ref()]);
+        assert!(verify_account_proof(
+            &pubkey,
+            &account,
+            slot,
+            &proof,
+            &accounts_hash
+        ));
+        assert!(!verify_account_proof(
+            &pubkey,
+            &account,
+            slot + 1,
+            &proof,
+            &accounts_hash
+        ));
+        assert!(!verify_account_proof(
+            &Pubkey::new_unique(),
+            &account,
+            slot,
+            &proof,
+            &accounts_hash
+        ));
+
+        // Closed accounts have no proof, even if their default hash is in the tree
+        let closed_proof = MerkleProof {
+            levels: vec![MerkleProofLevel {
+                index: 0,
+                siblings: vec![Hash::default()],
+            }],
+        };
+        let accounts_hash = hashv(&[Hash::default().as_ref(), Hash::default().as_ref()]);
+        assert!(!verify_account_proof(
+            &pubkey,
+            &Account::default(),
+            slot,
+            &closed_proof,
+            &accounts_hash
+        ));
+    }
+}
@@ -72,6 +72,8 @@ pub struct ComputeBudget {
     pub secp256k1_recover_cost: u64,
     /// Optional program heap region size, if `None` then loader default
     pub heap_size: Option<usize>,
+    /// Number of compute units consumed to call a syscall that has no specific cost
+    pub syscall_base_cost: u64,
 }
 impl Default for ComputeBudget {
     fn default() -> Self {
@@ -96,6 +98,7 @@ impl ComputeBudget {
             sysvar_base_cost: 100,
             secp256k1_recover_cost: 25_000,
             heap_size: None,
+            syscall_base_cost: 100,
         }
     }
     pub fn process_transaction(
@@ -10,6 +10,7 @@ pub use signer::signers;
 pub use solana_program::*;
 
 pub mod account;
+pub mod account_proof;
 pub mod account_utils;
 pub mod builtins;
 pub mod client;
@@ -17,6 +18,7 @@ pub mod commitment_config;
 pub mod compute_budget;
 pub mod derivation_path;
 pub mod deserialize_utils;
+pub mod ed25519_instruction;
 pub mod entrypoint;
 pub mod entrypoint_deprecated;
 pub mod entrypoint_native;
@@ -113,6 +113,10 @@ pub trait InvokeContext {
     fn get_blockhash(&self) -> &Hash;
     /// Get this invocation's `FeeCalculator`
     fn get_fee_calculator(&self) -> &FeeCalculator;
+    /// Set the return data of the currently executing program
+    fn set_return_data(&mut self, data: Vec<u8>) -> Result<(), InstructionError>;
+    /// Get the return data and the program that set it
+    fn get_return_data(&self) -> (Pubkey, &[u8]);
 }
 
 /// Convenience macro to log a message with an `Rc<RefCell<dyn Logger>>`
@@ -237,6 +241,7 @@ impl From<BpfComputeBudget> for ComputeBudget {
             sysvar_base_cost: item.sysvar_base_cost,
             secp256k1_recover_cost: item.secp256k1_recover_cost,
             heap_size: item.heap_size,
+            ..ComputeBudget::default()
         }
     }
 }
@@ -280,6 +285,7 @@ pub trait Logger {
 ///
 pub mod stable_log {
     use super::*;
+    use itertools::Itertools;
 
     /// Log a program invoke.
     ///
@@ -309,6 +315,24 @@ pub mod stable_log {
         ic_logger_msg!(logger, "Program log: {}", message);
     }
 
+    /// Emit a program data.
+    ///
+    /// The general form is:
+    ///
+    /// ```notrust
+    /// "Program data: <binary-data-in-base64>*"
+    /// ```
+    ///
+    /// That is, any program-generated output is guaranteed to be prefixed by "Program data: ",
+    /// with each field base64 encoded and separated by a single space
+    pub fn program_data(logger: &Rc<RefCell<dyn Logger>>, data: &[&[u8]]) {
+        ic_logger_msg!(
+            logger,
+            "Program data: {}",
+            data.iter().map(base64::encode).join(" ")
+        );
+    }
+
     /// Log successful program execution.
     ///
     /// The general form is:
@@ -393,6 +417,7 @@ pub struct MockInvokeContext<'a> {
     pub disabled_features: HashSet<Pubkey>,
     pub blockhash: Hash,
     pub fee_calculator: FeeCalculator,
+    pub return_data: (Pubkey, Vec<u8>),
 }
 impl<'a> MockInvokeContext<'a> {
     pub fn new(keyed_accounts: Vec<KeyedAccount<'a>>) -> Self {
@@ -411,6 +436,7 @@ impl<'a> MockInvokeContext<'a> {
             disabled_features: HashSet::default(),
             blockhash: Hash::default(),
             fee_calculator: FeeCalculator::default(),
+            return_data: (Pubkey::default(), Vec::new()),
         };
         invoke_context
             .invoke_stack
@@ -539,4 +565,11 @@ impl<'a> InvokeContext for MockInvokeContext<'a> {
     fn get_fee_calculator(&self) -> &FeeCalculator {
         &self.fee_calculator
     }
+    fn set_return_data(&mut self, data: Vec<u8>) -> Result<(), InstructionError> {
+        self.return_data = (*self.get_caller()?, data);
+        Ok(())
+    }
+    fn get_return_data(&self) -> (Pubkey, &[u8]) {
+        (self.return_data.0, &self.return_data.1)
+    }
 }
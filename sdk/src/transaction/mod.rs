@@ -4,6 +4,7 @@
 
 use {
     crate::{
+        ed25519_instruction::verify_signatures,
         hash::Hash,
         instruction::{CompiledInstruction, Instruction, InstructionError},
         message::{Message, SanitizeMessageError},
@@ -430,6 +431,7 @@ impl Transaction {
         &self,
         libsecp256k1_0_5_upgrade_enabled: bool,
         libsecp256k1_fail_on_bad_count: bool,
+        ed25519_program_enabled: bool,
     ) -> Result<()> {
         for instruction in &self.message().instructions {
             // The Transaction may not be sanitized at this point
@@ -452,6 +454,15 @@ impl Transaction {
                     libsecp256k1_fail_on_bad_count,
                 );
                 e.map_err(|_| TransactionError::InvalidAccountIndex)?;
+            } else if ed25519_program_enabled && crate::ed25519_program::check_id(program_id) {
+                let instruction_datas: Vec<_> = self
+                    .message()
+                    .instructions
+                    .iter()
+                    .map(|instruction| instruction.data.as_ref())
+                    .collect();
+                verify_signatures(&instruction.data, &instruction_datas)
+                    .map_err(|_| TransactionError::InvalidAccountIndex)?;
             }
         }
         Ok(())
@@ -2,6 +2,8 @@
 
 use {
     crate::{
+        ed25519_instruction::verify_signatures,
+        ed25519_program,
         hash::Hash,
         message::{v0, MappedAddresses, MappedMessage, SanitizedMessage, VersionedMessage},
         nonce::NONCED_TX_MARKER_IX_INDEX,
@@ -202,11 +204,12 @@ impl SanitizedTransaction {
         }
     }
 
-    /// Verify the encoded secp256k1 signatures in this transaction
+    /// Verify the encoded secp256k1 and ed25519 signatures in this transaction
     pub fn verify_precompiles(
         &self,
         libsecp256k1_0_5_upgrade_enabled: bool,
         libsecp256k1_fail_on_bad_count: bool,
+        ed25519_program_enabled: bool,
     ) -> Result<()> {
         for (program_id, instruction) in self.message.program_instructions_iter() {
             if secp256k1_program::check_id(program_id) {
@@ -224,6 +227,15 @@ impl SanitizedTransaction {
                     libsecp256k1_fail_on_bad_count,
                 );
                 e.map_err(|_| TransactionError::InvalidAccountIndex)?;
+            } else if ed25519_program_enabled && ed25519_program::check_id(program_id) {
+                let instruction_datas: Vec<_> = self
+                    .message
+                    .instructions()
+                    .iter()
+                    .map(|instruction| instruction.data.as_ref())
+                    .collect();
+                verify_signatures(&instruction.data, &instruction_datas)
+                    .map_err(|_| TransactionError::InvalidAccountIndex)?;
             }
         }
         Ok(())
@@ -9,7 +9,12 @@ fn main() -> Result<(), std::io::Error> {
 
     tonic_build::configure()
         .build_client(true)
-        .build_server(false)
+        .build_server(true)
+        // The server is only used by the in-process emulator
+        .server_mod_attribute(
+            "google.bigtable.v2",
+            "#[cfg(any(test, feature = \"emulator\"))]",
+        )
         .format(true)
         .out_dir(&out_dir)
         .compile(
@@ -1081,3 +1081,348 @@ pub mod bigtable_client {
         }
     }
 }
+#[doc = r" Generated server implementations."]
+#[cfg(any(test, feature = "emulator"))]
+pub mod bigtable_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with BigtableServer."]
+    #[async_trait]
+    pub trait Bigtable: Send + Sync + 'static {
+        #[doc = "Server streaming response type for the ReadRows method."]
+        type ReadRowsStream: futures_core::Stream<Item = Result<super::ReadRowsResponse, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        #[doc = " Streams back the contents of all requested rows in key order, optionally"]
+        #[doc = " applying the same Reader filter to each. Depending on their size,"]
+        #[doc = " rows and cells may be broken up across multiple responses, but"]
+        #[doc = " atomicity of each row will still be preserved. See the"]
+        #[doc = " ReadRowsResponse documentation for details."]
+        async fn read_rows(
+            &self,
+            request: tonic::Request<super::ReadRowsRequest>,
+        ) -> Result<tonic::Response<Self::ReadRowsStream>, tonic::Status>;
+        #[doc = "Server streaming response type for the SampleRowKeys method."]
+        type SampleRowKeysStream: futures_core::Stream<Item = Result<super::SampleRowKeysResponse, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        #[doc = " Returns a sample of row keys in the table. The returned row keys will"]
+        #[doc = " delimit contiguous sections of the table of approximately equal size,"]
+        #[doc = " which can be used to break up the data for distributed tasks like"]
+        #[doc = " mapreduces."]
+        async fn sample_row_keys(
+            &self,
+            request: tonic::Request<super::SampleRowKeysRequest>,
+        ) -> Result<tonic::Response<Self::SampleRowKeysStream>, tonic::Status>;
+        #[doc = " Mutates a row atomically. Cells already present in the row are left"]
+        #[doc = " unchanged unless explicitly changed by `mutation`."]
+        async fn mutate_row(
+            &self,
+            request: tonic::Request<super::MutateRowRequest>,
+        ) -> Result<tonic::Response<super::MutateRowResponse>, tonic::Status>;
+        #[doc = "Server streaming response type for the MutateRows method."]
+        type MutateRowsStream: futures_core::Stream<Item = Result<super::MutateRowsResponse, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        #[doc = " Mutates multiple rows in a batch. Each individual row is mutated"]
+        #[doc = " atomically as in MutateRow, but the entire batch is not executed"]
+        #[doc = " atomically."]
+        async fn mutate_rows(
+            &self,
+            request: tonic::Request<super::MutateRowsRequest>,
+        ) -> Result<tonic::Response<Self::MutateRowsStream>, tonic::Status>;
+        #[doc = " Mutates a row atomically based on the output of a predicate Reader filter."]
+        async fn check_and_mutate_row(
+            &self,
+            request: tonic::Request<super::CheckAndMutateRowRequest>,
+        ) -> Result<tonic::Response<super::CheckAndMutateRowResponse>, tonic::Status>;
+        #[doc = " Modifies a row atomically on the server. The method reads the latest"]
+        #[doc = " existing timestamp and value from the specified columns and writes a new"]
+        #[doc = " entry based on pre-defined read/modify/write rules. The new value for the"]
+        #[doc = " timestamp is the greater of the existing timestamp or the current server"]
+        #[doc = " time. The method returns the new contents of all modified cells."]
+        async fn read_modify_write_row(
+            &self,
+            request: tonic::Request<super::ReadModifyWriteRowRequest>,
+        ) -> Result<tonic::Response<super::ReadModifyWriteRowResponse>, tonic::Status>;
+    }
+    #[doc = " Service for reading from and writing to existing Bigtable tables."]
+    #[derive(Debug)]
+    pub struct BigtableServer<T: Bigtable> {
+        inner: _Inner<T>,
+        accept_compression_encodings: (),
+        send_compression_encodings: (),
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Bigtable> BigtableServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for BigtableServer<T>
+    where
+        T: Bigtable,
+        B: Body + Send + Sync + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/google.bigtable.v2.Bigtable/ReadRows" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReadRowsSvc<T: Bigtable>(pub Arc<T>);
+                    impl<T: Bigtable> tonic::server::ServerStreamingService<super::ReadRowsRequest> for ReadRowsSvc<T> {
+                        type Response = super::ReadRowsResponse;
+                        type ResponseStream = T::ReadRowsStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReadRowsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).read_rows(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ReadRowsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.bigtable.v2.Bigtable/SampleRowKeys" => {
+                    #[allow(non_camel_case_types)]
+                    struct SampleRowKeysSvc<T: Bigtable>(pub Arc<T>);
+                    impl<T: Bigtable>
+                        tonic::server::ServerStreamingService<super::SampleRowKeysRequest>
+                        for SampleRowKeysSvc<T>
+                    {
+                        type Response = super::SampleRowKeysResponse;
+                        type ResponseStream = T::SampleRowKeysStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SampleRowKeysRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sample_row_keys(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SampleRowKeysSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.bigtable.v2.Bigtable/MutateRow" => {
+                    #[allow(non_camel_case_types)]
+                    struct MutateRowSvc<T: Bigtable>(pub Arc<T>);
+                    impl<T: Bigtable> tonic::server::UnaryService<super::MutateRowRequest> for MutateRowSvc<T> {
+                        type Response = super::MutateRowResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MutateRowRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).mutate_row(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MutateRowSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.bigtable.v2.Bigtable/MutateRows" => {
+                    #[allow(non_camel_case_types)]
+                    struct MutateRowsSvc<T: Bigtable>(pub Arc<T>);
+                    impl<T: Bigtable>
+                        tonic::server::ServerStreamingService<super::MutateRowsRequest>
+                        for MutateRowsSvc<T>
+                    {
+                        type Response = super::MutateRowsResponse;
+                        type ResponseStream = T::MutateRowsStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MutateRowsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).mutate_rows(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MutateRowsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.bigtable.v2.Bigtable/CheckAndMutateRow" => {
+                    #[allow(non_camel_case_types)]
+                    struct CheckAndMutateRowSvc<T: Bigtable>(pub Arc<T>);
+                    impl<T: Bigtable> tonic::server::UnaryService<super::CheckAndMutateRowRequest>
+                        for CheckAndMutateRowSvc<T>
+                    {
+                        type Response = super::CheckAndMutateRowResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CheckAndMutateRowRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).check_and_mutate_row(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CheckAndMutateRowSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/google.bigtable.v2.Bigtable/ReadModifyWriteRow" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReadModifyWriteRowSvc<T: Bigtable>(pub Arc<T>);
+                    impl<T: Bigtable> tonic::server::UnaryService<super::ReadModifyWriteRowRequest>
+                        for ReadModifyWriteRowSvc<T>
+                    {
+                        type Response = super::ReadModifyWriteRowResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReadModifyWriteRowRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).read_modify_write_row(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ReadModifyWriteRowSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Bigtable> Clone for BigtableServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Bigtable> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Bigtable> tonic::transport::NamedService for BigtableServer<T> {
+        const NAME: &'static str = "google.bigtable.v2.Bigtable";
+    }
+}
@@ -15,8 +15,8 @@ use {
     },
 };
 
-mod google {
-    mod rpc {
+pub(crate) mod google {
+    pub(crate) mod rpc {
         include!(concat!(
             env!("CARGO_MANIFEST_DIR"),
             concat!("/proto/google.rpc.rs")
@@ -31,7 +31,7 @@ mod google {
         }
     }
 }
-use google::bigtable::v2::*;
+pub(crate) use google::bigtable::v2::*;
 
 pub type RowKey = String;
 pub type RowData = Vec<(CellName, CellValue)>;
@@ -123,18 +123,7 @@ impl BigTableConnection {
         timeout: Option<Duration>,
     ) -> Result<Self> {
         match std::env::var("BIGTABLE_EMULATOR_HOST") {
-            Ok(endpoint) => {
-                info!("Connecting to bigtable emulator at {}", endpoint);
-
-                Ok(Self {
-                    access_token: None,
-                    channel: tonic::transport::Channel::from_shared(format!("http://{}", endpoint))
-                        .map_err(|err| Error::InvalidUri(endpoint, err.to_string()))?
-                        .connect_lazy()?,
-                    table_prefix: format!("projects/emulator/instances/{}/tables/", instance_name),
-                    timeout,
-                })
-            }
+            Ok(endpoint) => Self::new_for_emulator(instance_name, &endpoint, timeout),
 
             Err(_) => {
                 let access_token = AccessToken::new(if read_only {
@@ -179,6 +168,31 @@ impl BigTableConnection {
         }
     }
 
+    /// Establish a plaintext, unauthenticated connection to the BigTable emulator listening on
+    /// `endpoint` (a `host:port` pair, as found in `BIGTABLE_EMULATOR_HOST`).
+    pub fn new_for_emulator(
+        instance_name: &str,
+        endpoint: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Self> {
+        info!("Connecting to bigtable emulator at {}", endpoint);
+
+        let channel = tonic::transport::Channel::from_shared(format!("http://{}", endpoint))
+            .map_err(|err| Error::InvalidUri(endpoint.to_string(), err.to_string()))?;
+        let channel = if let Some(timeout) = timeout {
+            channel.timeout(timeout)
+        } else {
+            channel
+        };
+
+        Ok(Self {
+            access_token: None,
+            channel: channel.connect_lazy()?,
+            table_prefix: format!("projects/emulator/instances/{}/tables/", instance_name),
+            timeout,
+        })
+    }
+
     /// Create a new BigTable client.
     ///
     /// Clients require `&mut self`, due to `Tonic::transport::Channel` limitations, however
@@ -663,7 +677,12 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StoredConfirmedBlock;
+    use crate::{
+        bigtable_emulator::BigTableEmulator,
+        compression::{compress, CompressionMethod},
+        StoredConfirmedBlock,
+    };
+    use enum_iterator::IntoEnumIterator;
     use prost::Message;
     use solana_sdk::{hash::Hash, signature::Keypair, system_transaction};
     use solana_storage_proto::convert::generated;
@@ -689,6 +708,7 @@ mod tests {
                 pre_token_balances: Some(vec![]),
                 post_token_balances: Some(vec![]),
                 rewards: Some(vec![]),
+                return_data: None,
             }),
         };
         let block = ConfirmedBlock {
@@ -776,4 +796,113 @@ mod tests {
         >(&[("bin".to_string(), vec![1, 2, 3, 4])], "", "".to_string());
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_emulator_cells() {
+        let emulator = BigTableEmulator::start().await.unwrap();
+        let connection = BigTableConnection::new_for_emulator("test", emulator.endpoint(), None)
+            .expect("connect to emulator");
+        let mut bigtable = connection.client();
+
+        // Cells written by older uploaders may use any compression method
+        let keys: Vec<RowKey> = (0..4u64).map(|i| format!("{:016x}", i)).collect();
+        for (i, method) in CompressionMethod::into_enum_iter().enumerate() {
+            let data = compress(method, &bincode::serialize(&(i as u64)).unwrap()).unwrap();
+            bigtable
+                .put_row_data(
+                    "numbers",
+                    "x",
+                    &[(&keys[i], vec![("bin".to_string(), data)])],
+                )
+                .await
+                .unwrap();
+        }
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(
+                bigtable
+                    .get_bincode_cell::<u64>("numbers", key.clone())
+                    .await
+                    .unwrap(),
+                i as u64
+            );
+        }
+        assert!(matches!(
+            bigtable
+                .get_single_row_data("numbers", "missing".to_string())
+                .await,
+            Err(Error::RowNotFound)
+        ));
+
+        assert_eq!(
+            bigtable
+                .get_row_keys("numbers", Some(keys[1].clone()), None, 2)
+                .await
+                .unwrap(),
+            keys[1..3].to_vec()
+        );
+        assert_eq!(
+            bigtable
+                .get_row_keys("numbers", None, Some(keys[1].clone()), 0)
+                .await
+                .unwrap(),
+            keys[..2].to_vec()
+        );
+        let row_data = bigtable
+            .get_row_data("numbers", Some(keys[3].clone()), None, 0)
+            .await
+            .unwrap();
+        assert_eq!(row_data.len(), 1);
+        assert_eq!(row_data[0].0, keys[3]);
+        assert_eq!(row_data[0].1[0].0, "bin");
+
+        // Blocks uploaded before the switch to protobuf are still readable
+        let block = ConfirmedBlock {
+            transactions: vec![],
+            parent_slot: 1,
+            blockhash: Hash::default().to_string(),
+            previous_blockhash: Hash::default().to_string(),
+            rewards: vec![],
+            block_time: Some(1_234_567_890),
+            block_height: Some(2),
+        };
+        bigtable
+            .put_bincode_cells::<StoredConfirmedBlock>(
+                "blocks",
+                &[("bincode".to_string(), block.clone().into())],
+            )
+            .await
+            .unwrap();
+        bigtable
+            .put_protobuf_cells::<generated::ConfirmedBlock>(
+                "blocks",
+                &[("protobuf".to_string(), block.clone().into())],
+            )
+            .await
+            .unwrap();
+
+        match bigtable
+            .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
+                "blocks",
+                "bincode".to_string(),
+            )
+            .await
+            .unwrap()
+        {
+            CellData::Bincode(bincode_block) => assert_eq!(block, bincode_block.into()),
+            CellData::Protobuf(_) => panic!("expected CellData::Bincode"),
+        }
+        match bigtable
+            .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
+                "blocks",
+                "protobuf".to_string(),
+            )
+            .await
+            .unwrap()
+        {
+            CellData::Protobuf(protobuf_block) => {
+                assert_eq!(block, protobuf_block.try_into().unwrap())
+            }
+            CellData::Bincode(_) => panic!("expected CellData::Protobuf"),
+        }
+    }
 }
@@ -0,0 +1,335 @@
+//! An in-process stand-in for the Cloud BigTable emulator
+
+use {
+    crate::{
+        bigtable::{
+            bigtable_server::{Bigtable, BigtableServer},
+            google::rpc,
+            mutate_rows_request, mutate_rows_response, mutation,
+            read_rows_response::{cell_chunk::RowStatus, CellChunk},
+            row_filter, row_range, CheckAndMutateRowRequest, CheckAndMutateRowResponse,
+            MutateRowRequest, MutateRowResponse, MutateRowsRequest, MutateRowsResponse, Mutation,
+            ReadModifyWriteRowRequest, ReadModifyWriteRowResponse, ReadRowsRequest,
+            ReadRowsResponse, RowFilter, RowRange, RowSet, SampleRowKeysRequest,
+            SampleRowKeysResponse,
+        },
+        Result,
+    },
+    async_trait::async_trait,
+    log::*,
+    std::{
+        collections::{BTreeMap, HashMap},
+        sync::RwLock,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    tokio::{net::TcpListener, sync::oneshot},
+    tokio_stream::wrappers::TcpListenerStream,
+    tonic::{Request, Response, Status},
+};
+
+type ResponseStream<T> = futures::stream::Iter<std::vec::IntoIter<std::result::Result<T, Status>>>;
+
+#[derive(Clone)]
+struct Cell {
+    timestamp_micros: i64,
+    value: Vec<u8>,
+}
+
+// Cells are keyed by family name and column qualifier, and only the latest version of each
+// cell is kept
+type Row = BTreeMap<(String, Vec<u8>), Cell>;
+type Cells = Vec<((String, Vec<u8>), Cell)>;
+type Table = BTreeMap<Vec<u8>, Row>;
+
+#[derive(Default)]
+struct EmulatorService {
+    tables: RwLock<HashMap<String, Table>>,
+}
+
+fn now_micros() -> i64 {
+    // BigTable timestamps have millisecond granularity
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+        * 1000
+}
+
+fn row_in_range(row_key: &[u8], row_range: &RowRange) -> bool {
+    let after_start = match &row_range.start_key {
+        Some(row_range::StartKey::StartKeyClosed(key)) => key.is_empty() || row_key >= &key[..],
+        Some(row_range::StartKey::StartKeyOpen(key)) => key.is_empty() || row_key > &key[..],
+        None => true,
+    };
+    let before_end = match &row_range.end_key {
+        Some(row_range::EndKey::EndKeyClosed(key)) => key.is_empty() || row_key <= &key[..],
+        Some(row_range::EndKey::EndKeyOpen(key)) => key.is_empty() || row_key < &key[..],
+        None => true,
+    };
+    after_start && before_end
+}
+
+fn row_in_set(row_key: &[u8], row_set: &RowSet) -> bool {
+    (row_set.row_keys.is_empty() && row_set.row_ranges.is_empty())
+        || row_set.row_keys.iter().any(|key| key == row_key)
+        || row_set
+            .row_ranges
+            .iter()
+            .any(|row_range| row_in_range(row_key, row_range))
+}
+
+fn apply_filter(row_filter: &RowFilter, mut cells: Cells) -> std::result::Result<Cells, Status> {
+    match &row_filter.filter {
+        None | Some(row_filter::Filter::PassAllFilter(true)) => {}
+        Some(row_filter::Filter::Chain(chain)) => {
+            for row_filter in &chain.filters {
+                cells = apply_filter(row_filter, cells)?;
+            }
+        }
+        Some(row_filter::Filter::CellsPerColumnLimitFilter(limit)) if *limit > 0 => {}
+        Some(row_filter::Filter::CellsPerRowLimitFilter(limit)) if *limit > 0 => {
+            cells.truncate(*limit as usize);
+        }
+        Some(row_filter::Filter::StripValueTransformer(true)) => {
+            for (_, cell) in cells.iter_mut() {
+                cell.value = vec![];
+            }
+        }
+        Some(filter) => {
+            return Err(Status::unimplemented(format!(
+                "Unsupported row filter: {:?}",
+                filter
+            )));
+        }
+    }
+    Ok(cells)
+}
+
+fn mutate_row(
+    table: &mut Table,
+    row_key: Vec<u8>,
+    mutations: Vec<Mutation>,
+) -> std::result::Result<(), Status> {
+    if mutations.is_empty() {
+        return Err(Status::invalid_argument("No mutations provided"));
+    }
+
+    // Mutations of a row are applied atomically
+    let mut row = table.get(&row_key).cloned().unwrap_or_default();
+    for mutation in mutations {
+        match mutation.mutation {
+            Some(mutation::Mutation::SetCell(set_cell)) => {
+                let timestamp_micros = if set_cell.timestamp_micros == -1 {
+                    now_micros()
+                } else {
+                    set_cell.timestamp_micros
+                };
+                row.insert(
+                    (set_cell.family_name, set_cell.column_qualifier),
+                    Cell {
+                        timestamp_micros,
+                        value: set_cell.value,
+                    },
+                );
+            }
+            Some(mutation::Mutation::DeleteFromColumn(delete)) => {
+                row.remove(&(delete.family_name, delete.column_qualifier));
+            }
+            Some(mutation::Mutation::DeleteFromFamily(delete)) => {
+                row.retain(|(family_name, _), _| *family_name != delete.family_name);
+            }
+            Some(mutation::Mutation::DeleteFromRow(_)) => row.clear(),
+            None => return Err(Status::invalid_argument("Empty mutation")),
+        }
+    }
+
+    if row.is_empty() {
+        table.remove(&row_key);
+    } else {
+        table.insert(row_key, row);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Bigtable for EmulatorService {
+    type ReadRowsStream = ResponseStream<ReadRowsResponse>;
+
+    async fn read_rows(
+        &self,
+        request: Request<ReadRowsRequest>,
+    ) -> std::result::Result<Response<Self::ReadRowsStream>, Status> {
+        let request = request.into_inner();
+        let tables = self.tables.read().unwrap();
+
+        let mut responses = vec![];
+        for (row_key, row) in tables.get(&request.table_name).into_iter().flatten() {
+            if request.rows_limit > 0 && responses.len() as i64 >= request.rows_limit {
+                break;
+            }
+            if let Some(row_set) = &request.rows {
+                if !row_in_set(row_key, row_set) {
+                    continue;
+                }
+            }
+
+            let mut cells: Cells = row.clone().into_iter().collect();
+            if let Some(row_filter) = &request.filter {
+                cells = apply_filter(row_filter, cells)?;
+            }
+            if cells.is_empty() {
+                continue;
+            }
+
+            // Send each row in its own response, with one chunk per cell
+            let last_index = cells.len() - 1;
+            let chunks = cells
+                .into_iter()
+                .enumerate()
+                .map(|(i, ((family_name, qualifier), cell))| CellChunk {
+                    row_key: if i == 0 { row_key.clone() } else { vec![] },
+                    family_name: Some(family_name),
+                    qualifier: Some(qualifier),
+                    timestamp_micros: cell.timestamp_micros,
+                    value: cell.value,
+                    row_status: if i == last_index {
+                        Some(RowStatus::CommitRow(true))
+                    } else {
+                        None
+                    },
+                    ..CellChunk::default()
+                })
+                .collect();
+            responses.push(Ok(ReadRowsResponse {
+                chunks,
+                ..ReadRowsResponse::default()
+            }));
+        }
+        Ok(Response::new(futures::stream::iter(responses)))
+    }
+
+    type SampleRowKeysStream = ResponseStream<SampleRowKeysResponse>;
+
+    async fn sample_row_keys(
+        &self,
+        _request: Request<SampleRowKeysRequest>,
+    ) -> std::result::Result<Response<Self::SampleRowKeysStream>, Status> {
+        Err(Status::unimplemented("SampleRowKeys"))
+    }
+
+    async fn mutate_row(
+        &self,
+        request: Request<MutateRowRequest>,
+    ) -> std::result::Result<Response<MutateRowResponse>, Status> {
+        let request = request.into_inner();
+        let mut tables = self.tables.write().unwrap();
+        let table = tables.entry(request.table_name).or_default();
+
+        mutate_row(table, request.row_key, request.mutations)?;
+        Ok(Response::new(MutateRowResponse::default()))
+    }
+
+    type MutateRowsStream = ResponseStream<MutateRowsResponse>;
+
+    async fn mutate_rows(
+        &self,
+        request: Request<MutateRowsRequest>,
+    ) -> std::result::Result<Response<Self::MutateRowsStream>, Status> {
+        let request = request.into_inner();
+        let mut tables = self.tables.write().unwrap();
+        let table = tables.entry(request.table_name).or_default();
+
+        let entries = request
+            .entries
+            .into_iter()
+            .enumerate()
+            .map(
+                |(index, mutate_rows_request::Entry { row_key, mutations })| {
+                    let status = match mutate_row(table, row_key, mutations) {
+                        Ok(()) => rpc::Status::default(),
+                        Err(status) => rpc::Status {
+                            code: status.code() as i32,
+                            message: status.message().to_string(),
+                            details: vec![],
+                        },
+                    };
+                    mutate_rows_response::Entry {
+                        index: index as i64,
+                        status: Some(status),
+                    }
+                },
+            )
+            .collect();
+        Ok(Response::new(futures::stream::iter(vec![Ok(
+            MutateRowsResponse { entries },
+        )])))
+    }
+
+    async fn check_and_mutate_row(
+        &self,
+        _request: Request<CheckAndMutateRowRequest>,
+    ) -> std::result::Result<Response<CheckAndMutateRowResponse>, Status> {
+        Err(Status::unimplemented("CheckAndMutateRow"))
+    }
+
+    async fn read_modify_write_row(
+        &self,
+        _request: Request<ReadModifyWriteRowRequest>,
+    ) -> std::result::Result<Response<ReadModifyWriteRowResponse>, Status> {
+        Err(Status::unimplemented("ReadModifyWriteRow"))
+    }
+}
+
+/// A BigTable emulator that runs in the current process, keeping all tables in memory.
+///
+/// Only the parts of the data API that this crate uses are implemented: `ReadRows` with the
+/// row sets and filters issued by `BigTable`, plus `MutateRow` and `MutateRows`. Unlike the
+/// Cloud BigTable emulator, tables do not need to be created before they are written to.
+/// The emulator shuts down when dropped.
+pub struct BigTableEmulator {
+    endpoint: String,
+    exit_sender: Option<oneshot::Sender<()>>,
+}
+
+impl BigTableEmulator {
+    /// Start an emulator listening on an unused localhost port. Must be called from within a
+    /// tokio runtime, which will run the emulator.
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let endpoint = listener.local_addr()?.to_string();
+        let (exit_sender, exit_receiver) = oneshot::channel::<()>();
+
+        let server = tonic::transport::Server::builder()
+            .add_service(BigtableServer::new(EmulatorService::default()));
+        tokio::spawn(async move {
+            if let Err(err) = server
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                    exit_receiver.await.ok();
+                })
+                .await
+            {
+                warn!("BigTable emulator failed: {}", err);
+            }
+        });
+
+        info!("BigTable emulator listening on {}", endpoint);
+        Ok(Self {
+            endpoint,
+            exit_sender: Some(exit_sender),
+        })
+    }
+
+    /// The `host:port` the emulator is listening on, suitable for `BIGTABLE_EMULATOR_HOST` or
+    /// `LedgerStorage::new_for_emulator()`
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl Drop for BigTableEmulator {
+    fn drop(&mut self) {
+        if let Some(exit_sender) = self.exit_sender.take() {
+            let _ = exit_sender.send(());
+        }
+    }
+}
@@ -0,0 +1,622 @@
+//! Long-term ledger storage in a local directory, for deployments without access to BigTable.
+//!
+//! Each of the `blocks`, `tx` and `tx-by-addr` tables maintained by [`LedgerStorage`] is a
+//! subdirectory holding one file per row, named by the row key and compressed and encoded exactly
+//! like the BigTable cell. Rows are spread over nested directories named by a prefix of the row
+//! key, to keep directories small:
+//!
+//! * `blocks/<first 12 digits of the slot key>/<slot key>`
+//! * `tx/<first 2 characters of the signature>/<next 2 characters>/<signature>`
+//! * `tx-by-addr/<first 2 characters of the address>/<address>/<first 12 digits of the inverted
+//!   slot key>/<inverted slot key>`
+//!
+//! Directory and file names sort in the same order as the BigTable row keys, so that range queries
+//! behave identically. The slots of the stored blocks are also appended to `blocks.index`, as
+//! little-endian `u64`s, so that blocks are listed without walking the `blocks` table.
+//!
+//! [`LedgerStorage`]: crate::LedgerStorage
+
+use {
+    crate::{
+        collect_signatures_for_address,
+        compression::{compress_best, decompress},
+        find_transaction_in_block, index_confirmed_block, key_to_slot, slot_to_key, Error,
+        LedgerStorageAdapter, Result, TransactionInfo,
+    },
+    async_trait::async_trait,
+    log::*,
+    prost::Message,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
+    solana_storage_proto::convert::{generated, tx_by_addr},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransaction, ConfirmedTransactionStatusWithSignature,
+        TransactionByAddrInfo, TransactionStatus,
+    },
+    std::{
+        collections::BTreeSet,
+        convert::TryInto,
+        fs,
+        io::{self, Read, Seek, SeekFrom, Write},
+        mem::size_of,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, MutexGuard},
+    },
+};
+
+const TABLES: &[&str] = &["blocks", "tx", "tx-by-addr"];
+
+// File listing the slots of the stored blocks
+const SLOT_INDEX_FILE: &str = "blocks.index";
+
+// Length of the slot key prefix naming the directories of the `blocks` and `tx-by-addr` tables
+const SLOT_KEY_SHARD_LEN: usize = 12;
+
+// Suffix of partially written rows, which are ignored until they are renamed into place
+const TMP_SUFFIX: &str = ".tmp";
+
+// Slots read from the slot index file so far, and the length of the file they were read from
+#[derive(Debug, Default)]
+struct SlotIndex {
+    slots: BTreeSet<Slot>,
+    len: u64,
+}
+
+/// A [`LedgerStorageAdapter`] that keeps its tables in a local directory
+#[derive(Clone, Debug)]
+pub struct FileLedgerStorage {
+    path: PathBuf,
+    read_only: bool,
+    slot_index: Arc<Mutex<SlotIndex>>,
+}
+
+impl FileLedgerStorage {
+    /// Open the storage in the directory at `path`, which is created if it does not exist unless
+    /// `read_only` is set
+    pub fn new<P: AsRef<Path>>(path: P, read_only: bool) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if read_only {
+            if !path.is_dir() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Ledger storage not found: {}", path.display()),
+                )
+                .into());
+            }
+        } else {
+            for table in TABLES {
+                fs::create_dir_all(path.join(table))?;
+            }
+        }
+        Ok(Self {
+            path,
+            read_only,
+            slot_index: Arc::default(),
+        })
+    }
+
+    // Run the blocking file system operations of `f` off the async runtime's worker threads
+    async fn spawn_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Self) -> Result<T> + Send + 'static,
+    {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || f(storage))
+            .await
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+    }
+
+    fn row_path(&self, table: &str, key: &str) -> PathBuf {
+        let table_path = self.path.join(table);
+        match table {
+            "blocks" => table_path.join(shard(key, SLOT_KEY_SHARD_LEN)).join(key),
+            "tx-by-addr" => {
+                let (address, key) = key.split_at(key.find('/').unwrap_or(0));
+                let key = key.trim_start_matches('/');
+                Self::address_path(&table_path, address)
+                    .join(shard(key, SLOT_KEY_SHARD_LEN))
+                    .join(key)
+            }
+            _ => table_path
+                .join(shard(key, 2))
+                .join(shard(key.get(2..).unwrap_or_default(), 2))
+                .join(key),
+        }
+    }
+
+    fn address_path(tx_by_addr_path: &Path, address: &str) -> PathBuf {
+        tx_by_addr_path.join(shard(address, 2)).join(address)
+    }
+
+    // Sorted names of the rows, or the row directories, in `dir`
+    fn row_keys(dir: &Path) -> Result<Vec<String>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+        let mut keys = vec![];
+        for entry in entries {
+            if let Some(key) = entry?.file_name().to_str() {
+                if !key.ends_with(TMP_SUFFIX) {
+                    keys.push(key.to_string());
+                }
+            }
+        }
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    // Catch up with the slots appended to the slot index file since it was last read
+    fn slot_index(&self) -> Result<MutexGuard<'_, SlotIndex>> {
+        let mut slot_index = self.slot_index.lock().unwrap();
+        let mut file = match fs::File::open(self.path.join(SLOT_INDEX_FILE)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(slot_index),
+            Err(err) => return Err(err.into()),
+        };
+        file.seek(SeekFrom::Start(slot_index.len))?;
+        let mut buf = vec![];
+        file.read_to_end(&mut buf)?;
+        // A slot that is still being appended is picked up on the next read
+        let slots = buf.chunks_exact(size_of::<Slot>());
+        slot_index.len += (slots.len() * size_of::<Slot>()) as u64;
+        for slot in slots {
+            slot_index
+                .slots
+                .insert(Slot::from_le_bytes(slot.try_into().unwrap()));
+        }
+        Ok(slot_index)
+    }
+
+    fn append_to_slot_index(&self, slot: Slot) -> Result<()> {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path.join(SLOT_INDEX_FILE))?
+            .write_all(&slot.to_le_bytes())?;
+        Ok(())
+    }
+
+    fn read_row(&self, table: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.row_path(table, key)) {
+            Ok(data) => Ok(Some(decompress(&data)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Write the row to a temporary file first so that readers never observe a partial row
+    fn write_row(&self, table: &str, key: &str, data: &[u8]) -> Result<usize> {
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Ledger storage is read-only",
+            )
+            .into());
+        }
+        let path = self.row_path(table, key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = compress_best(data)?;
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(TMP_SUFFIX);
+        fs::write(&tmp_path, &data)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(data.len())
+    }
+
+    fn write_protobuf_row<T: Message>(&self, table: &str, key: &str, row: T) -> Result<usize> {
+        let mut buf = Vec::with_capacity(row.encoded_len());
+        row.encode(&mut buf).unwrap();
+        self.write_row(table, key, &buf)
+    }
+
+    fn decode_protobuf_row<T: Message + Default>(data: &[u8], table: &str, key: &str) -> Result<T> {
+        T::decode(data).map_err(|err| {
+            Error::ObjectCorrupt(format!("Failed to decode {}/{}: {}", table, key, err))
+        })
+    }
+
+    fn read_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        let key = slot_to_key(slot);
+        let data = self
+            .read_row("blocks", &key)?
+            .ok_or(Error::BlockNotFound(slot))?;
+        let block = Self::decode_protobuf_row::<generated::ConfirmedBlock>(&data, "blocks", &key)?;
+        block
+            .try_into()
+            .map_err(|_| Error::ObjectCorrupt(format!("blocks/{}", key)))
+    }
+
+    fn get_transaction_info(&self, signature: &Signature) -> Result<TransactionInfo> {
+        let key = signature.to_string();
+        let data = self.read_row("tx", &key)?.ok_or(Error::SignatureNotFound)?;
+        bincode::deserialize(&data)
+            .map_err(|err| Error::ObjectCorrupt(format!("Failed to decode tx/{}: {}", key, err)))
+    }
+
+    fn get_tx_by_addr_row(
+        &self,
+        address: &Pubkey,
+        key: &str,
+    ) -> Result<(Slot, Vec<TransactionByAddrInfo>)> {
+        let row_key = format!("{}/{}", address, key);
+        let slot = !key_to_slot(key).ok_or_else(|| {
+            Error::ObjectCorrupt(format!(
+                "Failed to convert key to slot: tx-by-addr/{}",
+                row_key
+            ))
+        })?;
+        let data = self
+            .read_row("tx-by-addr", &row_key)?
+            .ok_or_else(|| Error::ObjectCorrupt(format!("Missing row: tx-by-addr/{}", row_key)))?;
+        let tx_by_addr = Self::decode_protobuf_row::<tx_by_addr::TransactionByAddr>(
+            &data,
+            "tx-by-addr",
+            &row_key,
+        )?;
+        let tx_by_addr_infos = tx_by_addr.try_into().map_err(|err| {
+            Error::ObjectCorrupt(format!(
+                "Failed to deserialize: {}: tx-by-addr/{}",
+                err, row_key
+            ))
+        })?;
+        Ok((slot, tx_by_addr_infos))
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        let before = match before_signature {
+            None => (Slot::MAX, 0),
+            Some(before_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(before_signature)?;
+                (slot, index)
+            }
+        };
+        let until = match until_signature {
+            None => (0, u32::MAX),
+            Some(until_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(until_signature)?;
+                (slot, index)
+            }
+        };
+
+        // Rows are keyed by the inverted slot, so they sort in descending slot order. Only the
+        // directories overlapping the range are listed, and rows are read until `limit` is reached
+        let first_key = slot_to_key(!before.0);
+        let last_key = slot_to_key(!until.0);
+        let address_path = Self::address_path(&self.path.join("tx-by-addr"), &address.to_string());
+        let keys = Self::row_keys(&address_path)?
+            .into_iter()
+            .filter(|shard| {
+                shard.as_str() >= shard_of(&first_key) && shard.as_str() <= shard_of(&last_key)
+            })
+            .flat_map(|shard| match Self::row_keys(&address_path.join(shard)) {
+                Ok(keys) => keys
+                    .into_iter()
+                    .filter(|key| *key >= first_key && *key <= last_key)
+                    .map(Ok)
+                    .collect(),
+                Err(err) => vec![Err(err)],
+            });
+        let tx_by_addr_rows =
+            keys.map(|key| key.and_then(|key| self.get_tx_by_addr_row(address, &key)));
+
+        collect_signatures_for_address(tx_by_addr_rows, before, until, limit)
+    }
+
+    fn write_block(&self, slot: Slot, confirmed_block: ConfirmedBlock) -> Result<()> {
+        let mut bytes_written = 0;
+
+        let (tx_infos, by_addr) = index_confirmed_block(slot, &confirmed_block);
+        for (signature, tx_info) in tx_infos {
+            let data = bincode::serialize(&tx_info).unwrap();
+            bytes_written += self.write_row("tx", &signature.to_string(), &data)?;
+        }
+
+        for (address, tx_by_addr_infos) in by_addr {
+            bytes_written += self.write_protobuf_row(
+                "tx-by-addr",
+                &format!("{}/{}", address, slot_to_key(!slot)),
+                tx_by_addr::TransactionByAddr {
+                    tx_by_addrs: tx_by_addr_infos
+                        .into_iter()
+                        .map(|by_addr| by_addr.into())
+                        .collect(),
+                },
+            )?;
+        }
+
+        let num_transactions = confirmed_block.transactions.len();
+
+        // Store the block itself last, after all other metadata about the block has been
+        // successfully stored, so that partially stored blocks never become visible
+        bytes_written += self.write_protobuf_row::<generated::ConfirmedBlock>(
+            "blocks",
+            &slot_to_key(slot),
+            confirmed_block.into(),
+        )?;
+        self.append_to_slot_index(slot)?;
+        info!(
+            "stored block for slot {}: {} transactions, {} bytes",
+            slot, num_transactions, bytes_written
+        );
+
+        Ok(())
+    }
+}
+
+// The first `len` characters of a row key, naming the directory that holds the row
+fn shard(key: &str, len: usize) -> &str {
+    key.get(..len).unwrap_or(key)
+}
+
+fn shard_of(slot_key: &str) -> &str {
+    shard(slot_key, SLOT_KEY_SHARD_LEN)
+}
+
+#[async_trait]
+impl LedgerStorageAdapter for FileLedgerStorage {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        self.spawn_blocking(|storage| Ok(storage.slot_index()?.slots.iter().next().copied()))
+            .await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        self.spawn_blocking(move |storage| {
+            Ok(storage
+                .slot_index()?
+                .slots
+                .range(start_slot..)
+                .take(limit)
+                .copied()
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        self.spawn_blocking(move |storage| storage.read_block(slot))
+            .await
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        let signature = *signature;
+        self.spawn_blocking(move |storage| Ok(storage.get_transaction_info(&signature)?.into()))
+            .await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransaction>> {
+        let signature = *signature;
+        self.spawn_blocking(move |storage| {
+            let TransactionInfo { slot, index, .. } = storage.get_transaction_info(&signature)?;
+            let block = storage.read_block(slot)?;
+            Ok(find_transaction_in_block(&signature, slot, index, block))
+        })
+        .await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        let address = *address;
+        let before_signature = before_signature.copied();
+        let until_signature = until_signature.copied();
+        self.spawn_blocking(move |storage| {
+            storage.get_signatures_for_address(
+                &address,
+                before_signature.as_ref(),
+                until_signature.as_ref(),
+                limit,
+            )
+        })
+        .await
+    }
+
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: ConfirmedBlock,
+    ) -> Result<()> {
+        self.spawn_blocking(move |storage| storage.write_block(slot, confirmed_block))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_transaction,
+            transaction::TransactionError,
+        },
+        solana_transaction_status::{TransactionStatusMeta, TransactionWithStatusMeta},
+    };
+
+    fn create_block(
+        slot: Slot,
+        payer: &Keypair,
+        recipient: &Pubkey,
+        results: &[std::result::Result<(), TransactionError>],
+    ) -> ConfirmedBlock {
+        let transactions = results
+            .iter()
+            .enumerate()
+            .map(|(i, status)| TransactionWithStatusMeta {
+                transaction: system_transaction::transfer(
+                    payer,
+                    recipient,
+                    i as u64 + 1,
+                    Hash::new_unique(),
+                ),
+                meta: Some(TransactionStatusMeta {
+                    status: status.clone(),
+                    inner_instructions: Some(vec![]),
+                    log_messages: Some(vec![]),
+                    pre_token_balances: Some(vec![]),
+                    post_token_balances: Some(vec![]),
+                    rewards: Some(vec![]),
+                    ..TransactionStatusMeta::default()
+                }),
+            })
+            .collect();
+        ConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: slot.saturating_sub(1),
+            transactions,
+            rewards: vec![],
+            block_time: Some(slot as i64),
+            block_height: Some(slot),
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_file_ledger_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(FileLedgerStorage::new(dir.path().join("missing"), true).is_err());
+
+        let storage = FileLedgerStorage::new(dir.path(), false).unwrap();
+        // Opened before any block is stored, to check that it catches up with the slot index
+        let reader = FileLedgerStorage::new(dir.path(), true).unwrap();
+        assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+        assert_eq!(reader.get_first_available_block().await.unwrap(), None);
+        assert!(matches!(
+            storage.get_confirmed_block(5).await,
+            Err(Error::BlockNotFound(5))
+        ));
+
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let failure = Err(TransactionError::AccountNotFound);
+        let blocks = vec![
+            (5, create_block(5, &payer, &recipient, &[Ok(()), Ok(())])),
+            (
+                7,
+                create_block(7, &payer, &recipient, std::slice::from_ref(&failure)),
+            ),
+            (300, create_block(300, &payer, &recipient, &[Ok(())])),
+        ];
+        for (slot, block) in &blocks {
+            storage
+                .upload_confirmed_block(*slot, block.clone())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(5));
+        assert_eq!(
+            storage.get_confirmed_blocks(0, 0).await.unwrap(),
+            vec![5, 7, 300]
+        );
+        assert_eq!(storage.get_confirmed_blocks(6, 1).await.unwrap(), vec![7]);
+        assert_eq!(
+            reader.get_confirmed_blocks(0, 0).await.unwrap(),
+            vec![5, 7, 300]
+        );
+        assert!(dir
+            .path()
+            .join("blocks/000000000000/000000000000012c")
+            .is_file());
+        assert_eq!(storage.get_confirmed_block(7).await.unwrap(), blocks[1].1);
+
+        let signature = blocks[1].1.transactions[0].transaction.signatures[0];
+        let signature_key = signature.to_string();
+        assert!(dir
+            .path()
+            .join("tx")
+            .join(&signature_key[..2])
+            .join(&signature_key[2..4])
+            .join(&signature_key)
+            .is_file());
+        let status = storage.get_signature_status(&signature).await.unwrap();
+        assert_eq!(status.slot, 7);
+        assert_eq!(status.err, failure.clone().err());
+        let transaction = storage
+            .get_confirmed_transaction(&signature)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, 7);
+        assert_eq!(transaction.block_time, Some(7));
+        assert_eq!(transaction.transaction, blocks[1].1.transactions[0]);
+        assert!(matches!(
+            storage.get_signature_status(&Signature::default()).await,
+            Err(Error::SignatureNotFound)
+        ));
+
+        // Signatures are returned newest first
+        let signature_slots = |results: Vec<(ConfirmedTransactionStatusWithSignature, u32)>| {
+            results
+                .into_iter()
+                .map(|(status, index)| (status.signature, status.slot, index))
+                .collect::<Vec<_>>()
+        };
+        let signature_at =
+            |i: usize, j: usize| blocks[i].1.transactions[j].transaction.signatures[0];
+        let all = vec![
+            (signature_at(2, 0), 300, 0),
+            (signature_at(1, 0), 7, 0),
+            (signature_at(0, 1), 5, 1),
+            (signature_at(0, 0), 5, 0),
+        ];
+        assert_eq!(
+            signature_slots(
+                storage
+                    .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+                    .await
+                    .unwrap()
+            ),
+            all
+        );
+        assert_eq!(
+            signature_slots(
+                storage
+                    .get_confirmed_signatures_for_address(
+                        &payer.pubkey(),
+                        Some(&signature_at(2, 0)),
+                        Some(&signature_at(0, 0)),
+                        10
+                    )
+                    .await
+                    .unwrap()
+            ),
+            all[1..3].to_vec()
+        );
+        assert_eq!(
+            signature_slots(
+                storage
+                    .get_confirmed_signatures_for_address(&recipient, None, None, 2)
+                    .await
+                    .unwrap()
+            ),
+            all[..2].to_vec()
+        );
+
+        // A read-only handle sees the same data but can't store blocks
+        let storage = FileLedgerStorage::new(dir.path(), true).unwrap();
+        assert_eq!(storage.get_confirmed_block(300).await.unwrap(), blocks[2].1);
+        assert!(storage
+            .upload_confirmed_block(301, blocks[2].1.clone())
+            .await
+            .is_err());
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::integer_arithmetic)]
 use {
+    async_trait::async_trait,
     log::*,
     serde::{Deserialize, Serialize},
     solana_sdk::{
@@ -26,9 +27,16 @@ extern crate serde_derive;
 
 mod access_token;
 mod bigtable;
+#[cfg(any(test, feature = "emulator"))]
+mod bigtable_emulator;
 mod compression;
+mod file_storage;
 mod root_ca_certificate;
 
+#[cfg(feature = "emulator")]
+pub use crate::bigtable_emulator::BigTableEmulator;
+pub use crate::file_storage::FileLedgerStorage;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -45,6 +53,9 @@ pub enum Error {
 
     #[error("Signature not found")]
     SignatureNotFound,
+
+    #[error("Object is corrupt: {0}")]
+    ObjectCorrupt(String),
 }
 
 impl std::convert::From<bigtable::Error> for Error {
@@ -201,6 +212,7 @@ impl From<StoredConfirmedBlockTransactionStatusMeta> for TransactionStatusMeta {
             pre_token_balances: None,
             post_token_balances: None,
             rewards: None,
+            return_data: None,
         }
     }
 }
@@ -306,6 +318,178 @@ impl From<LegacyTransactionByAddrInfo> for TransactionByAddrInfo {
     }
 }
 
+// Extract the transaction at `index` of the block at `slot`, as located by the `tx` table
+fn find_transaction_in_block(
+    signature: &Signature,
+    slot: Slot,
+    index: u32,
+    block: ConfirmedBlock,
+) -> Option<ConfirmedTransaction> {
+    match block.transactions.into_iter().nth(index as usize) {
+        None => {
+            warn!("Transaction info for {} is corrupt", signature);
+            None
+        }
+        Some(bucket_block_transaction) => {
+            if bucket_block_transaction.transaction.signatures[0] != *signature {
+                warn!(
+                    "Transaction info or confirmed block for {} is corrupt",
+                    signature
+                );
+                None
+            } else {
+                Some(ConfirmedTransaction {
+                    slot,
+                    transaction: bucket_block_transaction,
+                    block_time: block.block_time,
+                })
+            }
+        }
+    }
+}
+
+// Collect the transactions of `tx-by-addr` rows, which are given in descending slot order, that
+// lie strictly between the `before` and `until` positions, each a slot and a transaction index
+// within the slot
+fn collect_signatures_for_address(
+    tx_by_addr_rows: impl IntoIterator<Item = Result<(Slot, Vec<TransactionByAddrInfo>)>>,
+    (first_slot, before_transaction_index): (Slot, u32),
+    (last_slot, until_transaction_index): (Slot, u32),
+    limit: usize,
+) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+    let mut infos = vec![];
+    for tx_by_addr_row in tx_by_addr_rows {
+        let (slot, mut tx_by_addr_infos) = tx_by_addr_row?;
+        tx_by_addr_infos.reverse();
+        for tx_by_addr_info in tx_by_addr_infos.into_iter() {
+            // Filter out records before `before_transaction_index`
+            if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
+                continue;
+            }
+            // Filter out records after `until_transaction_index`
+            if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
+                continue;
+            }
+            infos.push((
+                ConfirmedTransactionStatusWithSignature {
+                    signature: tx_by_addr_info.signature,
+                    slot,
+                    err: tx_by_addr_info.err,
+                    memo: tx_by_addr_info.memo,
+                    block_time: tx_by_addr_info.block_time,
+                },
+                tx_by_addr_info.index,
+            ));
+            // Respect limit
+            if infos.len() >= limit {
+                return Ok(infos);
+            }
+        }
+    }
+    Ok(infos)
+}
+
+type TransactionsByAddr<'a> = HashMap<&'a Pubkey, Vec<TransactionByAddrInfo>>;
+
+// Index the transactions of a block by signature and by address, as stored in the `tx` and
+// `tx-by-addr` tables
+fn index_confirmed_block(
+    slot: Slot,
+    confirmed_block: &ConfirmedBlock,
+) -> (Vec<(Signature, TransactionInfo)>, TransactionsByAddr<'_>) {
+    let mut by_addr: TransactionsByAddr = HashMap::new();
+
+    let mut tx_infos = vec![];
+    for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
+        let TransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
+        let err = meta.as_ref().and_then(|meta| meta.status.clone().err());
+        let index = index as u32;
+        let signature = transaction.signatures[0];
+        let memo = extract_and_fmt_memos(&transaction.message);
+
+        for address in &transaction.message.account_keys {
+            if !is_sysvar_id(address) {
+                by_addr
+                    .entry(address)
+                    .or_default()
+                    .push(TransactionByAddrInfo {
+                        signature,
+                        err: err.clone(),
+                        index,
+                        memo: memo.clone(),
+                        block_time: confirmed_block.block_time,
+                    });
+            }
+        }
+
+        tx_infos.push((
+            signature,
+            TransactionInfo {
+                slot,
+                index,
+                err,
+                memo,
+            },
+        ));
+    }
+    (tx_infos, by_addr)
+}
+
+/// Long-term storage of confirmed blocks and of the indexes needed to look up their transactions
+/// by signature and by address
+///
+/// Implemented by [`LedgerStorage`], which is backed by BigTable, and by [`FileLedgerStorage`],
+/// which keeps the same data in a local directory
+#[async_trait]
+pub trait LedgerStorageAdapter: Send + Sync {
+    /// Return the available slot that contains a block
+    async fn get_first_available_block(&self) -> Result<Option<Slot>>;
+
+    /// Fetch the next slots after the provided slot that contains a block
+    ///
+    /// start_slot: slot to start the search from (inclusive)
+    /// limit: stop after this many slots have been found; if limit==0, all records in the table
+    /// after start_slot will be read
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>>;
+
+    /// Fetch the confirmed block from the desired slot
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock>;
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus>;
+
+    /// Fetch a confirmed transaction
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransaction>>;
+
+    /// Get confirmed signatures for the provided address, in descending ledger order
+    ///
+    /// address: address to search for
+    /// before_signature: start with the first signature older than this one
+    /// until_signature: end with the last signature more recent than this one
+    /// limit: stop after this many signatures; if limit==0, all records in the table will be read
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    >;
+
+    /// Upload a new confirmed block and associated meta data
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: ConfirmedBlock,
+    ) -> Result<()>;
+}
+
 #[derive(Clone)]
 pub struct LedgerStorage {
     connection: bigtable::BigTableConnection,
@@ -318,6 +502,14 @@ impl LedgerStorage {
         Ok(Self { connection })
     }
 
+    /// Connect to the BigTable emulator listening on `endpoint`, such as a `BigTableEmulator`,
+    /// regardless of the `BIGTABLE_EMULATOR_HOST` environment variable
+    pub fn new_for_emulator(endpoint: &str, timeout: Option<std::time::Duration>) -> Result<Self> {
+        let connection =
+            bigtable::BigTableConnection::new_for_emulator("solana-ledger", endpoint, timeout)?;
+        Ok(Self { connection })
+    }
+
     /// Return the available slot that contains a block
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         let mut bigtable = self.connection.client();
@@ -392,27 +584,7 @@ impl LedgerStorage {
 
         // Load the block and return the transaction
         let block = self.get_confirmed_block(slot).await?;
-        match block.transactions.into_iter().nth(index as usize) {
-            None => {
-                warn!("Transaction info for {} is corrupt", signature);
-                Ok(None)
-            }
-            Some(bucket_block_transaction) => {
-                if bucket_block_transaction.transaction.signatures[0] != *signature {
-                    warn!(
-                        "Transaction info or confirmed block for {} is corrupt",
-                        signature
-                    );
-                    Ok(None)
-                } else {
-                    Ok(Some(ConfirmedTransaction {
-                        slot,
-                        transaction: bucket_block_transaction,
-                        block_time: block.block_time,
-                    }))
-                }
-            }
-        }
+        Ok(find_transaction_in_block(signature, slot, index, block))
     }
 
     /// Get confirmed signatures for the provided address, in descending ledger order
@@ -460,8 +632,6 @@ impl LedgerStorage {
             }
         };
 
-        let mut infos = vec![];
-
         let starting_slot_tx_len = bigtable
             .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
                 "tx-by-addr",
@@ -487,7 +657,7 @@ impl LedgerStorage {
             )
             .await?;
 
-        'outer: for (row_key, data) in tx_by_addr_data {
+        let tx_by_addr_rows = tx_by_addr_data.into_iter().map(|(row_key, data)| {
             let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
                 bigtable::Error::ObjectCorrupt(format!(
                     "Failed to convert key to slot: tx-by-addr/{}",
@@ -500,7 +670,7 @@ impl LedgerStorage {
                 tx_by_addr::TransactionByAddr,
             >(&data, "tx-by-addr", row_key.clone())?;
 
-            let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
+            let cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
                 bigtable::CellData::Bincode(tx_by_addr) => {
                     tx_by_addr.into_iter().map(|legacy| legacy.into()).collect()
                 }
@@ -514,34 +684,15 @@ impl LedgerStorage {
                     })?
                 }
             };
-
-            cell_data.reverse();
-            for tx_by_addr_info in cell_data.into_iter() {
-                // Filter out records before `before_transaction_index`
-                if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
-                    continue;
-                }
-                // Filter out records after `until_transaction_index`
-                if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
-                    continue;
-                }
-                infos.push((
-                    ConfirmedTransactionStatusWithSignature {
-                        signature: tx_by_addr_info.signature,
-                        slot,
-                        err: tx_by_addr_info.err,
-                        memo: tx_by_addr_info.memo,
-                        block_time: tx_by_addr_info.block_time,
-                    },
-                    tx_by_addr_info.index,
-                ));
-                // Respect limit
-                if infos.len() >= limit {
-                    break 'outer;
-                }
-            }
-        }
-        Ok(infos)
+            Ok((slot, cell_data))
+        });
+
+        collect_signatures_for_address(
+            tx_by_addr_rows,
+            (first_slot, before_transaction_index),
+            (last_slot, until_transaction_index),
+            limit,
+        )
     }
 
     // Upload a new confirmed block and associated meta data.
@@ -552,41 +703,11 @@ impl LedgerStorage {
     ) -> Result<()> {
         let mut bytes_written = 0;
 
-        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
-
-        let mut tx_cells = vec![];
-        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
-            let TransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
-            let err = meta.as_ref().and_then(|meta| meta.status.clone().err());
-            let index = index as u32;
-            let signature = transaction.signatures[0];
-            let memo = extract_and_fmt_memos(&transaction.message);
-
-            for address in &transaction.message.account_keys {
-                if !is_sysvar_id(address) {
-                    by_addr
-                        .entry(address)
-                        .or_default()
-                        .push(TransactionByAddrInfo {
-                            signature,
-                            err: err.clone(),
-                            index,
-                            memo: memo.clone(),
-                            block_time: confirmed_block.block_time,
-                        });
-                }
-            }
-
-            tx_cells.push((
-                signature.to_string(),
-                TransactionInfo {
-                    slot,
-                    index,
-                    err,
-                    memo,
-                },
-            ));
-        }
+        let (tx_infos, by_addr) = index_confirmed_block(slot, &confirmed_block);
+        let tx_cells: Vec<_> = tx_infos
+            .into_iter()
+            .map(|(signature, tx_info)| (signature.to_string(), tx_info))
+            .collect();
 
         let tx_by_addr_cells: Vec<_> = by_addr
             .into_iter()
@@ -639,6 +760,57 @@ impl LedgerStorage {
     }
 }
 
+#[async_trait]
+impl LedgerStorageAdapter for LedgerStorage {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        LedgerStorage::get_first_available_block(self).await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        LedgerStorage::get_confirmed_blocks(self, start_slot, limit).await
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        LedgerStorage::get_confirmed_block(self, slot).await
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        LedgerStorage::get_signature_status(self, signature).await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransaction>> {
+        LedgerStorage::get_confirmed_transaction(self, signature).await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        LedgerStorage::get_confirmed_signatures_for_address(
+            self,
+            address,
+            before_signature,
+            until_signature,
+            limit,
+        )
+        .await
+    }
+
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: ConfirmedBlock,
+    ) -> Result<()> {
+        LedgerStorage::upload_confirmed_block(self, slot, confirmed_block).await
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
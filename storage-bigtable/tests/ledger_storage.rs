@@ -0,0 +1,148 @@
+#![cfg(feature = "emulator")]
+
+use {
+    solana_sdk::{
+        clock::Slot,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        system_transaction,
+        transaction::TransactionError,
+    },
+    solana_storage_bigtable::{BigTableEmulator, Error, LedgerStorage},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature, TransactionStatusMeta,
+        TransactionWithStatusMeta,
+    },
+};
+
+fn create_block(
+    slot: Slot,
+    payer: &Keypair,
+    recipient: &Pubkey,
+    results: &[Result<(), TransactionError>],
+) -> ConfirmedBlock {
+    let transactions = results
+        .iter()
+        .enumerate()
+        .map(|(i, status)| TransactionWithStatusMeta {
+            transaction: system_transaction::transfer(
+                payer,
+                recipient,
+                i as u64 + 1,
+                Hash::new_unique(),
+            ),
+            meta: Some(TransactionStatusMeta {
+                status: status.clone(),
+                inner_instructions: Some(vec![]),
+                log_messages: Some(vec![]),
+                pre_token_balances: Some(vec![]),
+                post_token_balances: Some(vec![]),
+                rewards: Some(vec![]),
+                ..TransactionStatusMeta::default()
+            }),
+        })
+        .collect();
+    ConfirmedBlock {
+        previous_blockhash: Hash::default().to_string(),
+        blockhash: Hash::new_unique().to_string(),
+        parent_slot: slot.saturating_sub(1),
+        transactions,
+        rewards: vec![],
+        block_time: Some(slot as i64),
+        block_height: Some(slot),
+    }
+}
+
+#[tokio::test]
+async fn test_ledger_storage_with_emulator() {
+    let emulator = BigTableEmulator::start().await.unwrap();
+    let storage = LedgerStorage::new_for_emulator(emulator.endpoint(), None).unwrap();
+
+    assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+    assert!(matches!(
+        storage.get_confirmed_block(5).await,
+        Err(Error::BlockNotFound(5))
+    ));
+
+    let payer = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let failure = Err(TransactionError::AccountNotFound);
+    let blocks = vec![
+        (5, create_block(5, &payer, &recipient, &[Ok(()), Ok(())])),
+        (
+            7,
+            create_block(7, &payer, &recipient, std::slice::from_ref(&failure)),
+        ),
+        (300, create_block(300, &payer, &recipient, &[Ok(())])),
+    ];
+    for (slot, block) in &blocks {
+        storage
+            .upload_confirmed_block(*slot, block.clone())
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(storage.get_first_available_block().await.unwrap(), Some(5));
+    assert_eq!(
+        storage.get_confirmed_blocks(0, 0).await.unwrap(),
+        vec![5, 7, 300]
+    );
+    assert_eq!(storage.get_confirmed_blocks(6, 1).await.unwrap(), vec![7]);
+    assert_eq!(storage.get_confirmed_block(7).await.unwrap(), blocks[1].1);
+
+    let signature = blocks[1].1.transactions[0].transaction.signatures[0];
+    let status = storage.get_signature_status(&signature).await.unwrap();
+    assert_eq!(status.slot, 7);
+    assert_eq!(status.err, failure.clone().err());
+    let transaction = storage
+        .get_confirmed_transaction(&signature)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(transaction.slot, 7);
+    assert_eq!(transaction.block_time, Some(7));
+    assert_eq!(transaction.transaction, blocks[1].1.transactions[0]);
+    assert!(matches!(
+        storage.get_signature_status(&Signature::default()).await,
+        Err(Error::SignatureNotFound)
+    ));
+
+    // Signatures are returned newest first
+    let signature_slots = |results: Vec<(ConfirmedTransactionStatusWithSignature, u32)>| {
+        results
+            .into_iter()
+            .map(|(status, index)| (status.signature, status.slot, index))
+            .collect::<Vec<_>>()
+    };
+    let signature_at = |i: usize, j: usize| blocks[i].1.transactions[j].transaction.signatures[0];
+    let all = vec![
+        (signature_at(2, 0), 300, 0),
+        (signature_at(1, 0), 7, 0),
+        (signature_at(0, 1), 5, 1),
+        (signature_at(0, 0), 5, 0),
+    ];
+    assert_eq!(
+        signature_slots(
+            storage
+                .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+                .await
+                .unwrap()
+        ),
+        all
+    );
+    assert_eq!(
+        signature_slots(
+            storage
+                .get_confirmed_signatures_for_address(
+                    &payer.pubkey(),
+                    Some(&signature_at(2, 0)),
+                    Some(&signature_at(0, 0)),
+                    10
+                )
+                .await
+                .unwrap()
+        ),
+        all[1..3].to_vec()
+    );
+}
@@ -0,0 +1,193 @@
+use {
+    solana_sdk::{message::Message, pubkey::Pubkey},
+    std::str::FromStr,
+};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Data logged by a program with `sol_log_data`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogData {
+    /// The program that logged the data, which may have been invoked by the instruction's
+    /// program
+    pub program_id: Pubkey,
+    pub fields: Vec<Vec<u8>>,
+}
+
+/// Extract the data logged with `sol_log_data` from a transaction's log messages, grouped by
+/// the index of the top-level instruction that was executing when it was logged.
+///
+/// The returned vector has one entry for each instruction of `message`. Programs that do not
+/// log their invocation, such as the signature verification programs, cannot log data either,
+/// so their instructions are simply left empty. Data that was logged after the log messages were
+/// truncated is lost.
+pub fn extract_log_data(message: &Message, log_messages: &[String]) -> Vec<Vec<LogData>> {
+    let mut log_data = vec![vec![]; message.instructions.len()];
+    let mut next_instruction_index = 0;
+    let mut instruction_index: Option<usize> = None;
+    let mut invoke_stack = vec![];
+
+    for log_message in log_messages {
+        if let Some(fields) = log_message.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if let (Some(instruction_index), Some(program_id)) =
+                (instruction_index, invoke_stack.last())
+            {
+                if let Some(fields) = parse_fields(fields) {
+                    log_data[instruction_index].push(LogData {
+                        program_id: *program_id,
+                        fields,
+                    });
+                }
+            }
+        } else if let Some((program_id, depth)) = parse_invoke(log_message) {
+            if depth == 1 {
+                invoke_stack.clear();
+                instruction_index = message.instructions[next_instruction_index..]
+                    .iter()
+                    .position(|instruction| {
+                        message
+                            .account_keys
+                            .get(instruction.program_id_index as usize)
+                            == Some(&program_id)
+                    })
+                    .map(|position| next_instruction_index + position);
+                if let Some(instruction_index) = instruction_index {
+                    next_instruction_index = instruction_index + 1;
+                }
+            }
+            invoke_stack.push(program_id);
+        } else if is_program_result(log_message) {
+            invoke_stack.pop();
+        }
+    }
+    log_data
+}
+
+fn parse_fields(fields: &str) -> Option<Vec<Vec<u8>>> {
+    if fields.is_empty() {
+        return Some(vec![]);
+    }
+    fields
+        .split(' ')
+        .map(|field| base64::decode(field).ok())
+        .collect()
+}
+
+// Parses "Program <address> invoke [<depth>]"
+fn parse_invoke(log_message: &str) -> Option<(Pubkey, usize)> {
+    let mut words = log_message.strip_prefix("Program ")?.split(' ');
+    let program_id = Pubkey::from_str(words.next()?).ok()?;
+    if words.next()? != "invoke" {
+        return None;
+    }
+    let depth = words
+        .next()?
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .parse()
+        .ok()?;
+    Some((program_id, depth))
+}
+
+// Matches "Program <address> success" and "Program <address> failed: <error>"
+fn is_program_result(log_message: &str) -> bool {
+    log_message
+        .strip_prefix("Program ")
+        .and_then(|rest| rest.split_once(' '))
+        .map(|(program_id, result)| {
+            Pubkey::from_str(program_id).is_ok()
+                && (result == "success" || result.starts_with("failed: "))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_sdk::{instruction::CompiledInstruction, secp256k1_program},
+    };
+
+    #[test]
+    fn test_extract_log_data() {
+        let program_a = Pubkey::new_unique();
+        let program_b = Pubkey::new_unique();
+        let instruction = |program_id_index| CompiledInstruction {
+            program_id_index,
+            accounts: vec![],
+            data: vec![],
+        };
+        let message = Message {
+            account_keys: vec![
+                Pubkey::new_unique(),
+                program_a,
+                secp256k1_program::id(),
+                program_b,
+            ],
+            instructions: vec![
+                instruction(1),
+                instruction(2),
+                instruction(3),
+                instruction(1),
+            ],
+            ..Message::default()
+        };
+        let log_messages: Vec<String> = vec![
+            format!("Program {} invoke [1]", program_a),
+            "Program data: AQI= AwQF".to_string(),
+            format!("Program {} invoke [2]", program_b),
+            "Program log: Program data: AAAA".to_string(),
+            "Program data: ".to_string(),
+            format!("Program {} success", program_b),
+            "Program data: Bg==".to_string(),
+            format!("Program {} success", program_a),
+            // The secp256k1 instruction doesn't log anything
+            format!("Program {} invoke [1]", program_b),
+            "Program data: not-base64".to_string(),
+            format!("Program {} failed: custom program error: 0x0", program_b),
+            format!("Program {} invoke [1]", program_a),
+            "Program data: Bw==".to_string(),
+            format!("Program {} success", program_a),
+        ];
+
+        assert_eq!(
+            extract_log_data(&message, &log_messages),
+            vec![
+                vec![
+                    LogData {
+                        program_id: program_a,
+                        fields: vec![vec![1, 2], vec![3, 4, 5]],
+                    },
+                    LogData {
+                        program_id: program_b,
+                        fields: vec![],
+                    },
+                    LogData {
+                        program_id: program_a,
+                        fields: vec![vec![6]],
+                    },
+                ],
+                vec![],
+                vec![],
+                vec![LogData {
+                    program_id: program_a,
+                    fields: vec![vec![7]],
+                }],
+            ]
+        );
+
+        // Truncated logs
+        assert_eq!(
+            extract_log_data(&message, &log_messages[..2]),
+            vec![
+                vec![LogData {
+                    program_id: program_a,
+                    fields: vec![vec![1, 2], vec![3, 4, 5]],
+                }],
+                vec![],
+                vec![],
+                vec![],
+            ]
+        );
+    }
+}
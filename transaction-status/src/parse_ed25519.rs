@@ -0,0 +1,142 @@
+use crate::parse_instruction::{ParsableProgram, ParseInstructionError, ParsedInstructionEnum};
+use serde_json::{json, Value};
+use solana_sdk::{
+    ed25519_instruction::{
+        get_signature_offsets, CURRENT_INSTRUCTION_INDEX, PUBKEY_SERIALIZED_SIZE,
+        SIGNATURE_SERIALIZED_SIZE,
+    },
+    instruction::CompiledInstruction,
+};
+
+pub fn parse_ed25519(
+    instruction: &CompiledInstruction,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let signature_offsets = get_signature_offsets(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Ed25519))?;
+    let signatures = signature_offsets
+        .iter()
+        .map(|offsets| {
+            Ok(json!({
+                "publicKey": parse_data(
+                    &instruction.data,
+                    offsets.public_key_instruction_index,
+                    offsets.public_key_offset,
+                    PUBKEY_SERIALIZED_SIZE,
+                    |data| bs58::encode(data).into_string(),
+                )?,
+                "signature": parse_data(
+                    &instruction.data,
+                    offsets.signature_instruction_index,
+                    offsets.signature_offset,
+                    SIGNATURE_SERIALIZED_SIZE,
+                    |data| bs58::encode(data).into_string(),
+                )?,
+                "message": parse_data(
+                    &instruction.data,
+                    offsets.message_instruction_index,
+                    offsets.message_data_offset,
+                    offsets.message_data_size as usize,
+                    |data| base64::encode(data),
+                )?,
+            }))
+        })
+        .collect::<Result<Vec<_>, ParseInstructionError>>()?;
+    Ok(ParsedInstructionEnum {
+        instruction_type: "verify".to_string(),
+        info: json!({ "signatures": signatures }),
+    })
+}
+
+// Data held by the ed25519 instruction itself is decoded; data held by another instruction of the
+// transaction is described by its location
+fn parse_data(
+    data: &[u8],
+    instruction_index: u16,
+    offset: u16,
+    size: usize,
+    encode: impl Fn(&[u8]) -> String,
+) -> Result<Value, ParseInstructionError> {
+    if instruction_index != CURRENT_INSTRUCTION_INDEX {
+        return Ok(json!({
+            "instructionIndex": instruction_index,
+            "offset": offset,
+            "size": size,
+        }));
+    }
+    let start = offset as usize;
+    data.get(start..start.saturating_add(size))
+        .map(|data| Value::String(encode(data)))
+        .ok_or(ParseInstructionError::InstructionNotParsable(
+            ParsableProgram::Ed25519,
+        ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::thread_rng;
+    use solana_sdk::ed25519_instruction::{new_ed25519_instruction, Ed25519SignatureOffsets};
+
+    #[test]
+    fn test_parse_ed25519_instruction() {
+        let keypair = ed25519_dalek::Keypair::generate(&mut thread_rng());
+        let message = b"hello";
+        let instruction = new_ed25519_instruction(&keypair, message);
+        let compiled_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: instruction.data,
+        };
+        let signature = ed25519_dalek::Signer::sign(&keypair, message);
+        assert_eq!(
+            parse_ed25519(&compiled_instruction).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "verify".to_string(),
+                info: json!({
+                    "signatures": [{
+                        "publicKey": bs58::encode(keypair.public.as_bytes()).into_string(),
+                        "signature": bs58::encode(signature.to_bytes()).into_string(),
+                        "message": base64::encode(message),
+                    }],
+                }),
+            }
+        );
+
+        let offsets = Ed25519SignatureOffsets {
+            signature_offset: 10,
+            signature_instruction_index: 1,
+            public_key_offset: 74,
+            public_key_instruction_index: 1,
+            message_data_offset: 106,
+            message_data_size: 5,
+            message_instruction_index: 2,
+        };
+        let mut data = vec![1, 0];
+        data.extend_from_slice(&bincode::serialize(&offsets).unwrap());
+        let compiled_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data,
+        };
+        assert_eq!(
+            parse_ed25519(&compiled_instruction).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "verify".to_string(),
+                info: json!({
+                    "signatures": [{
+                        "publicKey": {"instructionIndex": 1, "offset": 74, "size": 32},
+                        "signature": {"instructionIndex": 1, "offset": 10, "size": 64},
+                        "message": {"instructionIndex": 2, "offset": 106, "size": 5},
+                    }],
+                }),
+            }
+        );
+
+        let bad_compiled_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: vec![1, 0, 0],
+        };
+        assert!(parse_ed25519(&bad_compiled_instruction).is_err());
+    }
+}
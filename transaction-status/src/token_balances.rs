@@ -1,9 +1,14 @@
 use crate::TransactionTokenBalance;
 use solana_account_decoder::parse_token::{
-    spl_token_id_v2_0, spl_token_v2_0_native_mint, token_amount_to_ui_amount, UiTokenAmount,
+    pubkey_from_spl_token_v2_0, spl_token_id_v2_0, spl_token_v2_0_native_mint,
+    token_amount_to_ui_amount, UiTokenAmount,
 };
 use solana_runtime::{bank::Bank, transaction_batch::TransactionBatch};
-use solana_sdk::{account::ReadableAccount, pubkey::Pubkey};
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount},
+    message::SanitizedMessage,
+    pubkey::Pubkey,
+};
 use spl_token_v2_0::{
     solana_program::program_pack::Pack,
     state::{Account as TokenAccount, Mint},
@@ -39,51 +44,92 @@ fn get_mint_decimals(bank: &Bank, mint: &Pubkey) -> Option<u8> {
         Some(spl_token_v2_0::native_mint::DECIMALS)
     } else {
         let mint_account = bank.get_account(mint)?;
+        get_mint_decimals_from_data(mint_account.data())
+    }
+}
+
+fn get_mint_decimals_from_data(data: &[u8]) -> Option<u8> {
+    Mint::unpack(data).map(|mint| mint.decimals).ok()
+}
 
-        let decimals = Mint::unpack(mint_account.data())
-            .map(|mint| mint.decimals)
-            .ok()?;
+fn collect_message_token_balances<F>(
+    message: &SanitizedMessage,
+    mut collect_token_balance: F,
+) -> Vec<TransactionTokenBalance>
+where
+    F: FnMut(&Pubkey) -> Option<(String, UiTokenAmount)>,
+{
+    let has_token_program = message.account_keys_iter().any(is_token_program);
 
-        Some(decimals)
+    let mut transaction_balances: Vec<TransactionTokenBalance> = vec![];
+    if has_token_program {
+        for (index, account_id) in message.account_keys_iter().enumerate() {
+            if message.is_invoked(index) || is_token_program(account_id) {
+                continue;
+            }
+
+            if let Some((mint, ui_token_amount)) = collect_token_balance(account_id) {
+                transaction_balances.push(TransactionTokenBalance {
+                    account_index: index as u8,
+                    mint,
+                    ui_token_amount,
+                });
+            }
+        }
     }
+    transaction_balances
 }
 
 pub fn collect_token_balances(
     bank: &Bank,
     batch: &TransactionBatch,
-    mut mint_decimals: &mut HashMap<Pubkey, u8>,
+    mint_decimals: &mut HashMap<Pubkey, u8>,
 ) -> TransactionTokenBalances {
     let mut balances: TransactionTokenBalances = vec![];
 
     for transaction in batch.sanitized_transactions() {
-        let has_token_program = transaction
-            .message()
-            .account_keys_iter()
-            .any(|p| is_token_program(p));
-
-        let mut transaction_balances: Vec<TransactionTokenBalance> = vec![];
-        if has_token_program {
-            for (index, account_id) in transaction.message().account_keys_iter().enumerate() {
-                if transaction.message().is_invoked(index) || is_token_program(account_id) {
-                    continue;
-                }
-
-                if let Some((mint, ui_token_amount)) =
-                    collect_token_balance_from_account(bank, account_id, &mut mint_decimals)
-                {
-                    transaction_balances.push(TransactionTokenBalance {
-                        account_index: index as u8,
-                        mint,
-                        ui_token_amount,
-                    });
-                }
-            }
-        }
-        balances.push(transaction_balances);
+        balances.push(collect_message_token_balances(
+            transaction.message(),
+            |account_id| collect_token_balance_from_account(bank, account_id, mint_decimals),
+        ));
     }
     balances
 }
 
+/// Collect the token balances of a simulated transaction. Pre-balances are read from `bank`,
+/// post-balances from the accounts the simulation left behind, which are never committed.
+pub fn collect_simulated_token_balances(
+    bank: &Bank,
+    message: &SanitizedMessage,
+    post_simulation_accounts: &[(Pubkey, AccountSharedData)],
+) -> (Vec<TransactionTokenBalance>, Vec<TransactionTokenBalance>) {
+    let mut mint_decimals = HashMap::new();
+    let pre_token_balances = collect_message_token_balances(message, |account_id| {
+        collect_token_balance_from_account(bank, account_id, &mut mint_decimals)
+    });
+
+    let post_simulation_accounts: HashMap<&Pubkey, &AccountSharedData> = post_simulation_accounts
+        .iter()
+        .map(|(pubkey, account)| (pubkey, account))
+        .collect();
+    let post_token_balances = collect_message_token_balances(message, |account_id| {
+        let account = post_simulation_accounts.get(account_id)?;
+        let token_account = TokenAccount::unpack(account.data()).ok()?;
+        let mint = pubkey_from_spl_token_v2_0(&token_account.mint);
+        // A mint written by the simulated transaction takes precedence over the bank's copy
+        let decimals = match post_simulation_accounts.get(&mint) {
+            Some(mint_account) => get_mint_decimals_from_data(mint_account.data()),
+            None => get_mint_decimals(bank, &mint),
+        }?;
+        Some((
+            mint.to_string(),
+            token_amount_to_ui_amount(token_account.amount, decimals),
+        ))
+    });
+
+    (pre_token_balances, post_token_balances)
+}
+
 pub fn collect_token_balance_from_account(
     bank: &Bank,
     account_id: &Pubkey,
@@ -106,3 +152,118 @@ pub fn collect_token_balance_from_account(
         token_amount_to_ui_amount(token_account.amount, decimals),
     ))
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_runtime::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_sdk::{
+            account::Account,
+            instruction::{AccountMeta, Instruction},
+            message::Message,
+        },
+        spl_token_v2_0::{solana_program::program_option::COption, state::AccountState},
+    };
+
+    fn token_account(mint: &Pubkey, amount: u64) -> AccountSharedData {
+        let mut data = vec![0; TokenAccount::get_packed_len()];
+        TokenAccount::pack(
+            TokenAccount {
+                mint: spl_token_v2_0::solana_program::pubkey::Pubkey::new(mint.as_ref()),
+                amount,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        AccountSharedData::from(Account {
+            lamports: 1,
+            data,
+            owner: spl_token_id_v2_0(),
+            ..Account::default()
+        })
+    }
+
+    fn mint_account(decimals: u8) -> AccountSharedData {
+        let mut data = vec![0; Mint::get_packed_len()];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::None,
+                supply: 1_000,
+                decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+        AccountSharedData::from(Account {
+            lamports: 1,
+            data,
+            owner: spl_token_id_v2_0(),
+            ..Account::default()
+        })
+    }
+
+    #[test]
+    fn test_collect_simulated_token_balances() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+
+        let mint = Pubkey::new_unique();
+        let new_mint = Pubkey::new_unique();
+        let token_account_pubkey = Pubkey::new_unique();
+        let new_token_account_pubkey = Pubkey::new_unique();
+        bank.store_account(&mint, &mint_account(2));
+        bank.store_account(&token_account_pubkey, &token_account(&mint, 420));
+
+        let payer = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(
+            spl_token_id_v2_0(),
+            &[],
+            vec![
+                AccountMeta::new(token_account_pubkey, false),
+                AccountMeta::new(new_token_account_pubkey, false),
+                AccountMeta::new(new_mint, false),
+            ],
+        );
+        let message = SanitizedMessage::Legacy(Message::new(&[instruction], Some(&payer)));
+
+        // The simulation debits the existing token account and creates a token account for a
+        // mint that only exists in the simulation
+        let post_simulation_accounts = vec![
+            (payer, AccountSharedData::default()),
+            (token_account_pubkey, token_account(&mint, 400)),
+            (new_token_account_pubkey, token_account(&new_mint, 7)),
+            (new_mint, mint_account(0)),
+        ];
+
+        let (pre_token_balances, post_token_balances) =
+            collect_simulated_token_balances(&bank, &message, &post_simulation_accounts);
+        assert_eq!(
+            pre_token_balances,
+            vec![TransactionTokenBalance {
+                account_index: 1,
+                mint: mint.to_string(),
+                ui_token_amount: token_amount_to_ui_amount(420, 2),
+            }]
+        );
+        assert_eq!(
+            post_token_balances,
+            vec![
+                TransactionTokenBalance {
+                    account_index: 1,
+                    mint: mint.to_string(),
+                    ui_token_amount: token_amount_to_ui_amount(400, 2),
+                },
+                TransactionTokenBalance {
+                    account_index: 2,
+                    mint: new_mint.to_string(),
+                    ui_token_amount: token_amount_to_ui_amount(7, 0),
+                },
+            ]
+        );
+    }
+}
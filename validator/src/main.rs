@@ -11,7 +11,7 @@ use {
         input_parsers::{keypair_of, keypairs_of, pubkey_of, value_of},
         input_validators::{
             is_bin, is_keypair, is_keypair_or_ask_keyword, is_parsable, is_pubkey,
-            is_pubkey_or_keypair, is_slot,
+            is_pubkey_or_keypair, is_slot, is_url,
         },
         keypair::SKIP_SEED_PHRASE_VALIDATION_ARG,
     },
@@ -21,24 +21,33 @@ use {
     },
     solana_core::{
         ledger_cleanup_service::{DEFAULT_MAX_LEDGER_SHREDS, DEFAULT_MIN_MAX_LEDGER_SHREDS},
+        snapshot_uploader::{HttpSnapshotUploader, S3Credentials},
         tower_storage,
         tpu::DEFAULT_TPU_COALESCE_MS,
         validator::{
             is_snapshot_config_invalid, Validator, ValidatorConfig, ValidatorStartProgress,
         },
     },
-    solana_download_utils::{download_snapshot, DownloadProgressRecord},
+    solana_download_utils::{
+        download_snapshot, download_snapshot_archives_from_url,
+        get_latest_snapshot_archives_from_url, DownloadProgressRecord,
+    },
     solana_genesis_utils::download_then_check_genesis_hash,
     solana_gossip::{
         cluster_info::{ClusterInfo, Node, VALIDATOR_PORT_RANGE},
         contact_info::ContactInfo,
         gossip_service::GossipService,
     },
-    solana_ledger::blockstore_db::BlockstoreRecoveryMode,
+    solana_ledger::{account_history::AccountHistoryConfig, blockstore_db::BlockstoreRecoveryMode},
     solana_perf::recycler::enable_recycler_warming,
     solana_poh::poh_service,
     solana_replica_lib::accountsdb_repl_server::AccountsDbReplServiceConfig,
-    solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
+    solana_rpc::{
+        rpc::JsonRpcConfig,
+        rpc_access_log::RpcAccessLogConfig,
+        rpc_pubsub_service::PubSubConfig,
+        rpc_rate_limiter::{RateLimit, RpcRateLimitConfig},
+    },
     solana_runtime::{
         accounts_db::{
             AccountShrinkThreshold, DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE,
@@ -46,7 +55,7 @@ use {
         },
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
-            AccountsIndexConfig,
+            AccountsIndexConfig, CustomAccountIndex,
         },
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
         snapshot_archive_info::SnapshotArchiveInfoGetter,
@@ -56,10 +65,11 @@ use {
         },
     },
     solana_sdk::{
+        bs58,
         clock::{Slot, DEFAULT_S_PER_SLOT},
         commitment_config::CommitmentConfig,
         hash::Hash,
-        pubkey::Pubkey,
+        pubkey::{Pubkey, PUBKEY_BYTES},
         signature::{Keypair, Signer},
     },
     solana_streamer::socket::SocketAddrSpace,
@@ -68,7 +78,7 @@ use {
         redirect_stderr_to_file,
     },
     std::{
-        collections::{HashSet, VecDeque},
+        collections::{HashMap, HashSet, VecDeque},
         env,
         fs::{self, File},
         net::{IpAddr, SocketAddr, TcpListener, UdpSocket},
@@ -92,6 +102,8 @@ enum Operation {
 
 const EXCLUDE_KEY: &str = "account-index-exclude-key";
 const INCLUDE_KEY: &str = "account-index-include-key";
+const CUSTOM_EXCLUDE_KEY: &str = "account-index-custom-exclude-key";
+const CUSTOM_INCLUDE_KEY: &str = "account-index-custom-include-key";
 // The default minimal snapshot download speed (bytes/second)
 const DEFAULT_MIN_SNAPSHOT_DOWNLOAD_SPEED: u64 = 10485760;
 // The maximum times of snapshot download abort and retry
@@ -337,6 +349,72 @@ fn hash_validator(hash: String) -> Result<(), String> {
         .map_err(|e| format!("{:?}", e))
 }
 
+// Validates `METHOD=VALUE`, where VALUE parses as `T`
+fn method_value_validator<T>(value: String) -> Result<(), String>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let (method, value) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected METHOD=VALUE, got {}", value))?;
+    if method.is_empty() {
+        return Err("Missing RPC method name".to_string());
+    }
+    value
+        .parse::<T>()
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+// Parses `PROGRAM_ID:OFFSET:LENGTH`
+fn parse_custom_account_index(value: &str) -> Result<CustomAccountIndex, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let (program_id, offset, length) = match parts[..] {
+        [program_id, offset, length] => (program_id, offset, length),
+        _ => return Err(format!("Expected PROGRAM_ID:OFFSET:LENGTH, got {}", value)),
+    };
+    let program_id = Pubkey::from_str(program_id)
+        .map_err(|err| format!("Invalid program id {}: {}", program_id, err))?;
+    let offset = offset
+        .parse::<usize>()
+        .map_err(|err| format!("Invalid offset {}: {}", offset, err))?;
+    let length = length
+        .parse::<usize>()
+        .map_err(|err| format!("Invalid length {}: {}", length, err))?;
+    if length == 0 || length > PUBKEY_BYTES {
+        return Err(format!(
+            "Invalid length {}: must be between 1 and {}",
+            length, PUBKEY_BYTES
+        ));
+    }
+    Ok(CustomAccountIndex {
+        program_id,
+        offset,
+        length,
+    })
+}
+
+// Parses `PROGRAM_ID:OFFSET:LENGTH:KEY`, where KEY is the base-58 encoded indexed bytes
+fn parse_custom_account_index_key(value: &str) -> Result<(CustomAccountIndex, Pubkey), String> {
+    let (index, key) = value
+        .rsplit_once(':')
+        .ok_or_else(|| format!("Expected PROGRAM_ID:OFFSET:LENGTH:KEY, got {}", value))?;
+    let index = parse_custom_account_index(index)?;
+    let key_bytes = bs58::decode(key)
+        .into_vec()
+        .map_err(|err| format!("Invalid key {}: {}", key, err))?;
+    if key_bytes.len() != index.length {
+        return Err(format!(
+            "Invalid key {}: expected {} bytes, got {}",
+            key,
+            index.length,
+            key_bytes.len()
+        ));
+    }
+    Ok((index, CustomAccountIndex::key_from_bytes(&key_bytes)))
+}
+
 fn is_trusted_validator(id: &Pubkey, trusted_validators: &Option<HashSet<Pubkey>>) -> bool {
     if let Some(trusted_validators) = trusted_validators {
         trusted_validators.contains(id)
@@ -1016,6 +1094,41 @@ fn rpc_bootstrap(
     }
 }
 
+fn download_snapshot_from_snapshot_url(
+    snapshot_url: &str,
+    snapshot_archives_dir: &Path,
+    maximum_local_snapshot_age: Slot,
+    maximum_snapshots_to_retain: usize,
+    use_progress_bar: bool,
+) -> Result<(), String> {
+    let (full_snapshot_archive_info, incremental_snapshot_archive_info) =
+        get_latest_snapshot_archives_from_url(snapshot_url, snapshot_archives_dir)?;
+    let snapshot_slot = incremental_snapshot_archive_info
+        .as_ref()
+        .map(|archive_info| archive_info.slot())
+        .unwrap_or_else(|| full_snapshot_archive_info.slot());
+    if let Some(highest_local_snapshot_slot) =
+        snapshot_utils::get_highest_full_snapshot_archive_slot(snapshot_archives_dir)
+    {
+        if highest_local_snapshot_slot > snapshot_slot.saturating_sub(maximum_local_snapshot_age) {
+            info!(
+                "Reusing local snapshot at slot {} instead of downloading the snapshot at slot {} \
+                 from {}",
+                highest_local_snapshot_slot, snapshot_slot, snapshot_url
+            );
+            return Ok(());
+        }
+    }
+    download_snapshot_archives_from_url(
+        snapshot_url,
+        &full_snapshot_archive_info,
+        incremental_snapshot_archive_info.as_ref(),
+        use_progress_bar,
+        maximum_snapshots_to_retain,
+        &mut None,
+    )
+}
+
 fn get_cluster_shred_version(entrypoints: &[SocketAddr]) -> Option<u16> {
     let entrypoints = {
         let mut index: Vec<_> = (0..entrypoints.len()).collect();
@@ -1052,6 +1165,7 @@ pub fn main() {
         PubSubConfig::default().max_out_buffer_capacity.to_string();
     let default_rpc_pubsub_max_active_subscriptions =
         PubSubConfig::default().max_active_subscriptions.to_string();
+    let default_rpc_pubsub_max_replay_slots = PubSubConfig::default().max_replay_slots.to_string();
     let default_rpc_send_transaction_retry_ms = ValidatorConfig::default()
         .send_transaction_retry_ms
         .to_string();
@@ -1144,6 +1258,19 @@ pub fn main() {
                 .help("Do not attempt to fetch a snapshot from the cluster, \
                       start from a local snapshot if present"),
         )
+        .arg(
+            Arg::with_name("snapshot_url")
+                .long("snapshot-url")
+                .value_name("URL")
+                .takes_value(true)
+                .conflicts_with("no_snapshot_fetch")
+                .validator(is_url)
+                .help("Download the latest snapshot archives uploaded to URL with \
+                       --snapshot-upload-url instead of fetching a snapshot from an RPC node of \
+                       the cluster. The full snapshot archive, and the incremental snapshot \
+                       archive based on it if any, are those listed in \
+                       URL/latest-snapshot-archives"),
+        )
         .arg(
             Arg::with_name("no_genesis_fetch")
                 .long("no-genesis-fetch")
@@ -1246,6 +1373,34 @@ pub fn main() {
                 .help("Include CPI inner instructions and logs in the \
                         historical transaction info stored"),
         )
+        .arg(
+            Arg::with_name("enable_rpc_account_history")
+                .long("enable-rpc-account-history")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help("Record the accounts written in each rooted slot, to serve the \
+                       getAccountInfoAtSlot RPC method"),
+        )
+        .arg(
+            Arg::with_name("rpc_account_history_owner")
+                .long("rpc-account-history-owner")
+                .value_name("PROGRAM ID")
+                .takes_value(true)
+                .multiple(true)
+                .requires("enable_rpc_account_history")
+                .validator(is_pubkey)
+                .help("Only record the history of accounts owned by this program. \
+                       May be specified multiple times. [default: all accounts]"),
+        )
+        .arg(
+            Arg::with_name("enable_rpc_account_proofs")
+                .long("enable-rpc-account-proofs")
+                .takes_value(false)
+                .help("Keep the Merkle tree of each accounts hash calculated, to serve the \
+                       getAccountProof RPC method against the latest accounts hash published \
+                       in gossip. Building the tree scans every account once more per \
+                       accounts hash, and it takes about 70 bytes of memory per account"),
+        )
         .arg(
             Arg::with_name("rpc_max_multiple_accounts")
                 .long("rpc-max-multiple-accounts")
@@ -1419,6 +1574,37 @@ pub fn main() {
                 .default_value(default_max_snapshot_to_retain)
                 .help("The maximum number of snapshots to hold on to when purging older snapshots.")
         )
+        .arg(
+            Arg::with_name("snapshot_upload_url")
+                .long("snapshot-upload-url")
+                .value_name("URL")
+                .takes_value(true)
+                .validator(is_url)
+                .help("Upload each new full and incremental snapshot archive with an HTTP PUT \
+                       to URL/<archive file name>, list the latest ones in \
+                       URL/latest-snapshot-archives, and delete the uploaded archives that are \
+                       purged locally"),
+        )
+        .arg(
+            Arg::with_name("snapshot_upload_s3_region")
+                .long("snapshot-upload-s3-region")
+                .value_name("REGION")
+                .takes_value(true)
+                .requires("snapshot_upload_url")
+                .help("Sign the snapshot archive uploads as requests to an S3-compatible \
+                       object store in REGION, with the credentials in the AWS_ACCESS_KEY_ID, \
+                       AWS_SECRET_ACCESS_KEY and optional AWS_SESSION_TOKEN environment variables"),
+        )
+        .arg(
+            Arg::with_name("maximum_uploaded_snapshots_to_retain")
+                .long("maximum-uploaded-snapshots-to-retain")
+                .value_name("MAXIMUM_SNAPSHOTS_TO_RETAIN")
+                .takes_value(true)
+                .requires("snapshot_upload_url")
+                .validator(is_parsable::<usize>)
+                .help("The maximum number of uploaded full snapshot archives to hold on to \
+                       when purging older uploaded archives [default: --maximum-snapshots-to-retain]"),
+        )
         .arg(
             Arg::with_name("minimal_snapshot_download_speed")
                 .long("minimal-snapshot-download-speed")
@@ -1667,12 +1853,107 @@ pub fn main() {
                 .default_value("30")
                 .help("Number of seconds before timing out RPC requests backed by BigTable"),
         )
+        .arg(
+            Arg::with_name("rpc_local_ledger_storage_path")
+                .long("rpc-local-ledger-storage-path")
+                .value_name("DIR")
+                .takes_value(true)
+                .help("Use the ledger archive in DIR instead of BigTable for \
+                       --enable-rpc-bigtable-ledger-storage and --enable-bigtable-ledger-upload"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit")
+                .long("rpc-rate-limit")
+                .value_name("REQUESTS_PER_SECOND[:BURST]")
+                .takes_value(true)
+                .validator(is_parsable::<RateLimit>)
+                .help("Limit the RPC requests of each client, across all methods. \
+                       BURST defaults to REQUESTS_PER_SECOND"),
+        )
+        .arg(
+            Arg::with_name("rpc_method_rate_limit")
+                .long("rpc-method-rate-limit")
+                .value_name("METHOD=REQUESTS_PER_SECOND[:BURST]")
+                .takes_value(true)
+                .multiple(true)
+                .validator(method_value_validator::<RateLimit>)
+                .help("Limit the calls of an RPC method by each client. \
+                       May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("rpc_method_weight")
+                .long("rpc-method-weight")
+                .value_name("METHOD=WEIGHT")
+                .takes_value(true)
+                .multiple(true)
+                .validator(method_value_validator::<u64>)
+                .help("Number of requests counted against --rpc-rate-limit for each call of \
+                       an RPC method [default: 1]. Methods weighing more than 1 are heavy. \
+                       May be specified multiple times"),
+        )
+        .arg(
+            Arg::with_name("rpc_max_concurrent_heavy_calls")
+                .long("rpc-max-concurrent-heavy-calls")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help("Maximum number of heavy RPC method calls to execute at once; \
+                       see --rpc-method-weight"),
+        )
+        .arg(
+            Arg::with_name("rpc_client_address_header")
+                .long("rpc-client-address-header")
+                .value_name("HEADER")
+                .takes_value(true)
+                .validator(|header| {
+                    if !header.is_empty()
+                        && header
+                            .bytes()
+                            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+                    {
+                        Ok(())
+                    } else {
+                        Err(format!("Invalid HTTP header name: {}", header))
+                    }
+                })
+                .help("Identify RPC clients by the last address in this HTTP header, such \
+                       as X-Forwarded-For, as appended by a trusted reverse proxy, instead \
+                       of by the peer address of the connection"),
+        )
+        .arg(
+            Arg::with_name("enable_rpc_call_metrics")
+                .long("enable-rpc-call-metrics")
+                .takes_value(false)
+                .help("Report the method, params and HTTP response sizes, latency, client \
+                       and error code of each RPC call as a metrics datapoint"),
+        )
+        .arg(
+            Arg::with_name("rpc_access_log")
+                .long("rpc-access-log")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Append a line of JSON describing each RPC call to this file"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_enable_vote_subscription")
                 .long("rpc-pubsub-enable-vote-subscription")
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `voteSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_block_subscription")
+                .long("rpc-pubsub-enable-block-subscription")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `blockSubscribe` subscription"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_max_connections")
                 .long("rpc-pubsub-max-connections")
@@ -1722,6 +2003,17 @@ pub fn main() {
                 .help("The maximum number of active subscriptions that RPC PubSub will accept \
                        across all connections."),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_max_replay_slots")
+                .long("rpc-pubsub-max-replay-slots")
+                .takes_value(true)
+                .value_name("NUMBER")
+                .validator(is_parsable::<usize>)
+                .default_value(&default_rpc_pubsub_max_replay_slots)
+                .help("The number of recent slots of notifications that RPC PubSub keeps, \
+                       for subscriptions that replay notifications from an earlier slot. \
+                       Set to 0 to disable replay."),
+        )
         .arg(
             Arg::with_name("rpc_send_transaction_retry_ms")
                 .long("rpc-send-retry-ms")
@@ -1901,6 +2193,40 @@ pub fn main() {
                 .value_name("KEY")
                 .help("When account indexes are enabled, only include specific keys in the index. This overrides --account-index-exclude-key."),
         )
+        .arg(
+            Arg::with_name("account_index_custom")
+                .long("account-index-custom")
+                .takes_value(true)
+                .multiple(true)
+                .validator(|value| parse_custom_account_index(&value).map(|_| ()))
+                .value_name("PROGRAM_ID:OFFSET:LENGTH")
+                .help("Enable an accounts index on the LENGTH bytes at OFFSET in the data of \
+                       the accounts owned by PROGRAM_ID. LENGTH is at most 32. \
+                       getProgramAccounts requests with a memcmp filter at OFFSET of at least \
+                       LENGTH bytes use the index"),
+        )
+        .arg(
+            Arg::with_name("account_index_custom_exclude_key")
+                .long(CUSTOM_EXCLUDE_KEY)
+                .takes_value(true)
+                .multiple(true)
+                .validator(|value| parse_custom_account_index_key(&value).map(|_| ()))
+                .value_name("PROGRAM_ID:OFFSET:LENGTH:KEY")
+                .help("Exclude this key, the base-58 encoded indexed bytes, from the custom \
+                       accounts index enabled with --account-index-custom PROGRAM_ID:OFFSET:LENGTH"),
+        )
+        .arg(
+            Arg::with_name("account_index_custom_include_key")
+                .long(CUSTOM_INCLUDE_KEY)
+                .takes_value(true)
+                .multiple(true)
+                .conflicts_with("account_index_custom_exclude_key")
+                .validator(|value| parse_custom_account_index_key(&value).map(|_| ()))
+                .value_name("PROGRAM_ID:OFFSET:LENGTH:KEY")
+                .help("Only include specific keys, the base-58 encoded indexed bytes, in the \
+                       custom accounts index enabled with --account-index-custom \
+                       PROGRAM_ID:OFFSET:LENGTH"),
+        )
         .arg(
             Arg::with_name("no_accounts_db_caching")
                 .long("no-accounts-db-caching")
@@ -2280,7 +2606,9 @@ pub fn main() {
 
     let rpc_bootstrap_config = RpcBootstrapConfig {
         no_genesis_fetch: matches.is_present("no_genesis_fetch"),
-        no_snapshot_fetch: matches.is_present("no_snapshot_fetch"),
+        // A snapshot from --snapshot-url is downloaded instead
+        no_snapshot_fetch: matches.is_present("no_snapshot_fetch")
+            || matches.is_present("snapshot_url"),
         no_check_vote_account: matches.is_present("no_check_vote_account"),
         no_untrusted_rpc: matches.is_present("no_untrusted_rpc"),
         max_genesis_archive_unpacked_size: value_t_or_exit!(
@@ -2468,6 +2796,14 @@ pub fn main() {
         rpc_config: JsonRpcConfig {
             enable_rpc_transaction_history: matches.is_present("enable_rpc_transaction_history"),
             enable_cpi_and_log_storage: matches.is_present("enable_cpi_and_log_storage"),
+            account_history: matches.is_present("enable_rpc_account_history").then(|| {
+                AccountHistoryConfig {
+                    owners: values_t!(matches, "rpc_account_history_owner", Pubkey)
+                        .ok()
+                        .map(|owners| owners.into_iter().collect()),
+                }
+            }),
+            enable_rpc_account_proofs: matches.is_present("enable_rpc_account_proofs"),
             enable_bigtable_ledger_storage: matches
                 .is_present("enable_rpc_bigtable_ledger_storage"),
             enable_bigtable_ledger_upload: matches.is_present("enable_bigtable_ledger_upload"),
@@ -2490,6 +2826,17 @@ pub fn main() {
             rpc_bigtable_timeout: value_t!(matches, "rpc_bigtable_timeout", u64)
                 .ok()
                 .map(Duration::from_secs),
+            rate_limit: process_rpc_rate_limit(&matches),
+            access_log: RpcAccessLogConfig {
+                enable_metrics: matches.is_present("enable_rpc_call_metrics"),
+                log_path: matches.value_of("rpc_access_log").map(PathBuf::from),
+            },
+            client_address_header: matches
+                .value_of("rpc_client_address_header")
+                .map(|header| header.to_string()),
+            local_ledger_storage_path: matches
+                .value_of("rpc_local_ledger_storage_path")
+                .map(PathBuf::from),
             account_indexes: account_indexes.clone(),
             rpc_scan_and_fix_roots: matches.is_present("rpc_scan_and_fix_roots"),
         },
@@ -2505,6 +2852,9 @@ pub fn main() {
         }),
         pubsub_config: PubSubConfig {
             enable_vote_subscription: matches.is_present("rpc_pubsub_enable_vote_subscription"),
+            enable_block_subscription: matches.is_present("rpc_pubsub_enable_block_subscription"),
+            enable_transaction_subscription: matches
+                .is_present("rpc_pubsub_enable_transaction_subscription"),
             max_connections: value_t_or_exit!(matches, "rpc_pubsub_max_connections", usize),
             max_fragment_size: value_t_or_exit!(matches, "rpc_pubsub_max_fragment_size", usize),
             max_in_buffer_capacity: value_t_or_exit!(
@@ -2522,6 +2872,7 @@ pub fn main() {
                 "rpc_pubsub_max_active_subscriptions",
                 usize
             ),
+            max_replay_slots: value_t_or_exit!(matches, "rpc_pubsub_max_replay_slots", usize),
         },
         voting_disabled: matches.is_present("no_voting") || restricted_repair_only_mode,
         wait_for_supermajority: value_t!(matches, "wait_for_supermajority", Slot).ok(),
@@ -2681,6 +3032,28 @@ pub fn main() {
         maximum_snapshots_to_retain,
     });
 
+    if let Some(snapshot_upload_url) = matches.value_of("snapshot_upload_url") {
+        let s3_credentials = matches.value_of("snapshot_upload_s3_region").map(|region| {
+            S3Credentials::from_env(region.to_string()).unwrap_or_else(|err| {
+                eprintln!("Missing S3 credentials to upload snapshots: {}", err);
+                exit(1);
+            })
+        });
+        let maximum_uploaded_snapshots_to_retain =
+            value_t!(matches, "maximum_uploaded_snapshots_to_retain", usize)
+                .unwrap_or(maximum_snapshots_to_retain);
+        let snapshot_uploader = HttpSnapshotUploader::new(
+            snapshot_upload_url,
+            s3_credentials,
+            maximum_uploaded_snapshots_to_retain,
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("Unable to upload snapshots: {}", err);
+            exit(1);
+        });
+        validator_config.snapshot_uploader = Some(Arc::new(snapshot_uploader));
+    }
+
     validator_config.accounts_hash_interval_slots =
         value_t_or_exit!(matches, "accounts_hash_interval_slots", u64);
     if validator_config.accounts_hash_interval_slots == 0 {
@@ -2846,6 +3219,22 @@ pub fn main() {
     let identity_keypair = Arc::new(identity_keypair);
 
     let should_check_duplicate_instance = !matches.is_present("no_duplicate_instance_check");
+    if let Some(snapshot_url) = matches.value_of("snapshot_url") {
+        download_snapshot_from_snapshot_url(
+            snapshot_url,
+            &snapshot_archives_dir,
+            maximum_local_snapshot_age,
+            maximum_snapshots_to_retain,
+            use_progress_bar,
+        )
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "Failed to download a snapshot from {}: {}",
+                snapshot_url, err
+            );
+            exit(1);
+        });
+    }
     if !cluster_entrypoints.is_empty() {
         rpc_bootstrap(
             &node,
@@ -2942,8 +3331,62 @@ fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
         None
     };
 
+    let mut custom_indexes: HashMap<_, Option<AccountSecondaryIndexesIncludeExclude>> = matches
+        .values_of("account_index_custom")
+        .unwrap_or_default()
+        .map(|value| (parse_custom_account_index(value).unwrap(), None))
+        .collect();
+    for (arg, exclude) in [
+        ("account_index_custom_include_key", false),
+        ("account_index_custom_exclude_key", true),
+    ] {
+        for value in matches.values_of(arg).unwrap_or_default() {
+            let (index, key) = parse_custom_account_index_key(value).unwrap();
+            let keys = custom_indexes.get_mut(&index).unwrap_or_else(|| {
+                eprintln!(
+                    "{} refers to a custom account index that is not enabled",
+                    value
+                );
+                exit(1);
+            });
+            keys.get_or_insert_with(|| AccountSecondaryIndexesIncludeExclude {
+                exclude,
+                keys: HashSet::new(),
+            })
+            .keys
+            .insert(key);
+        }
+    }
+
     AccountSecondaryIndexes {
         keys,
         indexes: account_indexes,
+        custom_indexes,
+    }
+}
+
+fn process_rpc_rate_limit(matches: &ArgMatches) -> RpcRateLimitConfig {
+    let method_values = |name| {
+        matches
+            .values_of(name)
+            .unwrap_or_default()
+            .map(|value| {
+                let (method, value) = value.split_once('=').unwrap();
+                (method.to_string(), value.to_string())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    RpcRateLimitConfig {
+        client_limit: value_t!(matches, "rpc_rate_limit", RateLimit).ok(),
+        method_limits: method_values("rpc_method_rate_limit")
+            .into_iter()
+            .map(|(method, limit)| (method, limit.parse().unwrap()))
+            .collect(),
+        method_weights: method_values("rpc_method_weight")
+            .into_iter()
+            .map(|(method, weight)| (method, weight.parse().unwrap()))
+            .collect(),
+        max_concurrent_heavy_calls: value_t!(matches, "rpc_max_concurrent_heavy_calls", usize).ok(),
     }
 }